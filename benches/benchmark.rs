@@ -2,19 +2,23 @@
 //!
 //! Run with: cargo bench
 
+#[cfg(not(feature = "no-float"))]
 use std::hint::black_box;
 
+#[cfg(not(feature = "no-float"))]
 use battery_estimator::{
     compensate_aging, compensate_aging_fixed, compensate_temperature, compensate_temperature_fixed,
     default_temperature_compensation, default_temperature_compensation_fixed, BatteryChemistry,
     Curve, CurvePoint, Fixed, SocEstimator,
 };
+#[cfg(not(feature = "no-float"))]
 use criterion::{criterion_group, criterion_main, Criterion};
 
 // ============================================================================
 // SOC Estimation Benchmarks
 // ============================================================================
 
+#[cfg(not(feature = "no-float"))]
 fn bench_estimate_soc(c: &mut Criterion) {
     let lipo = SocEstimator::new(BatteryChemistry::LiPo);
     let lifepo4 = SocEstimator::new(BatteryChemistry::LiFePO4);
@@ -42,6 +46,7 @@ fn bench_estimate_soc(c: &mut Criterion) {
     group.finish();
 }
 
+#[cfg(not(feature = "no-float"))]
 fn bench_estimate_soc_fixed(c: &mut Criterion) {
     let estimator = SocEstimator::new(BatteryChemistry::LiPo);
     let voltage = Fixed::from_num(3.7);
@@ -51,6 +56,36 @@ fn bench_estimate_soc_fixed(c: &mut Criterion) {
     });
 }
 
+#[cfg(not(feature = "no-float"))]
+fn bench_estimate_soc_slice(c: &mut Criterion) {
+    let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+    // Slow discharge as in a DMA buffer of consecutive readings
+    let voltages: Vec<Fixed> = (0..1024)
+        .map(|i| Fixed::from_num(4.1) - Fixed::from_num(i) / 1024)
+        .collect();
+    let mut socs = vec![Fixed::ZERO; voltages.len()];
+
+    c.bench_function("estimate_soc_slice_1024", |b| {
+        b.iter(|| estimator.estimate_soc_slice(black_box(&voltages), &mut socs))
+    });
+
+    // Interleaved readings of many batteries, as in fleet log replay
+    let mut state = 0x2545_f491_u32;
+    let shuffled: Vec<Fixed> = (0..1024)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            Fixed::from_num(3.1) + Fixed::from_bits((state % 72_000) as i32)
+        })
+        .collect();
+
+    c.bench_function("estimate_soc_slice_1024_shuffled", |b| {
+        b.iter(|| estimator.estimate_soc_slice(black_box(&shuffled), &mut socs))
+    });
+}
+
+#[cfg(not(feature = "no-float"))]
 fn bench_estimate_soc_with_temp(c: &mut Criterion) {
     let estimator = SocEstimator::new(BatteryChemistry::LiPo);
 
@@ -71,6 +106,7 @@ fn bench_estimate_soc_with_temp(c: &mut Criterion) {
     group.finish();
 }
 
+#[cfg(not(feature = "no-float"))]
 fn bench_estimate_soc_compensated(c: &mut Criterion) {
     let estimator = SocEstimator::with_all_compensation(
         BatteryChemistry::LiPo,
@@ -89,6 +125,7 @@ fn bench_estimate_soc_compensated(c: &mut Criterion) {
 // Curve Benchmarks
 // ============================================================================
 
+#[cfg(not(feature = "no-float"))]
 fn bench_curve_operations(c: &mut Criterion) {
     let curve = Curve::new(&[
         CurvePoint::new(3.0, 0.0),
@@ -111,6 +148,7 @@ fn bench_curve_operations(c: &mut Criterion) {
     group.finish();
 }
 
+#[cfg(not(feature = "no-float"))]
 fn bench_curve_creation(c: &mut Criterion) {
     c.bench_function("curve_new_small", |b| {
         b.iter(|| Curve::new(&[CurvePoint::new(3.0, 0.0), CurvePoint::new(4.0, 100.0)]))
@@ -139,6 +177,7 @@ fn bench_curve_creation(c: &mut Criterion) {
 // Compensation Benchmarks
 // ============================================================================
 
+#[cfg(not(feature = "no-float"))]
 fn bench_temperature_compensation(c: &mut Criterion) {
     let mut group = c.benchmark_group("temperature_compensation");
 
@@ -180,6 +219,7 @@ fn bench_temperature_compensation(c: &mut Criterion) {
     group.finish();
 }
 
+#[cfg(not(feature = "no-float"))]
 fn bench_aging_compensation(c: &mut Criterion) {
     let mut group = c.benchmark_group("aging_compensation");
 
@@ -204,6 +244,7 @@ fn bench_aging_compensation(c: &mut Criterion) {
 // CurvePoint Benchmarks
 // ============================================================================
 
+#[cfg(not(feature = "no-float"))]
 fn bench_curve_point(c: &mut Criterion) {
     let mut group = c.benchmark_group("curve_point");
 
@@ -237,6 +278,7 @@ fn bench_curve_point(c: &mut Criterion) {
 // Estimator Creation Benchmarks
 // ============================================================================
 
+#[cfg(not(feature = "no-float"))]
 fn bench_estimator_creation(c: &mut Criterion) {
     let mut group = c.benchmark_group("estimator_creation");
 
@@ -287,6 +329,7 @@ fn bench_estimator_creation(c: &mut Criterion) {
 // Custom Curve Estimator
 // ============================================================================
 
+#[cfg(not(feature = "no-float"))]
 fn bench_custom_curve(c: &mut Criterion) {
     const CUSTOM_CURVE: Curve = Curve::new(&[
         CurvePoint::new(3.0, 0.0),
@@ -305,6 +348,7 @@ fn bench_custom_curve(c: &mut Criterion) {
 // Boundary Cases
 // ============================================================================
 
+#[cfg(not(feature = "no-float"))]
 fn bench_boundary_cases(c: &mut Criterion) {
     let estimator = SocEstimator::new(BatteryChemistry::LiPo);
 
@@ -337,6 +381,7 @@ fn bench_boundary_cases(c: &mut Criterion) {
 // Throughput Test - Multiple Voltages
 // ============================================================================
 
+#[cfg(not(feature = "no-float"))]
 fn bench_throughput(c: &mut Criterion) {
     let estimator = SocEstimator::new(BatteryChemistry::LiPo);
     let voltages: [f32; 100] = core::array::from_fn(|i| {
@@ -352,10 +397,12 @@ fn bench_throughput(c: &mut Criterion) {
     });
 }
 
+#[cfg(not(feature = "no-float"))]
 criterion_group!(
     benches,
     bench_estimate_soc,
     bench_estimate_soc_fixed,
+    bench_estimate_soc_slice,
     bench_estimate_soc_with_temp,
     bench_estimate_soc_compensated,
     bench_curve_operations,
@@ -369,4 +416,10 @@ criterion_group!(
     bench_throughput,
 );
 
+#[cfg(not(feature = "no-float"))]
 criterion_main!(benches);
+
+#[cfg(feature = "no-float")]
+fn main() {
+    eprintln!("this benchmark needs the float API; build without `no-float`");
+}
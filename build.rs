@@ -0,0 +1,23 @@
+//! Detects a nightly compiler for the `simd` feature
+//!
+//! `core::simd` is nightly-only. The `simd` feature therefore enables the
+//! vector path only when this script sets `cfg(nightly)`; on stable and beta
+//! it compiles to the scalar path, so `--all-features` builds everywhere.
+
+use std::env;
+use std::process::Command;
+
+fn main() {
+    println!("cargo::rustc-check-cfg=cfg(nightly)");
+    println!("cargo::rerun-if-env-changed=RUSTC");
+
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let nightly = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).contains("nightly"))
+        .unwrap_or(false);
+    if nightly {
+        println!("cargo::rustc-cfg=nightly");
+    }
+}
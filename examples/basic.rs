@@ -1,7 +1,9 @@
 //! Comprehensive Test - Testing all voltage values including two decimal places
 
+#[cfg(not(feature = "no-float"))]
 use battery_estimator::{BatteryChemistry, SocEstimator};
 
+#[cfg(not(feature = "no-float"))]
 fn main() {
     println!("Battery SOC Estimator - Comprehensive Test");
     println!("==========================================\n");
@@ -29,6 +31,7 @@ fn main() {
 }
 
 /// Test specific battery type
+#[cfg(not(feature = "no-float"))]
 fn test_battery(chemistry: BatteryChemistry, name: &str, min_v: f32, max_v: f32) {
     println!("Testing {} Battery ({}V - {}V):", name, min_v, max_v);
     println!("{}", "-".repeat(50));
@@ -73,6 +76,7 @@ fn test_battery(chemistry: BatteryChemistry, name: &str, min_v: f32, max_v: f32)
 }
 
 /// Generate key voltage points
+#[cfg(not(feature = "no-float"))]
 fn generate_key_voltages(min_v: f32, max_v: f32) -> Vec<f32> {
     let mut voltages = Vec::new();
 
@@ -102,6 +106,7 @@ fn generate_key_voltages(min_v: f32, max_v: f32) -> Vec<f32> {
 }
 
 /// Determine whether printing is needed
+#[cfg(not(feature = "no-float"))]
 fn should_print(voltage: f32, min_v: f32, max_v: f32, step: f32) -> bool {
     // Always print boundaries and specific points
     if (voltage - min_v).abs() < step * 0.5 {
@@ -117,6 +122,7 @@ fn should_print(voltage: f32, min_v: f32, max_v: f32, step: f32) -> bool {
 }
 
 /// Test characteristic points
+#[cfg(not(feature = "no-float"))]
 fn test_characteristic_points(estimator: &SocEstimator, name: &str) {
     match name {
         "LiPo" => {
@@ -196,6 +202,7 @@ fn test_characteristic_points(estimator: &SocEstimator, name: &str) {
 }
 
 /// Test boundary cases
+#[cfg(not(feature = "no-float"))]
 fn test_boundary_cases() {
     let lipo = SocEstimator::new(BatteryChemistry::LiPo);
 
@@ -221,6 +228,7 @@ fn test_boundary_cases() {
 }
 
 /// Test error cases
+#[cfg(not(feature = "no-float"))]
 fn test_error_cases() {
     // Test potential error scenarios here
     println!("Testing with extreme values:");
@@ -236,3 +244,8 @@ fn test_error_cases() {
         }
     }
 }
+
+#[cfg(feature = "no-float")]
+fn main() {
+    eprintln!("this example needs the float API; build without `no-float`");
+}
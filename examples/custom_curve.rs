@@ -1,7 +1,9 @@
 //! Custom Curve Example
 
+#[cfg(not(feature = "no-float"))]
 use battery_estimator::{Curve, CurvePoint, SocEstimator};
 
+#[cfg(not(feature = "no-float"))]
 fn main() {
     println!("Battery SOC Estimator - Custom Curve Example");
     println!("============================================\n");
@@ -27,3 +29,8 @@ fn main() {
         }
     }
 }
+
+#[cfg(feature = "no-float")]
+fn main() {
+    eprintln!("this example needs the float API; build without `no-float`");
+}
@@ -1,7 +1,9 @@
 //! Precise Test - Testing at 0.01V Intervals
 
+#[cfg(not(feature = "no-float"))]
 use battery_estimator::{BatteryChemistry, SocEstimator};
 
+#[cfg(not(feature = "no-float"))]
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Battery SOC Estimator - Precise Voltage Test");
     println!("============================================\n");
@@ -65,3 +67,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[cfg(feature = "no-float")]
+fn main() {
+    eprintln!("this example needs the float API; build without `no-float`");
+}
@@ -2,8 +2,10 @@
 //!
 //! Tests SOC changes at different temperatures, demonstrating temperature compensation effects
 
+#[cfg(not(feature = "no-float"))]
 use battery_estimator::{default_temperature_compensation, BatteryChemistry, SocEstimator};
 
+#[cfg(not(feature = "no-float"))]
 fn main() {
     println!("Battery SOC Estimator - Temperature Compensation Test");
     println!("=====================================================\n");
@@ -23,6 +25,7 @@ fn main() {
 }
 
 /// Test all battery types at different temperatures
+#[cfg(not(feature = "no-float"))]
 fn test_all_batteries_with_temperature() {
     let chemistries = [
         (BatteryChemistry::LiPo, "LiPo", 3.2, 4.2),
@@ -79,6 +82,7 @@ fn test_all_batteries_with_temperature() {
 }
 
 /// Generate test voltage points
+#[cfg(not(feature = "no-float"))]
 fn generate_test_voltages(min_v: f32, max_v: f32) -> Vec<f32> {
     let mut voltages = Vec::new();
 
@@ -106,6 +110,7 @@ fn generate_test_voltages(min_v: f32, max_v: f32) -> Vec<f32> {
 }
 
 /// Show temperature impact on SOC percentage
+#[cfg(not(feature = "no-float"))]
 fn show_temperature_impact(chemistry: BatteryChemistry, min_v: f32, max_v: f32) {
     let estimator = SocEstimator::new(chemistry);
     let mid_voltage = (min_v + max_v) / 2.0;
@@ -146,6 +151,7 @@ fn show_temperature_impact(chemistry: BatteryChemistry, min_v: f32, max_v: f32)
 }
 
 /// Detailed temperature compensation analysis
+#[cfg(not(feature = "no-float"))]
 fn detailed_temperature_analysis() {
     let estimator = SocEstimator::new(BatteryChemistry::LiPo);
 
@@ -208,6 +214,7 @@ fn detailed_temperature_analysis() {
 }
 
 /// Test extreme temperature conditions
+#[cfg(not(feature = "no-float"))]
 fn test_extreme_temperatures() {
     let estimator = SocEstimator::new(BatteryChemistry::LiPo);
     let voltage = 3.7;
@@ -258,6 +265,7 @@ fn test_extreme_temperatures() {
 }
 
 /// Test compensation boundaries
+#[cfg(not(feature = "no-float"))]
 fn test_compensation_bounds() {
     let estimator = SocEstimator::new(BatteryChemistry::LiPo);
     let voltage = 3.7;
@@ -291,3 +299,8 @@ fn test_compensation_bounds() {
         Err(e) => println!("Error: {}", e),
     }
 }
+
+#[cfg(feature = "no-float")]
+fn main() {
+    eprintln!("this example needs the float API; build without `no-float`");
+}
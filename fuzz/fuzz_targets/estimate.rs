@@ -0,0 +1,21 @@
+//! Interpolation and compensation with arbitrary curves, configurations
+//! and measurements
+//!
+//! Run with `cargo +nightly fuzz run estimate` from the repository root.
+
+#![no_main]
+
+use battery_estimator::{BatteryChemistry, Curve, EstimatorConfig, LogRecord, SocEstimator};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (Curve, EstimatorConfig, BatteryChemistry, LogRecord)| {
+    let (curve, config, chemistry, record) = input;
+
+    let _ = curve.voltage_to_soc_fixed(record.voltage);
+    let _ = curve.voltage_mv_to_soc_permille(record.voltage.to_bits() as u16);
+    let _ = curve.soc_permille_to_voltage_mv(record.reference_soc.to_bits() as u16);
+    let _ = curve.voltage_integral(record.reference_soc);
+
+    let estimator = SocEstimator::with_config(chemistry, config);
+    let _ = estimator.estimate_soc_compensated_fixed(record.voltage, record.temperature);
+});
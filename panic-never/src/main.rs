@@ -0,0 +1,101 @@
+//! Link-time check that the estimation API contains no panics
+//!
+//! Every call below takes its arguments through `black_box`, so the
+//! optimizer must keep each path for arbitrary inputs. The panic handler
+//! calls a function that does not exist: if any panic survives
+//! optimization, linking fails with an undefined `library_can_panic`
+//! symbol.
+//!
+//! Run with
+//! `cargo build --release --target thumbv7em-none-eabihf` from this
+//! directory.
+
+#![no_std]
+#![no_main]
+
+use battery_estimator::{
+    compensate_aging_limited, compensate_temperature_limited, soc_bars, soc_quantized,
+    BatteryChemistry, BorrowedCurve, CompactCurve, CompensationLimits, Curve, CurvePoint,
+    EstimatorConfig, Fixed, KalmanFilter, LiteConfig, LiteEstimator, LiteFixed, RoundingMode,
+    SocEstimator, SocHistory, SocQuantizer, SocRounding, SocTracker, TrackerConfig,
+};
+use core::hint::black_box;
+
+#[panic_handler]
+fn panic(_: &core::panic::PanicInfo) -> ! {
+    extern "Rust" {
+        fn library_can_panic() -> !;
+    }
+    // SAFETY: the symbol does not exist, so a build that could reach this
+    // call fails to link instead of running it
+    unsafe { library_can_panic() }
+}
+
+fn fixed() -> Fixed {
+    Fixed::from_bits(black_box(0))
+}
+
+#[no_mangle]
+pub extern "C" fn _start() -> ! {
+    let chemistry = black_box(BatteryChemistry::LiPo);
+    let config = black_box(EstimatorConfig::default());
+    let mv = black_box(0u16);
+    let points: &[CurvePoint] = black_box(&[]);
+
+    let curve: &Curve = black_box(Curve::for_chemistry(chemistry));
+    let _ = black_box(curve.points());
+    let _ = black_box(curve.voltage_to_soc_fixed(fixed()));
+    let _ = black_box(curve.voltage_mv_to_soc_permille(mv));
+    let _ = black_box(curve.soc_permille_to_voltage_mv(mv));
+    let _ = black_box(curve.accuracy_bound().map(|bound| bound.total()));
+    let _ = black_box(BorrowedCurve::new(points).voltage_to_soc_fixed(fixed()));
+    if let Ok(compact) = CompactCurve::<8>::from_points(points) {
+        let _ = black_box(compact.voltage_to_soc_fixed(fixed()));
+    }
+
+    let estimator = SocEstimator::with_config(chemistry, config);
+    let voltages: &[Fixed] = black_box(&[]);
+    let socs: &mut [Fixed] = black_box(&mut []);
+    let _ = black_box(estimator.estimate_soc_fixed(fixed()));
+    let _ = black_box(estimator.estimate_soc_permille(mv));
+    let _ = black_box(estimator.estimate_soc_with_temp_fixed(fixed(), fixed()));
+    let _ = black_box(estimator.estimate_soc_compensated_fixed(fixed(), fixed()));
+    let _ = black_box(estimator.estimate_soc_under_load_fixed(fixed(), fixed()));
+    let _ = black_box(estimator.estimate_soc_batch(voltages));
+    let _ = black_box(estimator.estimate_soc_slice(voltages, socs));
+    let _ = black_box(estimator.remaining_capacity_mah(fixed()));
+    let _ = black_box(estimator.estimate_soc(black_box(0.0)));
+    let _ = black_box(estimator.estimate_soc_compensated(black_box(0.0), black_box(0.0)));
+    let _ = black_box(estimator.estimate_soc_f64(black_box(0.0)));
+    let _ = black_box(estimator.estimate_soc_compensated_f64(black_box(0.0), black_box(0.0)));
+
+    let limits = black_box(CompensationLimits::DEFAULT);
+    let _ = black_box(compensate_temperature_limited(
+        fixed(),
+        fixed(),
+        fixed(),
+        fixed(),
+        &limits,
+    ));
+    let _ = black_box(compensate_aging_limited(fixed(), fixed(), fixed(), &limits));
+
+    let lite = LiteEstimator::with_config(chemistry, black_box(LiteConfig::default()));
+    let lite_fixed = || LiteFixed::from_bits(black_box(0));
+    let _ = black_box(lite.estimate_soc(lite_fixed()));
+    let _ = black_box(lite.estimate_soc_compensated(lite_fixed(), lite_fixed()));
+
+    let mut tracker = SocTracker::new(estimator, black_box(TrackerConfig::default()))
+        .with_voltage_filter(KalmanFilter::new(fixed(), fixed()))
+        .with_segment_cache()
+        .with_history(SocHistory::new(black_box(0)));
+    let _ = black_box(tracker.update(fixed(), fixed(), fixed()));
+
+    let mut quantizer = SocQuantizer::bars(black_box(0)).with_hysteresis(fixed());
+    let _ = black_box(quantizer.update(fixed()));
+    let _ = black_box(soc_bars(fixed(), black_box(0)));
+    let _ = black_box(soc_quantized(fixed(), fixed()));
+    let rounding = SocRounding::new(black_box(RoundingMode::Nearest), black_box(0));
+    let _ = black_box(rounding.round(fixed()));
+
+    loop {}
+}
@@ -0,0 +1,179 @@
+//! Worst-case error of a curve lookup
+//!
+//! A curve stores voltages in whole millivolts and SOC in tenths of a
+//! percent, a lookup truncates the measured voltage to millivolts, and the
+//! interpolation runs in I16F16. Each step loses a little accuracy, most on
+//! the steepest segment. [`AccuracyBound`] adds these losses up so that a
+//! product's gauge accuracy claim can cite a computed figure.
+
+use crate::curve::segment_slope;
+use crate::{CurvePoint, Fixed};
+
+/// Worst-case error of the fixed-point rounding, in SOC bits
+///
+/// Converting each point's SOC from tenths truncates less than one bit,
+/// the precomputed slope loses less than one bit over a segment and the
+/// interpolation rounds to half a bit.
+const ARITHMETIC_BITS: i32 = 3;
+
+/// Worst-case SOC error of a [`Curve`](crate::Curve) lookup
+///
+/// Returned by [`SizedCurve::accuracy_bound()`](crate::SizedCurve::accuracy_bound).
+/// All errors are in percent SOC, as magnitudes. They are relative to the
+/// straight lines between the points a curve was defined with: how well
+/// those points and lines follow the real cell is a property of the curve
+/// data, not of this crate, and is not included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccuracyBound {
+    /// Storing each point's SOC in tenths of a percent (up to 0.1%)
+    pub soc_resolution: Fixed,
+    /// Storing each point's voltage in whole millivolts (up to 1 mV on the
+    /// steepest segment)
+    pub voltage_resolution: Fixed,
+    /// Truncating the measured voltage to whole millivolts before the
+    /// lookup (up to 1 mV on the steepest segment)
+    pub measurement_quantization: Fixed,
+    /// Fixed-point rounding of the interpolation
+    pub arithmetic: Fixed,
+    /// Index of the upper point of the steepest segment
+    pub steepest_segment: usize,
+    /// SOC slope of the steepest segment, in percent per millivolt
+    pub max_slope: Fixed,
+}
+
+impl AccuracyBound {
+    /// Computes the bound of points sorted by increasing voltage
+    pub(crate) fn of_points(points: &[CurvePoint]) -> Self {
+        let (steepest_segment, slope) = points
+            .iter()
+            .zip(points.iter().skip(1))
+            .map(|(prev, curr)| segment_slope(*prev, *curr))
+            .enumerate()
+            .fold((0, 0), |steepest, (index, slope)| {
+                if slope > steepest.1 {
+                    (index + 1, slope)
+                } else {
+                    steepest
+                }
+            });
+        // The slope is in SOC bits per mV, scaled by 2^16; round it up
+        let per_mv = ((slope + 0xFFFF) >> 16).min(i32::MAX as i64) as i32;
+        let max_slope = Fixed::from_bits(per_mv);
+
+        Self {
+            soc_resolution: Fixed::from_bits((1 << 16) / 10 + 1),
+            voltage_resolution: max_slope,
+            measurement_quantization: max_slope,
+            arithmetic: Fixed::from_bits(ARITHMETIC_BITS),
+            steepest_segment,
+            max_slope,
+        }
+    }
+
+    /// Sum of all error sources, in percent SOC
+    ///
+    /// Saturates at [`Fixed::MAX`].
+    pub fn total(&self) -> Fixed {
+        self.soc_resolution
+            .saturating_add(self.voltage_resolution)
+            .saturating_add(self.measurement_quantization)
+            .saturating_add(self.arithmetic)
+    }
+
+    /// Error of the lookup alone, for points taken as exact
+    ///
+    /// The measurement quantization plus the arithmetic; the part that
+    /// remains if the curve data is exactly representable, e.g. given in
+    /// whole millivolts and tenths of a percent.
+    pub fn lookup(&self) -> Fixed {
+        self.measurement_quantization
+            .saturating_add(self.arithmetic)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Curve, CurvePoint, Error, Fixed};
+
+    #[test]
+    fn test_linear_curve_bound() {
+        let curve = Curve::new(&[
+            CurvePoint::from_raw(3_000, 0),
+            CurvePoint::from_raw(4_000, 1000),
+        ]);
+        let bound = curve.accuracy_bound().unwrap();
+        let tenth = Fixed::from_num(0.1);
+        assert!((bound.max_slope - tenth).abs() <= Fixed::DELTA);
+        assert!(bound.soc_resolution >= tenth);
+        assert_eq!(bound.steepest_segment, 1);
+        assert!((bound.total() - Fixed::from_num(0.3)).abs() < Fixed::from_num(0.001));
+    }
+
+    #[test]
+    fn test_steepest_segment_dominates() {
+        let curve = Curve::new(&[
+            CurvePoint::from_raw(3_000, 0),
+            CurvePoint::from_raw(3_900, 100),
+            CurvePoint::from_raw(3_950, 900),
+            CurvePoint::from_raw(4_100, 1000),
+        ]);
+        let bound = curve.accuracy_bound().unwrap();
+        assert_eq!(bound.steepest_segment, 2);
+        // 80% over 50 mV
+        assert!((bound.max_slope - Fixed::from_num(1.6)).abs() <= Fixed::DELTA);
+        assert!(bound.lookup() < bound.total());
+    }
+
+    #[cfg(not(feature = "no-float"))]
+    #[test]
+    fn test_lookup_error_within_bound() {
+        use crate::BatteryChemistry;
+
+        for chemistry in [
+            BatteryChemistry::LiPo,
+            BatteryChemistry::LiFePO4,
+            BatteryChemistry::LiIon,
+            BatteryChemistry::Lipo410Full340Cutoff,
+        ] {
+            let curve = Curve::for_chemistry(chemistry);
+            let bound = curve.accuracy_bound().unwrap();
+            let points = curve.points();
+            // Exact interpolation of the stored points at a measured
+            // voltage between whole millivolts
+            for tenth_mv in 25_000..45_000 {
+                let voltage = tenth_mv as f64 / 10_000.0;
+                let exact = match points.iter().position(|p| p.voltage() as f64 > voltage) {
+                    Some(0) => points[0].soc() as f64,
+                    None => points[points.len() - 1].soc() as f64,
+                    Some(i) => {
+                        let (prev, curr) = (points[i - 1], points[i]);
+                        let (v0, s0) = (
+                            prev.voltage_mv as f64 / 1000.0,
+                            prev.soc_tenth as f64 / 10.0,
+                        );
+                        let (v1, s1) = (
+                            curr.voltage_mv as f64 / 1000.0,
+                            curr.soc_tenth as f64 / 10.0,
+                        );
+                        s0 + (s1 - s0) * (voltage - v0) / (v1 - v0)
+                    }
+                };
+                let soc = curve
+                    .voltage_to_soc_fixed(Fixed::from_num(voltage))
+                    .unwrap();
+                let error = (soc.to_num::<f64>() - exact).abs();
+                assert!(
+                    error <= bound.lookup().to_num::<f64>(),
+                    "{chemistry:?} at {voltage} V: {error}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_curve() {
+        assert_eq!(Curve::empty().accuracy_bound(), Err(Error::InvalidCurve));
+        let reversed = Curve::new(&[CurvePoint::new(4.0, 100.0), CurvePoint::new(3.0, 0.0)]);
+        assert_eq!(reversed.accuracy_bound(), Err(Error::InvalidCurve));
+    }
+}
@@ -0,0 +1,195 @@
+//! `arbitrary` implementations for fuzzing
+//!
+//! Enabled with the `arbitrary` feature. Values are deliberately not
+//! sanitized: curves may be unsorted, short or hold SOCs above 100%, and
+//! configurations may carry any fixed-point value, so fuzz targets reach
+//! the error and saturation paths of interpolation and compensation.
+//! [`LogRecord`] serves as the measurement input.
+
+use crate::{
+    BatteryChemistry, Calibration, Curve, CurvePoint, EstimatorConfig, LogRecord, OutputPolicy,
+    MAX_CURVE_POINTS,
+};
+use arbitrary::{size_hint, Arbitrary, Result, Unstructured};
+
+/// Size of an arbitrary [`Fixed`] in bytes
+const FIXED_SIZE: usize = 4;
+
+impl<'a> Arbitrary<'a> for CurvePoint {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(CurvePoint {
+            voltage_mv: u.arbitrary()?,
+            soc_tenth: u.arbitrary()?,
+        })
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (4, Some(4))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Curve {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let len = u.int_in_range(0..=MAX_CURVE_POINTS)?;
+        let mut points = [CurvePoint {
+            voltage_mv: 0,
+            soc_tenth: 0,
+        }; MAX_CURVE_POINTS];
+        for point in &mut points[..len] {
+            *point = u.arbitrary()?;
+        }
+        Ok(Curve::new(&points[..len]))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        size_hint::and(
+            (1, Some(1)),
+            (0, Some(MAX_CURVE_POINTS * CurvePoint::size_hint(depth).0)),
+        )
+    }
+}
+
+impl<'a> Arbitrary<'a> for BatteryChemistry {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(*u.choose(&[
+            BatteryChemistry::LiPo,
+            BatteryChemistry::LiFePO4,
+            BatteryChemistry::LiIon,
+            BatteryChemistry::Lipo410Full340Cutoff,
+        ])?)
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (1, Some(4))
+    }
+}
+
+impl<'a> Arbitrary<'a> for OutputPolicy {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(*u.choose(&[OutputPolicy::Clamp, OutputPolicy::Reject, OutputPolicy::Raw])?)
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (1, Some(4))
+    }
+}
+
+impl<'a> Arbitrary<'a> for EstimatorConfig {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut config = EstimatorConfig::default();
+        if u.arbitrary()? {
+            config = config.with_temperature_compensation();
+        }
+        if u.arbitrary()? {
+            config = config.with_aging_compensation();
+        }
+        if u.arbitrary()? {
+            config = config.with_strict_voltage_range();
+        }
+        config.nominal_temperature = u.arbitrary()?;
+        config.temperature_coefficient = u.arbitrary()?;
+        config.age_years = u.arbitrary()?;
+        config.aging_factor = u.arbitrary()?;
+        config.divider_ratio = u.arbitrary()?;
+        config.input_gain = u.arbitrary()?;
+        config.input_offset = u.arbitrary()?;
+        config.capacity_mah = u.arbitrary()?;
+        config.internal_resistance = u.arbitrary()?;
+        config.output_policy = u.arbitrary()?;
+        Ok(config)
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (3 + 9 * FIXED_SIZE + 1, Some(3 + 9 * FIXED_SIZE + 4))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Calibration {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Calibration {
+            gain: u.arbitrary()?,
+            offset: u.arbitrary()?,
+        })
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (2 * FIXED_SIZE, Some(2 * FIXED_SIZE))
+    }
+}
+
+impl<'a> Arbitrary<'a> for LogRecord {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(LogRecord {
+            time_ms: u.arbitrary()?,
+            voltage: u.arbitrary()?,
+            current_ma: u.arbitrary()?,
+            temperature: u.arbitrary()?,
+            reference_soc: u.arbitrary()?,
+        })
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (8 + 4 * FIXED_SIZE, Some(8 + 4 * FIXED_SIZE))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Fixed, SocEstimator};
+
+    const SOC_FULL: Fixed = Fixed::from_bits(100 << 16);
+
+    /// Pseudo-random fuzz input of `len` bytes
+    fn bytes(seed: u64, buf: &mut [u8]) {
+        let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+        for byte in buf {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            *byte = state as u8;
+        }
+    }
+
+    #[test]
+    fn test_arbitrary_inputs_do_not_panic() {
+        let mut buf = [0u8; 256];
+        for seed in 0..5000 {
+            bytes(seed, &mut buf);
+            let mut u = Unstructured::new(&buf);
+            let (Ok(curve), Ok(config), Ok(chemistry), Ok(record)) = (
+                Curve::arbitrary(&mut u),
+                EstimatorConfig::arbitrary(&mut u),
+                BatteryChemistry::arbitrary(&mut u),
+                LogRecord::arbitrary(&mut u),
+            ) else {
+                continue;
+            };
+
+            let _ = curve.voltage_to_soc_fixed(record.voltage);
+            let _ = curve.voltage_mv_to_soc_permille(record.voltage.to_bits() as u16);
+            let _ = curve.soc_permille_to_voltage_mv(record.reference_soc.to_bits() as u16);
+            let _ = curve.voltage_integral(record.reference_soc);
+            let _ = curve.validate_sorted();
+
+            let estimator = SocEstimator::with_config(chemistry, config);
+            let result =
+                estimator.estimate_soc_compensated_fixed(record.voltage, record.temperature);
+            if let (OutputPolicy::Clamp, Ok(soc)) = (config.output_policy, result) {
+                assert!(
+                    (Fixed::ZERO..=SOC_FULL).contains(&soc),
+                    "seed {seed}: {soc}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_arbitrary_curve_respects_point_limit() {
+        let buf = [0xFFu8; 512];
+        let curve = Curve::arbitrary(&mut Unstructured::new(&buf)).unwrap();
+        assert!(curve.len() <= MAX_CURVE_POINTS);
+        let empty = Curve::arbitrary(&mut Unstructured::new(&[])).unwrap();
+        assert!(empty.is_empty());
+    }
+}
@@ -0,0 +1,292 @@
+//! Command-line tool for bring-up and support
+//!
+//! Prints curve tables, converts voltages to SOC, fits curves from CSV
+//! discharge logs and simulates constant-current discharges without
+//! writing a program. Build with `cargo install battery-estimator
+//! --features cli`.
+
+use std::error::Error as StdError;
+use std::fs::File;
+use std::io::{self, BufReader, Write};
+use std::process::ExitCode;
+
+use battery_estimator::{
+    BatteryChemistry, CsvColumns, Curve, DischargeSimulator, Fixed, LoadProfile, SimConfig,
+    SocEstimator, SocTracker, TrackerConfig,
+};
+
+const USAGE: &str = "\
+Usage: battery-estimator <command> [arguments]
+
+Commands:
+  table <curve>                          Print the curve's voltage-SOC table
+  soc <curve> <voltage> [--temp <C>]     Convert a voltage (V) to SOC
+  fit <log.csv> [--capacity] [--rust]    Fit a curve to a CSV discharge log
+  simulate <curve> --capacity <mAh> --current <mA> [--interval <s>]
+                                         Simulate a constant-current discharge
+
+<curve> is a chemistry (LiPo, LiFePO4, LiIon, Lipo410Full340Cutoff) or the
+path of a voltage,soc CSV file.";
+
+type CliResult<T> = Result<T, Box<dyn StdError>>;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() || args[0] == "--help" || args[0] == "help" {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+    match run(&args, &mut io::stdout().lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        // Output piped into `head` and the like
+        Err(error)
+            if error
+                .downcast_ref::<io::Error>()
+                .is_some_and(|error| error.kind() == io::ErrorKind::BrokenPipe) =>
+        {
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("error: {error}\nRun `battery-estimator --help` for usage.");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Dispatches a command line (without the program name)
+fn run(args: &[String], out: &mut dyn Write) -> CliResult<()> {
+    let (command, args) = args.split_first().ok_or("missing command")?;
+    let mut args = Args::new(args);
+    match command.as_str() {
+        "table" => {
+            let curve = load_curve(&args.positional("curve")?)?;
+            args.finish()?;
+            print_table(curve, out)
+        }
+        "soc" => {
+            let curve = load_curve(&args.positional("curve")?)?;
+            let voltage = parse_fixed(&args.positional("voltage")?)?;
+            let temperature = args
+                .option("--temp")?
+                .map(|t| parse_fixed(&t))
+                .transpose()?;
+            args.finish()?;
+            let estimator = SocEstimator::with_custom_curve(curve);
+            let soc = match temperature {
+                Some(temperature) => estimator.estimate_soc_with_temp_fixed(voltage, temperature),
+                None => estimator.estimate_soc_fixed(voltage),
+            }?;
+            writeln!(out, "{:.1}", soc.to_num::<f64>())?;
+            Ok(())
+        }
+        "fit" => {
+            let path = args.positional("log")?;
+            let columns = if args.flag("--capacity") {
+                CsvColumns::Capacity
+            } else {
+                CsvColumns::Soc
+            };
+            let rust = args.flag("--rust");
+            args.finish()?;
+            let curve = Curve::from_csv(BufReader::new(File::open(path)?), columns)?;
+            if rust {
+                print_rust(&curve, out)
+            } else {
+                Ok(curve.write_csv(out)?)
+            }
+        }
+        "simulate" => {
+            let curve = load_curve(&args.positional("curve")?)?;
+            let capacity = parse_fixed(&args.option("--capacity")?.ok_or("missing --capacity")?)?;
+            let current = parse_fixed(&args.option("--current")?.ok_or("missing --current")?)?;
+            let interval = match args.option("--interval")? {
+                Some(interval) => parse_fixed(&interval)?,
+                None => Fixed::from_num(60),
+            };
+            args.finish()?;
+            if capacity <= Fixed::ZERO || current <= Fixed::ZERO || interval <= Fixed::ZERO {
+                return Err("capacity, current and interval must be positive".into());
+            }
+            simulate(curve, capacity, current, interval, out)
+        }
+        _ => Err(format!("unknown command `{command}`").into()),
+    }
+}
+
+/// Remaining command-line arguments of a command
+struct Args {
+    args: Vec<String>,
+}
+
+impl Args {
+    fn new(args: &[String]) -> Self {
+        Self {
+            args: args.to_vec(),
+        }
+    }
+
+    /// Removes and returns the first argument not starting with `--`
+    fn positional(&mut self, name: &str) -> CliResult<String> {
+        let index = self
+            .args
+            .iter()
+            .position(|arg| !arg.starts_with("--"))
+            .ok_or_else(|| format!("missing <{name}>"))?;
+        Ok(self.args.remove(index))
+    }
+
+    /// Removes `name` and returns whether it was present
+    fn flag(&mut self, name: &str) -> bool {
+        let index = self.args.iter().position(|arg| arg == name);
+        index.map(|index| self.args.remove(index)).is_some()
+    }
+
+    /// Removes `name` and its value
+    fn option(&mut self, name: &str) -> CliResult<Option<String>> {
+        let Some(index) = self.args.iter().position(|arg| arg == name) else {
+            return Ok(None);
+        };
+        if index + 1 >= self.args.len() {
+            return Err(format!("missing value for {name}").into());
+        }
+        self.args.remove(index);
+        Ok(Some(self.args.remove(index)))
+    }
+
+    /// Fails if any argument was not consumed
+    fn finish(self) -> CliResult<()> {
+        match self.args.first() {
+            Some(arg) => Err(format!("unexpected argument `{arg}`").into()),
+            None => Ok(()),
+        }
+    }
+}
+
+fn parse_fixed(text: &str) -> CliResult<Fixed> {
+    let value: f64 = text
+        .parse()
+        .map_err(|_| format!("invalid number `{text}`"))?;
+    Fixed::checked_from_num(value).ok_or_else(|| format!("number out of range `{text}`").into())
+}
+
+/// Built-in chemistry curve, or a curve read from a `voltage,soc` CSV file
+///
+/// File curves are leaked to obtain the `'static` lifetime the estimator
+/// requires.
+fn load_curve(name: &str) -> CliResult<&'static Curve> {
+    if let Ok(chemistry) = name.parse::<BatteryChemistry>() {
+        return Ok(Curve::for_chemistry(chemistry));
+    }
+    let file = File::open(name).map_err(|error| format!("{name}: {error}"))?;
+    let curve = Curve::from_csv(BufReader::new(file), CsvColumns::Soc)?;
+    Ok(Box::leak(Box::new(curve)))
+}
+
+fn print_table(curve: &Curve, out: &mut dyn Write) -> CliResult<()> {
+    writeln!(out, "{:>9}  {:>6}", "voltage", "soc")?;
+    for point in curve.points().iter().rev() {
+        writeln!(
+            out,
+            "{:>7.3} V  {:>5.1}%",
+            point.voltage_mv as f64 / 1000.0,
+            point.soc_tenth as f64 / 10.0
+        )?;
+    }
+    Ok(())
+}
+
+/// Prints the curve as a `const` definition to paste into firmware
+fn print_rust(curve: &Curve, out: &mut dyn Write) -> CliResult<()> {
+    writeln!(out, "const CURVE: Curve = Curve::new(&[")?;
+    for point in curve.points() {
+        writeln!(
+            out,
+            "    CurvePoint::new({:.3}, {:.1}),",
+            point.voltage_mv as f64 / 1000.0,
+            point.soc_tenth as f64 / 10.0
+        )?;
+    }
+    writeln!(out, "]);")?;
+    Ok(())
+}
+
+/// Discharges a full cell at a constant current, printing the terminal
+/// voltage, the true SOC and the tracked SOC at each interval as CSV
+fn simulate(
+    curve: &'static Curve,
+    capacity_mah: Fixed,
+    current_ma: Fixed,
+    interval: Fixed,
+    out: &mut dyn Write,
+) -> CliResult<()> {
+    let config = SimConfig::default()
+        .with_capacity_mah(capacity_mah)
+        .with_interval_ms((interval.to_num::<f64>() * 1000.0).round() as u32);
+    let simulator =
+        DischargeSimulator::with_custom_curve(curve, config, LoadProfile::Constant(current_ma))?;
+    let mut tracker = SocTracker::new(
+        SocEstimator::with_custom_curve(curve),
+        TrackerConfig::default().with_capacity_mah(capacity_mah),
+    );
+
+    writeln!(out, "time_s,voltage,true_soc,tracked_soc")?;
+    for sample in simulator {
+        let tracked = tracker.update(sample.voltage, sample.current_ma, interval)?;
+        writeln!(
+            out,
+            "{:.0},{:.3},{:.1},{:.1}",
+            sample.time_ms as f64 / 1000.0,
+            sample.voltage.to_num::<f64>(),
+            sample.soc.to_num::<f64>(),
+            tracked.to_num::<f64>()
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_to_string(args: &[&str]) -> CliResult<String> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let mut out = Vec::new();
+        run(&args, &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_soc_command() {
+        assert_eq!(run_to_string(&["soc", "LiPo", "3.75"]).unwrap(), "60.0\n");
+        assert!(run_to_string(&["soc", "NiMH", "1.2"]).is_err());
+        assert!(run_to_string(&["soc", "LiPo"]).is_err());
+        assert!(run_to_string(&["soc", "LiPo", "3.7", "extra"]).is_err());
+    }
+
+    #[test]
+    fn test_table_command() {
+        let table = run_to_string(&["table", "LiFePO4"]).unwrap();
+        let curve = Curve::for_chemistry(BatteryChemistry::LiFePO4);
+        assert_eq!(table.lines().count(), curve.len() + 1);
+        assert!(table.lines().nth(1).unwrap().contains("100.0%"));
+    }
+
+    #[test]
+    fn test_simulate_command() {
+        let output = run_to_string(&[
+            "simulate",
+            "LiIon",
+            "--capacity",
+            "1000",
+            "--current",
+            "500",
+            "--interval",
+            "360",
+        ])
+        .unwrap();
+        // 5% per step from 100% to 0%, plus the header
+        assert_eq!(output.lines().count(), 22);
+        assert!(output.lines().last().unwrap().contains(",0.0,"));
+        assert!(run_to_string(&["simulate", "LiIon", "--current", "500"]).is_err());
+    }
+}
@@ -0,0 +1,249 @@
+//! Bluetooth LE Battery Service encoding
+//!
+//! The Battery Service (0x180F) exposes the Battery Level characteristic
+//! (0x2A19), a single byte in percent, and since version 1.1 the Battery
+//! Level Status characteristic (0x2BED) with power and charge state. This
+//! module produces both characteristic values from SOC estimates so they
+//! can be handed to any BLE stack unchanged.
+
+use crate::{Fixed, SocTracker};
+
+/// 100% as a fixed-point constant
+const SOC_FULL: Fixed = Fixed::from_bits(100 << 16);
+
+/// Battery Level characteristic value (0x2A19)
+///
+/// Rounds to the nearest percent and clamps to 0-100, so a full battery
+/// reads 100 and an empty one 0 rather than wrapping or exceeding the
+/// characteristic's range.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{ble_battery_level, Fixed};
+///
+/// assert_eq!(ble_battery_level(Fixed::from_num(72.5)), 73);
+/// assert_eq!(ble_battery_level(Fixed::from_num(104.0)), 100);
+/// assert_eq!(ble_battery_level(Fixed::from_num(-2.0)), 0);
+/// ```
+#[inline]
+pub fn ble_battery_level(soc: Fixed) -> u8 {
+    let soc = soc.clamp(Fixed::ZERO, SOC_FULL);
+    ((soc.to_bits() + (1 << 15)) >> 16) as u8
+}
+
+/// Battery charge state of the Battery Level Status power state field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(u8)]
+pub enum BleChargeState {
+    /// Unknown
+    #[default]
+    Unknown = 0,
+    /// Charging
+    Charging = 1,
+    /// Discharging while the device is active
+    DischargingActive = 2,
+    /// Discharging while the device is inactive
+    DischargingInactive = 3,
+}
+
+/// Battery charge level of the Battery Level Status power state field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(u8)]
+pub enum BleChargeLevel {
+    /// Unknown
+    #[default]
+    Unknown = 0,
+    /// Good
+    Good = 1,
+    /// Low
+    Low = 2,
+    /// Critical
+    Critical = 3,
+}
+
+/// Battery Level Status characteristic value (0x2BED)
+///
+/// Encodes the flags byte, the power state word and, if set, the battery
+/// level. The identifier and additional status fields are not used.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{BleBatteryLevelStatus, BleChargeLevel, BleChargeState};
+///
+/// let status = BleBatteryLevelStatus {
+///     charge_state: BleChargeState::Charging,
+///     charge_level: BleChargeLevel::Good,
+///     battery_level: Some(80),
+///     ..BleBatteryLevelStatus::default()
+/// };
+///
+/// let mut buffer = [0u8; BleBatteryLevelStatus::MAX_SIZE];
+/// assert_eq!(status.encode(&mut buffer), &[0x02, 0xB5, 0x00, 80]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BleBatteryLevelStatus {
+    /// A battery is present
+    pub battery_present: bool,
+    /// Wired external power is connected, `None` if unknown
+    pub wired_power: Option<bool>,
+    /// Charge state
+    pub charge_state: BleChargeState,
+    /// Charge level
+    pub charge_level: BleChargeLevel,
+    /// Battery level in percent, `None` to omit the field
+    pub battery_level: Option<u8>,
+}
+
+impl Default for BleBatteryLevelStatus {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            battery_present: true,
+            wired_power: None,
+            charge_state: BleChargeState::Unknown,
+            charge_level: BleChargeLevel::Unknown,
+            battery_level: None,
+        }
+    }
+}
+
+impl BleBatteryLevelStatus {
+    /// Largest encoded size in bytes
+    pub const MAX_SIZE: usize = 4;
+
+    /// Flags bit: the battery level field is present
+    const FLAG_BATTERY_LEVEL: u8 = 0x02;
+
+    /// Builds the status from a tracker's state
+    ///
+    /// The charge state follows the sign of `current_ma` (zero counts as
+    /// discharging while inactive), and the charge level the tracker's low
+    /// and critical SOC thresholds.
+    ///
+    /// # Returns
+    ///
+    /// `None` until the tracker has processed its first sample.
+    pub fn from_tracker(tracker: &SocTracker, current_ma: Fixed) -> Option<Self> {
+        let soc = tracker.soc()?;
+        let config = tracker.config();
+
+        let charge_state = if current_ma > Fixed::ZERO {
+            BleChargeState::Charging
+        } else if current_ma < Fixed::ZERO {
+            BleChargeState::DischargingActive
+        } else {
+            BleChargeState::DischargingInactive
+        };
+        let charge_level = if soc < config.critical_soc_threshold {
+            BleChargeLevel::Critical
+        } else if soc < config.low_soc_threshold {
+            BleChargeLevel::Low
+        } else {
+            BleChargeLevel::Good
+        };
+
+        Some(Self {
+            charge_state,
+            charge_level,
+            battery_level: Some(ble_battery_level(soc)),
+            ..Self::default()
+        })
+    }
+
+    /// Power state field (battery present, wired power, charge state and
+    /// level; wireless power and charging type reported as unknown)
+    pub const fn power_state(&self) -> u16 {
+        let wired_power = match self.wired_power {
+            Some(false) => 0,
+            Some(true) => 1,
+            None => 2,
+        };
+        self.battery_present as u16
+            | wired_power << 1
+            | 2 << 3
+            | (self.charge_state as u16) << 5
+            | (self.charge_level as u16) << 7
+    }
+
+    /// Encodes the characteristic value into `buffer`
+    ///
+    /// Returns the used part of `buffer`. The battery level is clamped to
+    /// 100.
+    pub fn encode<'a>(&self, buffer: &'a mut [u8; Self::MAX_SIZE]) -> &'a [u8] {
+        let [low, high] = self.power_state().to_le_bytes();
+        buffer[1] = low;
+        buffer[2] = high;
+        match self.battery_level {
+            Some(level) => {
+                buffer[0] = Self::FLAG_BATTERY_LEVEL;
+                buffer[3] = level.min(100);
+                &buffer[..4]
+            }
+            None => {
+                buffer[0] = 0;
+                &buffer[..3]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BatteryChemistry, SocEstimator, TrackerConfig};
+
+    #[test]
+    fn test_battery_level_rounding() {
+        assert_eq!(ble_battery_level(Fixed::from_num(0.49)), 0);
+        assert_eq!(ble_battery_level(Fixed::from_num(0.5)), 1);
+        assert_eq!(ble_battery_level(Fixed::from_num(99.5)), 100);
+        assert_eq!(ble_battery_level(Fixed::MAX), 100);
+        assert_eq!(ble_battery_level(Fixed::MIN), 0);
+    }
+
+    #[test]
+    fn test_level_status_encoding() {
+        let mut buffer = [0u8; BleBatteryLevelStatus::MAX_SIZE];
+        let status = BleBatteryLevelStatus {
+            battery_present: false,
+            wired_power: Some(true),
+            charge_state: BleChargeState::DischargingInactive,
+            charge_level: BleChargeLevel::Critical,
+            battery_level: None,
+        };
+        // Critical, discharging inactive, wireless power unknown, wired power, no battery
+        assert_eq!(status.power_state(), 0x01F2);
+        assert_eq!(status.encode(&mut buffer), &[0x00, 0xF2, 0x01]);
+
+        let status = BleBatteryLevelStatus {
+            battery_level: Some(250),
+            ..status
+        };
+        assert_eq!(status.encode(&mut buffer), &[0x02, 0xF2, 0x01, 100]);
+    }
+
+    #[test]
+    fn test_level_status_from_tracker() {
+        let mut tracker = SocTracker::new(
+            SocEstimator::new(BatteryChemistry::LiPo),
+            TrackerConfig::default(),
+        );
+        assert_eq!(
+            BleBatteryLevelStatus::from_tracker(&tracker, Fixed::ZERO),
+            None
+        );
+
+        tracker
+            .update(Fixed::from_num(3.4), Fixed::ZERO, Fixed::ONE)
+            .unwrap();
+        let status = BleBatteryLevelStatus::from_tracker(&tracker, Fixed::from_num(-50)).unwrap();
+        assert_eq!(status.charge_state, BleChargeState::DischargingActive);
+        assert_eq!(status.charge_level, BleChargeLevel::Low);
+        assert_eq!(
+            status.battery_level,
+            Some(ble_battery_level(tracker.soc().unwrap()))
+        );
+    }
+}
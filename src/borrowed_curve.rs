@@ -0,0 +1,167 @@
+//! Zero-copy curve borrowing its points
+//!
+//! [`Curve::new()`](crate::Curve::new) copies the points into a 32-slot
+//! array, so a 3-point custom curve carries the dead space of 29 more.
+//! [`BorrowedCurve`] keeps a reference to points that already live
+//! elsewhere, typically a `static` array in flash, and is built in O(1).
+
+#[cfg(not(feature = "no-float"))]
+use crate::curve::volts_from_f32;
+use crate::curve::{interpolate_points, validate_points};
+use crate::{CurvePoint, Error, ErrorDetail, Fixed, SocEstimate};
+
+/// A voltage-to-SOC curve over a borrowed slice of points
+///
+/// The points must be ordered by strictly increasing voltage, as for
+/// [`Curve`](crate::Curve); use [`validate_sorted()`](Self::validate_sorted)
+/// to check them. Interpolation behaves like
+/// [`Curve::voltage_to_soc_fixed()`](crate::Curve::voltage_to_soc_fixed).
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{BorrowedCurve, CurvePoint, Fixed};
+///
+/// static POINTS: [CurvePoint; 3] = [
+///     CurvePoint::new(3.0, 0.0),
+///     CurvePoint::new(3.5, 50.0),
+///     CurvePoint::new(4.0, 100.0),
+/// ];
+/// const CURVE: BorrowedCurve<'static> = BorrowedCurve::new(&POINTS);
+///
+/// assert_eq!(core::mem::size_of_val(&CURVE), core::mem::size_of::<&[CurvePoint]>());
+/// assert_eq!(CURVE.voltage_to_soc_fixed(Fixed::from_num(3.75)).unwrap(), Fixed::from_num(75));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BorrowedCurve<'a> {
+    points: &'a [CurvePoint],
+}
+
+impl<'a> BorrowedCurve<'a> {
+    /// Creates a curve over `points` without copying them
+    #[inline]
+    pub const fn new(points: &'a [CurvePoint]) -> Self {
+        Self { points }
+    }
+
+    /// Returns the borrowed points
+    #[inline]
+    pub const fn points(&self) -> &'a [CurvePoint] {
+        self.points
+    }
+
+    /// Returns the number of points
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Returns `true` if the curve has no points
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Checks that the curve has at least 2 points with strictly
+    /// increasing voltages
+    #[inline]
+    pub fn validate_sorted(&self) -> Result<(), Error> {
+        self.validate_sorted_detail().map_err(Error::from)
+    }
+
+    /// Like [`validate_sorted()`](Self::validate_sorted), but reports the
+    /// index of the first point that breaks the voltage order
+    #[inline]
+    pub fn validate_sorted_detail(&self) -> Result<(), ErrorDetail> {
+        validate_points(self.points)
+    }
+
+    /// Converts a voltage to SOC percentage using fixed-point arithmetic
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(soc)` - SOC percentage as fixed-point
+    /// * `Err(Error::InvalidCurve)` - Curve has fewer than 2 points
+    /// * `Err(Error::NumericalError)` - Points out of order
+    #[inline]
+    pub fn voltage_to_soc_fixed(&self, voltage: Fixed) -> Result<Fixed, Error> {
+        interpolate_points(self.points, voltage)
+    }
+
+    /// Converts a voltage in volts to SOC percentage
+    ///
+    /// Returns [`Error::NonFiniteInput`] for a NaN or infinite voltage.
+    #[cfg(not(feature = "no-float"))]
+    pub fn voltage_to_soc(&self, voltage: f32) -> Result<f32, Error> {
+        self.voltage_to_soc_fixed(volts_from_f32(voltage)?)
+            .map(|soc| soc.to_num::<f32>())
+    }
+
+    /// Returns the voltage of the first and last point as fixed-point values
+    ///
+    /// Both are zero for an empty curve.
+    pub fn voltage_range_fixed(&self) -> (Fixed, Fixed) {
+        match (self.points.first(), self.points.last()) {
+            (Some(first), Some(last)) => (first.voltage_fixed(), last.voltage_fixed()),
+            _ => (Fixed::ZERO, Fixed::ZERO),
+        }
+    }
+}
+
+impl<'a> From<&'a [CurvePoint]> for BorrowedCurve<'a> {
+    #[inline]
+    fn from(points: &'a [CurvePoint]) -> Self {
+        Self::new(points)
+    }
+}
+
+impl SocEstimate for BorrowedCurve<'_> {
+    #[inline]
+    fn estimate_soc_fixed(&self, voltage: Fixed) -> Result<Fixed, Error> {
+        self.voltage_to_soc_fixed(voltage)
+    }
+
+    #[inline]
+    fn voltage_range_fixed(&self) -> (Fixed, Fixed) {
+        BorrowedCurve::voltage_range_fixed(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BatteryChemistry, Curve};
+
+    #[test]
+    fn test_borrowed_curve_matches_curve() {
+        let curve = Curve::for_chemistry(BatteryChemistry::LiIon);
+        let borrowed = BorrowedCurve::new(curve.points());
+
+        for mv in (2_400..=4_300).step_by(7) {
+            let voltage = Fixed::from_num(mv) / Fixed::from_num(1000);
+            assert_eq!(
+                borrowed.voltage_to_soc_fixed(voltage),
+                curve.voltage_to_soc_fixed(voltage)
+            );
+        }
+        assert_eq!(borrowed.voltage_range_fixed(), curve.voltage_range_fixed());
+        assert_eq!(borrowed.validate_sorted(), Ok(()));
+    }
+
+    #[test]
+    fn test_borrowed_curve_invalid() {
+        let empty = BorrowedCurve::new(&[]);
+        assert!(empty.is_empty());
+        assert_eq!(
+            empty.voltage_to_soc_fixed(Fixed::ONE),
+            Err(Error::InvalidCurve)
+        );
+        assert_eq!(empty.voltage_range_fixed(), (Fixed::ZERO, Fixed::ZERO));
+
+        let points = [CurvePoint::new(4.0, 100.0), CurvePoint::new(3.0, 0.0)];
+        assert_eq!(
+            BorrowedCurve::from(&points[..]).validate_sorted_detail(),
+            Err(Error::InvalidCurve.at_curve_point(1))
+        );
+    }
+}
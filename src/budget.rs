@@ -0,0 +1,98 @@
+//! RAM budgets of the main types, checked at compile time
+//!
+//! Small MCUs often have a few kilobytes of RAM, so a field added to
+//! [`SocTracker`] or a wider array in [`Curve`] can quietly push firmware
+//! over its limit. The constants in this module are the documented upper
+//! bounds of the type sizes; the crate fails to compile if a type outgrows
+//! its budget. Firmware can check its own limits the same way with
+//! [`assert_size_within!`](crate::assert_size_within) or [`fits()`].
+//!
+//! The budgets hold on 64-bit hosts, where the types are largest; on 32-bit
+//! targets they are a few bytes smaller, and `no-float` builds, which drop
+//! the `f32` copy of each estimator configuration, 32 bytes. Estimators and trackers are passed
+//! by value, so the budgets also bound the stack space of each copy. Call
+//! depth itself cannot be checked at compile time.
+//!
+//! # Examples
+//!
+//! ```
+//! use battery_estimator::{assert_size_within, budget, PackEstimator, SocTracker};
+//!
+//! // This firmware reserves 512 bytes for gauging
+//! assert_size_within!(SocTracker, 512);
+//! assert_size_within!(PackEstimator<4>, budget::pack_estimator(4));
+//!
+//! assert!(budget::fits::<SocTracker>(budget::SOC_TRACKER));
+//! ```
+
+use crate::{Curve, EstimatorConfig, PackEstimator, SocEstimator, SocHistory, SocTracker};
+
+/// Fails compilation if `size_of::<$type>()` exceeds `$budget` bytes
+///
+/// Expands to a `const` item, so it can be used at module level or inside
+/// a function body.
+///
+/// ```compile_fail
+/// battery_estimator::assert_size_within!(battery_estimator::Curve, 64);
+/// ```
+#[macro_export]
+macro_rules! assert_size_within {
+    ($type:ty, $budget:expr) => {
+        const _: () = ::core::assert!(
+            ::core::mem::size_of::<$type>() <= $budget,
+            ::core::concat!(::core::stringify!($type), " exceeds its RAM budget"),
+        );
+    };
+}
+
+/// Bytes of a [`Curve`]
+pub const CURVE: usize = 268;
+
+/// Bytes of an [`EstimatorConfig`]
+pub const ESTIMATOR_CONFIG: usize = 56;
+
+/// Bytes of a [`SocEstimator`]
+pub const SOC_ESTIMATOR: usize = 104;
+
+/// Bytes of a [`SocHistory`]
+pub const SOC_HISTORY: usize = 264;
+
+/// Bytes of a [`SocTracker`], including its optional history
+pub const SOC_TRACKER: usize = 504;
+
+/// Bytes of a [`PackEstimator`] for `cells` series cell groups
+///
+/// A fixed part plus the state of health, baseline and usage counters of
+/// each cell group.
+pub const fn pack_estimator(cells: usize) -> usize {
+    136 + 24 * cells
+}
+
+/// Returns `true` if `T` takes at most `budget` bytes
+#[inline]
+pub const fn fits<T>(budget: usize) -> bool {
+    core::mem::size_of::<T>() <= budget
+}
+
+assert_size_within!(Curve, CURVE);
+assert_size_within!(EstimatorConfig, ESTIMATOR_CONFIG);
+assert_size_within!(SocEstimator, SOC_ESTIMATOR);
+assert_size_within!(SocHistory, SOC_HISTORY);
+assert_size_within!(SocTracker, SOC_TRACKER);
+assert_size_within!(PackEstimator<1>, pack_estimator(1));
+assert_size_within!(PackEstimator<16>, pack_estimator(16));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budgets() {
+        assert!(fits::<SocTracker>(SOC_TRACKER));
+        assert!(!fits::<SocTracker>(SOC_ESTIMATOR));
+        assert!(fits::<PackEstimator<3>>(pack_estimator(3)));
+        // Exact with floats only
+        #[cfg(not(feature = "no-float"))]
+        assert!(!fits::<PackEstimator<4>>(pack_estimator(3)));
+    }
+}
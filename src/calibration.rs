@@ -0,0 +1,282 @@
+//! Per-device voltage calibration
+//!
+//! Resistor tolerances and ADC gain errors shift every device's voltage
+//! reading by a few percent, which moves the SOC by far more on a flat
+//! curve. A [`Calibration`] learns a gain and offset correction from
+//! reference measurements (typically taken once at manufacturing against
+//! a known-good meter), is stored in a small checksummed blob in flash,
+//! and is applied by the estimator before every curve lookup via
+//! [`EstimatorConfig::with_calibration()`].
+
+use crate::persist::{crc8, get_fixed, put_fixed};
+use crate::{Error, EstimatorConfig, Fixed};
+
+/// Serialized format version
+const CALIBRATION_VERSION: u8 = 1;
+
+/// Learned gain and offset correction of voltage readings
+///
+/// Corrected voltages are `measured * gain + offset`. Measured and
+/// reference voltages are in the units the estimator receives, i.e.
+/// before its divider ratio is applied.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{BatteryChemistry, Calibration, EstimatorConfig, Fixed, SocEstimator};
+///
+/// // At manufacturing: the device reads 3.02 V and 4.03 V where the
+/// // reference meter shows 3.00 V and 4.00 V
+/// let calibration = Calibration::from_two_points(
+///     (Fixed::from_num(3.02), Fixed::from_num(3.0)),
+///     (Fixed::from_num(4.03), Fixed::from_num(4.0)),
+/// )
+/// .unwrap();
+/// let blob = calibration.to_bytes();
+///
+/// // At boot
+/// let calibration = Calibration::from_bytes(&blob).unwrap_or_default();
+/// let config = EstimatorConfig::default().with_calibration(calibration);
+/// let estimator = SocEstimator::with_config(BatteryChemistry::LiPo, config);
+///
+/// let corrected = calibration.apply(Fixed::from_num(4.03));
+/// assert!((corrected - Fixed::from_num(4.0)).abs() < Fixed::from_num(0.001));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Calibration {
+    /// Multiplicative correction
+    pub gain: Fixed,
+    /// Additive correction in volts
+    pub offset: Fixed,
+}
+
+impl Calibration {
+    /// Size of the serialized calibration in bytes
+    pub const SERIALIZED_SIZE: usize = 10;
+
+    /// Calibration that leaves readings unchanged
+    #[inline]
+    pub const fn identity() -> Self {
+        Self {
+            gain: Fixed::ONE,
+            offset: Fixed::ZERO,
+        }
+    }
+
+    /// Learns a gain correction from a single reference measurement
+    ///
+    /// Suited to ADC reference or divider errors, which scale readings
+    /// without shifting them.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(calibration)` - Gain `reference / measured`, no offset
+    /// * `Err(Error::InvalidConfig)` - A voltage is not positive
+    pub fn from_reference(measured: Fixed, reference: Fixed) -> Result<Self, Error> {
+        if measured <= Fixed::ZERO || reference <= Fixed::ZERO {
+            return Err(Error::InvalidConfig);
+        }
+        let gain = reference
+            .checked_div(measured)
+            .ok_or(Error::InvalidConfig)?;
+        Ok(Self {
+            gain,
+            offset: Fixed::ZERO,
+        })
+    }
+
+    /// Learns gain and offset from two `(measured, reference)` pairs
+    ///
+    /// Take the points near both ends of the battery's voltage range for
+    /// the best correction in between.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(calibration)` - Maps both measured voltages onto their references
+    /// * `Err(Error::InvalidConfig)` - The measured voltages are equal, or
+    ///   the resulting gain is not positive or out of range
+    pub fn from_two_points(low: (Fixed, Fixed), high: (Fixed, Fixed)) -> Result<Self, Error> {
+        let (measured_low, reference_low) = low;
+        let (measured_high, reference_high) = high;
+        let measured_span = measured_high
+            .checked_sub(measured_low)
+            .ok_or(Error::InvalidConfig)?;
+        let reference_span = reference_high
+            .checked_sub(reference_low)
+            .ok_or(Error::InvalidConfig)?;
+        if measured_span == Fixed::ZERO {
+            return Err(Error::InvalidConfig);
+        }
+
+        let gain = reference_span
+            .checked_div(measured_span)
+            .ok_or(Error::InvalidConfig)?;
+        if gain <= Fixed::ZERO {
+            return Err(Error::InvalidConfig);
+        }
+        let offset = measured_low
+            .checked_mul(gain)
+            .and_then(|scaled| reference_low.checked_sub(scaled))
+            .ok_or(Error::InvalidConfig)?;
+        Ok(Self { gain, offset })
+    }
+
+    /// Corrects a voltage reading
+    #[inline]
+    pub fn apply(&self, measured: Fixed) -> Fixed {
+        measured
+            .saturating_mul(self.gain)
+            .saturating_add(self.offset)
+    }
+
+    /// Serialize the calibration for non-volatile storage
+    ///
+    /// Layout (little-endian, fixed-point values as raw I16F16 bits):
+    ///
+    /// | Offset | Size | Content |
+    /// |--------|------|---------|
+    /// | 0 | 1 | Format version |
+    /// | 1 | 4 | Gain |
+    /// | 5 | 4 | Offset in volts |
+    /// | 9 | 1 | CRC-8 (poly 0x07) over all preceding bytes |
+    pub fn to_bytes(&self) -> [u8; Self::SERIALIZED_SIZE] {
+        let mut blob = [0u8; Self::SERIALIZED_SIZE];
+        blob[0] = CALIBRATION_VERSION;
+        put_fixed(&mut blob, 1, self.gain);
+        put_fixed(&mut blob, 5, self.offset);
+
+        let last = Self::SERIALIZED_SIZE - 1;
+        blob[last] = crc8(&blob[..last]);
+        blob
+    }
+
+    /// Deserialize a calibration produced by [`to_bytes()`](Self::to_bytes)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(calibration)` - The stored calibration
+    /// * `Err(Error::InvalidState)` - Checksum, version, or gain check
+    ///   failed (e.g., an uncalibrated device with erased flash)
+    pub fn from_bytes(blob: &[u8; Self::SERIALIZED_SIZE]) -> Result<Self, Error> {
+        let last = Self::SERIALIZED_SIZE - 1;
+        if blob[0] != CALIBRATION_VERSION || blob[last] != crc8(&blob[..last]) {
+            return Err(Error::InvalidState);
+        }
+        let gain = get_fixed(blob, 1);
+        if gain <= Fixed::ZERO {
+            return Err(Error::InvalidState);
+        }
+        Ok(Self {
+            gain,
+            offset: get_fixed(blob, 5),
+        })
+    }
+}
+
+impl Default for Calibration {
+    #[inline]
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl EstimatorConfig {
+    /// Apply a per-device calibration to all voltages before curve lookup
+    ///
+    /// Sets [`input_gain`](Self::input_gain) and
+    /// [`input_offset`](Self::input_offset).
+    #[inline]
+    pub fn with_calibration(self, calibration: Calibration) -> Self {
+        self.with_input_gain(calibration.gain)
+            .with_input_offset(calibration.offset)
+    }
+
+    /// The calibration currently applied
+    #[inline]
+    pub fn calibration(&self) -> Calibration {
+        Calibration {
+            gain: self.input_gain,
+            offset: self.input_offset,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BatteryChemistry, SocEstimator};
+
+    #[test]
+    fn test_two_point_calibration() {
+        let calibration = Calibration::from_two_points(
+            (Fixed::from_num(1.5), Fixed::from_num(1.6)),
+            (Fixed::from_num(2.0), Fixed::from_num(2.2)),
+        )
+        .unwrap();
+        let tolerance = Fixed::from_num(0.0001);
+        assert!((calibration.gain - Fixed::from_num(1.2)).abs() < tolerance);
+        assert!((calibration.offset - Fixed::from_num(-0.2)).abs() < tolerance);
+        let corrected = calibration.apply(Fixed::from_num(1.75));
+        assert!((corrected - Fixed::from_num(1.9)).abs() < tolerance);
+
+        let same = (Fixed::from_num(3), Fixed::from_num(3));
+        assert_eq!(
+            Calibration::from_two_points(same, same),
+            Err(Error::InvalidConfig)
+        );
+        let inverted = (Fixed::from_num(4), Fixed::from_num(2));
+        assert_eq!(
+            Calibration::from_two_points(same, inverted),
+            Err(Error::InvalidConfig)
+        );
+    }
+
+    #[test]
+    fn test_reference_calibration() {
+        let calibration =
+            Calibration::from_reference(Fixed::from_num(4), Fixed::from_num(4.2)).unwrap();
+        assert!((calibration.gain - Fixed::from_num(1.05)).abs() < Fixed::from_num(0.0001));
+        assert_eq!(calibration.offset, Fixed::ZERO);
+        assert_eq!(
+            Calibration::from_reference(Fixed::ZERO, Fixed::ONE),
+            Err(Error::InvalidConfig)
+        );
+    }
+
+    #[test]
+    fn test_calibration_bytes() {
+        let calibration = Calibration {
+            gain: Fixed::from_num(0.98),
+            offset: Fixed::from_num(0.015),
+        };
+        let blob = calibration.to_bytes();
+        assert_eq!(Calibration::from_bytes(&blob), Ok(calibration));
+
+        let mut corrupted = blob;
+        corrupted[3] ^= 0x10;
+        assert_eq!(
+            Calibration::from_bytes(&corrupted),
+            Err(Error::InvalidState)
+        );
+        assert_eq!(
+            Calibration::from_bytes(&[0xFF; Calibration::SERIALIZED_SIZE]),
+            Err(Error::InvalidState)
+        );
+    }
+
+    #[test]
+    fn test_calibration_applied_before_lookup() {
+        // The device reads 2% low
+        let calibration =
+            Calibration::from_reference(Fixed::from_num(3.675), Fixed::from_num(3.75)).unwrap();
+        let config = EstimatorConfig::default().with_calibration(calibration);
+        assert_eq!(config.calibration(), calibration);
+
+        let estimator = SocEstimator::with_config(BatteryChemistry::LiPo, config);
+        let soc = estimator
+            .estimate_soc_fixed(Fixed::from_num(3.675))
+            .unwrap();
+        assert!((soc - Fixed::from_num(60)).abs() < Fixed::from_num(0.5));
+    }
+}
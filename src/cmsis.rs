@@ -0,0 +1,276 @@
+//! CMSIS-DSP kernels for the slice estimation path
+//!
+//! Enabled with the `cmsis-dsp` feature on Arm targets; the feature has no
+//! effect elsewhere. The element-wise steps of
+//! [`SocEstimator::estimate_soc_slice()`](crate::SocEstimator::estimate_soc_slice)
+//! (measurement scaling and clamping) run through the q31 kernels of the
+//! CMSIS-DSP library, treating I16F16 bits as q31 values. The library is
+//! not built by this crate: link the variant matching the core, e.g.
+//! `-l static=arm_cortexM4lf_math`.
+//!
+//! The curve lookup stays scalar (CMSIS-DSP only interpolates uniformly
+//! spaced tables), as does the [`KalmanFilter`](crate::KalmanFilter), whose
+//! recursive gain update has no CMSIS-DSP kernel.
+//!
+//! This is the only module with `unsafe` code, limited to the FFI calls.
+//! Off Arm, the module is compiled for the tests only, with a portable
+//! reference of the three kernels behind the same functions, so the
+//! rounding of the CMSIS-DSP path is tested on the host.
+
+#![allow(unsafe_code)]
+
+use crate::Fixed;
+
+#[cfg(target_arch = "arm")]
+unsafe extern "C" {
+    fn arm_scale_q31(src: *const i32, scale_fract: i32, shift: i8, dst: *mut i32, block_size: u32);
+    fn arm_offset_q31(src: *const i32, offset: i32, dst: *mut i32, block_size: u32);
+    fn arm_clip_q31(src: *const i32, dst: *mut i32, low: i32, high: i32, num_samples: u32);
+}
+
+/// Writes `src[i] * factor` into `dst`, saturating
+///
+/// `arm_scale_q31` keeps the upper 32 bits of the product before shifting,
+/// so the result can be below the [`saturating_mul()`](Fixed::saturating_mul)
+/// of the scalar path: by less than 4 LSBs for factors under 2, 8 under 4,
+/// and so on. That can place a sample on a millivolt boundary 1 mV lower.
+/// `dst` must be at least as long as `src`.
+pub(crate) fn scale(src: &[Fixed], factor: Fixed, dst: &mut [Fixed]) {
+    let dst = &mut dst[..src.len()];
+    let (fract, shift) = scale_params(factor);
+
+    #[cfg(target_arch = "arm")]
+    // SAFETY: `Fixed` is a `repr(transparent)` wrapper around `i32`, so both
+    // pointers address `i32` buffers; `dst` was cut to `src.len()` above, so
+    // the kernel reads and writes `src.len()` elements in bounds; the shared
+    // and mutable borrows cannot overlap
+    unsafe {
+        arm_scale_q31(
+            src.as_ptr().cast(),
+            fract,
+            shift,
+            dst.as_mut_ptr().cast(),
+            src.len() as u32,
+        );
+    }
+
+    #[cfg(not(target_arch = "arm"))]
+    for (out, &value) in dst.iter_mut().zip(src) {
+        *out = Fixed::from_bits(reference::scale_q31(value.to_bits(), fract, shift));
+    }
+}
+
+/// Multiplies `values` by `factor` in place, saturating
+///
+/// Rounds like [`scale()`].
+pub(crate) fn scale_in_place(values: &mut [Fixed], factor: Fixed) {
+    let (fract, shift) = scale_params(factor);
+
+    #[cfg(target_arch = "arm")]
+    {
+        let ptr = values.as_mut_ptr().cast();
+        // SAFETY: `Fixed` is a `repr(transparent)` wrapper around `i32`; the
+        // kernel reads and writes `values.len()` elements of the exclusively
+        // borrowed slice, and CMSIS-DSP documents in-place operation
+        // (`src == dst`) as supported
+        unsafe { arm_scale_q31(ptr, fract, shift, ptr, values.len() as u32) }
+    }
+
+    #[cfg(not(target_arch = "arm"))]
+    for value in values.iter_mut() {
+        *value = Fixed::from_bits(reference::scale_q31(value.to_bits(), fract, shift));
+    }
+}
+
+/// Adds `offset` to `values` in place, saturating
+pub(crate) fn offset_in_place(values: &mut [Fixed], offset: Fixed) {
+    #[cfg(target_arch = "arm")]
+    {
+        let ptr = values.as_mut_ptr().cast();
+        // SAFETY: `Fixed` is a `repr(transparent)` wrapper around `i32`; the
+        // kernel reads and writes `values.len()` elements of the exclusively
+        // borrowed slice, and CMSIS-DSP documents in-place operation
+        // (`src == dst`) as supported
+        unsafe { arm_offset_q31(ptr, offset.to_bits(), ptr, values.len() as u32) }
+    }
+
+    #[cfg(not(target_arch = "arm"))]
+    for value in values.iter_mut() {
+        *value = Fixed::from_bits(reference::offset_q31(value.to_bits(), offset.to_bits()));
+    }
+}
+
+/// Clamps `values` to `low..=high` in place
+pub(crate) fn clip_in_place(values: &mut [Fixed], low: Fixed, high: Fixed) {
+    #[cfg(target_arch = "arm")]
+    {
+        let ptr = values.as_mut_ptr().cast();
+        // SAFETY: `Fixed` is a `repr(transparent)` wrapper around `i32`; the
+        // kernel reads and writes `values.len()` elements of the exclusively
+        // borrowed slice, and CMSIS-DSP documents in-place operation
+        // (`src == dst`) as supported
+        unsafe {
+            arm_clip_q31(ptr, ptr, low.to_bits(), high.to_bits(), values.len() as u32);
+        }
+    }
+
+    #[cfg(not(target_arch = "arm"))]
+    for value in values.iter_mut() {
+        *value = Fixed::from_bits(reference::clip_q31(
+            value.to_bits(),
+            low.to_bits(),
+            high.to_bits(),
+        ));
+    }
+}
+
+/// Splits an I16F16 factor into the q31 fraction and shift of `arm_scale_q31`
+///
+/// The kernel computes `((x * fract) >> 32) << (shift + 1)`. Shifting the
+/// factor bits left by `k` and the result left by `16 - k` multiplies by
+/// the factor; `k` is as large as the factor allows, at most 15.
+const fn scale_params(factor: Fixed) -> (i32, i8) {
+    let bits = factor.to_bits();
+    let headroom = if bits < 0 {
+        (!bits).leading_zeros()
+    } else {
+        bits.leading_zeros()
+    };
+    let k = if headroom > 16 { 15 } else { headroom - 1 };
+    (bits << k, (15 - k) as i8)
+}
+
+/// Per-element reference of the CMSIS-DSP q31 kernels
+///
+/// Follows the scalar (non-Helium) code of CMSIS-DSP 1.x bit for bit.
+#[cfg(not(target_arch = "arm"))]
+mod reference {
+    /// `arm_scale_q31`: upper 32 bits of the product, then a saturating shift
+    pub(super) fn scale_q31(value: i32, fract: i32, shift: i8) -> i32 {
+        let product = ((i64::from(value) * i64::from(fract)) >> 32) as i32;
+        let shift = i32::from(shift) + 1;
+        if shift >= 0 {
+            let out = product.wrapping_shl(shift as u32);
+            if product != out >> shift {
+                i32::MAX ^ (product >> 31)
+            } else {
+                out
+            }
+        } else {
+            product >> -shift
+        }
+    }
+
+    /// `arm_offset_q31`: saturating addition (`__QADD`)
+    pub(super) fn offset_q31(value: i32, offset: i32) -> i32 {
+        value.saturating_add(offset)
+    }
+
+    /// `arm_clip_q31`
+    pub(super) fn clip_q31(value: i32, low: i32, high: i32) -> i32 {
+        if value > high {
+            high
+        } else if value < low {
+            low
+        } else {
+            value
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FACTORS: [f64; 8] = [1.0, 0.5, 1.5, 1.999, 2.0, 3.3, 11.0, -0.75];
+
+    fn samples() -> [Fixed; 9] {
+        [
+            Fixed::ZERO,
+            Fixed::from_num(0.001),
+            Fixed::from_num(3.7),
+            Fixed::from_num(4.2),
+            Fixed::from_num(-2.5),
+            Fixed::from_num(100),
+            Fixed::MAX,
+            Fixed::MIN,
+            Fixed::from_bits(1),
+        ]
+    }
+
+    #[test]
+    fn test_scale_params_reconstruct_factor() {
+        for factor in FACTORS {
+            let factor = Fixed::from_num(factor);
+            let (fract, shift) = scale_params(factor);
+            let rebuilt = i64::from(fract) << (i32::from(shift) + 1);
+            assert_eq!(rebuilt, i64::from(factor.to_bits()) << 16);
+        }
+    }
+
+    #[test]
+    fn test_scale_within_documented_bound() {
+        for factor in FACTORS {
+            let factor = Fixed::from_num(factor);
+            // Less than 4 LSBs under 2, doubling per power of two above
+            let bound = 4i64 << factor.abs().to_num::<u32>().checked_ilog2().unwrap_or(0);
+            let src = samples();
+            let mut dst = [Fixed::ZERO; 9];
+            scale(&src, factor, &mut dst);
+            for (&value, &scaled) in src.iter().zip(&dst) {
+                let expected = value.saturating_mul(factor);
+                let diff = i64::from(expected.to_bits()) - i64::from(scaled.to_bits());
+                assert!(
+                    (0..bound).contains(&diff) || expected == scaled,
+                    "{value} * {factor}: {scaled} vs {expected}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_scale_saturates() {
+        let mut values = [Fixed::MAX, Fixed::MIN, Fixed::from_num(20000)];
+        scale_in_place(&mut values, Fixed::from_num(4));
+        assert_eq!(values, [Fixed::MAX, Fixed::MIN, Fixed::MAX]);
+    }
+
+    #[test]
+    fn test_scale_in_place_matches_scale() {
+        for factor in FACTORS {
+            let factor = Fixed::from_num(factor);
+            let src = samples();
+            let mut dst = [Fixed::ZERO; 9];
+            scale(&src, factor, &mut dst);
+            let mut values = src;
+            scale_in_place(&mut values, factor);
+            assert_eq!(values, dst);
+        }
+    }
+
+    #[test]
+    fn test_offset_matches_saturating_add() {
+        for offset in [
+            Fixed::ZERO,
+            Fixed::from_num(0.05),
+            Fixed::from_num(-1.25),
+            Fixed::MAX,
+        ] {
+            let mut values = samples();
+            offset_in_place(&mut values, offset);
+            for (&value, &shifted) in samples().iter().zip(&values) {
+                assert_eq!(shifted, value.saturating_add(offset));
+            }
+        }
+    }
+
+    #[test]
+    fn test_clip_matches_clamp() {
+        let (low, high) = (Fixed::ZERO, Fixed::from_num(100));
+        let mut values = samples();
+        clip_in_place(&mut values, low, high);
+        for (&value, &clipped) in samples().iter().zip(&values) {
+            assert_eq!(clipped, value.clamp(low, high));
+        }
+    }
+}
@@ -0,0 +1,413 @@
+//! Delta-encoded curve storage for flash-constrained firmware
+//!
+//! A [`Curve`] always reserves room for 32 points plus their precomputed
+//! slopes, which adds up quickly when a product embeds one curve per
+//! chemistry, temperature band or cell vendor. [`CompactCurve`] stores only
+//! the first point and the differences to each following point, one byte
+//! per voltage and SOC step whenever the step fits.
+//!
+//! # Encoding
+//!
+//! After the first point, each point is stored as a voltage byte followed by
+//! an SOC byte:
+//!
+//! - Voltage: the increase over the previous point in mV (1-255). A `0` byte
+//!   is followed by the absolute voltage as a little-endian `u16`.
+//! - SOC: the increase over the previous point in tenths of a percent
+//!   (0-254). A `255` byte is followed by the absolute SOC as a
+//!   little-endian `u16`, which also covers falling SOC.
+//!
+//! The built-in LiPo curve takes 18 bytes this way.
+
+#[cfg(not(feature = "no-float"))]
+use crate::curve::volts_from_f32;
+use crate::curve::{interpolate, order_error, segment_slope, truncate_mv};
+use crate::instrument;
+use crate::{Curve, CurvePoint, Error, Fixed, SocEstimate};
+
+/// Voltage byte announcing an absolute `u16` voltage
+const VOLTAGE_ESCAPE: u8 = 0;
+/// SOC byte announcing an absolute `u16` SOC
+const SOC_ESCAPE: u8 = u8::MAX;
+
+/// Returns the number of bytes [`CompactCurve`] needs for `points`
+///
+/// Use it to size the curve at compile time. Points that would be rejected
+/// by [`CompactCurve::from_points()`] are counted like any other.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{compact_curve_len, CompactCurve, CurvePoint};
+///
+/// const POINTS: [CurvePoint; 3] = [
+///     CurvePoint::new(3.0, 0.0),
+///     CurvePoint::new(3.5, 50.0),
+///     CurvePoint::new(3.6, 60.0),
+/// ];
+/// // 3.0 V -> 3.5 V (+50%) needs absolute values, 3.5 V -> 3.6 V fits in bytes
+/// assert_eq!(compact_curve_len(&POINTS), 8);
+///
+/// const CURVE: CompactCurve<{ compact_curve_len(&POINTS) }> =
+///     match CompactCurve::from_points(&POINTS) {
+///         Ok(curve) => curve,
+///         Err(_) => panic!("invalid curve"),
+///     };
+/// assert_eq!(CURVE.len(), 3);
+/// ```
+pub const fn compact_curve_len(points: &[CurvePoint]) -> usize {
+    let mut len = 0usize;
+    let mut i = 1;
+    while i < points.len() {
+        let (voltage, soc) = deltas(points[i - 1], points[i]);
+        let bytes = if voltage.is_some() { 1 } else { 3 } + if soc.is_some() { 1 } else { 3 };
+        len = len.saturating_add(bytes);
+        i += 1;
+    }
+    len
+}
+
+/// Byte-sized voltage and SOC steps from `prev` to `curr`, if they fit
+const fn deltas(prev: CurvePoint, curr: CurvePoint) -> (Option<u8>, Option<u8>) {
+    let voltage = curr.voltage_mv.wrapping_sub(prev.voltage_mv);
+    let soc = curr.soc_tenth.wrapping_sub(prev.soc_tenth);
+    (
+        if curr.voltage_mv > prev.voltage_mv && voltage <= u8::MAX as u16 {
+            Some(voltage as u8)
+        } else {
+            None
+        },
+        if curr.soc_tenth >= prev.soc_tenth && soc < SOC_ESCAPE as u16 {
+            Some(soc as u8)
+        } else {
+            None
+        },
+    )
+}
+
+/// A voltage-to-SOC curve delta-encoded into `N` bytes
+///
+/// Built once from points ordered by strictly increasing voltage, usually
+/// in a `const` with `N` computed by [`compact_curve_len()`]. Lookups decode
+/// the points one by one, so they take O(n) instead of the O(log n) of
+/// [`Curve`], and return exactly the same SOC as
+/// [`Curve::voltage_to_soc_fixed()`].
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{BatteryChemistry, CompactCurve, Curve, Fixed};
+///
+/// let lipo = Curve::for_chemistry(BatteryChemistry::LiPo);
+/// let compact = CompactCurve::<18>::from_curve(lipo).unwrap();
+/// assert!(core::mem::size_of_val(&compact) < core::mem::size_of::<Curve>() / 8);
+///
+/// let voltage = Fixed::from_num(3.75);
+/// assert_eq!(compact.voltage_to_soc_fixed(voltage), lipo.voltage_to_soc_fixed(voltage));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CompactCurve<const N: usize> {
+    /// First (lowest voltage) point
+    first: CurvePoint,
+    /// Last (highest voltage) point, cached for O(1) boundary checks
+    last: CurvePoint,
+    /// Number of points
+    len: u16,
+    /// Number of bytes in use
+    used: u16,
+    /// Encoded steps of the points after the first, zero past `used`
+    bytes: [u8; N],
+}
+
+impl<const N: usize> CompactCurve<N> {
+    /// Encodes points ordered by strictly increasing voltage
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(curve)` - The encoded curve
+    /// * `Err(Error::InvalidCurve)` - Fewer than 2 points, decreasing
+    ///   voltages, more than `N` bytes needed (see [`compact_curve_len()`])
+    ///   or more than 65535 points
+    /// * `Err(Error::DuplicateVoltage)` - Two consecutive points share a voltage
+    pub const fn from_points(points: &[CurvePoint]) -> Result<Self, Error> {
+        if points.len() < 2 || points.len() > u16::MAX as usize {
+            return Err(Error::InvalidCurve);
+        }
+        if compact_curve_len(points) > N || N > u16::MAX as usize {
+            return Err(Error::InvalidCurve);
+        }
+
+        let mut bytes = [0u8; N];
+        let mut used = 0;
+        let mut i = 1;
+        while i < points.len() {
+            let (prev, curr) = (points[i - 1], points[i]);
+            if curr.voltage_mv <= prev.voltage_mv {
+                return Err(order_error(prev, curr));
+            }
+            let (voltage, soc) = deltas(prev, curr);
+            used = match voltage {
+                Some(delta) => push(&mut bytes, used, delta),
+                None => push_u16(&mut bytes, used, VOLTAGE_ESCAPE, curr.voltage_mv),
+            };
+            used = match soc {
+                Some(delta) => push(&mut bytes, used, delta),
+                None => push_u16(&mut bytes, used, SOC_ESCAPE, curr.soc_tenth),
+            };
+            i += 1;
+        }
+
+        Ok(Self {
+            first: points[0],
+            last: points[points.len() - 1],
+            len: points.len() as u16,
+            used: used as u16,
+            bytes,
+        })
+    }
+
+    /// Encodes the points of a [`Curve`]
+    ///
+    /// Fails like [`from_points()`](Self::from_points).
+    #[inline]
+    pub const fn from_curve(curve: &Curve) -> Result<Self, Error> {
+        Self::from_points(curve.points())
+    }
+
+    /// Decodes the curve into a [`Curve`], keeping at most its first 32 points
+    pub fn to_curve(&self) -> Curve {
+        let mut points = [CurvePoint::new(0.0, 0.0); crate::MAX_CURVE_POINTS];
+        let mut len = 0;
+        for (slot, point) in points.iter_mut().zip(self.points()) {
+            *slot = point;
+            len += 1;
+        }
+        Curve::new(&points[..len])
+    }
+
+    /// Returns an iterator decoding the points in order
+    #[inline]
+    pub fn points(&self) -> impl Iterator<Item = CurvePoint> + '_ {
+        Points {
+            bytes: self.encoded(),
+            next: Some(self.first),
+        }
+    }
+
+    /// Returns the encoded steps of the points after the first
+    #[inline]
+    pub fn encoded(&self) -> &[u8] {
+        // `used` never exceeds `N`; `split_at_checked()` keeps the
+        // lookups free of a bounds-check panic
+        match self.bytes.split_at_checked(self.used as usize) {
+            Some((used, _)) => used,
+            None => &self.bytes,
+        }
+    }
+
+    /// Returns the number of points
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    /// Always `false`: a compact curve has at least 2 points
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Converts a voltage to SOC percentage using fixed-point arithmetic
+    ///
+    /// Same result as [`Curve::voltage_to_soc_fixed()`] for the same points.
+    pub fn voltage_to_soc_fixed(&self, voltage: Fixed) -> Result<Fixed, Error> {
+        instrument::lookup();
+        let voltage_mv = truncate_mv(voltage);
+        if voltage_mv >= self.last.voltage_mv as i32 {
+            return Ok(self.last.soc_fixed());
+        }
+        if voltage_mv <= self.first.voltage_mv as i32 {
+            return Ok(self.first.soc_fixed());
+        }
+
+        let mut prev = self.first;
+        for curr in self.points().skip(1) {
+            if voltage_mv < curr.voltage_mv as i32 {
+                let offset = voltage_mv - prev.voltage_mv as i32;
+                instrument::ops(0, 1);
+                return Ok(interpolate(
+                    prev.soc_fixed(),
+                    segment_slope(prev, curr),
+                    offset,
+                ));
+            }
+            prev = curr;
+        }
+        Err(Error::NumericalError)
+    }
+
+    /// Converts a voltage in volts to SOC percentage
+    ///
+    /// Returns [`Error::NonFiniteInput`] for a NaN or infinite voltage.
+    #[cfg(not(feature = "no-float"))]
+    pub fn voltage_to_soc(&self, voltage: f32) -> Result<f32, Error> {
+        self.voltage_to_soc_fixed(volts_from_f32(voltage)?)
+            .map(|soc| soc.to_num::<f32>())
+    }
+
+    /// Returns the voltage of the first and last point as fixed-point values
+    #[inline]
+    pub fn voltage_range_fixed(&self) -> (Fixed, Fixed) {
+        (self.first.voltage_fixed(), self.last.voltage_fixed())
+    }
+}
+
+impl<const N: usize> SocEstimate for CompactCurve<N> {
+    #[inline]
+    fn estimate_soc_fixed(&self, voltage: Fixed) -> Result<Fixed, Error> {
+        self.voltage_to_soc_fixed(voltage)
+    }
+
+    #[inline]
+    fn voltage_range_fixed(&self) -> (Fixed, Fixed) {
+        CompactCurve::voltage_range_fixed(self)
+    }
+}
+
+/// Writes one byte, returning the new length
+///
+/// [`compact_curve_len()`] has already checked that every byte fits.
+const fn push<const N: usize>(bytes: &mut [u8; N], used: usize, byte: u8) -> usize {
+    if used < N {
+        bytes[used] = byte;
+    }
+    used.saturating_add(1)
+}
+
+/// Writes an escape byte and a little-endian value, returning the new length
+const fn push_u16<const N: usize>(
+    bytes: &mut [u8; N],
+    used: usize,
+    escape: u8,
+    value: u16,
+) -> usize {
+    let [low, high] = value.to_le_bytes();
+    let used = push(bytes, used, escape);
+    let used = push(bytes, used, low);
+    push(bytes, used, high)
+}
+
+/// Decoder behind [`CompactCurve::points()`]
+struct Points<'a> {
+    /// Encoded steps not yet decoded
+    bytes: &'a [u8],
+    /// Point to yield next
+    next: Option<CurvePoint>,
+}
+
+impl Points<'_> {
+    /// Decodes one field: a byte step, or an absolute value after `escape`
+    fn field(&mut self, previous: u16, escape: u8) -> Option<u16> {
+        match *self.bytes {
+            [byte, low, high, ref rest @ ..] if byte == escape => {
+                self.bytes = rest;
+                Some(u16::from_le_bytes([low, high]))
+            }
+            [byte, ref rest @ ..] if byte != escape => {
+                self.bytes = rest;
+                Some(previous.wrapping_add(byte as u16))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Iterator for Points<'_> {
+    type Item = CurvePoint;
+
+    fn next(&mut self) -> Option<CurvePoint> {
+        let point = self.next?;
+        self.next = self
+            .field(point.voltage_mv, VOLTAGE_ESCAPE)
+            .zip(self.field(point.soc_tenth, SOC_ESCAPE))
+            .map(|(voltage_mv, soc_tenth)| CurvePoint {
+                voltage_mv,
+                soc_tenth,
+            });
+        Some(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BatteryChemistry;
+
+    #[test]
+    fn test_compact_curve_matches_curve() {
+        for chemistry in [
+            BatteryChemistry::LiPo,
+            BatteryChemistry::LiFePO4,
+            BatteryChemistry::LiIon,
+            BatteryChemistry::Lipo410Full340Cutoff,
+        ] {
+            let curve = Curve::for_chemistry(chemistry);
+            let compact = CompactCurve::<64>::from_curve(curve).unwrap();
+            assert_eq!(compact.len(), curve.len());
+            assert_eq!(compact.encoded().len(), compact_curve_len(curve.points()));
+            assert_eq!(compact.to_curve(), *curve);
+
+            for mv in (2_400..=4_300).step_by(7) {
+                let voltage = Fixed::from_num(mv) / Fixed::from_num(1000);
+                assert_eq!(
+                    compact.voltage_to_soc_fixed(voltage),
+                    curve.voltage_to_soc_fixed(voltage)
+                );
+            }
+            assert_eq!(compact.voltage_range_fixed(), curve.voltage_range_fixed());
+        }
+    }
+
+    #[test]
+    fn test_compact_curve_escapes() {
+        let points = [
+            CurvePoint {
+                voltage_mv: 0,
+                soc_tenth: 1000,
+            },
+            // Large voltage step, falling SOC
+            CurvePoint {
+                voltage_mv: u16::MAX - 1,
+                soc_tenth: 0,
+            },
+            // Byte-sized steps, SOC step of exactly the escape value
+            CurvePoint {
+                voltage_mv: u16::MAX,
+                soc_tenth: 255,
+            },
+        ];
+        let curve = CompactCurve::<10>::from_points(&points).unwrap();
+        assert_eq!(
+            curve.encoded(),
+            [0, 0xFE, 0xFF, 0xFF, 0, 0, 1, 0xFF, 0xFF, 0]
+        );
+        assert!(curve.points().eq(points));
+
+        assert_eq!(
+            CompactCurve::<9>::from_points(&points),
+            Err(Error::InvalidCurve)
+        );
+        assert_eq!(
+            CompactCurve::<10>::from_points(&[points[1], points[0]]),
+            Err(Error::InvalidCurve)
+        );
+        assert_eq!(
+            CompactCurve::<10>::from_points(&points[..1]),
+            Err(Error::InvalidCurve)
+        );
+        assert_eq!(
+            CompactCurve::<10>::from_points(&[points[0], points[0]]),
+            Err(Error::DuplicateVoltage)
+        );
+    }
+}
@@ -3,7 +3,249 @@
 //! This module provides functions to adjust SOC estimates based on
 //! environmental conditions and battery age.
 
-use crate::Fixed;
+use crate::{instrument, Error, Fixed};
+
+/// Bounds of the temperature and aging compensation model
+///
+/// The free compensation functions and
+/// [`LiteEstimator`](crate::LiteEstimator) apply [`DEFAULT`](Self::DEFAULT);
+/// [`SocEstimator`](crate::SocEstimator) applies the limits of its
+/// configuration (see
+/// [`EstimatorConfig::with_compensation_limits()`](crate::EstimatorConfig::with_compensation_limits))
+/// in every precision. Use [`compensate_temperature_limited()`] and
+/// [`compensate_aging_limited()`] to compensate with other bounds directly.
+///
+/// Changes are fractions of the SOC: above the nominal temperature the
+/// linear change is scaled by `warm_scale` and capped at `max_warm_gain`,
+/// any temperature change is floored at `-max_cold_loss`, and aging
+/// removes at most `max_aging_loss`.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{compensate_temperature_limited, CompensationLimits, Fixed};
+///
+/// // Never report more than the curve SOC in warm conditions
+/// let limits = CompensationLimits {
+///     max_warm_gain: Fixed::ZERO,
+///     ..CompensationLimits::DEFAULT
+/// };
+///
+/// let soc = compensate_temperature_limited(
+///     Fixed::from_num(50),
+///     Fixed::from_num(45),
+///     Fixed::from_num(25),
+///     Fixed::from_num(0.005),
+///     &limits,
+/// );
+/// assert_eq!(soc, Ok(Fixed::from_num(50)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CompensationLimits {
+    /// Fraction of the linear change applied above the nominal temperature
+    pub warm_scale: Fixed,
+    /// Largest SOC gain from warm temperatures
+    pub max_warm_gain: Fixed,
+    /// Largest SOC loss from temperature
+    pub max_cold_loss: Fixed,
+    /// Largest SOC loss from aging
+    pub max_aging_loss: Fixed,
+}
+
+impl CompensationLimits {
+    /// Halve warm changes and cap them at +5%, floor temperature changes at
+    /// −30% and aging at −50%
+    pub const DEFAULT: Self = Self {
+        warm_scale: Fixed::from_bits(1 << 15),     // 0.5
+        max_warm_gain: Fixed::from_bits(3277),     // 0.05
+        max_cold_loss: Fixed::from_bits(19661),    // 0.30
+        max_aging_loss: Fixed::from_bits(1 << 15), // 0.5
+    };
+
+    /// Bounded SOC change for a linear change of `delta * coefficient`
+    #[inline]
+    fn temperature_change<T: Precision>(&self, linear: T, warm: bool) -> T {
+        let change = if warm {
+            linear
+                .mul(T::from_fixed(self.warm_scale))
+                .min(T::from_fixed(self.max_warm_gain))
+        } else {
+            linear
+        };
+        change.max(T::ZERO.sub(T::from_fixed(self.max_cold_loss)))
+    }
+
+    /// Bounded SOC loss for a linear loss of `age_years * aging_factor`
+    #[inline]
+    fn aging_loss<T: Precision>(&self, linear: T) -> T {
+        linear.min(T::from_fixed(self.max_aging_loss))
+    }
+
+    /// Returns `true` if every bound lies in 0 to 1
+    pub(crate) fn is_valid(&self) -> bool {
+        [
+            self.warm_scale,
+            self.max_warm_gain,
+            self.max_cold_loss,
+            self.max_aging_loss,
+        ]
+        .iter()
+        .all(|bound| (Fixed::ZERO..=Fixed::ONE).contains(bound))
+    }
+}
+
+impl Default for CompensationLimits {
+    #[inline]
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Arithmetic of one compensation precision
+///
+/// The fixed-point, `f32` and `f64` paths share one model through this
+/// trait; fixed-point operations saturate.
+pub(crate) trait Precision: Copy + PartialOrd {
+    const ZERO: Self;
+    const ONE: Self;
+    /// 100% SOC
+    const HUNDRED: Self;
+
+    fn from_fixed(value: Fixed) -> Self;
+    fn is_finite(self) -> bool;
+    fn add(self, rhs: Self) -> Self;
+    fn sub(self, rhs: Self) -> Self;
+    fn mul(self, rhs: Self) -> Self;
+    fn min(self, other: Self) -> Self;
+    fn max(self, other: Self) -> Self;
+}
+
+impl Precision for Fixed {
+    const ZERO: Self = Fixed::ZERO;
+    const ONE: Self = Fixed::ONE;
+    const HUNDRED: Self = Fixed::from_bits(100 << 16);
+
+    #[inline]
+    fn from_fixed(value: Fixed) -> Self {
+        value
+    }
+    #[inline]
+    fn is_finite(self) -> bool {
+        true
+    }
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        self.saturating_add(rhs)
+    }
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        self.saturating_sub(rhs)
+    }
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        self.saturating_mul(rhs)
+    }
+    #[inline]
+    fn min(self, other: Self) -> Self {
+        Ord::min(self, other)
+    }
+    #[inline]
+    fn max(self, other: Self) -> Self {
+        Ord::max(self, other)
+    }
+}
+
+/// Implements [`Precision`] for a float type
+#[cfg(not(feature = "no-float"))]
+macro_rules! float_precision {
+    ($float:ty) => {
+        impl Precision for $float {
+            const ZERO: Self = 0.0;
+            const ONE: Self = 1.0;
+            const HUNDRED: Self = 100.0;
+
+            #[inline]
+            fn from_fixed(value: Fixed) -> Self {
+                value.to_num()
+            }
+            #[inline]
+            fn is_finite(self) -> bool {
+                <$float>::is_finite(self)
+            }
+            #[inline]
+            fn add(self, rhs: Self) -> Self {
+                self + rhs
+            }
+            #[inline]
+            fn sub(self, rhs: Self) -> Self {
+                self - rhs
+            }
+            #[inline]
+            fn mul(self, rhs: Self) -> Self {
+                self * rhs
+            }
+            #[inline]
+            fn min(self, other: Self) -> Self {
+                <$float>::min(self, other)
+            }
+            #[inline]
+            fn max(self, other: Self) -> Self {
+                <$float>::max(self, other)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "no-float"))]
+float_precision!(f32);
+#[cfg(not(feature = "no-float"))]
+float_precision!(f64);
+
+/// Temperature compensation in any precision, bounded by `limits`
+///
+/// Non-finite inputs return `soc` unchanged.
+#[inline]
+pub(crate) fn temperature_compensated<T: Precision>(
+    soc: T,
+    temperature: T,
+    nominal_temp: T,
+    coefficient: T,
+    limits: &CompensationLimits,
+) -> T {
+    if !(soc.is_finite()
+        && temperature.is_finite()
+        && nominal_temp.is_finite()
+        && coefficient.is_finite())
+    {
+        return soc;
+    }
+
+    let delta_temp = temperature.sub(nominal_temp);
+    let change = limits.temperature_change(delta_temp.mul(coefficient), delta_temp >= T::ZERO);
+    soc.mul(T::ONE.add(change))
+}
+
+/// Aging compensation in any precision, bounded by `limits`
+///
+/// Non-finite inputs and a negative age or factor return `soc` unchanged.
+#[inline]
+pub(crate) fn aging_compensated<T: Precision>(
+    soc: T,
+    age_years: T,
+    aging_factor: T,
+    limits: &CompensationLimits,
+) -> T {
+    if !(soc.is_finite() && age_years.is_finite() && aging_factor.is_finite())
+        || age_years < T::ZERO
+        || aging_factor < T::ZERO
+    {
+        return soc;
+    }
+
+    let loss = limits.aging_loss(age_years.mul(aging_factor));
+    soc.mul(T::ONE.sub(loss))
+}
 
 /// Applies temperature compensation to SOC value using fixed-point arithmetic
 ///
@@ -36,6 +278,8 @@ use crate::Fixed;
 /// - Below nominal: SOC decreases (less usable capacity due to higher internal resistance)
 /// - Above nominal: SOC increases slightly (better efficiency, capped for safety)
 /// - Compensation is bounded to prevent unrealistic values
+/// - Intermediate overflow saturates; use [`compensate_temperature_checked()`]
+///   to detect it
 ///
 /// # Examples
 ///
@@ -68,26 +312,91 @@ pub fn compensate_temperature_fixed(
     nominal_temp: Fixed,
     coefficient: Fixed,
 ) -> Fixed {
-    let delta_temp = temperature - nominal_temp;
-
-    let capacity_change = if delta_temp < Fixed::ZERO {
-        delta_temp * coefficient
-    } else {
-        let change = delta_temp * coefficient / Fixed::from_num(2);
-        if change > Fixed::from_num(0.05) {
-            Fixed::from_num(0.05)
-        } else {
-            change
-        }
-    };
+    instrument::ops(2, (temperature >= nominal_temp) as u32);
+    temperature_compensated(
+        soc,
+        temperature,
+        nominal_temp,
+        coefficient,
+        &CompensationLimits::DEFAULT,
+    )
+}
 
-    let bounded_change = if capacity_change < Fixed::from_num(-0.30) {
-        Fixed::from_num(-0.30)
-    } else {
-        capacity_change
-    };
+/// Checked variant of [`compensate_temperature_fixed()`]
+///
+/// [`compensate_temperature_fixed()`] saturates when an intermediate value
+/// leaves the I16F16 range (e.g., a temperature difference of thousands of
+/// degrees times a large coefficient). This variant reports the overflow
+/// instead.
+///
+/// # Returns
+///
+/// * `Ok(soc)` - Temperature-compensated SOC percentage as fixed-point
+/// * `Err(Error::NumericalError)` - An intermediate value overflowed
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{compensate_temperature_checked, Error, Fixed};
+///
+/// let soc = compensate_temperature_checked(
+///     Fixed::from_num(50),
+///     Fixed::from_num(25),
+///     Fixed::from_num(25),
+///     Fixed::from_num(0.005),
+/// );
+/// assert_eq!(soc, Ok(Fixed::from_num(50)));
+///
+/// let overflow = compensate_temperature_checked(
+///     Fixed::from_num(50),
+///     Fixed::from_num(-30000),
+///     Fixed::from_num(25),
+///     Fixed::from_num(10),
+/// );
+/// assert_eq!(overflow, Err(Error::NumericalError));
+/// ```
+pub fn compensate_temperature_checked(
+    soc: Fixed,
+    temperature: Fixed,
+    nominal_temp: Fixed,
+    coefficient: Fixed,
+) -> Result<Fixed, Error> {
+    compensate_temperature_limited(
+        soc,
+        temperature,
+        nominal_temp,
+        coefficient,
+        &CompensationLimits::DEFAULT,
+    )
+}
 
-    soc * (Fixed::ONE + bounded_change)
+/// [`compensate_temperature_checked()`] with custom [`CompensationLimits`]
+///
+/// # Returns
+///
+/// * `Ok(soc)` - Temperature-compensated SOC percentage as fixed-point
+/// * `Err(Error::NumericalError)` - An intermediate value overflowed
+pub fn compensate_temperature_limited(
+    soc: Fixed,
+    temperature: Fixed,
+    nominal_temp: Fixed,
+    coefficient: Fixed,
+    limits: &CompensationLimits,
+) -> Result<Fixed, Error> {
+    let delta_temp = temperature
+        .checked_sub(nominal_temp)
+        .ok_or(Error::NumericalError)?;
+    let linear = delta_temp
+        .checked_mul(coefficient)
+        .ok_or(Error::NumericalError)?;
+
+    instrument::ops(2, (delta_temp >= Fixed::ZERO) as u32);
+    let change = limits.temperature_change(linear, delta_temp >= Fixed::ZERO);
+
+    Fixed::ONE
+        .checked_add(change)
+        .and_then(|factor| soc.checked_mul(factor))
+        .ok_or(Error::NumericalError)
 }
 
 /// Applies aging compensation to SOC value using fixed-point arithmetic
@@ -111,6 +420,8 @@ pub fn compensate_temperature_fixed(
 /// - Aged battery: SOC appears lower due to reduced capacity
 /// - Maximum compensation is 50% (to prevent unrealistic values)
 /// - Invalid inputs (negative age) return original SOC
+/// - Intermediate overflow saturates; use [`compensate_aging_checked()`] to
+///   detect it
 ///
 /// # Examples
 ///
@@ -128,23 +439,55 @@ pub fn compensate_temperature_fixed(
 /// ```
 #[inline]
 pub fn compensate_aging_fixed(soc: Fixed, age_years: Fixed, aging_factor: Fixed) -> Fixed {
-    if age_years < Fixed::ZERO {
-        return soc;
-    }
-
-    if aging_factor < Fixed::ZERO {
-        return soc;
-    }
+    instrument::ops(2, 0);
+    aging_compensated(soc, age_years, aging_factor, &CompensationLimits::DEFAULT)
+}
 
-    let age_compensation = age_years * aging_factor;
+/// Checked variant of [`compensate_aging_fixed()`]
+///
+/// The aging correction itself is capped at 50%, so only an overflowing
+/// `age_years * aging_factor` or SOC product is reported; the plain
+/// function saturates in that case.
+///
+/// # Returns
+///
+/// * `Ok(soc)` - Age-compensated SOC percentage as fixed-point
+/// * `Err(Error::NumericalError)` - An intermediate value overflowed
+pub fn compensate_aging_checked(
+    soc: Fixed,
+    age_years: Fixed,
+    aging_factor: Fixed,
+) -> Result<Fixed, Error> {
+    compensate_aging_limited(soc, age_years, aging_factor, &CompensationLimits::DEFAULT)
+}
 
-    let clamped = if age_compensation > Fixed::from_num(0.5) {
-        Fixed::from_num(0.5)
-    } else {
-        age_compensation
-    };
+/// [`compensate_aging_checked()`] with custom [`CompensationLimits`]
+///
+/// # Returns
+///
+/// * `Ok(soc)` - Age-compensated SOC percentage as fixed-point
+/// * `Err(Error::NumericalError)` - An intermediate value overflowed
+pub fn compensate_aging_limited(
+    soc: Fixed,
+    age_years: Fixed,
+    aging_factor: Fixed,
+    limits: &CompensationLimits,
+) -> Result<Fixed, Error> {
+    if age_years < Fixed::ZERO || aging_factor < Fixed::ZERO {
+        return Ok(soc);
+    }
 
-    soc * (Fixed::ONE - clamped)
+    instrument::ops(2, 0);
+    let loss = limits.aging_loss(
+        age_years
+            .checked_mul(aging_factor)
+            .ok_or(Error::NumericalError)?,
+    );
+
+    Fixed::ONE
+        .checked_sub(loss)
+        .and_then(|factor| soc.checked_mul(factor))
+        .ok_or(Error::NumericalError)
 }
 
 /// Applies default temperature compensation using fixed-point arithmetic
@@ -187,6 +530,9 @@ pub fn default_temperature_compensation_fixed(soc: Fixed, temperature: Fixed) ->
 // ============================================================================
 // Legacy floating-point API
 // ============================================================================
+//
+// These compute natively in `f32` with the same model as the fixed-point
+// functions, so no soft-float conversions are hidden behind each call.
 
 /// Applies temperature compensation to SOC value (floating-point API)
 ///
@@ -238,6 +584,7 @@ pub fn default_temperature_compensation_fixed(soc: Fixed, temperature: Fixed) ->
 /// let warm_soc = compensate_temperature(50.0, 35.0, 25.0, 0.005);
 /// assert!(warm_soc >= 50.0); // SOC may increase slightly in warmth
 /// ```
+#[cfg(not(feature = "no-float"))]
 #[inline]
 pub fn compensate_temperature(
     soc: f32,
@@ -245,20 +592,13 @@ pub fn compensate_temperature(
     nominal_temp: f32,
     coefficient: f32,
 ) -> f32 {
-    if !soc.is_finite()
-        || !temperature.is_finite()
-        || !nominal_temp.is_finite()
-        || !coefficient.is_finite()
-    {
-        return soc;
-    }
-
-    let soc_fixed = Fixed::from_num(soc);
-    let temp_fixed = Fixed::from_num(temperature);
-    let nominal_fixed = Fixed::from_num(nominal_temp);
-    let coeff_fixed = Fixed::from_num(coefficient);
-
-    compensate_temperature_fixed(soc_fixed, temp_fixed, nominal_fixed, coeff_fixed).to_num::<f32>()
+    temperature_compensated(
+        soc,
+        temperature,
+        nominal_temp,
+        coefficient,
+        &CompensationLimits::DEFAULT,
+    )
 }
 
 /// Applies aging compensation to SOC value (floating-point API)
@@ -296,25 +636,10 @@ pub fn compensate_temperature(
 /// let aged_soc = compensate_aging(50.0, 2.0, 0.02);
 /// assert!(aged_soc < 50.0); // Reduced by ~4%
 /// ```
+#[cfg(not(feature = "no-float"))]
 #[inline]
 pub fn compensate_aging(soc: f32, age_years: f32, aging_factor: f32) -> f32 {
-    if !soc.is_finite() || !age_years.is_finite() || !aging_factor.is_finite() {
-        return soc;
-    }
-
-    if age_years < 0.0 {
-        return soc;
-    }
-
-    if aging_factor < 0.0 {
-        return soc;
-    }
-
-    let soc_fixed = Fixed::from_num(soc);
-    let age_fixed = Fixed::from_num(age_years);
-    let factor_fixed = Fixed::from_num(aging_factor);
-
-    compensate_aging_fixed(soc_fixed, age_fixed, factor_fixed).to_num::<f32>()
+    aging_compensated(soc, age_years, aging_factor, &CompensationLimits::DEFAULT)
 }
 
 /// Applies default temperature compensation (floating-point API)
@@ -345,22 +670,17 @@ pub fn compensate_aging(soc: f32, age_years: f32, aging_factor: f32) -> f32 {
 /// let cold_soc = default_temperature_compensation(50.0, 0.0);
 /// assert!(cold_soc < 50.0); // SOC decreases in cold
 /// ```
+#[cfg(not(feature = "no-float"))]
 #[inline]
 pub fn default_temperature_compensation(soc: f32, temperature: f32) -> f32 {
-    if !soc.is_finite() || !temperature.is_finite() {
-        return soc;
-    }
-
-    let soc_fixed = Fixed::from_num(soc);
-    let temp_fixed = Fixed::from_num(temperature);
-
-    default_temperature_compensation_fixed(soc_fixed, temp_fixed).to_num::<f32>()
+    compensate_temperature(soc, temperature, 25.0, 0.005)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_temperature_compensation_at_nominal() {
         // Room temperature (25°C) should have no change
@@ -368,6 +688,7 @@ mod tests {
         assert_eq!(compensate_temperature(50.0, 25.0, 25.0, 0.005), 50.0);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_temperature_compensation_cold() {
         // Cold temperature should DECREASE SOC (less usable capacity)
@@ -386,6 +707,7 @@ mod tests {
         );
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_temperature_compensation_warm() {
         // Warm temperature should slightly INCREASE SOC (better efficiency)
@@ -396,6 +718,7 @@ mod tests {
         );
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_temperature_compensation_bounds() {
         // Test boundary limits
@@ -417,6 +740,7 @@ mod tests {
         );
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_aging_compensation_invalid_inputs() {
         // Test NaN input for soc
@@ -435,6 +759,7 @@ mod tests {
         );
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_aging_compensation_negative_age() {
         // Negative age should be treated as no aging
@@ -445,6 +770,7 @@ mod tests {
         );
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_aging_compensation_negative_factor() {
         // Negative aging factor should be treated as no aging
@@ -455,6 +781,7 @@ mod tests {
         );
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_temperature_compensation_invalid_inputs() {
         // Test NaN inputs
@@ -484,6 +811,7 @@ mod tests {
         );
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_aging_compensation() {
         // New battery should have no change
@@ -501,6 +829,7 @@ mod tests {
         );
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_temperature_compensation_different_coefficients() {
         let base_soc = 50.0;
@@ -524,6 +853,7 @@ mod tests {
         );
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_temperature_compensation_different_nominal_temps() {
         let base_soc = 50.0;
@@ -545,6 +875,7 @@ mod tests {
         );
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_aging_compensation_different_factors() {
         let base_soc = 50.0;
@@ -566,6 +897,7 @@ mod tests {
         );
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_aging_compensation_different_ages() {
         let base_soc = 50.0;
@@ -584,6 +916,7 @@ mod tests {
         );
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_aging_compensation_zero_age() {
         // Test that zero age results in no compensation
@@ -591,6 +924,7 @@ mod tests {
         assert_eq!(result, 50.0, "Zero age should result in no compensation");
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_aging_compensation_max_limit() {
         // Test that aging compensation is limited to 50%
@@ -603,6 +937,7 @@ mod tests {
         assert!(result >= 25.0, "Should be limited to 50% max compensation");
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_temperature_compensation_max_limit() {
         // Test that temperature compensation is limited to ±5%
@@ -625,6 +960,7 @@ mod tests {
         );
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_compensation_edge_cases() {
         // Test compensation at boundary SOC values
@@ -648,6 +984,7 @@ mod tests {
         assert!(aging_comp_full <= 100.0, "100% SOC should not exceed 100%");
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_temperature_compensation_fractional_values() {
         // Test with fractional SOC values
@@ -658,6 +995,7 @@ mod tests {
         assert!(result >= 0.0, "Result should be non-negative");
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_aging_compensation_fractional_values() {
         // Test with fractional age and factor values
@@ -668,6 +1006,7 @@ mod tests {
         assert!(result < 50.0, "Result should be less than base SOC");
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_compensation_negative_coefficient() {
         // Test with negative temperature coefficient (unusual but possible)
@@ -675,6 +1014,7 @@ mod tests {
         assert!(result.is_finite(), "Result should be finite");
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_compensation_zero_coefficient() {
         // Test with zero coefficient (should result in no change)
@@ -682,6 +1022,7 @@ mod tests {
         assert_eq!(result, 50.0, "Zero coefficient should result in no change");
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_aging_compensation_zero_factor() {
         // Test with zero aging factor (should result in no change)
@@ -689,6 +1030,7 @@ mod tests {
         assert_eq!(result, 50.0, "Zero aging factor should result in no change");
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_temperature_compensation_precision() {
         // Test that compensation maintains reasonable precision
@@ -702,6 +1044,7 @@ mod tests {
         );
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_combined_compensation_effects() {
         // Test that temperature and aging can be applied sequentially
@@ -721,6 +1064,7 @@ mod tests {
         );
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_clamp_function() {
         // Test clamping at upper bound (warm temperature, +5% max)
@@ -850,6 +1194,135 @@ mod tests {
         assert!(result >= soc * Fixed::from_num(1.04));
     }
 
+    #[cfg(not(feature = "no-float"))]
+    #[test]
+    fn test_float_api_matches_fixed() {
+        for &temperature in &[-20.0f32, 0.0, 10.5, 25.0, 40.0, 80.0] {
+            let float = compensate_temperature(60.0, temperature, 25.0, 0.005);
+            let fixed = compensate_temperature_fixed(
+                Fixed::from_num(60),
+                Fixed::from_num(temperature),
+                Fixed::from_num(25),
+                Fixed::from_num(0.005),
+            );
+            assert!((float - fixed.to_num::<f32>()).abs() < 0.05);
+        }
+
+        for &age in &[0.0f32, 1.5, 10.0, 40.0] {
+            let float = compensate_aging(60.0, age, 0.02);
+            let fixed = compensate_aging_fixed(
+                Fixed::from_num(60),
+                Fixed::from_num(age),
+                Fixed::from_num(0.02),
+            );
+            assert!((float - fixed.to_num::<f32>()).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn test_compensation_limits_default() {
+        let limits = CompensationLimits::default();
+        assert_eq!(limits.warm_scale, Fixed::from_num(0.5));
+        assert_eq!(limits.max_warm_gain, Fixed::from_num(0.05));
+        assert_eq!(limits.max_cold_loss, Fixed::from_num(0.30));
+        assert_eq!(limits.max_aging_loss, Fixed::from_num(0.5));
+    }
+
+    #[cfg(not(feature = "no-float"))]
+    #[test]
+    fn test_all_apis_agree() {
+        let soc = Fixed::from_num(80);
+        for temperature in [-100, -40, -10, 0, 24, 25, 26, 35, 45, 60, 85] {
+            let temperature = Fixed::from_num(temperature);
+            for coefficient in [0.0, 0.002, 0.005, 0.02] {
+                let coefficient = Fixed::from_num(coefficient);
+                let nominal = Fixed::from_num(25);
+                let fixed = compensate_temperature_fixed(soc, temperature, nominal, coefficient);
+                let checked =
+                    compensate_temperature_checked(soc, temperature, nominal, coefficient);
+                assert_eq!(checked, Ok(fixed));
+                let f32_soc = compensate_temperature(
+                    soc.to_num(),
+                    temperature.to_num(),
+                    nominal.to_num(),
+                    coefficient.to_num(),
+                );
+                let f64_soc = temperature_compensated::<f64>(
+                    soc.to_num(),
+                    temperature.to_num(),
+                    nominal.to_num(),
+                    coefficient.to_num(),
+                    &CompensationLimits::DEFAULT,
+                );
+                assert!((f32_soc - fixed.to_num::<f32>()).abs() < 0.01);
+                assert!((f64_soc - fixed.to_num::<f64>()).abs() < 0.01);
+            }
+        }
+
+        for age in [0, 1, 5, 25, 100] {
+            let age = Fixed::from_num(age);
+            let factor = Fixed::from_num(0.02);
+            let fixed = compensate_aging_fixed(soc, age, factor);
+            assert_eq!(compensate_aging_checked(soc, age, factor), Ok(fixed));
+            let f32_soc = compensate_aging(soc.to_num(), age.to_num(), factor.to_num());
+            let f64_soc = aging_compensated::<f64>(
+                soc.to_num(),
+                age.to_num(),
+                factor.to_num(),
+                &CompensationLimits::DEFAULT,
+            );
+            assert!((f32_soc - fixed.to_num::<f32>()).abs() < 0.01);
+            assert!((f64_soc - fixed.to_num::<f64>()).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn test_custom_compensation_limits() {
+        let limits = CompensationLimits {
+            warm_scale: Fixed::ONE,
+            max_warm_gain: Fixed::from_num(0.10),
+            max_cold_loss: Fixed::from_num(0.20),
+            max_aging_loss: Fixed::from_num(0.25),
+        };
+        let soc = Fixed::from_num(50);
+        let nominal = Fixed::from_num(25);
+        let coefficient = Fixed::from_num(0.005);
+
+        // 10°C warm: 5% unscaled instead of 2.5%
+        let warm =
+            compensate_temperature_limited(soc, Fixed::from_num(35), nominal, coefficient, &limits)
+                .unwrap();
+        assert!((warm - Fixed::from_num(52.5)).abs() < Fixed::from_num(0.01));
+
+        // -75°C: floored at -20% instead of -30%
+        let cold = compensate_temperature_limited(
+            soc,
+            Fixed::from_num(-50),
+            nominal,
+            coefficient,
+            &limits,
+        )
+        .unwrap();
+        assert!((cold - Fixed::from_num(40)).abs() < Fixed::from_num(0.01));
+
+        let aged =
+            compensate_aging_limited(soc, Fixed::from_num(40), Fixed::from_num(0.02), &limits);
+        assert!((aged.unwrap() - Fixed::from_num(37.5)).abs() < Fixed::from_num(0.01));
+
+        // Defaults reproduce the checked functions
+        assert_eq!(
+            compensate_temperature_limited(
+                soc,
+                Fixed::from_num(35),
+                nominal,
+                coefficient,
+                &CompensationLimits::DEFAULT
+            ),
+            compensate_temperature_checked(soc, Fixed::from_num(35), nominal, coefficient)
+        );
+    }
+
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_default_temperature_compensation_nan() {
         // Test NaN handling (line 363)
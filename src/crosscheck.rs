@@ -0,0 +1,238 @@
+//! Cross-checking against a hardware fuel gauge
+//!
+//! Boards that carry a MAX17048/MAX17049-style fuel gauge get two
+//! independent SOC figures: the gauge's ModelGauge output and this crate's
+//! voltage-curve estimate. [`GaugeCrossCheck`] compares them, blends them
+//! into one value and flags readings where they disagree by more than a
+//! threshold — a sign of a wrong curve, an unconfigured gauge or a
+//! failing cell.
+//!
+//! The gauge is read by the application's own I2C driver; only the raw
+//! register words are passed in, as a [`Max1704xReading`].
+
+use crate::{Error, Fixed, SocEstimator};
+
+/// 100% as a fixed-point constant
+const SOC_FULL: Fixed = Fixed::from_bits(100 << 16);
+
+/// Default disagreement threshold (10%)
+const DEFAULT_MAX_DISAGREEMENT: Fixed = Fixed::from_bits(10 << 16);
+
+/// Default weight of the gauge in the blended SOC (50%)
+const DEFAULT_GAUGE_WEIGHT: Fixed = Fixed::from_bits(1 << 15);
+
+/// Raw VCELL and SOC registers of a MAX17048/MAX17049 fuel gauge
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{Fixed, Max1704xReading};
+///
+/// // VCELL = 48000 * 78.125 µV, SOC = 0x3280 (50.5%)
+/// let reading = Max1704xReading::new(48000, 0x3280);
+///
+/// assert_eq!(reading.voltage(), Fixed::from_num(3.75));
+/// assert_eq!(reading.soc(), Fixed::from_num(50.5));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Max1704xReading {
+    /// VCELL register (78.125 µV per cell per LSB)
+    pub vcell: u16,
+    /// SOC register (1/256 % per LSB)
+    pub soc: u16,
+}
+
+impl Max1704xReading {
+    /// Register address of VCELL
+    pub const REG_VCELL: u8 = 0x02;
+    /// Register address of SOC
+    pub const REG_SOC: u8 = 0x04;
+
+    /// Creates a reading from the two register words
+    ///
+    /// Both registers are big-endian on the bus; pass them as read with
+    /// `u16::from_be_bytes`.
+    #[inline]
+    pub const fn new(vcell: u16, soc: u16) -> Self {
+        Self { vcell, soc }
+    }
+
+    /// Cell voltage in volts
+    ///
+    /// The MAX17049 measures two cells in series; its VCELL has twice the
+    /// LSB, so this is the average cell voltage there.
+    #[inline]
+    pub fn voltage(&self) -> Fixed {
+        // 78.125 µV in 16.16 bits is exactly 5.12 LSB
+        Fixed::from_bits((self.vcell as i64 * 128 / 25) as i32)
+    }
+
+    /// SOC reported by the gauge in percent
+    ///
+    /// The gauge may report slightly above 100% right after charging.
+    #[inline]
+    pub fn soc(&self) -> Fixed {
+        Fixed::from_bits((self.soc as i32) << 8)
+    }
+}
+
+/// Outcome of a [`GaugeCrossCheck`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrossCheckResult {
+    /// SOC estimated from the voltage curve, in percent
+    pub curve_soc: Fixed,
+    /// SOC reported by the gauge, clamped to 0-100%
+    pub gauge_soc: Fixed,
+    /// Weighted blend of both, in percent
+    pub fused_soc: Fixed,
+    /// Absolute difference between the two, in percent
+    pub disagreement: Fixed,
+    /// `true` if the disagreement is within the configured threshold
+    pub agrees: bool,
+}
+
+/// Compares a hardware gauge against the voltage-curve estimate
+///
+/// The curve is evaluated at the gauge's own VCELL reading, so both
+/// figures describe the same instant.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{
+///     BatteryChemistry, Fixed, GaugeCrossCheck, Max1704xReading, SocEstimator,
+/// };
+///
+/// let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+/// let check = GaugeCrossCheck::default().with_max_disagreement(Fixed::from_num(15));
+///
+/// // Gauge says 90% while the cell sits at 3.6 V
+/// let reading = Max1704xReading::new(46080, 90 << 8);
+/// let result = check.check(&estimator, reading, Fixed::from_num(25)).unwrap();
+///
+/// assert!(!result.agrees);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GaugeCrossCheck {
+    /// Largest tolerated difference between the two SOCs, in percent
+    pub max_disagreement: Fixed,
+    /// Weight of the gauge in the blended SOC (0 = curve only, 1 = gauge only)
+    pub gauge_weight: Fixed,
+}
+
+impl GaugeCrossCheck {
+    /// Creates a cross-check with a 10% threshold and equal weights
+    #[inline]
+    pub const fn default() -> Self {
+        Self {
+            max_disagreement: DEFAULT_MAX_DISAGREEMENT,
+            gauge_weight: DEFAULT_GAUGE_WEIGHT,
+        }
+    }
+
+    /// Set the disagreement threshold in percent
+    #[inline]
+    pub fn with_max_disagreement(mut self, threshold: Fixed) -> Self {
+        self.max_disagreement = threshold;
+        self
+    }
+
+    /// Set the weight of the gauge in the blended SOC (clamped to 0-1)
+    #[inline]
+    pub fn with_gauge_weight(mut self, weight: Fixed) -> Self {
+        self.gauge_weight = weight.clamp(Fixed::ZERO, Fixed::ONE);
+        self
+    }
+
+    /// Compares a gauge reading with the curve estimate
+    ///
+    /// # Arguments
+    ///
+    /// * `estimator` - Estimator providing the curve and compensation
+    /// * `reading` - Raw gauge registers
+    /// * `temperature` - Battery temperature in °C for compensation
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(result)` - Both SOCs, their blend and whether they agree
+    /// * `Err(Error)` - The curve estimate failed
+    pub fn check(
+        &self,
+        estimator: &SocEstimator,
+        reading: Max1704xReading,
+        temperature: Fixed,
+    ) -> Result<CrossCheckResult, Error> {
+        let curve_soc = estimator.estimate_soc_compensated_fixed(reading.voltage(), temperature)?;
+        let gauge_soc = reading.soc().clamp(Fixed::ZERO, SOC_FULL);
+        let disagreement = (curve_soc - gauge_soc).abs();
+        let fused_soc = curve_soc + (gauge_soc - curve_soc) * self.gauge_weight;
+
+        Ok(CrossCheckResult {
+            curve_soc,
+            gauge_soc,
+            fused_soc,
+            disagreement,
+            agrees: disagreement <= self.max_disagreement,
+        })
+    }
+}
+
+impl Default for GaugeCrossCheck {
+    #[inline]
+    fn default() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BatteryChemistry;
+
+    #[test]
+    fn test_reading_conversion() {
+        let reading = Max1704xReading::new(53760, 0x6400);
+        assert_eq!(reading.voltage(), Fixed::from_num(4.2));
+        assert_eq!(reading.soc(), Fixed::from_num(100));
+
+        let reading = Max1704xReading::new(0, 0x0080);
+        assert_eq!(reading.voltage(), Fixed::ZERO);
+        assert_eq!(reading.soc(), Fixed::from_num(0.5));
+    }
+
+    #[test]
+    fn test_cross_check() {
+        let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+        let voltage_counts = 48000; // 3.75 V
+        let curve_soc = estimator
+            .estimate_soc_fixed(Max1704xReading::new(voltage_counts, 0).voltage())
+            .unwrap();
+
+        let check = GaugeCrossCheck::default();
+        let gauge_soc = curve_soc.to_num::<u16>() + 4;
+        let result = check
+            .check(
+                &estimator,
+                Max1704xReading::new(voltage_counts, gauge_soc << 8),
+                Fixed::from_num(25),
+            )
+            .unwrap();
+        assert!(result.agrees);
+        assert_eq!(result.curve_soc, curve_soc);
+        assert_eq!(result.fused_soc, (result.curve_soc + result.gauge_soc) / 2);
+
+        let gauge_only = check.with_gauge_weight(Fixed::from_num(2));
+        let result = gauge_only
+            .check(
+                &estimator,
+                Max1704xReading::new(voltage_counts, 0),
+                Fixed::from_num(25),
+            )
+            .unwrap();
+        assert!(!result.agrees);
+        assert_eq!(result.fused_soc, Fixed::ZERO);
+        assert_eq!(result.disagreement, curve_soc);
+    }
+}
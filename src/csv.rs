@@ -0,0 +1,274 @@
+//! CSV import and export of curves
+//!
+//! Lab discharge logs hold hundreds or thousands of samples, far more than
+//! the [`MAX_CURVE_POINTS`] a [`Curve`] stores. [`Curve::from_csv()`] reads
+//! such a log, turns capacity columns into SOC, smooths out voltage
+//! recovery so SOC never falls with rising voltage, and keeps the points
+//! that best preserve the curve's shape. [`Curve::write_csv()`] writes a
+//! curve back out in the `voltage,soc` format it reads.
+
+use std::io::{self, BufRead, Write};
+use std::string::ToString;
+use std::vec::Vec;
+
+use crate::{Curve, CurvePoint, Error, MAX_CURVE_POINTS};
+
+/// Meaning of the second CSV column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CsvColumns {
+    /// `voltage,soc` with SOC in percent
+    Soc,
+    /// `voltage,capacity` in log order, from full to empty
+    ///
+    /// The capacity may count up (discharged mAh) or down (remaining mAh);
+    /// the first row is taken as 100% and the last row as 0%.
+    Capacity,
+}
+
+impl Curve {
+    /// Builds a curve from a CSV discharge log
+    ///
+    /// Rows hold a voltage in volts and a SOC or capacity value, separated
+    /// by `,`, `;` or a tab; further columns are ignored. Blank lines,
+    /// lines starting with `#` and a leading header row are skipped. Rows
+    /// may come in any voltage order.
+    ///
+    /// Samples are rounded to the curve's resolution (1 mV, 0.1%), SOCs of
+    /// equal voltages are averaged, and SOC is made non-decreasing in
+    /// voltage by pooling violating neighbours. Logs with more distinct
+    /// voltages than [`MAX_CURVE_POINTS`] are reduced to that many points
+    /// by repeatedly keeping the sample the current curve misses by the
+    /// most.
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidData`] - A row is malformed (the message
+    ///   names the line), or the log does not yield a valid curve:
+    ///   [`Error::EmptyInput`] without data rows, [`Error::InvalidCurve`]
+    ///   with fewer than 2 distinct voltages or a constant capacity
+    /// * Any error of the underlying reader
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{Curve, CsvColumns, CurvePoint};
+    ///
+    /// let log = "voltage,discharged_mah\n4.20,0\n3.90,500\n3.70,1000\n3.00,2000\n";
+    /// let curve = Curve::from_csv(log.as_bytes(), CsvColumns::Capacity).unwrap();
+    ///
+    /// assert_eq!(curve.len(), 4);
+    /// assert_eq!(curve.points()[1], CurvePoint::from_raw(3700, 500));
+    /// ```
+    pub fn from_csv<R: BufRead>(reader: R, columns: CsvColumns) -> io::Result<Curve> {
+        let mut rows = parse_rows(reader)?;
+        if rows.is_empty() {
+            return Err(Error::EmptyInput.into());
+        }
+
+        if columns == CsvColumns::Capacity {
+            let full = rows[0].1;
+            let empty = rows[rows.len() - 1].1;
+            if full == empty {
+                return Err(Error::InvalidCurve.into());
+            }
+            for row in &mut rows {
+                row.1 = (row.1 - empty) / (full - empty) * 100.0;
+            }
+        }
+
+        let samples = monotonic_samples(&rows);
+        if samples.len() < 2 {
+            return Err(Error::InvalidCurve.into());
+        }
+
+        let points: Vec<CurvePoint> = select_points(&samples, MAX_CURVE_POINTS)
+            .into_iter()
+            .map(|index| CurvePoint::from_raw(samples[index].0, samples[index].1))
+            .collect();
+        Ok(Curve::new(&points))
+    }
+
+    /// Writes the curve as `voltage,soc` CSV with a header row
+    ///
+    /// Values are written at the curve's full resolution, so reading the
+    /// output with [`from_csv()`](Self::from_csv) returns an equal curve.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "voltage,soc")?;
+        for point in self.points() {
+            writeln!(
+                writer,
+                "{}.{:03},{}.{}",
+                point.voltage_mv / 1000,
+                point.voltage_mv % 1000,
+                point.soc_tenth / 10,
+                point.soc_tenth % 10
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads `(voltage, value)` rows, skipping comments and a header row
+fn parse_rows<R: BufRead>(reader: R) -> io::Result<Vec<(f64, f64)>> {
+    let mut rows = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split([',', ';', '\t']).map(str::trim);
+        let voltage = fields.next().and_then(|field| field.parse::<f64>().ok());
+        let value = fields.next().and_then(|field| field.parse::<f64>().ok());
+        match (voltage, value) {
+            (Some(voltage), Some(value)) if voltage.is_finite() && value.is_finite() => {
+                rows.push((voltage, value));
+            }
+            // Header row
+            (None, _) if rows.is_empty() => {}
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    ["malformed CSV row on line ", &(index + 1).to_string()].concat(),
+                ))
+            }
+        }
+    }
+    Ok(rows)
+}
+
+/// Rounds rows to `(mV, SOC tenths)`, merges equal voltages and makes SOC
+/// non-decreasing in voltage (pool-adjacent-violators)
+fn monotonic_samples(rows: &[(f64, f64)]) -> Vec<(u16, u16)> {
+    let mut rounded: Vec<(u16, f64)> = rows
+        .iter()
+        .map(|&(voltage, soc)| {
+            let voltage_mv = (voltage * 1000.0).round().clamp(0.0, u16::MAX as f64) as u16;
+            (voltage_mv, (soc * 10.0).clamp(0.0, 1000.0))
+        })
+        .collect();
+    rounded.sort_by_key(|&(voltage_mv, _)| voltage_mv);
+
+    let groups: Vec<(u16, f64)> = rounded
+        .chunk_by(|a, b| a.0 == b.0)
+        .map(|group| {
+            let sum: f64 = group.iter().map(|&(_, soc)| soc).sum();
+            (group[0].0, sum / group.len() as f64)
+        })
+        .collect();
+
+    // Blocks of (SOC sum, voltage count), merged while out of order
+    let mut blocks: Vec<(f64, usize)> = Vec::new();
+    for &(_, soc) in &groups {
+        let mut block = (soc, 1);
+        while let Some(&(sum, count)) = blocks.last() {
+            if sum / count as f64 <= block.0 / block.1 as f64 {
+                break;
+            }
+            blocks.pop();
+            block = (sum + block.0, count + block.1);
+        }
+        blocks.push(block);
+    }
+
+    let socs = blocks
+        .iter()
+        .flat_map(|&(sum, count)| core::iter::repeat_n((sum / count as f64).round() as u16, count));
+    groups
+        .iter()
+        .zip(socs)
+        .map(|(&(voltage_mv, _), soc_tenth)| (voltage_mv, soc_tenth))
+        .collect()
+}
+
+/// Indices of at most `limit` samples that best approximate all samples
+///
+/// Starts with both ends and repeatedly adds the sample farthest in SOC
+/// from the interpolation between its selected neighbours.
+fn select_points(samples: &[(u16, u16)], limit: usize) -> Vec<usize> {
+    if samples.len() <= limit {
+        return (0..samples.len()).collect();
+    }
+    let mut selected = std::vec![0, samples.len() - 1];
+    while selected.len() < limit {
+        let mut worst = None;
+        let mut worst_error = 0.0;
+        for pair in selected.windows(2) {
+            let (start, end) = (samples[pair[0]], samples[pair[1]]);
+            let span = (end.0 - start.0) as f64;
+            for (index, sample) in samples.iter().enumerate().take(pair[1]).skip(pair[0] + 1) {
+                let t = (sample.0 - start.0) as f64 / span;
+                let interpolated = start.1 as f64 + (end.1 as f64 - start.1 as f64) * t;
+                let error = (sample.1 as f64 - interpolated).abs();
+                if error > worst_error {
+                    worst = Some(index);
+                    worst_error = error;
+                }
+            }
+        }
+        match worst {
+            Some(index) => {
+                let position = selected.partition_point(|&i| i < index);
+                selected.insert(position, index);
+            }
+            None => break,
+        }
+    }
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BatteryChemistry;
+
+    #[test]
+    fn test_csv_round_trip() {
+        let curve = Curve::for_chemistry(BatteryChemistry::LiPo);
+        let mut output = Vec::new();
+        curve.write_csv(&mut output).unwrap();
+
+        let text = std::str::from_utf8(&output).unwrap();
+        assert!(text.starts_with("voltage,soc\n"));
+
+        let restored = Curve::from_csv(output.as_slice(), CsvColumns::Soc).unwrap();
+        assert_eq!(&restored, curve);
+    }
+
+    #[test]
+    fn test_csv_import_downsamples() {
+        // Dense, slightly noisy linear log from 4.2 V / 100% to 3.0 V / 0%
+        let mut log = std::string::String::from("# lab log\nV;mAh;temp\n");
+        for step in 0..=1200 {
+            let voltage = 4.2 - step as f64 / 1000.0 + if step % 7 == 0 { 0.002 } else { 0.0 };
+            log.push_str(&std::format!("{voltage:.4};{step};25\n"));
+        }
+
+        let curve = Curve::from_csv(log.as_bytes(), CsvColumns::Capacity).unwrap();
+        assert!(curve.len() <= MAX_CURVE_POINTS);
+        assert_eq!(curve.validate_sorted(), Ok(()));
+        assert_eq!(curve.points().first().unwrap().soc_tenth, 0);
+        assert_eq!(curve.points().last().unwrap().soc_tenth, 1000);
+
+        let points = curve.points();
+        assert!(points.windows(2).all(|w| w[0].soc_tenth <= w[1].soc_tenth));
+        let soc = curve.voltage_to_soc(3.6).unwrap();
+        assert!((soc - 50.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_csv_import_errors() {
+        let error = Curve::from_csv("voltage,soc\n".as_bytes(), CsvColumns::Soc).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        let error = Curve::from_csv("3.0,0\nabc,5\n".as_bytes(), CsvColumns::Soc).unwrap_err();
+        assert!(error.to_string().contains("line 2"));
+
+        let error = Curve::from_csv("3.0,0\n3.0,50\n".as_bytes(), CsvColumns::Soc).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        let constant = Curve::from_csv("4.0,10\n3.0,10\n".as_bytes(), CsvColumns::Capacity);
+        assert!(constant.is_err());
+    }
+}
@@ -1,17 +1,30 @@
 //! Voltage-SOC curve definitions and interpolation
 //!
-//! This module provides the [`Curve`] struct for representing battery
-//! discharge curves and converting voltage measurements to state-of-charge (SOC) values.
+//! This module provides the [`SizedCurve`] struct, and its default-sized
+//! alias [`Curve`], for representing battery discharge curves and converting
+//! voltage measurements to state-of-charge (SOC) values.
 
-use crate::{CurvePoint, Error, Fixed};
+use crate::instrument;
+use crate::types::soc_from_tenths;
+use crate::{AccuracyBound, BatteryChemistry, CurvePoint, Error, ErrorDetail, Fixed};
 
-/// Maximum number of points allowed in a voltage curve
+/// Maximum number of points allowed in a [`Curve`]
 ///
 /// This limit ensures predictable memory usage and prevents excessive
-/// curve sizes that could impact performance in embedded systems.
+/// curve sizes that could impact performance in embedded systems. Use
+/// [`SizedCurve`] directly for a different capacity.
 pub const MAX_CURVE_POINTS: usize = 32;
 
-/// A voltage-to-SOC curve for battery state-of-charge estimation
+/// A voltage-to-SOC curve with the default capacity of
+/// [`MAX_CURVE_POINTS`] points
+///
+/// This is the curve type of the built-in chemistries and of
+/// [`SocEstimator`](crate::SocEstimator). See [`SizedCurve`] for the
+/// methods.
+pub type Curve = SizedCurve<MAX_CURVE_POINTS>;
+
+/// A voltage-to-SOC curve for battery state-of-charge estimation, with
+/// room for `N` points
 ///
 /// This struct represents a discharge curve that maps battery voltage
 /// to state-of-charge percentage using linear interpolation between data points.
@@ -19,14 +32,21 @@ pub const MAX_CURVE_POINTS: usize = 32;
 /// # Memory Optimization
 ///
 /// The curve is stored using fixed-size arrays with optimized types:
-/// - `points`: Fixed array of 32 points
+/// - `points`: Fixed array of `N` points (32 for [`Curve`]); a curve known
+///   to need 8 points can be declared as `SizedCurve<8>`, about a quarter of
+///   the size of a [`Curve`]. At most 255 points are used.
 /// - `len`: `u8` for point count (vs `usize`, saves memory)
 /// - `min_voltage_mv`/`max_voltage_mv`: `u16` for voltage limits
 /// - `min_soc_tenth`/`max_soc_tenth`: `u16` for cached SOC values (tenth of percent)
+/// - `slopes`: per-segment SOC slopes as `i32`, computed once in
+///   [`new()`](Self::new) (at compile time for `const` curves) so a lookup
+///   needs no division; only segments steeper than 0.5% per mV, which do
+///   not fit, are divided out on lookup
 ///
 /// # Examples
 ///
 /// ```no_run
+/// # #[cfg(not(feature = "no-float"))] {
 /// use battery_estimator::{Curve, CurvePoint};
 ///
 /// // Create a custom curve
@@ -41,6 +61,7 @@ pub const MAX_CURVE_POINTS: usize = 32;
 ///     Ok(soc) => println!("SOC: {:.1}%", soc),
 ///     Err(e) => eprintln!("Error: {}", e),
 /// }
+/// # }
 /// ```
 ///
 /// # Interpolation
@@ -49,10 +70,15 @@ pub const MAX_CURVE_POINTS: usize = 32;
 /// - Values at or below minimum voltage → Returns min SOC
 /// - Values at or above maximum voltage → Returns max SOC
 /// - Values between points → Linear interpolation
-#[derive(Debug, Clone, Copy)]
-pub struct Curve {
+///
+/// # Equality
+///
+/// Two curves are equal when they hold the same points. Slots past
+/// [`len()`](Self::len) are always zeroed, so they never affect the result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SizedCurve<const N: usize> {
     /// Array of curve points (fixed size for memory efficiency)
-    points: [CurvePoint; MAX_CURVE_POINTS],
+    points: [CurvePoint; N],
     /// Number of points in the curve (0-255)
     len: u8,
     /// Minimum voltage in millivolts
@@ -63,9 +89,13 @@ pub struct Curve {
     min_soc_tenth: u16,
     /// SOC at maximum voltage (cached in tenths of percent)
     max_soc_tenth: u16,
+    /// Slope of the segment ending at each point (see [`segment_slope()`]),
+    /// zero for the first point and unused slots, [`STEEP_SLOPE`] if it
+    /// does not fit
+    slopes: [i32; N],
 }
 
-impl Curve {
+impl<const N: usize> SizedCurve<N> {
     /// Creates an empty curve with no points
     ///
     /// # Examples
@@ -79,12 +109,13 @@ impl Curve {
     /// ```
     pub const fn empty() -> Self {
         Self {
-            points: [CurvePoint::new(0.0, 0.0); MAX_CURVE_POINTS],
+            points: [CurvePoint::new(0.0, 0.0); N],
             len: 0,
             min_voltage_mv: 0,
             max_voltage_mv: 0,
             min_soc_tenth: 0,
             max_soc_tenth: 0,
+            slopes: [0; N],
         }
     }
 
@@ -97,9 +128,10 @@ impl Curve {
     /// # Notes
     ///
     /// - Points **must be ordered by increasing voltage** for correct interpolation
-    /// - Maximum of 32 points will be stored
+    /// - Maximum of `N` points (32 for [`Curve`], at most 255) will be stored
     /// - Minimum of 2 points required for valid interpolation
-    /// - Use [`validate_sorted()`](Self::validate_sorted) to verify point order at runtime
+    /// - Use [`validate_sorted()`](Self::validate_sorted) to verify point order at runtime,
+    ///   or [`try_new()`](Self::try_new) to reject invalid points on construction
     ///
     /// # Examples
     ///
@@ -120,10 +152,13 @@ impl Curve {
         let mut min_soc = 0u16;
         let mut max_soc = 0u16;
 
-        while i < points.len() && i < MAX_CURVE_POINTS {
+        while i < points.len() && i < N && i <= u8::MAX as usize {
             let p = points[i];
             curve.points[i] = p;
 
+            if i > 0 {
+                curve.slopes[i] = store_slope(segment_slope(points[i - 1], p));
+            }
             if i == 0 {
                 min = p.voltage_mv;
                 max = p.voltage_mv;
@@ -154,6 +189,109 @@ impl Curve {
         curve
     }
 
+    /// Creates a curve, rejecting point sets that cannot be interpolated
+    ///
+    /// Unlike [`new()`](Self::new), which stores whatever it is given and
+    /// leaves the checks to [`validate_sorted()`](Self::validate_sorted) or
+    /// the first lookup, this fails where the curve is defined. Being
+    /// `const`, it can reject a bad table at compile time.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(curve)` - The curve of all `points`
+    /// * `Err(detail)` - [`Error::InvalidCurve`] at the index of the first
+    ///   offending point: fewer than 2 points (index = length), more than
+    ///   `N` points (index = `N`), a voltage below its predecessor, or an
+    ///   SOC above 100%; [`Error::DuplicateVoltage`] at the second of two
+    ///   points with the same voltage; [`Error::NonMonotonicSoc`] at a
+    ///   point with a lower SOC than its predecessor
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{Curve, CurvePoint, Error, ErrorContext};
+    ///
+    /// const POINTS: [CurvePoint; 3] = [
+    ///     CurvePoint::new(3.0, 0.0),
+    ///     CurvePoint::new(3.6, 50.0),
+    ///     CurvePoint::new(4.1, 100.0),
+    /// ];
+    ///
+    /// // Fails to compile if the table is invalid
+    /// const CURVE: Curve = match Curve::try_new(&POINTS) {
+    ///     Ok(curve) => curve,
+    ///     Err(_) => panic!("invalid curve"),
+    /// };
+    /// assert_eq!(CURVE.len(), 3);
+    ///
+    /// let duplicate = Curve::try_new(&[POINTS[0], POINTS[1], POINTS[1]]).unwrap_err();
+    /// assert_eq!(duplicate.error, Error::DuplicateVoltage);
+    /// assert_eq!(duplicate.context, ErrorContext::CurvePoint(2));
+    /// ```
+    pub const fn try_new(points: &[CurvePoint]) -> Result<Self, ErrorDetail> {
+        let capacity = if N < u8::MAX as usize {
+            N
+        } else {
+            u8::MAX as usize
+        };
+        if points.len() < 2 {
+            return Err(Error::InvalidCurve.at_curve_point(points.len() as u8));
+        }
+        if points.len() > capacity {
+            return Err(Error::InvalidCurve.at_curve_point(capacity as u8));
+        }
+
+        let mut i = 0;
+        while i < points.len() {
+            if points[i].soc_tenth > 1000 {
+                return Err(Error::InvalidCurve.at_curve_point(i as u8));
+            }
+            if i > 0 {
+                if let Some(error) = point_error(points[i - 1], points[i]) {
+                    return Err(error.at_curve_point(i as u8));
+                }
+            }
+            i += 1;
+        }
+
+        Ok(Self::new(points))
+    }
+
+    /// Slope of the segment ending at point `idx`, as [`segment_slope()`]
+    ///
+    /// Zero for the first point and unused slots.
+    #[cfg(all(feature = "simd", nightly))]
+    pub(crate) fn slope(&self, idx: usize) -> i64 {
+        let points = self.points();
+        match (idx.checked_sub(1), points.get(idx), self.slopes.get(idx)) {
+            (Some(prev), Some(&curr), Some(&slope)) => match points.get(prev) {
+                Some(&prev) => load_slope(slope, prev, curr),
+                None => 0,
+            },
+            _ => 0,
+        }
+    }
+
+    /// Returns the points in use, ordered as given to [`new()`](Self::new)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{Curve, CurvePoint};
+    ///
+    /// let curve = Curve::new(&[CurvePoint::new(3.0, 0.0), CurvePoint::new(4.0, 100.0)]);
+    /// assert_eq!(curve.points()[1], CurvePoint::new(4.0, 100.0));
+    /// ```
+    #[inline]
+    pub const fn points(&self) -> &[CurvePoint] {
+        // `len` never exceeds `N`; the fallback only keeps the panic path
+        // out of the binary
+        match self.points.split_at_checked(self.len as usize) {
+            Some((points, _)) => points,
+            None => &self.points,
+        }
+    }
+
     /// Converts a voltage measurement to state-of-charge (SOC) percentage
     /// using fixed-point arithmetic
     ///
@@ -169,8 +307,12 @@ impl Curve {
     ///
     /// # Performance
     ///
-    /// This method uses binary search (via `partition_point`) for O(log n) lookup
-    /// and cached SOC values for O(1) boundary checks.
+    /// The lookup is branchless on a curve with non-decreasing voltages: the
+    /// voltage is clamped to the curve range, the segment is found by a
+    /// binary search whose iteration count depends only on the number of
+    /// points, and the boundary SOCs are picked by selects rather than early
+    /// returns. Every voltage thus takes the same path, keeping the
+    /// worst-case cycle count tight for calls from control-loop interrupts.
     ///
     /// # Examples
     ///
@@ -193,48 +335,39 @@ impl Curve {
     /// assert_eq!(soc, I16F16::from_num(100.0));
     /// ```
     pub fn voltage_to_soc_fixed(&self, voltage: Fixed) -> Result<Fixed, Error> {
+        self.voltage_to_soc_near(voltage, &mut 0)
+    }
+
+    /// [`voltage_to_soc_fixed()`](Self::voltage_to_soc_fixed) starting from
+    /// the segment of a previous lookup
+    ///
+    /// `segment` is updated to the segment of `voltage`. Consecutive samples
+    /// of a slowly changing voltage usually share a segment and skip the
+    /// binary search.
+    pub(crate) fn voltage_to_soc_near(
+        &self,
+        voltage: Fixed,
+        segment: &mut usize,
+    ) -> Result<Fixed, Error> {
         if self.len < 2 {
             return Err(Error::InvalidCurve);
         }
-
-        let voltage_mv = (voltage * Fixed::from_num(1000)).to_num::<i32>();
-
-        let max_voltage_mv = self.max_voltage_mv as i32;
-        let min_voltage_mv = self.min_voltage_mv as i32;
-        let max_soc = Fixed::from_num(self.max_soc_tenth) / Fixed::from_num(10);
-        let min_soc = Fixed::from_num(self.min_soc_tenth) / Fixed::from_num(10);
-
-        if voltage_mv >= max_voltage_mv {
-            return Ok(max_soc);
-        }
-
-        if voltage_mv <= min_voltage_mv {
-            return Ok(min_soc);
-        }
-
-        let points = &self.points[..self.len as usize];
-        let idx = points.partition_point(|p| p.voltage_mv as i32 <= voltage_mv);
-
-        if idx > 0 && idx < points.len() {
-            let prev = points[idx - 1];
-            let curr = points[idx];
-
-            if voltage_mv >= prev.voltage_mv as i32 && voltage_mv <= curr.voltage_mv as i32 {
-                let prev_voltage_mv = prev.voltage_mv as i32;
-                let curr_voltage_mv = curr.voltage_mv as i32;
-
-                let range = Fixed::from_num(curr_voltage_mv - prev_voltage_mv);
-                let ratio = Fixed::from_num(voltage_mv - prev_voltage_mv) / range;
-
-                let prev_soc = prev.soc_fixed();
-                let curr_soc = curr.soc_fixed();
-
-                let soc = prev_soc + ratio * (curr_soc - prev_soc);
-                return Ok(soc);
-            }
-        }
-
-        Err(Error::NumericalError)
+        let low = CurvePoint {
+            voltage_mv: self.min_voltage_mv,
+            soc_tenth: self.min_soc_tenth,
+        };
+        let high = CurvePoint {
+            voltage_mv: self.max_voltage_mv,
+            soc_tenth: self.max_soc_tenth,
+        };
+        lookup_fixed(
+            self.points(),
+            self.slopes.get(..self.len as usize),
+            low,
+            high,
+            voltage,
+            segment,
+        )
     }
 
     /// Converts a voltage measurement to state-of-charge (SOC) percentage
@@ -247,6 +380,7 @@ impl Curve {
     ///
     /// * `Ok(soc)` - SOC percentage (0.0 to 100.0)
     /// * `Err(Error::InvalidCurve)` - Curve has fewer than 2 points
+    /// * `Err(Error::NonFiniteInput)` - The voltage is NaN or infinite
     /// * `Err(Error::NumericalError)` - Division by zero or calculation error
     ///
     /// # Behavior
@@ -257,7 +391,7 @@ impl Curve {
     ///
     /// # Performance
     ///
-    /// This method uses binary search (via `partition_point`) for O(log n) lookup
+    /// This method uses the binary search shared by all curve types for O(log n) lookup
     /// and cached SOC values for O(1) boundary checks.
     ///
     /// # Examples
@@ -280,14 +414,235 @@ impl Curve {
     /// // Midpoint interpolation
     /// assert_eq!(curve.voltage_to_soc(3.5).unwrap(), 50.0);
     /// ```
+    #[cfg(not(feature = "no-float"))]
     pub fn voltage_to_soc(&self, voltage: f32) -> Result<f32, Error> {
-        // Check for NaN before conversion to avoid panic in Fixed::from_num
+        let soc_fixed = self.voltage_to_soc_fixed(volts_from_f32(voltage)?)?;
+        Ok(soc_fixed.to_num::<f32>())
+    }
+
+    /// Converts a voltage measurement to SOC using double precision
+    ///
+    /// Unlike [`voltage_to_soc()`](Self::voltage_to_soc), the voltage is not
+    /// rounded to whole millivolts and the interpolation runs entirely in
+    /// `f64`. Intended for host-side tools (log replay, simulation) rather
+    /// than firmware.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(soc)` - SOC percentage (0.0 to 100.0)
+    /// * `Err(Error::InvalidCurve)` - Curve has fewer than 2 points
+    /// * `Err(Error::NonFiniteInput)` - The voltage is NaN or infinite
+    /// * `Err(Error::NumericalError)` - Calculation error
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{Curve, CurvePoint};
+    ///
+    /// let curve = Curve::new(&[
+    ///     CurvePoint::new(3.0, 0.0),
+    ///     CurvePoint::new(4.0, 100.0),
+    /// ]);
+    ///
+    /// let soc = curve.voltage_to_soc_f64(3.7005).unwrap();
+    /// assert!((soc - 70.05).abs() < 1e-9);
+    /// ```
+    #[cfg(not(feature = "no-float"))]
+    pub fn voltage_to_soc_f64(&self, voltage: f64) -> Result<f64, Error> {
+        if self.len < 2 {
+            return Err(Error::InvalidCurve);
+        }
         if !voltage.is_finite() {
-            return Ok(0.0);
+            return Err(Error::NonFiniteInput);
         }
-        let voltage_fixed = Fixed::from_num(voltage);
-        let soc_fixed = self.voltage_to_soc_fixed(voltage_fixed)?;
-        Ok(soc_fixed.to_num::<f32>())
+
+        let voltage_mv = voltage * 1000.0;
+        // Positive between the end points, so truncation finds the same
+        // segment as the float
+        self.interpolate_float(voltage_mv, voltage_mv as i32)
+    }
+
+    /// Single-precision variant of [`voltage_to_soc_f64()`](Self::voltage_to_soc_f64)
+    ///
+    /// Unlike the public [`voltage_to_soc()`](Self::voltage_to_soc), the
+    /// voltage is neither rounded to millivolts nor converted to
+    /// fixed-point.
+    #[cfg(not(feature = "no-float"))]
+    pub(crate) fn voltage_to_soc_f32(&self, voltage: f32) -> Result<f32, Error> {
+        if self.len < 2 {
+            return Err(Error::InvalidCurve);
+        }
+        if !voltage.is_finite() {
+            return Err(Error::NonFiniteInput);
+        }
+
+        let voltage_mv = voltage * 1000.0;
+        self.interpolate_float(voltage_mv, voltage_mv as i32)
+    }
+
+    /// Float interpolation shared by the `f32` and `f64` lookups
+    ///
+    /// `segment_mv` is `voltage_mv` truncated to an integer, used to find
+    /// the segment.
+    #[cfg(not(feature = "no-float"))]
+    fn interpolate_float<T>(&self, voltage_mv: T, segment_mv: i32) -> Result<T, Error>
+    where
+        T: Copy
+            + PartialOrd
+            + From<u16>
+            + core::ops::Add<Output = T>
+            + core::ops::Sub<Output = T>
+            + core::ops::Mul<Output = T>
+            + core::ops::Div<Output = T>,
+    {
+        let ten = T::from(10);
+
+        if voltage_mv >= T::from(self.max_voltage_mv) {
+            return Ok(T::from(self.max_soc_tenth) / ten);
+        }
+
+        if voltage_mv <= T::from(self.min_voltage_mv) {
+            return Ok(T::from(self.min_soc_tenth) / ten);
+        }
+
+        let points = self.points();
+        let idx = upper_bound(points, segment_mv);
+
+        if let Some((&[.., prev], &[curr, ..])) = points.split_at_checked(idx) {
+            if curr.voltage_mv > prev.voltage_mv {
+                let range = T::from(curr.voltage_mv) - T::from(prev.voltage_mv);
+                let ratio = (voltage_mv - T::from(prev.voltage_mv)) / range;
+                let prev_soc = T::from(prev.soc_tenth) / ten;
+                let curr_soc = T::from(curr.soc_tenth) / ten;
+                return Ok(prev_soc + ratio * (curr_soc - prev_soc));
+            }
+        }
+
+        Err(Error::NumericalError)
+    }
+
+    /// Converts a millivolt reading to SOC in permille using integers only
+    ///
+    /// Curve points already store SOC in tenths of a percent, so the result
+    /// (0 to 1000) is exact at every point. Between points the value is
+    /// interpolated with 32-bit integer math and truncated toward the lower
+    /// point. No floating-point or fixed-point types are involved, which
+    /// suits the smallest MCUs and C callers, and the function is `const`,
+    /// so SOC values can be computed at compile time.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(permille)` - SOC in permille (0 to 1000)
+    /// * `Err(Error::InvalidCurve)` - Curve has fewer than 2 points
+    /// * `Err(Error::NumericalError)` - Calculation error
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{Curve, CurvePoint};
+    ///
+    /// let curve = Curve::new(&[
+    ///     CurvePoint::new(3.0, 0.0),
+    ///     CurvePoint::new(4.0, 100.0),
+    /// ]);
+    ///
+    /// assert_eq!(curve.voltage_mv_to_soc_permille(3_700).unwrap(), 700);
+    /// assert_eq!(curve.voltage_mv_to_soc_permille(3_701).unwrap(), 701);
+    /// ```
+    pub const fn voltage_mv_to_soc_permille(&self, voltage_mv: u16) -> Result<u16, Error> {
+        if self.len < 2 {
+            return Err(Error::InvalidCurve);
+        }
+
+        if voltage_mv >= self.max_voltage_mv {
+            return Ok(self.max_soc_tenth);
+        }
+
+        if voltage_mv <= self.min_voltage_mv {
+            return Ok(self.min_soc_tenth);
+        }
+
+        let points = self.points();
+        let idx = upper_bound(points, voltage_mv as i32);
+
+        if let Some((&[.., prev], &[curr, ..])) = points.split_at_checked(idx) {
+            let range = curr.voltage_mv as i64 - prev.voltage_mv as i64;
+            if range > 0 {
+                let offset = voltage_mv as i64 - prev.voltage_mv as i64;
+                let delta = curr.soc_tenth as i64 - prev.soc_tenth as i64;
+                let soc = prev.soc_tenth as i64 + offset * delta / range;
+                return Ok(soc as u16);
+            }
+        }
+
+        Err(Error::NumericalError)
+    }
+
+    /// Finds the voltage in millivolts at which the curve reaches a SOC
+    ///
+    /// The inverse of
+    /// [`voltage_mv_to_soc_permille()`](Self::voltage_mv_to_soc_permille),
+    /// for curves whose SOC rises with voltage. Between points the voltage
+    /// is interpolated with integer math and truncated toward the lower
+    /// point. SOC values outside the curve return the voltage of the
+    /// nearest end. Being `const`, it lets firmware bake thresholds into
+    /// constants.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(voltage_mv)` - Voltage in millivolts
+    /// * `Err(Error::InvalidCurve)` - Curve has fewer than 2 points
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{Curve, CurvePoint};
+    ///
+    /// const CURVE: Curve = Curve::new(&[
+    ///     CurvePoint::new(3.0, 0.0),
+    ///     CurvePoint::new(3.5, 50.0),
+    ///     CurvePoint::new(4.0, 100.0),
+    /// ]);
+    ///
+    /// // Low-battery warning threshold, computed at compile time
+    /// const LOW_BATTERY_MV: u16 = match CURVE.soc_permille_to_voltage_mv(200) {
+    ///     Ok(mv) => mv,
+    ///     Err(_) => panic!("invalid curve"),
+    /// };
+    /// assert_eq!(LOW_BATTERY_MV, 3_200);
+    /// ```
+    pub const fn soc_permille_to_voltage_mv(&self, soc_permille: u16) -> Result<u16, Error> {
+        if self.len < 2 {
+            return Err(Error::InvalidCurve);
+        }
+
+        if soc_permille <= self.min_soc_tenth {
+            return Ok(self.min_voltage_mv);
+        }
+
+        if soc_permille >= self.max_soc_tenth {
+            return Ok(self.max_voltage_mv);
+        }
+
+        let mut rest = self.points();
+        while let [prev, ref tail @ ..] = *rest {
+            let [curr, ..] = *tail else {
+                break;
+            };
+
+            if soc_permille <= curr.soc_tenth {
+                let delta = curr.soc_tenth as i64 - prev.soc_tenth as i64;
+                if delta <= 0 {
+                    return Ok(prev.voltage_mv);
+                }
+                let offset = soc_permille as i64 - prev.soc_tenth as i64;
+                let range = curr.voltage_mv as i64 - prev.voltage_mv as i64;
+                return Ok((prev.voltage_mv as i64 + offset * range / delta) as u16);
+            }
+            rest = tail;
+        }
+
+        Ok(self.max_voltage_mv)
     }
 
     /// Returns the voltage range of the curve
@@ -310,6 +665,7 @@ impl Curve {
     /// assert_eq!(min, 3.0);
     /// assert_eq!(max, 4.0);
     /// ```
+    #[cfg(not(feature = "no-float"))]
     #[inline]
     pub const fn voltage_range(&self) -> (f32, f32) {
         (
@@ -347,6 +703,97 @@ impl Curve {
         )
     }
 
+    /// Returns the voltage range of the curve in millivolts
+    #[inline]
+    pub(crate) const fn voltage_range_mv(&self) -> (u16, u16) {
+        (self.min_voltage_mv, self.max_voltage_mv)
+    }
+
+    /// Integrates the curve voltage over SOC from the lowest point up to `soc`
+    ///
+    /// The result is in volt-percent: multiplied by the capacity in Ah and
+    /// divided by 100 it gives the energy in Wh that a cell delivers when
+    /// discharged from `soc` down to the bottom of the curve. Each segment
+    /// is integrated exactly (trapezoid rule on the linear interpolation).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(integral)` - Integral in V·% as fixed-point
+    /// * `Err(Error::InvalidCurve)` - Curve has fewer than 2 points
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{Curve, CurvePoint, Fixed};
+    ///
+    /// let curve = Curve::new(&[
+    ///     CurvePoint::new(3.0, 0.0),
+    ///     CurvePoint::new(4.0, 100.0),
+    /// ]);
+    ///
+    /// // Mean voltage 3.5V over 100%
+    /// assert_eq!(curve.voltage_integral(Fixed::from_num(100)).unwrap(), Fixed::from_num(350));
+    /// // Mean voltage 3.25V over the lower 50%
+    /// assert_eq!(curve.voltage_integral(Fixed::from_num(50)).unwrap(), Fixed::from_num(162.5));
+    /// ```
+    pub fn voltage_integral(&self, soc: Fixed) -> Result<Fixed, Error> {
+        if self.len < 2 {
+            return Err(Error::InvalidCurve);
+        }
+
+        // Work in mV and 1/65536 % so that the segments sum up exactly
+        let soc_bits = soc.to_bits() as i64;
+        let mut area: i64 = 0;
+        for pair in self.points[..self.len as usize].windows(2) {
+            let (v0, s0) = (
+                pair[0].voltage_mv as i64,
+                pair[0].soc_fixed().to_bits() as i64,
+            );
+            let (v1, s1) = (
+                pair[1].voltage_mv as i64,
+                pair[1].soc_fixed().to_bits() as i64,
+            );
+            if s1 <= s0 || soc_bits <= s0 {
+                continue;
+            }
+            let end = soc_bits.min(s1);
+            let v_end = v0 + (v1 - v0) * (end - s0) / (s1 - s0);
+            area += (v0 + v_end) * (end - s0) / 2;
+        }
+
+        Ok(Fixed::from_bits(
+            (area / 1000).clamp(i32::MIN as i64, i32::MAX as i64) as i32,
+        ))
+    }
+
+    /// Returns the worst-case SOC error of a lookup on this curve
+    ///
+    /// Accounts for the storage of points in whole millivolts and tenths of
+    /// a percent, the truncation of the measured voltage to millivolts and
+    /// the fixed-point interpolation. Voltages beyond the curve's range read
+    /// as its end points and are within the bound too.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bound)` - The error sources; [`AccuracyBound::total()`] is the
+    ///   figure to quote
+    /// * `Err(Error::InvalidCurve)` - Curve has fewer than 2 points or is
+    ///   not sorted, see [`validate_sorted()`](Self::validate_sorted)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, Curve, Fixed};
+    ///
+    /// let bound = Curve::for_chemistry(BatteryChemistry::LiPo).accuracy_bound().unwrap();
+    /// // Within 1% of the curve data everywhere
+    /// assert!(bound.total() < Fixed::from_num(1));
+    /// ```
+    pub fn accuracy_bound(&self) -> Result<AccuracyBound, Error> {
+        self.validate_sorted()?;
+        Ok(AccuracyBound::of_points(self.points()))
+    }
+
     /// Returns the number of points in the curve
     ///
     /// # Examples
@@ -381,6 +828,356 @@ impl Curve {
     pub const fn is_empty(&self) -> bool {
         self.len == 0
     }
+
+    /// Checks that the curve can be used for interpolation
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - At least 2 points with strictly increasing voltages and
+    ///   non-decreasing SOC
+    /// * `Err(Error::InvalidCurve)` - Too few points, or points out of order
+    /// * `Err(Error::DuplicateVoltage)` - Two consecutive points share a voltage
+    /// * `Err(Error::NonMonotonicSoc)` - The SOC falls from one point to the next
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{Curve, CurvePoint, Error};
+    ///
+    /// let curve = Curve::new(&[CurvePoint::new(3.0, 0.0), CurvePoint::new(4.0, 100.0)]);
+    /// assert_eq!(curve.validate_sorted(), Ok(()));
+    ///
+    /// let reversed = Curve::new(&[CurvePoint::new(4.0, 100.0), CurvePoint::new(3.0, 0.0)]);
+    /// assert_eq!(reversed.validate_sorted(), Err(Error::InvalidCurve));
+    /// ```
+    pub fn validate_sorted(&self) -> Result<(), Error> {
+        self.validate_sorted_detail().map_err(Error::from)
+    }
+
+    /// Like [`validate_sorted()`](Self::validate_sorted), but reports the
+    /// index of the first point that breaks the voltage order
+    ///
+    /// A curve with fewer than 2 points reports its length as the index.
+    pub fn validate_sorted_detail(&self) -> Result<(), ErrorDetail> {
+        validate_points(self.points())
+    }
+}
+
+impl Curve {
+    /// Returns the built-in curve of a battery chemistry
+    ///
+    /// This is the curve [`SocEstimator::new()`](crate::SocEstimator::new)
+    /// uses. Its points can be copied and adjusted to derive a custom curve.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(not(feature = "no-float"))] {
+    /// use battery_estimator::{BatteryChemistry, Curve, CurvePoint};
+    ///
+    /// let lipo = Curve::for_chemistry(BatteryChemistry::LiPo);
+    /// assert_eq!(lipo.voltage_range(), (3.2, 4.2));
+    ///
+    /// // Same curve without the deep-discharge region below 3.3 V
+    /// let raised = Curve::new(&lipo.points()[1..]);
+    /// assert_eq!(raised.voltage_range(), (3.3, 4.2));
+    /// assert_eq!(raised.points()[0], CurvePoint::new(3.3, 5.0));
+    /// # }
+    /// ```
+    pub const fn for_chemistry(chemistry: BatteryChemistry) -> &'static Curve {
+        match chemistry {
+            BatteryChemistry::LiPo => &default_curves::LIPO,
+            BatteryChemistry::LiFePO4 => &default_curves::LIFEPO4,
+            BatteryChemistry::LiIon => &default_curves::LIION,
+            BatteryChemistry::Lipo410Full340Cutoff => &default_curves::LIPO410_FULL340_CUTOFF,
+        }
+    }
+}
+
+/// Interpolates SOC over points sorted by increasing voltage
+///
+/// Shared by the runtime-sized curve types. Follows the boundary behavior of
+/// [`Curve::voltage_to_soc_fixed()`]: voltages are truncated to millivolts
+/// and clamp to the SOC of the first and last point.
+pub(crate) fn interpolate_points(points: &[CurvePoint], voltage: Fixed) -> Result<Fixed, Error> {
+    match (points.first(), points.last()) {
+        (Some(&first), Some(&last)) if points.len() >= 2 => {
+            lookup_fixed(points, None, first, last, voltage, &mut 0)
+        }
+        _ => Err(Error::InvalidCurve),
+    }
+}
+
+/// Fixed-point lookup shared by every curve type
+///
+/// Voltages at or beyond `low` and `high` return their SOC; others are
+/// interpolated on their segment, which is the hinted `segment` (the index
+/// of its upper point) if the voltage falls into it and is otherwise found
+/// by binary search. `segment` is updated on return. `slopes` holds the
+/// precomputed [`segment_slope()`] of each point, if the curve type stores
+/// them.
+///
+/// Without a matching hint, every voltage takes the same path on points in
+/// voltage order: the search runs a fixed number of iterations over the
+/// clamped voltage, and the boundary SOCs are selected at the end rather
+/// than returned early. Points out of order fail the segment check with
+/// [`Error::NumericalError`] for voltages inside the range.
+fn lookup_fixed(
+    points: &[CurvePoint],
+    slopes: Option<&[i32]>,
+    low: CurvePoint,
+    high: CurvePoint,
+    voltage: Fixed,
+    segment: &mut usize,
+) -> Result<Fixed, Error> {
+    instrument::lookup();
+    let voltage_mv = truncate_mv(voltage);
+    let low_mv = low.voltage_mv as i32;
+    let high_mv = high.voltage_mv as i32;
+    let clamped_mv = voltage_mv.max(low_mv).min(high_mv);
+
+    let hint = *segment;
+    let idx = match points.split_at_checked(hint) {
+        Some((&[.., prev], &[curr, ..]))
+            if prev.voltage_mv as i32 <= clamped_mv && clamped_mv < curr.voltage_mv as i32 =>
+        {
+            hint
+        }
+        _ => {
+            // Lower point of the last segment starting at or below the
+            // voltage; `get()` keeps the search free of bounds-check panics
+            let mut base = 0;
+            let mut size = points.len().saturating_sub(1);
+            while size > 1 {
+                let half = size / 2;
+                let mid = base + half;
+                base = match points.get(mid) {
+                    Some(point) if point.voltage_mv as i32 <= clamped_mv => mid,
+                    _ => base,
+                };
+                size -= half;
+            }
+            base + 1
+        }
+    };
+    *segment = idx;
+
+    let soc = match (points.get(idx - 1), points.get(idx)) {
+        (Some(&prev), Some(&curr))
+            if prev.voltage_mv as i32 <= clamped_mv && clamped_mv <= curr.voltage_mv as i32 =>
+        {
+            let slope = match slopes.and_then(|slopes| slopes.get(idx)) {
+                Some(&slope) => load_slope(slope, prev, curr),
+                None => {
+                    instrument::ops(0, 1);
+                    segment_slope(prev, curr)
+                }
+            };
+            let offset = clamped_mv - prev.voltage_mv as i32;
+            Ok(interpolate(prev.soc_fixed(), slope, offset))
+        }
+        _ => Err(Error::NumericalError),
+    };
+    if voltage_mv >= high_mv {
+        Ok(high.soc_fixed())
+    } else if voltage_mv <= low_mv {
+        Ok(low.soc_fixed())
+    } else {
+        soc
+    }
+}
+
+/// Converts a voltage from the `f32` API into fixed-point
+///
+/// NaN and infinities are rejected with [`Error::NonFiniteInput`]; finite
+/// voltages beyond the I16F16 range saturate, like the fixed-point math.
+#[cfg(not(feature = "no-float"))]
+#[inline]
+pub(crate) fn volts_from_f32(voltage: f32) -> Result<Fixed, Error> {
+    if !voltage.is_finite() {
+        return Err(Error::NonFiniteInput);
+    }
+    // `checked_from_num()` rather than `saturating_from_num()`, whose NaN
+    // panic the optimizer cannot rule out from the check above
+    match Fixed::checked_from_num(voltage) {
+        Some(voltage) => Ok(voltage),
+        None if voltage < 0.0 => Ok(Fixed::MIN),
+        None => Ok(Fixed::MAX),
+    }
+}
+
+/// Voltage in whole millivolts, truncated like every curve lookup
+#[inline]
+pub(crate) fn truncate_mv(voltage: Fixed) -> i32 {
+    instrument::ops(1, 0);
+    voltage
+        .saturating_mul(Fixed::from_num(1000))
+        .to_num::<i32>()
+}
+
+/// SOC slope of the segment from `prev` to `curr`
+///
+/// In SOC fixed-point bits per mV, scaled by 2^16 so that the rounding of
+/// the division costs less than one bit over any segment. Zero if the
+/// voltages do not increase.
+pub(crate) const fn segment_slope(prev: CurvePoint, curr: CurvePoint) -> i64 {
+    let range = curr.voltage_mv as i64 - prev.voltage_mv as i64;
+    if range <= 0 {
+        return 0;
+    }
+    let delta = soc_from_tenths(curr.soc_tenth).to_bits() as i64
+        - soc_from_tenths(prev.soc_tenth).to_bits() as i64;
+    (delta << 16) / range
+}
+
+/// Stored slope of segments whose [`segment_slope()`] does not fit an `i32`
+///
+/// Only segments steeper than 0.5% per mV reach it.
+const STEEP_SLOPE: i32 = i32::MIN;
+
+/// [`segment_slope()`] as stored in a curve
+const fn store_slope(slope: i64) -> i32 {
+    if slope > i32::MAX as i64 || slope <= STEEP_SLOPE as i64 {
+        STEEP_SLOPE
+    } else {
+        slope as i32
+    }
+}
+
+/// Slope of the segment from `prev` to `curr` from its stored value
+#[inline]
+fn load_slope(stored: i32, prev: CurvePoint, curr: CurvePoint) -> i64 {
+    if stored == STEEP_SLOPE {
+        instrument::ops(0, 1);
+        segment_slope(prev, curr)
+    } else {
+        stored as i64
+    }
+}
+
+/// SOC `offset_mv` above the start of a segment, rounded to the nearest bit
+///
+/// `offset_mv` is below the segment's range, so the product stays under
+/// the scaled SOC delta (2^48) and the result between the segment's SOCs.
+/// The saturating operations never engage; they only keep overflow checks
+/// out of the lookup.
+#[inline]
+pub(crate) fn interpolate(prev_soc: Fixed, slope: i64, offset_mv: i32) -> Fixed {
+    instrument::ops(1, 0);
+    let delta = slope
+        .saturating_mul(offset_mv as i64)
+        .saturating_add(1 << 15)
+        >> 16;
+    Fixed::from_bits(prev_soc.to_bits().saturating_add(delta as i32))
+}
+
+/// Index of the first point above `voltage_mv`, by binary search
+///
+/// The segment search of the float and permille lookups; `const` because
+/// `partition_point` is not usable in the integer `const fn` lookups.
+const fn upper_bound(points: &[CurvePoint], voltage_mv: i32) -> usize {
+    let mut lo = 0usize;
+    let mut rest = points;
+    while let Some((left, &[mid, ref right @ ..])) = rest.split_at_checked(rest.len() / 2) {
+        if mid.voltage_mv as i32 <= voltage_mv {
+            lo = lo.saturating_add(left.len() + 1);
+            rest = right;
+        } else {
+            rest = left;
+        }
+    }
+    lo
+}
+
+/// Checks that points have strictly increasing voltages and non-decreasing
+/// SOC, reporting the first offending index
+pub(crate) fn validate_points(points: &[CurvePoint]) -> Result<(), ErrorDetail> {
+    if points.len() < 2 {
+        return Err(Error::InvalidCurve.at_curve_point(points.len().min(u8::MAX as usize) as u8));
+    }
+    match points
+        .windows(2)
+        .enumerate()
+        .find_map(|(index, w)| Some((index + 1, point_error(w[0], w[1])?)))
+    {
+        Some((index, error)) => Err(error.at_curve_point(index.min(u8::MAX as usize) as u8)),
+        None => Ok(()),
+    }
+}
+
+/// Error for a point that does not follow its predecessor on a valid curve
+#[inline]
+const fn point_error(prev: CurvePoint, curr: CurvePoint) -> Option<Error> {
+    if curr.voltage_mv <= prev.voltage_mv {
+        Some(order_error(prev, curr))
+    } else if curr.soc_tenth < prev.soc_tenth {
+        Some(Error::NonMonotonicSoc)
+    } else {
+        None
+    }
+}
+
+/// Error for a point whose voltage is not above its predecessor's
+#[inline]
+pub(crate) const fn order_error(prev: CurvePoint, curr: CurvePoint) -> Error {
+    if prev.voltage_mv == curr.voltage_mv {
+        Error::DuplicateVoltage
+    } else {
+        Error::InvalidCurve
+    }
+}
+
+/// Serialized as the sequence of its points, so only the points in use
+/// are written and the cached limits are rebuilt on deserialization
+#[cfg(feature = "serde")]
+impl<const N: usize> serde::Serialize for SizedCurve<N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+
+        let points = &self.points[..self.len as usize];
+        let mut seq = serializer.serialize_seq(Some(points.len()))?;
+        for point in points {
+            seq.serialize_element(point)?;
+        }
+        seq.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> serde::Deserialize<'de> for SizedCurve<N> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct CurveVisitor<const N: usize>;
+
+        impl<'de, const N: usize> serde::de::Visitor<'de> for CurveVisitor<N> {
+            type Value = SizedCurve<N>;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(
+                    f,
+                    "a sequence of at most {} curve points",
+                    N.min(u8::MAX as usize)
+                )
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<SizedCurve<N>, A::Error> {
+                let mut points = [CurvePoint::new(0.0, 0.0); N];
+                let mut len = 0;
+                while let Some(point) = seq.next_element()? {
+                    if len == N.min(u8::MAX as usize) {
+                        return Err(serde::de::Error::invalid_length(len + 1, &self));
+                    }
+                    points[len] = point;
+                    len += 1;
+                }
+                SizedCurve::try_new(&points[..len]).map_err(serde::de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_seq(CurveVisitor)
+    }
 }
 
 /// Predefined battery voltage curves
@@ -489,6 +1286,7 @@ pub mod default_curves {
 mod tests {
     use super::*;
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_basic() {
         let curve = Curve::new(&[CurvePoint::new(3.0, 0.0), CurvePoint::new(4.0, 100.0)]);
@@ -498,6 +1296,7 @@ mod tests {
         assert_eq!(curve.voltage_to_soc(3.5).unwrap(), 50.0);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_boundaries() {
         let curve = Curve::new(&[
@@ -515,6 +1314,7 @@ mod tests {
         assert_eq!(curve.voltage_to_soc(3.75).unwrap(), 75.0);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_invalid() {
         let curve = Curve::new(&[CurvePoint::new(3.0, 0.0)]);
@@ -523,6 +1323,7 @@ mod tests {
         assert!(curve.voltage_to_soc(3.5).is_err());
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_empty() {
         let curve = Curve::empty();
@@ -532,6 +1333,7 @@ mod tests {
         assert!(curve.voltage_to_soc(3.0).is_err());
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_multiple_points() {
         let curve = Curve::new(&[
@@ -555,6 +1357,7 @@ mod tests {
         assert!((soc - 75.0).abs() < 0.1);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_voltage_range() {
         let curve = Curve::new(&[CurvePoint::new(3.0, 0.0), CurvePoint::new(4.0, 100.0)]);
@@ -573,6 +1376,7 @@ mod tests {
         assert_eq!(max, Fixed::from_num(4.0));
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_max_points() {
         // Test that curve handles maximum number of points
@@ -592,6 +1396,7 @@ mod tests {
         assert!(curve.voltage_to_soc(3.5).is_ok());
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_numerical_error_fallback() {
         // Test the fallback NumericalError path when voltage is not found in any segment
@@ -612,6 +1417,7 @@ mod tests {
         ));
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_cached_soc_values() {
         // Test that cached SOC values are correctly computed
@@ -634,6 +1440,7 @@ mod tests {
         assert_eq!(curve.voltage_to_soc(4.5).unwrap(), 95.0);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_interpolation_precision() {
         let curve = Curve::new(&[
@@ -649,6 +1456,7 @@ mod tests {
         assert!((curve.voltage_to_soc(3.25).unwrap() - 25.0).abs() < 0.2);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_single_segment() {
         let curve = Curve::new(&[CurvePoint::new(3.0, 0.0), CurvePoint::new(4.0, 100.0)]);
@@ -659,6 +1467,7 @@ mod tests {
         assert!((curve.voltage_to_soc(3.75).unwrap() - 75.0).abs() < 0.2);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_dense_points() {
         // Test with many closely spaced points - use array for no_std compatibility
@@ -682,6 +1491,7 @@ mod tests {
         }
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_non_linear() {
         // Test with non-linear curve (exponential-like)
@@ -770,19 +1580,371 @@ mod tests {
         assert!(curve.voltage_to_soc_fixed(Fixed::from_num(3.0)).is_err());
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_voltage_to_soc_nan_handling() {
         let curve = Curve::new(&[CurvePoint::new(3.0, 0.0), CurvePoint::new(4.0, 100.0)]);
 
-        // Test NaN handling - should return 0.0 instead of panicking
-        let result = curve.voltage_to_soc(f32::NAN).unwrap();
-        assert_eq!(result, 0.0);
+        // NaN and infinities are rejected instead of panicking
+        for voltage in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            assert_eq!(curve.voltage_to_soc(voltage), Err(Error::NonFiniteInput));
+        }
+        for voltage in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert_eq!(
+                curve.voltage_to_soc_f64(voltage),
+                Err(Error::NonFiniteInput)
+            );
+        }
 
-        // Test infinity handling
-        let result = curve.voltage_to_soc(f32::INFINITY).unwrap();
-        assert_eq!(result, 0.0);
+        // Finite voltages beyond the fixed-point range saturate
+        assert_eq!(curve.voltage_to_soc(1e10), Ok(100.0));
+        assert_eq!(curve.voltage_to_soc(-1e10), Ok(0.0));
+    }
 
-        let result = curve.voltage_to_soc(f32::NEG_INFINITY).unwrap();
-        assert_eq!(result, 0.0);
+    #[test]
+    fn test_voltage_integral() {
+        let curve = Curve::new(&[
+            CurvePoint::new(3.0, 0.0),
+            CurvePoint::new(3.5, 50.0),
+            CurvePoint::new(4.0, 100.0),
+        ]);
+
+        assert_eq!(curve.voltage_integral(Fixed::ZERO).unwrap(), Fixed::ZERO);
+        assert_eq!(
+            curve.voltage_integral(Fixed::from_num(25)).unwrap(),
+            Fixed::from_num(78.125)
+        );
+        assert_eq!(
+            curve.voltage_integral(Fixed::from_num(100)).unwrap(),
+            Fixed::from_num(350)
+        );
+        // SOC beyond the curve does not add energy
+        assert_eq!(
+            curve.voltage_integral(Fixed::from_num(150)).unwrap(),
+            Fixed::from_num(350)
+        );
+        assert_eq!(
+            Curve::empty().voltage_integral(Fixed::from_num(50)),
+            Err(Error::InvalidCurve)
+        );
+    }
+
+    #[cfg(not(feature = "no-float"))]
+    #[test]
+    fn test_voltage_to_soc_f64() {
+        let curve = Curve::new(&[
+            CurvePoint::new(3.0, 0.0),
+            CurvePoint::new(3.5, 50.0),
+            CurvePoint::new(4.0, 100.0),
+        ]);
+
+        assert_eq!(curve.voltage_to_soc_f64(2.5).unwrap(), 0.0);
+        assert_eq!(curve.voltage_to_soc_f64(4.5).unwrap(), 100.0);
+        assert_eq!(curve.voltage_to_soc_f64(3.5).unwrap(), 50.0);
+        // Sub-millivolt resolution is preserved
+        assert!((curve.voltage_to_soc_f64(3.25005).unwrap() - 25.005).abs() < 1e-9);
+        assert_eq!(
+            Curve::empty().voltage_to_soc_f64(3.5),
+            Err(Error::InvalidCurve)
+        );
+    }
+
+    #[test]
+    fn test_voltage_mv_to_soc_permille() {
+        let curve = Curve::new(&[
+            CurvePoint::new(3.0, 0.0),
+            CurvePoint::new(3.5, 50.0),
+            CurvePoint::new(4.0, 100.0),
+        ]);
+
+        assert_eq!(curve.voltage_mv_to_soc_permille(0).unwrap(), 0);
+        assert_eq!(curve.voltage_mv_to_soc_permille(u16::MAX).unwrap(), 1000);
+        assert_eq!(curve.voltage_mv_to_soc_permille(3_500).unwrap(), 500);
+        assert_eq!(curve.voltage_mv_to_soc_permille(3_250).unwrap(), 250);
+        assert_eq!(curve.voltage_mv_to_soc_permille(3_999).unwrap(), 999);
+        assert_eq!(
+            Curve::empty().voltage_mv_to_soc_permille(3_500),
+            Err(Error::InvalidCurve)
+        );
+    }
+
+    #[test]
+    fn test_duplicate_voltage_validation() {
+        let curve = Curve::new(&[
+            CurvePoint::new(3.0, 0.0),
+            CurvePoint::new(3.5, 40.0),
+            CurvePoint::new(3.5, 60.0),
+            CurvePoint::new(4.0, 100.0),
+        ]);
+        assert_eq!(curve.validate_sorted(), Err(Error::DuplicateVoltage));
+        assert_eq!(
+            curve.validate_sorted_detail(),
+            Err(Error::DuplicateVoltage.at_curve_point(2))
+        );
+
+        // A decrease is still an ordering error
+        let decreasing = Curve::new(&[
+            CurvePoint::new(3.0, 0.0),
+            CurvePoint::new(3.5, 50.0),
+            CurvePoint::new(3.4, 60.0),
+        ]);
+        assert_eq!(
+            decreasing.validate_sorted_detail(),
+            Err(Error::InvalidCurve.at_curve_point(2))
+        );
+        // Falling SOC, e.g. swapped table values
+        let swapped = Curve::new(&[
+            CurvePoint::new(3.0, 0.0),
+            CurvePoint::new(3.5, 60.0),
+            CurvePoint::new(3.7, 40.0),
+            CurvePoint::new(4.0, 100.0),
+        ]);
+        assert_eq!(
+            swapped.validate_sorted_detail(),
+            Err(Error::NonMonotonicSoc.at_curve_point(2))
+        );
+        assert_eq!(
+            Curve::try_new(swapped.points()),
+            Err(Error::NonMonotonicSoc.at_curve_point(2))
+        );
+
+        // Flat SOC segments are allowed
+        let flat = Curve::new(&[
+            CurvePoint::new(3.0, 0.0),
+            CurvePoint::new(3.3, 50.0),
+            CurvePoint::new(3.4, 50.0),
+        ]);
+        assert_eq!(flat.validate_sorted(), Ok(()));
+    }
+
+    #[test]
+    fn test_for_chemistry() {
+        use crate::SocEstimator;
+
+        let chemistries = [
+            BatteryChemistry::LiPo,
+            BatteryChemistry::LiFePO4,
+            BatteryChemistry::LiIon,
+            BatteryChemistry::Lipo410Full340Cutoff,
+        ];
+        for chemistry in chemistries {
+            let curve = Curve::for_chemistry(chemistry);
+            assert_eq!(curve, SocEstimator::new(chemistry).curve());
+            assert_eq!(curve.points().len(), curve.len());
+            assert!(curve.validate_sorted().is_ok());
+        }
+        assert!(Curve::empty().points().is_empty());
+    }
+
+    #[test]
+    fn test_curve_equality() {
+        let points = [
+            CurvePoint::new(3.0, 0.0),
+            CurvePoint::new(3.5, 50.0),
+            CurvePoint::new(4.0, 100.0),
+        ];
+        let curve = Curve::new(&points);
+
+        assert_eq!(curve, Curve::new(&points));
+        assert_ne!(curve, Curve::new(&points[..2]));
+        assert_ne!(curve, default_curves::LIPO);
+        assert_eq!(Curve::new(&[]), Curve::empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_curve_serde_round_trip() {
+        extern crate alloc;
+
+        let curve = Curve::new(&[
+            CurvePoint::new(3.0, 0.0),
+            CurvePoint::new(3.5, 50.0),
+            CurvePoint::new(4.0, 100.0),
+        ]);
+
+        let json = serde_json::to_string(&curve).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"voltage_mv":3000,"soc_tenth":0},{"voltage_mv":3500,"soc_tenth":500},{"voltage_mv":4000,"soc_tenth":1000}]"#
+        );
+
+        let restored: Curve = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, curve);
+        assert_eq!(restored.voltage_to_soc(3.75).unwrap(), 75.0);
+
+        let too_long = alloc::format!("[{}]", ["[3000,0]"; MAX_CURVE_POINTS + 1].join(","));
+        assert!(serde_json::from_str::<Curve>(&too_long).is_err());
+
+        // Tables that `Curve::try_new()` rejects do not deserialize either
+        let unsorted = serde_json::from_str::<Curve>("[[3500,500],[3000,0],[4000,1000]]");
+        assert!(alloc::format!("{}", unsorted.unwrap_err()).contains("curve point 1"));
+        assert!(serde_json::from_str::<Curve>("[[3000,0]]").is_err());
+    }
+
+    #[test]
+    fn test_soc_permille_to_voltage_mv() {
+        const CURVE: Curve = Curve::new(&[
+            CurvePoint::new(3.0, 0.0),
+            CurvePoint::new(3.5, 50.0),
+            CurvePoint::new(4.0, 100.0),
+        ]);
+        const HALF: Result<u16, Error> = CURVE.voltage_mv_to_soc_permille(3_500);
+
+        assert_eq!(HALF, Ok(500));
+        assert_eq!(CURVE.soc_permille_to_voltage_mv(0), Ok(3_000));
+        assert_eq!(CURVE.soc_permille_to_voltage_mv(250), Ok(3_250));
+        assert_eq!(CURVE.soc_permille_to_voltage_mv(1000), Ok(4_000));
+        assert_eq!(CURVE.soc_permille_to_voltage_mv(u16::MAX), Ok(4_000));
+
+        // Round trip at every 10 permille
+        for permille in (0..=1000).step_by(10) {
+            let mv = CURVE.soc_permille_to_voltage_mv(permille).unwrap();
+            assert_eq!(CURVE.voltage_mv_to_soc_permille(mv), Ok(permille));
+        }
+        assert_eq!(
+            Curve::empty().soc_permille_to_voltage_mv(500),
+            Err(Error::InvalidCurve)
+        );
+    }
+
+    #[test]
+    fn test_extreme_points_do_not_overflow() {
+        let curve = Curve::new(&[
+            CurvePoint {
+                voltage_mv: 0,
+                soc_tenth: 0,
+            },
+            CurvePoint {
+                voltage_mv: u16::MAX,
+                soc_tenth: u16::MAX,
+            },
+        ]);
+
+        let soc = curve.voltage_to_soc_fixed(Fixed::from_num(30)).unwrap();
+        assert!((soc - Fixed::from_num(3000)).abs() < Fixed::from_num(0.1));
+        assert_eq!(curve.voltage_mv_to_soc_permille(40_000), Ok(40_000));
+        assert_eq!(curve.soc_permille_to_voltage_mv(40_000), Ok(40_000));
+        assert_eq!(curve.points()[1].soc_fixed(), Fixed::from_num(6553.5));
+        let voltage = curve.points()[1].voltage_fixed();
+        assert!((voltage - Fixed::from_num(65.535)).abs() <= Fixed::DELTA);
+    }
+
+    #[test]
+    fn test_precomputed_slopes() {
+        const CURVE: Curve = Curve::new(&[
+            CurvePoint::new(3.0, 0.0),
+            CurvePoint::new(3.5, 50.0),
+            CurvePoint::new(3.5, 60.0),
+            CurvePoint::new(4.0, 100.0),
+        ]);
+
+        // 50% over 500 mV, in fixed-point bits per mV scaled by 2^16
+        assert_eq!(CURVE.slopes[1], ((50i64 << 32) / 500) as i32);
+        // A zero-width segment has no slope
+        assert_eq!(CURVE.slopes[2], 0);
+
+        let soc = CURVE.voltage_to_soc_fixed(Fixed::from_num(3.25)).unwrap();
+        assert!((soc - Fixed::from_num(25)).abs() <= Fixed::DELTA);
+        let soc = CURVE.voltage_to_soc_fixed(Fixed::from_num(3.75)).unwrap();
+        assert!((soc - Fixed::from_num(80)).abs() <= Fixed::DELTA);
+    }
+
+    #[test]
+    fn test_steep_slope_divided_on_lookup() {
+        const CURVE: Curve = Curve::new(&[
+            CurvePoint::new(3.0, 0.0),
+            CurvePoint::new(3.002, 10.0),
+            CurvePoint::new(4.0, 100.0),
+        ]);
+
+        // 5% per mV does not fit the stored `i32`
+        assert_eq!(CURVE.slopes[1], STEEP_SLOPE);
+        assert_ne!(CURVE.slopes[2], STEEP_SLOPE);
+
+        let voltage = Fixed::from_num(3.0015);
+        assert_eq!(CURVE.voltage_to_soc_fixed(voltage), Ok(Fixed::from_num(5)));
+        assert_eq!(
+            CURVE.voltage_to_soc_near(voltage, &mut 0),
+            Ok(Fixed::from_num(5))
+        );
+    }
+
+    #[test]
+    fn test_lookup_repeated_boundary_voltages() {
+        let repeated = Curve::new(&[
+            CurvePoint::new(3.0, 0.0),
+            CurvePoint::new(3.0, 5.0),
+            CurvePoint::new(3.5, 50.0),
+            CurvePoint::new(3.5, 60.0),
+            CurvePoint::new(4.0, 95.0),
+            CurvePoint::new(4.0, 100.0),
+        ]);
+        assert_eq!(
+            repeated.voltage_to_soc_fixed(Fixed::from_num(3)),
+            Ok(Fixed::ZERO)
+        );
+        assert_eq!(
+            repeated.voltage_to_soc_fixed(Fixed::from_num(3.75)),
+            Ok(Fixed::from_num(77.5))
+        );
+        assert_eq!(
+            repeated.voltage_to_soc_fixed(Fixed::from_num(4)),
+            Ok(Fixed::from_num(95))
+        );
+    }
+
+    #[test]
+    fn test_sized_curve() {
+        let lipo = Curve::for_chemistry(BatteryChemistry::LiPo);
+        let small = SizedCurve::<10>::new(lipo.points());
+        assert_eq!(small.points(), lipo.points());
+        assert!(core::mem::size_of::<SizedCurve<8>>() * 3 < core::mem::size_of::<Curve>());
+
+        for mv in (3_000..=4_300).step_by(7) {
+            let voltage = Fixed::from_num(mv) / Fixed::from_num(1000);
+            assert_eq!(
+                small.voltage_to_soc_fixed(voltage),
+                lipo.voltage_to_soc_fixed(voltage)
+            );
+        }
+
+        // Points beyond the capacity are dropped
+        let truncated = SizedCurve::<4>::new(lipo.points());
+        assert_eq!(truncated.points(), &lipo.points()[..4]);
+        assert_eq!(truncated.voltage_range_fixed().1, Fixed::from_num(3.5));
+        assert!(SizedCurve::<0>::new(lipo.points()).is_empty());
+    }
+
+    #[test]
+    fn test_try_new() {
+        use crate::ErrorContext;
+
+        let lipo = Curve::for_chemistry(BatteryChemistry::LiPo);
+        assert_eq!(Curve::try_new(lipo.points()), Ok(*lipo));
+
+        let rejected_at = |points: &[CurvePoint]| match SizedCurve::<4>::try_new(points) {
+            Ok(_) => None,
+            Err(detail) => {
+                assert_eq!(detail.error, Error::InvalidCurve);
+                match detail.context {
+                    ErrorContext::CurvePoint(index) => Some(index),
+                    _ => None,
+                }
+            }
+        };
+        let a = CurvePoint::from_raw(3000, 0);
+        let b = CurvePoint::from_raw(3500, 500);
+        let c = CurvePoint::from_raw(4000, 1000);
+
+        assert_eq!(rejected_at(&[a, b, c]), None);
+        assert_eq!(rejected_at(&[]), Some(0));
+        assert_eq!(rejected_at(&[a]), Some(1));
+        assert_eq!(rejected_at(&[a, b, c, c, c]), Some(4));
+        assert_eq!(rejected_at(&[a, c, b]), Some(2));
+        assert_eq!(
+            SizedCurve::<4>::try_new(&[a, b, b]),
+            Err(Error::DuplicateVoltage.at_curve_point(2))
+        );
+        assert_eq!(rejected_at(&[a, CurvePoint::from_raw(4200, 1001)]), Some(1));
     }
 }
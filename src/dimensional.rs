@@ -0,0 +1,79 @@
+//! Integration with the `uom` dimensional analysis crate
+//!
+//! Enabled with the `uom` feature. Projects that already carry physical
+//! quantities as `uom` types can pass them to the estimator directly instead
+//! of converting to volts and degrees by hand. SOC is returned as a
+//! [`Ratio`], so `soc.get::<percent>()` and `soc.get::<ratio>()` both work.
+//!
+//! [`Ratio`]: uom::si::f32::Ratio
+
+use crate::{Error, SocEstimator};
+use uom::si::electric_potential::volt;
+use uom::si::f32::{ElectricPotential, Ratio, ThermodynamicTemperature};
+use uom::si::ratio::percent;
+use uom::si::thermodynamic_temperature::degree_celsius;
+
+impl SocEstimator {
+    /// Estimate SOC from a `uom` voltage (without temperature compensation)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, SocEstimator};
+    /// use uom::si::electric_potential::millivolt;
+    /// use uom::si::f32::ElectricPotential;
+    /// use uom::si::ratio::percent;
+    ///
+    /// let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+    ///
+    /// let soc = estimator
+    ///     .estimate_soc_uom(ElectricPotential::new::<millivolt>(3750.0))
+    ///     .unwrap();
+    /// assert!((soc.get::<percent>() - 60.0).abs() < 0.01);
+    /// ```
+    pub fn estimate_soc_uom(&self, voltage: ElectricPotential) -> Result<Ratio, Error> {
+        let soc = self.estimate_soc(voltage.get::<volt>())?;
+        Ok(Ratio::new::<percent>(soc))
+    }
+
+    /// Estimate SOC from `uom` quantities (using configuration settings)
+    ///
+    /// `uom` variant of
+    /// [`estimate_soc_compensated()`](SocEstimator::estimate_soc_compensated).
+    pub fn estimate_soc_compensated_uom(
+        &self,
+        voltage: ElectricPotential,
+        temperature: ThermodynamicTemperature,
+    ) -> Result<Ratio, Error> {
+        let soc = self
+            .estimate_soc_compensated(voltage.get::<volt>(), temperature.get::<degree_celsius>())?;
+        Ok(Ratio::new::<percent>(soc))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BatteryChemistry, Fixed};
+    use uom::si::thermodynamic_temperature::kelvin;
+
+    #[test]
+    fn test_estimate_soc_uom() {
+        let estimator = SocEstimator::with_temperature_compensation(
+            BatteryChemistry::LiPo,
+            Fixed::from_num(25),
+            Fixed::from_num(0.005),
+        );
+        let voltage = ElectricPotential::new::<volt>(3.75);
+
+        let soc = estimator.estimate_soc_uom(voltage).unwrap();
+        assert!((soc.get::<percent>() - estimator.estimate_soc(3.75).unwrap()).abs() < 1e-3);
+
+        // 273.15 K is 0 °C
+        let cold = estimator
+            .estimate_soc_compensated_uom(voltage, ThermodynamicTemperature::new::<kelvin>(273.15))
+            .unwrap();
+        let expected = estimator.estimate_soc_compensated(3.75, 0.0).unwrap();
+        assert!((cold.get::<percent>() - expected).abs() < 0.1);
+    }
+}
@@ -5,6 +5,11 @@
 
 use core::fmt;
 
+use crate::Fixed;
+
+/// Result type used throughout this crate
+pub type Result<T> = core::result::Result<T, Error>;
+
 /// Errors that can occur during battery SOC estimation
 ///
 /// This enum represents all possible error conditions that may arise
@@ -13,6 +18,7 @@ use core::fmt;
 /// # Examples
 ///
 /// ```no_run
+/// # #[cfg(not(feature = "no-float"))] {
 /// use battery_estimator::{BatteryChemistry, SocEstimator, Error};
 ///
 /// let estimator = SocEstimator::new(BatteryChemistry::LiPo);
@@ -22,26 +28,43 @@ use core::fmt;
 ///     Err(Error::InvalidCurve) => eprintln!("Invalid battery curve"),
 ///     Err(Error::NumericalError) => eprintln!("Calculation error"),
 ///     Err(Error::InvalidTemperature) => eprintln!("Invalid temperature"),
+///     Err(Error::InvalidState) => eprintln!("Invalid saved state"),
+///     Err(Error::EmptyInput) => eprintln!("No samples provided"),
+///     Err(Error::SensorFault) => eprintln!("Implausible voltage reading"),
+///     Err(Error::PackMismatch) => eprintln!("Wrong pack configuration"),
+///     Err(Error::InvalidConfig) => eprintln!("Invalid configuration"),
+///     Err(Error::SocOutOfRange) => eprintln!("SOC out of range"),
+///     Err(Error::VoltageOutOfRange) => eprintln!("Voltage outside the curve"),
+///     Err(Error::NonFiniteInput) => eprintln!("Voltage is NaN or infinite"),
+///     Err(Error::DuplicateVoltage) => eprintln!("Curve points share a voltage"),
+///     Err(Error::NonMonotonicSoc) => eprintln!("Curve SOC falls as voltage rises"),
+///     Err(Error::TemperatureCoefficientOutOfRange) => eprintln!("Bad temperature coefficient"),
+///     Err(Error::AgingFactorOutOfRange) => eprintln!("Bad aging factor"),
+///     Err(Error::NominalTemperatureOutOfRange) => eprintln!("Bad nominal temperature"),
+///     Err(Error::TemperatureOutOfRange) => eprintln!("Outside the rated temperatures"),
 /// }
+/// # }
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Error {
     /// The voltage curve data is invalid
     ///
     /// This error occurs when:
     /// - The curve has fewer than 2 points (cannot interpolate)
     /// - The curve points are not properly ordered
-    /// - The curve has duplicate voltage values
     ///
     /// # Examples
     ///
     /// ```no_run
+    /// # #[cfg(not(feature = "no-float"))] {
     /// use battery_estimator::{Curve, CurvePoint, Error};
     ///
     /// // Invalid: Only one point
     /// let invalid_curve = Curve::new(&[CurvePoint::new(3.7, 50.0)]);
     /// let result = invalid_curve.voltage_to_soc(3.7);
     /// assert!(matches!(result, Err(Error::InvalidCurve)));
+    /// # }
     /// ```
     InvalidCurve,
     /// A numerical error occurred during calculation
@@ -68,29 +91,588 @@ pub enum Error {
     /// This error occurs when:
     /// - Temperature is NaN (Not a Number)
     /// - Temperature is infinity
-    /// - Temperature is outside reasonable bounds
+    ///
+    /// Finite temperatures beyond the rated limits of the battery are
+    /// reported as [`TemperatureOutOfRange`](Error::TemperatureOutOfRange).
     ///
     /// # Examples
     ///
     /// ```no_run
+    /// # #[cfg(not(feature = "no-float"))] {
     /// use battery_estimator::{BatteryChemistry, SocEstimator, Error};
     ///
     /// let estimator = SocEstimator::new(BatteryChemistry::LiPo);
     ///
     /// // Invalid temperature
     /// let result = estimator.estimate_soc_with_temp(3.7, f32::NAN);
+    /// assert_eq!(result, Err(Error::InvalidTemperature));
+    /// # }
     /// ```
     InvalidTemperature,
+    /// A saved tracker state or configuration could not be restored
+    ///
+    /// This error occurs when:
+    /// - The state blob has a wrong checksum (e.g., uninitialized flash)
+    /// - The state blob was written by an incompatible format version
+    /// - The stored values are outside their valid ranges
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use battery_estimator::{BatteryChemistry, Error, SocEstimator, SocTracker, TrackerConfig};
+    ///
+    /// let mut tracker = SocTracker::new(
+    ///     SocEstimator::new(BatteryChemistry::LiPo),
+    ///     TrackerConfig::default(),
+    /// );
+    ///
+    /// // Erased flash reads back as all 0xFF
+    /// let result = tracker.restore_state(&[0xFF; SocTracker::STATE_SIZE]);
+    /// assert_eq!(result, Err(Error::InvalidState));
+    /// ```
+    InvalidState,
+    /// No input samples were provided
+    ///
+    /// This error occurs when a batch operation receives an empty buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use battery_estimator::{BatteryChemistry, Error, SocEstimator};
+    ///
+    /// let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+    /// let result = estimator.estimate_soc_batch(&[]);
+    /// assert!(matches!(result, Err(Error::EmptyInput)));
+    /// ```
+    EmptyInput,
+    /// A voltage reading is physically implausible
+    ///
+    /// This error occurs when:
+    /// - The voltage changed by more than the configured maximum step
+    ///   between two consecutive tracker samples (e.g., a loose sense wire
+    ///   or an ADC glitch)
+    /// - A [`VoltageSource`](crate::VoltageSource) failed to take a reading
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use battery_estimator::{BatteryChemistry, Error, Fixed, SocEstimator, SocTracker, TrackerConfig};
+    ///
+    /// let config = TrackerConfig::default().with_max_voltage_step(Fixed::from_num(0.3));
+    /// let mut tracker = SocTracker::new(SocEstimator::new(BatteryChemistry::LiPo), config);
+    ///
+    /// tracker.update(Fixed::from_num(3.8), Fixed::ZERO, Fixed::ONE).unwrap();
+    /// let result = tracker.update(Fixed::from_num(3.2), Fixed::ZERO, Fixed::ONE);
+    /// assert_eq!(result, Err(Error::SensorFault));
+    /// ```
+    SensorFault,
+    /// A measured pack voltage does not fit the configured pack
+    ///
+    /// This error occurs when:
+    /// - The per-cell voltage derived from the pack voltage and the
+    ///   configured series count is far outside the chemistry's curve
+    ///   (e.g., a 4S pack configured as 3S)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use battery_estimator::{BatteryChemistry, Error, Fixed, PackEstimator, SocEstimator};
+    ///
+    /// let pack = PackEstimator::<3>::new(SocEstimator::new(BatteryChemistry::LiPo));
+    ///
+    /// // 16.8V is a full 4S pack, impossible for 3S LiPo
+    /// let result = pack.estimate_soc_pack_voltage(Fixed::from_num(16.8));
+    /// assert_eq!(result, Err(Error::PackMismatch));
+    /// ```
+    PackMismatch,
+    /// A configuration value is outside its valid range
+    ///
+    /// This error occurs when:
+    /// - The battery age, capacity or internal resistance is negative
+    /// - The voltage divider ratio or input gain is not positive
+    /// - A builder was finished without selecting a chemistry or curve
+    /// - A quantizer step is too fine for its `u8` levels
+    ///
+    /// Out-of-range compensation parameters have their own variants, such
+    /// as [`TemperatureCoefficientOutOfRange`](Error::TemperatureCoefficientOutOfRange).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use battery_estimator::{BatteryChemistry, Error, Fixed, SocEstimator};
+    ///
+    /// let result = SocEstimator::builder()
+    ///     .chemistry(BatteryChemistry::LiPo)
+    ///     .capacity_mah(Fixed::from_num(-100))
+    ///     .build();
+    /// assert!(matches!(result, Err(Error::InvalidConfig)));
+    /// ```
+    InvalidConfig,
+    /// An estimated SOC lies outside 0–100%
+    ///
+    /// This error occurs when:
+    /// - The estimator's [`OutputPolicy`](crate::OutputPolicy) is `Reject`
+    ///   and compensation (e.g., warm temperature) or a custom curve pushed
+    ///   the SOC outside 0–100%
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[cfg(not(feature = "no-float"))] {
+    /// use battery_estimator::{BatteryChemistry, Error, EstimatorConfig, OutputPolicy, SocEstimator};
+    ///
+    /// let config = EstimatorConfig::default()
+    ///     .with_temperature_compensation()
+    ///     .with_output_policy(OutputPolicy::Reject);
+    /// let estimator = SocEstimator::with_config(BatteryChemistry::LiPo, config);
+    ///
+    /// // Full cell at 45°C reads above 100%
+    /// let result = estimator.estimate_soc_compensated(4.25, 45.0);
+    /// assert_eq!(result, Err(Error::SocOutOfRange));
+    /// # }
+    /// ```
+    SocOutOfRange,
+    /// A voltage lies outside the range of the voltage curve
+    ///
+    /// This error occurs when:
+    /// - Strict voltage range checking is enabled with
+    ///   [`EstimatorConfig::with_strict_voltage_range()`](crate::EstimatorConfig::with_strict_voltage_range)
+    ///   and the scaled voltage is below the first or above the last curve
+    ///   point, e.g., because of an open sense line or a wrong divider
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use battery_estimator::{BatteryChemistry, Error, EstimatorConfig, Fixed, SocEstimator};
+    ///
+    /// let config = EstimatorConfig::default().with_strict_voltage_range();
+    /// let estimator = SocEstimator::with_config(BatteryChemistry::LiPo, config);
+    ///
+    /// // A floating ADC input reads near zero
+    /// let result = estimator.estimate_soc_fixed(Fixed::from_num(0.05));
+    /// assert_eq!(result, Err(Error::VoltageOutOfRange));
+    /// ```
+    VoltageOutOfRange,
+    /// A floating-point input is NaN or infinite
+    ///
+    /// This error occurs when:
+    /// - A voltage passed to an `f32` or `f64` method is NaN or ±∞ (e.g., a
+    ///   failed ADC conversion divided by zero)
+    ///
+    /// Non-finite temperatures are reported as
+    /// [`InvalidTemperature`](Error::InvalidTemperature) instead.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[cfg(not(feature = "no-float"))] {
+    /// use battery_estimator::{BatteryChemistry, Error, SocEstimator};
+    ///
+    /// let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+    ///
+    /// assert_eq!(estimator.estimate_soc(f32::NAN), Err(Error::NonFiniteInput));
+    /// # }
+    /// ```
+    NonFiniteInput,
+    /// Two voltage curve points have the same voltage
+    ///
+    /// This error occurs when:
+    /// - Curve validation finds a point at the voltage of its predecessor
+    ///   (the segment between them has no width to interpolate over)
+    ///
+    /// The validation methods that return an [`ErrorDetail`] report the
+    /// index of the second point as [`ErrorContext::CurvePoint`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use battery_estimator::{Curve, CurvePoint, Error, ErrorContext};
+    ///
+    /// let curve = Curve::new(&[
+    ///     CurvePoint::new(3.0, 0.0),
+    ///     CurvePoint::new(3.6, 40.0),
+    ///     CurvePoint::new(3.6, 60.0),
+    ///     CurvePoint::new(4.2, 100.0),
+    /// ]);
+    ///
+    /// let detail = curve.validate_sorted_detail().unwrap_err();
+    /// assert_eq!(detail.error, Error::DuplicateVoltage);
+    /// assert_eq!(detail.context, ErrorContext::CurvePoint(2));
+    /// ```
+    DuplicateVoltage,
+    /// The SOC of a voltage curve decreases as the voltage increases
+    ///
+    /// This error occurs when:
+    /// - Curve validation finds a point with a lower SOC than its
+    ///   predecessor (e.g., two values swapped in a hand-written table),
+    ///   which would make the SOC fall while the battery charges
+    ///
+    /// The validation methods that return an [`ErrorDetail`] report the
+    /// index of the lower point as [`ErrorContext::CurvePoint`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use battery_estimator::{Curve, CurvePoint, Error, ErrorContext};
+    ///
+    /// let curve = Curve::new(&[
+    ///     CurvePoint::new(3.0, 0.0),
+    ///     CurvePoint::new(3.6, 60.0),
+    ///     CurvePoint::new(3.8, 40.0),
+    ///     CurvePoint::new(4.2, 100.0),
+    /// ]);
+    ///
+    /// let detail = curve.validate_sorted_detail().unwrap_err();
+    /// assert_eq!(detail.error, Error::NonMonotonicSoc);
+    /// assert_eq!(detail.context, ErrorContext::CurvePoint(2));
+    /// ```
+    NonMonotonicSoc,
+    /// The temperature compensation coefficient is outside 0 to 0.1 per °C
+    ///
+    /// This error occurs when:
+    /// - [`EstimatorConfig::validate()`](crate::EstimatorConfig::validate)
+    ///   finds a negative coefficient, or one above 10% capacity per °C
+    ///   (typical cells lose 0.3–1%; a value like 0.5 usually means a
+    ///   percentage was entered instead of a fraction)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use battery_estimator::{Error, EstimatorConfig, Fixed};
+    ///
+    /// let config = EstimatorConfig::default().with_temperature_coefficient(Fixed::from_num(0.5));
+    /// assert_eq!(config.validate(), Err(Error::TemperatureCoefficientOutOfRange));
+    /// ```
+    TemperatureCoefficientOutOfRange,
+    /// The aging factor is outside 0 to 1.0 per year
+    ///
+    /// This error occurs when:
+    /// - [`EstimatorConfig::validate()`](crate::EstimatorConfig::validate)
+    ///   finds a negative aging factor, or one above a full capacity loss
+    ///   per year (e.g., `2.0` entered for 2%/year instead of `0.02`)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use battery_estimator::{Error, EstimatorConfig, Fixed};
+    ///
+    /// let config = EstimatorConfig::default().with_aging_factor(Fixed::from_num(2));
+    /// assert_eq!(config.validate(), Err(Error::AgingFactorOutOfRange));
+    /// ```
+    AgingFactorOutOfRange,
+    /// The nominal temperature is outside −60 to 120 °C
+    ///
+    /// This error occurs when:
+    /// - [`EstimatorConfig::validate()`](crate::EstimatorConfig::validate)
+    ///   finds a nominal temperature no battery is rated at (e.g., a value
+    ///   in Kelvin or tenths of a degree)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use battery_estimator::{Error, EstimatorConfig, Fixed};
+    ///
+    /// let config = EstimatorConfig::default().with_nominal_temperature(Fixed::from_num(298));
+    /// assert_eq!(config.validate(), Err(Error::NominalTemperatureOutOfRange));
+    /// ```
+    NominalTemperatureOutOfRange,
+    /// The temperature lies outside the operating range of the battery
+    ///
+    /// This error occurs when:
+    /// - Compensated estimation receives a temperature beyond
+    ///   [`SocEstimator::operating_temperature_range()`](crate::SocEstimator::operating_temperature_range),
+    ///   where the linear compensation model would be extrapolated
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[cfg(not(feature = "no-float"))] {
+    /// use battery_estimator::{BatteryChemistry, Error, SocEstimator};
+    ///
+    /// let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+    ///
+    /// let result = estimator.estimate_soc_with_temp(3.7, -200.0);
+    /// assert_eq!(result, Err(Error::TemperatureOutOfRange));
+    /// # }
+    /// ```
+    TemperatureOutOfRange,
 }
 
+/// With the `std` feature, the alternate form (`{:#}`) appends a hint on
+/// the likely cause and fix.
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())?;
+        #[cfg(feature = "std")]
+        if f.alternate() {
+            write!(f, ": {}", self.hint())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Reports the error as [`std::io::ErrorKind::InvalidData`]
+#[cfg(feature = "std")]
+impl From<Error> for std::io::Error {
+    fn from(error: Error) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, error)
+    }
+}
+
+impl Error {
+    /// Human-readable description, shared by all formatting backends
+    pub(crate) const fn message(self) -> &'static str {
         match self {
-            Error::InvalidCurve => write!(f, "Invalid voltage curve"),
-            Error::NumericalError => write!(f, "Numerical error in calculation"),
-            Error::InvalidTemperature => write!(f, "Invalid temperature"),
+            Error::InvalidCurve => "Invalid voltage curve",
+            Error::NumericalError => "Numerical error in calculation",
+            Error::InvalidTemperature => "Invalid temperature",
+            Error::InvalidState => "Invalid saved state",
+            Error::EmptyInput => "No input samples",
+            Error::SensorFault => "Implausible sensor reading",
+            Error::PackMismatch => "Pack voltage does not match configuration",
+            Error::InvalidConfig => "Invalid configuration",
+            Error::SocOutOfRange => "SOC outside 0-100%",
+            Error::VoltageOutOfRange => "Voltage outside the curve range",
+            Error::NonFiniteInput => "Input is NaN or infinite",
+            Error::DuplicateVoltage => "Duplicate voltage in curve",
+            Error::NonMonotonicSoc => "Curve SOC decreases with voltage",
+            Error::TemperatureCoefficientOutOfRange => "Temperature coefficient out of range",
+            Error::AgingFactorOutOfRange => "Aging factor out of range",
+            Error::NominalTemperatureOutOfRange => "Nominal temperature out of range",
+            Error::TemperatureOutOfRange => "Temperature outside operating range",
         }
     }
+
+    /// Likely cause and fix, used by the alternate `Display` form
+    #[cfg(feature = "std")]
+    const fn hint(self) -> &'static str {
+        match self {
+            Error::InvalidCurve => {
+                "the curve needs at least 2 points with strictly increasing voltages"
+            }
+            Error::NumericalError => "an intermediate value overflowed; check the inputs' range",
+            Error::InvalidTemperature => "the temperature reading is not a finite number",
+            Error::InvalidState => "the saved blob is corrupt or from another version; start fresh",
+            Error::EmptyInput => "pass at least one sample",
+            Error::SensorFault => "check the sense wiring and ADC; the sample was skipped",
+            Error::PackMismatch => "check the configured series cell count and chemistry",
+            Error::InvalidConfig => "a configuration value is negative or zero where not allowed",
+            Error::SocOutOfRange => {
+                "compensation pushed the SOC past 0-100%; use OutputPolicy::Clamp"
+            }
+            Error::VoltageOutOfRange => {
+                "check the sense wiring, divider ratio and chemistry of the curve"
+            }
+            Error::NonFiniteInput => "the reading is not a finite number; check the ADC conversion",
+            Error::DuplicateVoltage => "two curve points share a voltage; merge or remove one",
+            Error::NonMonotonicSoc => "SOC must not fall as voltage rises; look for swapped values",
+            Error::TemperatureCoefficientOutOfRange => {
+                "use a fraction per °C between 0 and 0.1, e.g. 0.005 for 0.5%/°C"
+            }
+            Error::AgingFactorOutOfRange => {
+                "use a fraction per year between 0 and 1, e.g. 0.02 for 2%/year"
+            }
+            Error::NominalTemperatureOutOfRange => "use degrees Celsius between -60 and 120",
+            Error::TemperatureOutOfRange => {
+                "the battery is outside its rated temperatures; check the sensor or widen the range"
+            }
+        }
+    }
+
+    /// Returns `true` if the error is caused by a single bad input
+    ///
+    /// Recoverable errors (an implausible or non-finite reading, an empty
+    /// sample buffer) can be handled by skipping the sample or retrying the
+    /// read. The other errors stem from the curve, configuration or stored
+    /// state and repeat on every call until that is fixed, so they call for
+    /// a fault state instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::Error;
+    ///
+    /// assert!(Error::SensorFault.is_recoverable());
+    /// assert!(!Error::InvalidCurve.is_recoverable());
+    /// ```
+    pub const fn is_recoverable(self) -> bool {
+        match self {
+            Error::NumericalError
+            | Error::InvalidTemperature
+            | Error::EmptyInput
+            | Error::SensorFault
+            | Error::SocOutOfRange
+            | Error::VoltageOutOfRange
+            | Error::NonFiniteInput
+            | Error::TemperatureOutOfRange => true,
+            Error::InvalidCurve
+            | Error::InvalidState
+            | Error::PackMismatch
+            | Error::InvalidConfig
+            | Error::DuplicateVoltage
+            | Error::NonMonotonicSoc
+            | Error::TemperatureCoefficientOutOfRange
+            | Error::AgingFactorOutOfRange
+            | Error::NominalTemperatureOutOfRange => false,
+        }
+    }
+
+    /// Attach the offending voltage to this error
+    #[inline]
+    pub const fn at_voltage(self, voltage: Fixed) -> ErrorDetail {
+        ErrorDetail::new(self, ErrorContext::Voltage(voltage))
+    }
+
+    /// Attach the index of the offending curve point to this error
+    #[inline]
+    pub const fn at_curve_point(self, index: u8) -> ErrorDetail {
+        ErrorDetail::new(self, ErrorContext::CurvePoint(index))
+    }
+
+    /// Attach the measurement being processed to this error
+    ///
+    /// The voltage is rounded to whole millivolts and the temperature to
+    /// hundredths of a degree, the resolution field logs need.
+    #[inline]
+    pub const fn at_measurement(self, voltage: Fixed, temperature: Fixed) -> ErrorDetail {
+        ErrorDetail::new(
+            self,
+            ErrorContext::Measurement {
+                voltage_mv: scale_rounded(voltage, 1000),
+                temperature_cdeg: scale_rounded(temperature, 100),
+            },
+        )
+    }
+}
+
+/// `value * scale` rounded to the nearest integer
+///
+/// Cannot overflow: `|value| < 2^15`, so the result stays below 2^25 for
+/// the scales used here.
+const fn scale_rounded(value: Fixed, scale: i64) -> i32 {
+    ((value.to_bits() as i64 * scale + (1 << 15)) >> 16) as i32
+}
+
+/// The value that caused an [`ErrorDetail`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorContext {
+    /// No additional information
+    None,
+    /// The voltage that was rejected, in volts
+    Voltage(Fixed),
+    /// The temperature that was rejected, in °C
+    Temperature(Fixed),
+    /// The index of the invalid curve point
+    CurvePoint(u8),
+    /// The measurement that was being processed
+    Measurement {
+        /// Measured voltage in millivolts
+        voltage_mv: i32,
+        /// Temperature in hundredths of a °C
+        temperature_cdeg: i32,
+    },
+}
+
+/// An [`Error`] together with the value that caused it
+///
+/// [`Error`] stays a plain, payload-free enum so it can be matched cheaply
+/// everywhere. Diagnostic methods return an `ErrorDetail` instead, whose
+/// `Display` output says what went wrong, for field logs. It converts into
+/// [`Error`] with `?` or `From`.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{Curve, CurvePoint, Error, ErrorContext};
+///
+/// let curve = Curve::new(&[
+///     CurvePoint::new(3.0, 0.0),
+///     CurvePoint::new(3.8, 60.0),
+///     CurvePoint::new(3.5, 40.0),
+/// ]);
+///
+/// let detail = curve.validate_sorted_detail().unwrap_err();
+/// assert_eq!(detail.error, Error::InvalidCurve);
+/// assert_eq!(detail.context, ErrorContext::CurvePoint(2));
+/// assert_eq!(detail.to_string(), "Invalid voltage curve (curve point 2)");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ErrorDetail {
+    /// The error kind
+    pub error: Error,
+    /// The offending value
+    pub context: ErrorContext,
+}
+
+impl ErrorDetail {
+    /// Creates an error detail
+    #[inline]
+    pub const fn new(error: Error, context: ErrorContext) -> Self {
+        Self { error, context }
+    }
+}
+
+impl From<Error> for ErrorDetail {
+    #[inline]
+    fn from(error: Error) -> Self {
+        Self::new(error, ErrorContext::None)
+    }
+}
+
+impl From<ErrorDetail> for Error {
+    #[inline]
+    fn from(detail: ErrorDetail) -> Self {
+        detail.error
+    }
+}
+
+/// With the `std` feature, the alternate form (`{:#}`) appends a hint on
+/// the likely cause and fix.
+impl fmt::Display for ErrorDetail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.context {
+            ErrorContext::None => write!(f, "{}", self.error),
+            ErrorContext::Voltage(voltage) => write!(f, "{} ({} V)", self.error, voltage),
+            ErrorContext::Temperature(temperature) => {
+                write!(f, "{} ({} °C)", self.error, temperature)
+            }
+            ErrorContext::CurvePoint(index) => write!(f, "{} (curve point {})", self.error, index),
+            ErrorContext::Measurement {
+                voltage_mv,
+                temperature_cdeg,
+            } => {
+                let sign = if temperature_cdeg < 0 { "-" } else { "" };
+                let cdeg = temperature_cdeg.unsigned_abs();
+                write!(
+                    f,
+                    "{} ({} mV, {}{}.{:02} °C)",
+                    self.error,
+                    voltage_mv,
+                    sign,
+                    cdeg / 100,
+                    cdeg % 100
+                )
+            }
+        }?;
+        #[cfg(feature = "std")]
+        if f.alternate() {
+            write!(f, ": {}", self.error.hint())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ErrorDetail {}
+
+#[cfg(feature = "std")]
+impl From<ErrorDetail> for std::io::Error {
+    fn from(detail: ErrorDetail) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, detail)
+    }
 }
 
 #[cfg(test)]
@@ -107,6 +689,27 @@ mod tests {
             "Numerical error in calculation"
         );
         assert_eq!(Error::InvalidTemperature.to_string(), "Invalid temperature");
+        assert_eq!(Error::InvalidState.to_string(), "Invalid saved state");
+        assert_eq!(Error::EmptyInput.to_string(), "No input samples");
+        assert_eq!(Error::SensorFault.to_string(), "Implausible sensor reading");
+        assert_eq!(
+            Error::PackMismatch.to_string(),
+            "Pack voltage does not match configuration"
+        );
+        assert_eq!(Error::InvalidConfig.to_string(), "Invalid configuration");
+        assert_eq!(Error::SocOutOfRange.to_string(), "SOC outside 0-100%");
+        assert_eq!(
+            Error::VoltageOutOfRange.to_string(),
+            "Voltage outside the curve range"
+        );
+        assert_eq!(
+            Error::NonFiniteInput.to_string(),
+            "Input is NaN or infinite"
+        );
+        assert_eq!(
+            Error::TemperatureOutOfRange.to_string(),
+            "Temperature outside operating range"
+        );
     }
 
     #[test]
@@ -139,9 +742,23 @@ mod tests {
             Error::InvalidCurve,
             Error::NumericalError,
             Error::InvalidTemperature,
+            Error::InvalidState,
+            Error::EmptyInput,
+            Error::SensorFault,
+            Error::PackMismatch,
+            Error::InvalidConfig,
+            Error::SocOutOfRange,
+            Error::VoltageOutOfRange,
+            Error::NonFiniteInput,
+            Error::DuplicateVoltage,
+            Error::NonMonotonicSoc,
+            Error::TemperatureCoefficientOutOfRange,
+            Error::AgingFactorOutOfRange,
+            Error::NominalTemperatureOutOfRange,
+            Error::TemperatureOutOfRange,
         ];
 
-        assert_eq!(errors.len(), 3);
+        assert_eq!(errors.len(), 17);
     }
 
     #[test]
@@ -155,4 +772,86 @@ mod tests {
         assert_ne!(error2, error3);
         assert_ne!(error1, error3);
     }
+
+    #[test]
+    fn test_error_detail() {
+        let detail = Error::PackMismatch.at_voltage(Fixed::from_num(16.5));
+        assert_eq!(Error::from(detail), Error::PackMismatch);
+        assert_eq!(
+            detail.to_string(),
+            "Pack voltage does not match configuration (16.5 V)"
+        );
+
+        let detail =
+            Error::NumericalError.at_measurement(Fixed::from_num(3.7126), Fixed::from_num(-5.25));
+        assert_eq!(
+            detail.context,
+            ErrorContext::Measurement {
+                voltage_mv: 3713,
+                temperature_cdeg: -525
+            }
+        );
+        assert_eq!(
+            detail.to_string(),
+            "Numerical error in calculation (3713 mV, -5.25 °C)"
+        );
+        let extreme = Error::NumericalError.at_measurement(Fixed::MIN, Fixed::MAX);
+        assert_eq!(
+            extreme.context,
+            ErrorContext::Measurement {
+                voltage_mv: -32_768_000,
+                temperature_cdeg: 3_276_800
+            }
+        );
+
+        let plain = ErrorDetail::from(Error::EmptyInput);
+        assert_eq!(plain.context, ErrorContext::None);
+        assert_eq!(plain.to_string(), "No input samples");
+    }
+
+    #[test]
+    fn test_error_is_recoverable() {
+        let recoverable = [
+            Error::NumericalError,
+            Error::InvalidTemperature,
+            Error::EmptyInput,
+            Error::SensorFault,
+            Error::SocOutOfRange,
+            Error::VoltageOutOfRange,
+            Error::NonFiniteInput,
+            Error::TemperatureOutOfRange,
+        ];
+        let fatal = [
+            Error::InvalidCurve,
+            Error::InvalidState,
+            Error::PackMismatch,
+            Error::InvalidConfig,
+            Error::DuplicateVoltage,
+            Error::NonMonotonicSoc,
+            Error::TemperatureCoefficientOutOfRange,
+            Error::AgingFactorOutOfRange,
+            Error::NominalTemperatureOutOfRange,
+        ];
+
+        assert!(recoverable.iter().all(|e| e.is_recoverable()));
+        assert!(fatal.iter().all(|e| !e.is_recoverable()));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_error_std_integration() {
+        extern crate std;
+        use std::boxed::Box;
+
+        let boxed: Box<dyn std::error::Error + Send + Sync> = Box::new(Error::SensorFault);
+        assert_eq!(boxed.to_string(), "Implausible sensor reading");
+        assert_eq!(
+            alloc::format!("{:#}", Error::PackMismatch),
+            "Pack voltage does not match configuration: \
+             check the configured series cell count and chemistry"
+        );
+
+        let io: std::io::Error = Error::InvalidState.into();
+        assert_eq!(io.kind(), std::io::ErrorKind::InvalidData);
+    }
 }
@@ -1,14 +1,42 @@
 //! SOC (State of Charge) Estimator with Temperature Compensation
 
-use crate::curve::default_curves;
+#[cfg(not(feature = "no-float"))]
+use crate::compensation::{aging_compensated, temperature_compensated};
+#[cfg(not(feature = "no-float"))]
+use crate::default_temperature_compensation;
 use crate::{
-    compensate_aging_fixed, compensate_temperature_fixed, default_temperature_compensation_fixed,
-    BatteryChemistry, Curve, Error, Fixed,
+    compensate_aging_limited, compensate_temperature_limited,
+    default_temperature_compensation_fixed, BatteryChemistry, Celsius, CompensationLimits, Curve,
+    Error, ErrorDetail, Fixed, Percent, Volts,
 };
+use crate::{compensation, instrument, logging};
+
+/// How estimation results outside 0–100% are reported
+///
+/// Compensation can push an SOC past its limits (a full cell in warm
+/// conditions reads up to 105%), and custom curves may define values
+/// beyond 100%. The policy is applied by every `SocEstimator` and
+/// [`LiteEstimator`](crate::LiteEstimator) estimation method, so with the
+/// default [`Clamp`](OutputPolicy::Clamp) every estimate is in 0–100% or
+/// an error. Curve lookups such as [`Curve::voltage_to_soc_fixed()`]
+/// return the curve values unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum OutputPolicy {
+    /// Saturate to 0–100% (default)
+    #[default]
+    Clamp,
+    /// Return [`Error::SocOutOfRange`]
+    Reject,
+    /// Return the value unchanged
+    Raw,
+}
 
 /// SOC estimator configuration
 #[repr(C)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EstimatorConfig {
     /// Nominal temperature (°C) as fixed-point
     pub nominal_temperature: Fixed,
@@ -18,6 +46,21 @@ pub struct EstimatorConfig {
     pub age_years: Fixed,
     /// Aging factor (capacity loss percentage per year) as fixed-point
     pub aging_factor: Fixed,
+    /// Voltage divider ratio (battery voltage / ADC pin voltage) as fixed-point
+    pub divider_ratio: Fixed,
+    /// Gain correction applied to the measured voltage as fixed-point
+    pub input_gain: Fixed,
+    /// Offset correction (volts) added after the gain as fixed-point
+    pub input_offset: Fixed,
+    /// Rated battery capacity (mAh) as fixed-point, zero if unknown
+    pub capacity_mah: Fixed,
+    /// Internal resistance (ohms) as fixed-point, zero to ignore voltage sag
+    pub internal_resistance: Fixed,
+    /// Bounds of the temperature and aging compensation
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub limits: CompensationLimits,
+    /// Handling of SOC results outside 0–100%
+    pub output_policy: OutputPolicy,
     /// Compensation flags (bit field compression)
     flags: u8,
 }
@@ -31,6 +74,13 @@ impl EstimatorConfig {
             temperature_coefficient: Fixed::from_bits(328),  // 0.005
             age_years: Fixed::ZERO,
             aging_factor: Fixed::from_bits(1311), // 0.02
+            divider_ratio: Fixed::ONE,
+            input_gain: Fixed::ONE,
+            input_offset: Fixed::ZERO,
+            capacity_mah: Fixed::ZERO,
+            internal_resistance: Fixed::ZERO,
+            limits: CompensationLimits::DEFAULT,
+            output_policy: OutputPolicy::Clamp,
             flags: 0,
         }
     }
@@ -49,6 +99,21 @@ impl EstimatorConfig {
         self
     }
 
+    /// Reject voltages outside the curve range
+    ///
+    /// By default a voltage below the first or above the last curve point
+    /// reads as the SOC of that point (usually 0% or 100%), which also
+    /// masks wiring faults such as an open sense line. With strict checking,
+    /// estimation returns [`Error::VoltageOutOfRange`] instead. The scaled
+    /// voltage is rounded to whole millivolts first, so the fixed-point
+    /// value of an end point (e.g., `Fixed::from_num(3.2)`, slightly below
+    /// 3.2 V) is in range.
+    #[inline]
+    pub const fn with_strict_voltage_range(mut self) -> Self {
+        self.flags |= 0x04;
+        self
+    }
+
     /// Set nominal temperature
     #[inline]
     pub fn with_nominal_temperature(mut self, temp: Fixed) -> Self {
@@ -77,6 +142,197 @@ impl EstimatorConfig {
         self
     }
 
+    /// Set the voltage divider ratio
+    ///
+    /// For a divider with `R1` from the battery to the ADC pin and `R2`
+    /// from the pin to ground, the ratio is `(R1 + R2) / R2`.
+    #[inline]
+    pub fn with_divider_ratio(mut self, ratio: Fixed) -> Self {
+        self.divider_ratio = ratio;
+        self
+    }
+
+    /// Set the gain correction of the measurement path
+    #[inline]
+    pub fn with_input_gain(mut self, gain: Fixed) -> Self {
+        self.input_gain = gain;
+        self
+    }
+
+    /// Set the offset correction of the measurement path in volts
+    #[inline]
+    pub fn with_input_offset(mut self, offset: Fixed) -> Self {
+        self.input_offset = offset;
+        self
+    }
+
+    /// Set the rated battery capacity in mAh
+    #[inline]
+    pub fn with_capacity_mah(mut self, capacity_mah: Fixed) -> Self {
+        self.capacity_mah = capacity_mah;
+        self
+    }
+
+    /// Set the internal resistance in ohms
+    #[inline]
+    pub fn with_internal_resistance(mut self, resistance: Fixed) -> Self {
+        self.internal_resistance = resistance;
+        self
+    }
+
+    /// Set the handling of SOC results outside 0–100%
+    #[inline]
+    pub const fn with_output_policy(mut self, policy: OutputPolicy) -> Self {
+        self.output_policy = policy;
+        self
+    }
+
+    /// Set the bounds of the temperature and aging compensation
+    ///
+    /// Applied by every compensated estimate of a [`SocEstimator`], in
+    /// fixed-point, `f32` and `f64`; the default-parameter estimates such
+    /// as [`SocEstimator::estimate_soc_with_temp_fixed()`] keep
+    /// [`CompensationLimits::DEFAULT`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{
+    ///     BatteryChemistry, CompensationLimits, EstimatorConfig, Fixed, SocEstimator,
+    /// };
+    ///
+    /// // Never report more than the curve SOC in warm conditions
+    /// let config = EstimatorConfig::default()
+    ///     .with_temperature_compensation()
+    ///     .with_compensation_limits(CompensationLimits {
+    ///         max_warm_gain: Fixed::ZERO,
+    ///         ..CompensationLimits::DEFAULT
+    ///     });
+    /// let estimator = SocEstimator::with_config(BatteryChemistry::LiPo, config);
+    ///
+    /// let soc = estimator.estimate_soc_compensated_fixed(Fixed::from_num(3.75), Fixed::from_num(40));
+    /// assert_eq!(soc, Ok(Fixed::from_num(60)));
+    /// ```
+    #[inline]
+    pub const fn with_compensation_limits(mut self, limits: CompensationLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Check that the settings are physically meaningful
+    ///
+    /// Catches configurations that would silently produce odd SOC values,
+    /// so they can be rejected at initialization. A zero capacity is valid
+    /// and means "unknown"; capacity-based outputs such as
+    /// [`SocEstimator::remaining_capacity_mah()`] then return `None`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The configuration is usable
+    /// * `Err(Error::TemperatureCoefficientOutOfRange)` - The temperature
+    ///   coefficient lies outside 0 to 0.1 per °C
+    /// * `Err(Error::AgingFactorOutOfRange)` - The aging factor lies outside
+    ///   0 to 1.0 per year
+    /// * `Err(Error::NominalTemperatureOutOfRange)` - The nominal temperature
+    ///   lies outside −60 to 120 °C
+    /// * `Err(Error::InvalidConfig)` - The age, capacity or resistance is
+    ///   negative, the divider ratio or input gain is not positive, or a
+    ///   compensation limit lies outside 0 to 1
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{Error, EstimatorConfig, Fixed};
+    ///
+    /// assert_eq!(EstimatorConfig::default().validate(), Ok(()));
+    ///
+    /// let config = EstimatorConfig::default().with_nominal_temperature(Fixed::from_num(250));
+    /// assert_eq!(config.validate(), Err(Error::NominalTemperatureOutOfRange));
+    /// ```
+    pub fn validate(&self) -> Result<(), Error> {
+        const MIN_NOMINAL_TEMPERATURE: Fixed = Fixed::from_bits(-60 << 16);
+        const MAX_NOMINAL_TEMPERATURE: Fixed = Fixed::from_bits(120 << 16);
+        const MAX_TEMPERATURE_COEFFICIENT: Fixed = Fixed::from_bits(6554); // 0.1
+        const MAX_AGING_FACTOR: Fixed = Fixed::ONE;
+
+        if !(Fixed::ZERO..=MAX_TEMPERATURE_COEFFICIENT).contains(&self.temperature_coefficient) {
+            return Err(Error::TemperatureCoefficientOutOfRange);
+        }
+        if !(Fixed::ZERO..=MAX_AGING_FACTOR).contains(&self.aging_factor) {
+            return Err(Error::AgingFactorOutOfRange);
+        }
+        if !(MIN_NOMINAL_TEMPERATURE..=MAX_NOMINAL_TEMPERATURE).contains(&self.nominal_temperature)
+        {
+            return Err(Error::NominalTemperatureOutOfRange);
+        }
+
+        let non_negative = [self.age_years, self.capacity_mah, self.internal_resistance];
+        if non_negative.iter().any(|&value| value < Fixed::ZERO)
+            || self.divider_ratio <= Fixed::ZERO
+            || self.input_gain <= Fixed::ZERO
+            || !self.limits.is_valid()
+        {
+            return Err(Error::InvalidConfig);
+        }
+        Ok(())
+    }
+
+    /// Apply the [`OutputPolicy`] to a SOC in any precision
+    fn apply_output_policy<T: compensation::Precision>(&self, soc: T) -> Result<T, Error> {
+        match self.output_policy {
+            OutputPolicy::Clamp => Ok(soc.max(T::ZERO).min(T::HUNDRED)),
+            OutputPolicy::Reject if !(soc >= T::ZERO && soc <= T::HUNDRED) => {
+                Err(Error::SocOutOfRange)
+            }
+            OutputPolicy::Reject | OutputPolicy::Raw => Ok(soc),
+        }
+    }
+
+    /// Converts a measured voltage into the battery voltage
+    ///
+    /// Applies `(measured * input_gain + input_offset) * divider_ratio`.
+    /// The default configuration passes voltages through unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{EstimatorConfig, Fixed};
+    ///
+    /// // 100k / 100k divider in front of the ADC
+    /// let config = EstimatorConfig::default().with_divider_ratio(Fixed::from_num(2));
+    ///
+    /// assert_eq!(config.scale_voltage(Fixed::from_num(1.875)), Fixed::from_num(3.75));
+    /// ```
+    #[inline]
+    pub fn scale_voltage(&self, measured: Fixed) -> Fixed {
+        instrument::ops(2, 0);
+        measured
+            .saturating_mul(self.input_gain)
+            .saturating_add(self.input_offset)
+            .saturating_mul(self.divider_ratio)
+    }
+
+    /// Integer variant of [`scale_voltage()`](Self::scale_voltage) in millivolts
+    ///
+    /// Works on the raw fixed-point bits so the default configuration maps
+    /// every millivolt value onto itself.
+    #[inline]
+    fn scale_voltage_mv(&self, measured_mv: u16) -> u16 {
+        let mut scaled = measured_mv as i64 * self.input_gain.to_bits() as i64;
+        scaled += self.input_offset.to_bits() as i64 * 1000;
+        // Saturates only far outside the u16 range clamped to below
+        scaled = scaled.saturating_mul(self.divider_ratio.to_bits() as i64) >> 16;
+        (scaled >> 16).clamp(0, u16::MAX as i64) as u16
+    }
+
+    /// Double-precision variant of [`scale_voltage()`](Self::scale_voltage)
+    #[cfg(not(feature = "no-float"))]
+    #[inline]
+    fn scale_voltage_f64(&self, measured: f64) -> f64 {
+        (measured * self.input_gain.to_num::<f64>() + self.input_offset.to_num::<f64>())
+            * self.divider_ratio.to_num::<f64>()
+    }
+
     /// Returns `true` if temperature compensation is enabled
     pub const fn is_temperature_compensation_enabled(self) -> bool {
         (self.flags & 0x01) != 0
@@ -86,6 +342,11 @@ impl EstimatorConfig {
     pub const fn is_aging_compensation_enabled(self) -> bool {
         (self.flags & 0x02) != 0
     }
+
+    /// Returns `true` if voltages outside the curve range are rejected
+    pub const fn is_strict_voltage_range_enabled(self) -> bool {
+        (self.flags & 0x04) != 0
+    }
 }
 
 // Non-const Default implementation
@@ -96,47 +357,227 @@ impl Default for EstimatorConfig {
     }
 }
 
+/// Result of a batch estimation over a buffer of voltage samples
+///
+/// Produced by [`SocEstimator::estimate_soc_batch()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchEstimate {
+    /// SOC percentage of the averaged voltage as fixed-point
+    pub soc: Fixed,
+    /// Mean voltage of the buffer as fixed-point
+    pub mean_voltage: Fixed,
+    /// Lowest voltage in the buffer as fixed-point
+    pub min_voltage: Fixed,
+    /// Highest voltage in the buffer as fixed-point
+    pub max_voltage: Fixed,
+    /// Number of samples processed
+    pub count: usize,
+}
+
+/// Common interface of all voltage-based SOC estimators
+///
+/// Implemented by [`SocEstimator`], [`SocTracker`](crate::SocTracker) and
+/// [`PackEstimator`](crate::PackEstimator), so application code can hold a
+/// `&dyn SocEstimate` and swap the implementation at runtime.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{BatteryChemistry, Fixed, PackEstimator, SocEstimate, SocEstimator};
+///
+/// let cell = SocEstimator::new(BatteryChemistry::LiPo);
+/// let pack = PackEstimator::<2>::new(cell);
+///
+/// let gauges: [(&dyn SocEstimate, Fixed); 2] = [
+///     (&cell, Fixed::from_num(3.75)),
+///     (&pack, Fixed::from_num(7.5)),
+/// ];
+/// for (gauge, voltage) in gauges {
+///     assert_eq!(gauge.estimate_soc_fixed(voltage).unwrap(), Fixed::from_num(60));
+/// }
+/// ```
+pub trait SocEstimate {
+    /// Estimate SOC from a voltage as fixed-point
+    fn estimate_soc_fixed(&self, voltage: Fixed) -> Result<Fixed, Error>;
+
+    /// Voltage range covered by the estimator as fixed-point values
+    fn voltage_range_fixed(&self) -> (Fixed, Fixed);
+
+    /// Estimate SOC from a typed voltage
+    ///
+    /// Same as [`estimate_soc_fixed()`](Self::estimate_soc_fixed), but the
+    /// unit types make it impossible to pass a temperature by mistake.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{
+    ///     BatteryChemistry, Fixed, Percent, SocEstimate, SocEstimator, Volts,
+    /// };
+    ///
+    /// let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+    /// let gauge: &dyn SocEstimate = &estimator;
+    ///
+    /// let soc = gauge.estimate(Volts::from_millivolts(3750)).unwrap();
+    /// assert_eq!(soc, Percent::new(Fixed::from_num(60)));
+    /// ```
+    #[inline]
+    fn estimate(&self, voltage: Volts) -> Result<Percent, Error> {
+        self.estimate_soc_fixed(voltage.raw()).map(Percent::new)
+    }
+}
+
+/// Operating temperature range in °C assumed for custom curves, the
+/// industrial range of most battery-powered electronics
+const DEFAULT_TEMPERATURE_RANGE: (Fixed, Fixed) =
+    (Fixed::from_bits(-40 << 16), Fixed::from_bits(85 << 16));
+
 /// SOC estimator
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SocEstimator {
     curve: &'static Curve,
     config: EstimatorConfig,
+    temperature_range: (Fixed, Fixed),
+    #[cfg(not(feature = "no-float"))]
+    config_f32: ConfigF32,
 }
 
-impl SocEstimator {
-    /// Create a new SOC estimator (default configuration)
-    pub const fn new(chemistry: BatteryChemistry) -> Self {
-        let curve = match chemistry {
-            BatteryChemistry::LiPo => &default_curves::LIPO,
-            BatteryChemistry::LiFePO4 => &default_curves::LIFEPO4,
-            BatteryChemistry::LiIon => &default_curves::LIION,
-            BatteryChemistry::Lipo410Full340Cutoff => &default_curves::LIPO410_FULL340_CUTOFF,
-        };
+/// The configuration values of the `f32` estimates, converted once when
+/// the configuration is set
+#[cfg(not(feature = "no-float"))]
+#[derive(Debug, Clone, Copy)]
+struct ConfigF32 {
+    input_gain: f32,
+    input_offset: f32,
+    divider_ratio: f32,
+    nominal_temperature: f32,
+    temperature_coefficient: f32,
+    age_years: f32,
+    aging_factor: f32,
+}
 
+#[cfg(not(feature = "no-float"))]
+impl ConfigF32 {
+    const fn new(config: &EstimatorConfig) -> Self {
+        // Exact power-of-two division, so this rounds like `to_num::<f32>()`
+        const fn to_f32(value: Fixed) -> f32 {
+            value.to_bits() as f32 / 65536.0
+        }
         Self {
-            curve,
-            config: EstimatorConfig::default(),
+            input_gain: to_f32(config.input_gain),
+            input_offset: to_f32(config.input_offset),
+            divider_ratio: to_f32(config.divider_ratio),
+            nominal_temperature: to_f32(config.nominal_temperature),
+            temperature_coefficient: to_f32(config.temperature_coefficient),
+            age_years: to_f32(config.age_years),
+            aging_factor: to_f32(config.aging_factor),
         }
     }
 
+    /// Single-precision variant of [`EstimatorConfig::scale_voltage()`]
+    #[inline]
+    fn scale_voltage(&self, measured: f32) -> f32 {
+        (measured * self.input_gain + self.input_offset) * self.divider_ratio
+    }
+}
+
+// Derived from the configuration, which `SocEstimator` compares already
+#[cfg(not(feature = "no-float"))]
+impl PartialEq for ConfigF32 {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+#[cfg(not(feature = "no-float"))]
+impl Eq for ConfigF32 {}
+
+impl SocEstimator {
+    /// Create a new SOC estimator (default configuration)
+    pub const fn new(chemistry: BatteryChemistry) -> Self {
+        Self::with_config(chemistry, EstimatorConfig::default())
+    }
+
     /// Create estimator with custom curve
+    ///
+    /// The operating temperature range defaults to −40 to 85 °C; use
+    /// [`with_operating_temperature_range()`](Self::with_operating_temperature_range)
+    /// to match the cell datasheet.
     pub const fn with_custom_curve(curve: &'static Curve) -> Self {
         Self {
             curve,
             config: EstimatorConfig::default(),
+            temperature_range: DEFAULT_TEMPERATURE_RANGE,
+            #[cfg(not(feature = "no-float"))]
+            config_f32: ConfigF32::new(&EstimatorConfig::default()),
         }
     }
 
     /// Create estimator with configuration (const version)
     pub const fn with_config(chemistry: BatteryChemistry, config: EstimatorConfig) -> Self {
-        let curve = match chemistry {
-            BatteryChemistry::LiPo => &default_curves::LIPO,
-            BatteryChemistry::LiFePO4 => &default_curves::LIFEPO4,
-            BatteryChemistry::LiIon => &default_curves::LIION,
-            BatteryChemistry::Lipo410Full340Cutoff => &default_curves::LIPO410_FULL340_CUTOFF,
-        };
+        Self {
+            curve: Curve::for_chemistry(chemistry),
+            config,
+            temperature_range: chemistry.operating_temperature_range(),
+            #[cfg(not(feature = "no-float"))]
+            config_f32: ConfigF32::new(&config),
+        }
+    }
+
+    /// Replace the operating temperature range in °C
+    ///
+    /// Compensated estimates return [`Error::TemperatureOutOfRange`] for
+    /// temperatures outside `min..=max`. Defaults to
+    /// [`BatteryChemistry::operating_temperature_range()`] for built-in
+    /// curves.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, Error, Fixed, SocEstimator};
+    ///
+    /// let estimator = SocEstimator::new(BatteryChemistry::LiPo)
+    ///     .with_operating_temperature_range(Fixed::from_num(0), Fixed::from_num(45));
+    ///
+    /// assert!(estimator.estimate_soc_with_temp_fixed(Fixed::from_num(3.7), Fixed::from_num(40)).is_ok());
+    /// assert_eq!(
+    ///     estimator.estimate_soc_with_temp_fixed(Fixed::from_num(3.7), Fixed::from_num(-10)),
+    ///     Err(Error::TemperatureOutOfRange)
+    /// );
+    /// ```
+    #[inline]
+    pub const fn with_operating_temperature_range(mut self, min: Fixed, max: Fixed) -> Self {
+        self.temperature_range = (min, max);
+        self
+    }
+
+    /// Operating temperature range in °C as `(min, max)`
+    #[inline]
+    pub const fn operating_temperature_range(&self) -> (Fixed, Fixed) {
+        self.temperature_range
+    }
 
-        Self { curve, config }
+    /// Start building an estimator with [`SocEstimatorBuilder`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, Fixed, SocEstimator};
+    ///
+    /// let estimator = SocEstimator::builder()
+    ///     .chemistry(BatteryChemistry::LiPo)
+    ///     .temperature_compensation(Fixed::from_num(25), Fixed::from_num(0.005))
+    ///     .aging_compensation(Fixed::from_num(2), Fixed::from_num(0.02))
+    ///     .capacity_mah(Fixed::from_num(2200))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(estimator.config().is_temperature_compensation_enabled());
+    /// assert!(estimator.config().is_aging_compensation_enabled());
+    /// ```
+    #[inline]
+    pub const fn builder() -> SocEstimatorBuilder {
+        SocEstimatorBuilder::new()
     }
 
     /// Estimate SOC using fixed-point arithmetic (without temperature compensation)
@@ -150,12 +591,211 @@ impl SocEstimator {
     /// * `Ok(soc)` - SOC percentage as fixed-point value
     /// * `Err(Error)` - Error if estimation fails
     pub fn estimate_soc_fixed(&self, voltage: Fixed) -> Result<Fixed, Error> {
-        self.curve.voltage_to_soc_fixed(voltage)
+        let soc = self.lookup(voltage)?;
+        self.finish(voltage, soc, soc, 0)
     }
 
     /// Estimate SOC (without temperature compensation)
+    ///
+    /// The lookup runs in `f32` on the unrounded voltage, so results can
+    /// differ from [`estimate_soc_fixed()`](Self::estimate_soc_fixed) by the
+    /// fixed-point rounding. Returns [`Error::NonFiniteInput`] for a NaN or infinite voltage; the
+    /// other `f32` and `f64` methods check their inputs the same way, and
+    /// report a non-finite temperature as [`Error::InvalidTemperature`].
+    #[cfg(not(feature = "no-float"))]
     pub fn estimate_soc(&self, voltage: f32) -> Result<f32, Error> {
-        self.curve.voltage_to_soc(voltage)
+        let soc = self.raw_soc_f32(voltage)?;
+        self.finish(voltage, soc, soc, 0)
+    }
+
+    /// Estimate SOC from a whole buffer of voltage samples
+    ///
+    /// Averages the buffer (e.g., one DMA transfer of ADC readings taken at
+    /// kHz rates) and performs a single curve lookup on the mean voltage,
+    /// so per-sample noise is rejected without a lookup per sample. The
+    /// sum is accumulated in 64 bits and cannot overflow for buffers of up
+    /// to 2^32 samples.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - Battery voltages as fixed-point values
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(estimate)` - SOC of the mean voltage plus buffer statistics
+    /// * `Err(Error::EmptyInput)` - The buffer is empty
+    /// * `Err(Error::NumericalError)` - The sum of a larger buffer overflowed
+    /// * `Err(Error)` - The curve lookup failed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, Fixed, SocEstimator};
+    ///
+    /// let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+    /// let samples = [Fixed::from_num(3.74), Fixed::from_num(3.76), Fixed::from_num(3.75)];
+    ///
+    /// let estimate = estimator.estimate_soc_batch(&samples).unwrap();
+    /// assert_eq!(estimate.count, 3);
+    /// assert_eq!(estimate.min_voltage, Fixed::from_num(3.74));
+    /// assert!((estimate.soc - Fixed::from_num(60.0)).abs() < Fixed::from_num(0.5));
+    /// ```
+    pub fn estimate_soc_batch(&self, samples: &[Fixed]) -> Result<BatchEstimate, Error> {
+        let first = *samples.first().ok_or(Error::EmptyInput)?;
+
+        let mut sum: i64 = 0;
+        let mut min_voltage = first;
+        let mut max_voltage = first;
+        for &sample in samples {
+            sum = sum
+                .checked_add(sample.to_bits() as i64)
+                .ok_or(Error::NumericalError)?;
+            min_voltage = min_voltage.min(sample);
+            max_voltage = max_voltage.max(sample);
+        }
+
+        // The mean of I16F16 values always fits back into I16F16
+        let mean_voltage = Fixed::from_bits((sum / samples.len() as i64) as i32);
+        let soc = self.estimate_soc_fixed(mean_voltage)?;
+
+        Ok(BatchEstimate {
+            soc,
+            mean_voltage,
+            min_voltage,
+            max_voltage,
+            count: samples.len(),
+        })
+    }
+
+    /// Estimate the SOC of every sample in a buffer
+    ///
+    /// Writes `socs[i]` as [`estimate_soc_fixed()`](Self::estimate_soc_fixed)
+    /// of `voltages[i]`, for post-processing DMA buffers or logs sample by
+    /// sample (see [`estimate_soc_batch()`](Self::estimate_soc_batch) for
+    /// one estimate of the whole buffer). The curve is checked once, and
+    /// each lookup starts from the previous sample's curve segment, so
+    /// slowly changing voltages skip the binary search. With the `simd`
+    /// feature, sorted curves are looked up eight samples at a time with
+    /// identical results. With the `cmsis-dsp` feature on Arm targets, the
+    /// measurement scaling and clamping run through CMSIS-DSP kernels,
+    /// whose rounding of a non-default input gain or divider ratio can move
+    /// a sample 1 mV lower.
+    ///
+    /// # Arguments
+    ///
+    /// * `voltages` - Battery voltages as fixed-point values
+    /// * `socs` - Output buffer of the same length
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Every SOC was written
+    /// * `Err(Error::InvalidConfig)` - The buffers differ in length
+    /// * `Err(Error)` - A lookup failed; earlier outputs are written, the
+    ///   rest are unchanged
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, Fixed, SocEstimator};
+    ///
+    /// let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+    /// let voltages = [Fixed::from_num(4.3), Fixed::from_num(3.75), Fixed::from_num(3.1)];
+    /// let mut socs = [Fixed::ZERO; 3];
+    ///
+    /// estimator.estimate_soc_slice(&voltages, &mut socs).unwrap();
+    /// assert_eq!(socs[0], Fixed::from_num(100));
+    /// assert_eq!(socs[2], Fixed::ZERO);
+    /// ```
+    pub fn estimate_soc_slice(&self, voltages: &[Fixed], socs: &mut [Fixed]) -> Result<(), Error> {
+        if voltages.len() != socs.len() {
+            return Err(Error::InvalidConfig);
+        }
+        if self.curve.len() < 2 {
+            return Err(Error::InvalidCurve);
+        }
+
+        #[cfg(all(feature = "cmsis-dsp", target_arch = "arm"))]
+        let (voltages, socs) = self.estimate_soc_slice_cmsis(voltages, socs)?;
+
+        #[cfg(all(feature = "simd", nightly))]
+        let (voltages, socs) = match crate::simd::SimdCurve::new(self.curve, &self.config) {
+            Some(simd) => {
+                let full = voltages.len() - voltages.len() % crate::simd::LANES;
+                let (chunks, rest) = voltages.split_at(full);
+                let (soc_chunks, soc_rest) = socs.split_at_mut(full);
+                for (measured, out) in chunks
+                    .chunks_exact(crate::simd::LANES)
+                    .zip(soc_chunks.chunks_exact_mut(crate::simd::LANES))
+                {
+                    let scaled = simd.scale(measured);
+                    let raw = simd.lookup(&scaled);
+                    for ((&voltage, &raw), soc) in scaled.iter().zip(&raw).zip(out) {
+                        self.check_voltage_range(voltage)?;
+                        *soc = self.config.apply_output_policy(raw)?;
+                    }
+                }
+                (rest, soc_rest)
+            }
+            None => (voltages, socs),
+        };
+
+        let mut segment = 0;
+        for (&voltage, soc) in voltages.iter().zip(socs.iter_mut()) {
+            let voltage = self.config.scale_voltage(voltage);
+            self.check_voltage_range(voltage)?;
+            let raw = self.curve.voltage_to_soc_near(voltage, &mut segment)?;
+            *soc = self.config.apply_output_policy(raw)?;
+        }
+        Ok(())
+    }
+
+    /// [`estimate_soc_slice()`](Self::estimate_soc_slice) with CMSIS-DSP
+    /// scaling and clamping
+    ///
+    /// Converts every sample and returns the (empty) remainder for the
+    /// portable paths.
+    #[cfg(all(feature = "cmsis-dsp", any(target_arch = "arm", test)))]
+    fn estimate_soc_slice_cmsis<'v, 's>(
+        &self,
+        voltages: &'v [Fixed],
+        socs: &'s mut [Fixed],
+    ) -> Result<(&'v [Fixed], &'s mut [Fixed]), Error> {
+        use crate::cmsis;
+
+        // Scale into the output buffer, skipping the lossy kernel for
+        // factors of 1
+        let config = &self.config;
+        if config.input_gain == Fixed::ONE {
+            socs.copy_from_slice(voltages);
+        } else {
+            cmsis::scale(voltages, config.input_gain, socs);
+        }
+        if config.input_offset != Fixed::ZERO {
+            cmsis::offset_in_place(socs, config.input_offset);
+        }
+        if config.divider_ratio != Fixed::ONE {
+            cmsis::scale_in_place(socs, config.divider_ratio);
+        }
+
+        let mut segment = 0;
+        for soc in socs.iter_mut() {
+            self.check_voltage_range(*soc)?;
+            *soc = self.curve.voltage_to_soc_near(*soc, &mut segment)?;
+        }
+
+        match config.output_policy {
+            OutputPolicy::Clamp => {
+                cmsis::clip_in_place(socs, Fixed::ZERO, Fixed::from_bits(100 << 16));
+            }
+            OutputPolicy::Reject | OutputPolicy::Raw => {
+                for soc in socs.iter_mut() {
+                    *soc = config.apply_output_policy(*soc)?;
+                }
+            }
+        }
+
+        let len = socs.len();
+        Ok((&voltages[len..], &mut socs[len..]))
     }
 
     /// Estimate SOC with default temperature compensation using fixed-point arithmetic
@@ -171,15 +811,18 @@ impl SocEstimator {
     ///
     /// # Returns
     ///
-    /// Temperature-compensated SOC percentage using default parameters
+    /// * `Ok(soc)` - Temperature-compensated SOC percentage using default parameters
+    /// * `Err(Error::TemperatureOutOfRange)` - The temperature lies outside
+    ///   the [operating range](Self::operating_temperature_range)
     pub fn estimate_soc_with_temp_fixed(
         &self,
         voltage: Fixed,
         temperature: Fixed,
     ) -> Result<Fixed, Error> {
-        let base_soc = self.curve.voltage_to_soc_fixed(voltage)?;
+        let base_soc = self.lookup(voltage)?;
+        self.check_temperature(temperature)?;
         let compensated = default_temperature_compensation_fixed(base_soc, temperature);
-        Ok(compensated.clamp(Fixed::ZERO, Fixed::from_num(100)))
+        self.finish(voltage, base_soc, compensated, logging::FLAG_TEMPERATURE)
     }
 
     /// Estimate SOC with default temperature compensation (ignores configuration)
@@ -196,19 +839,19 @@ impl SocEstimator {
     ///
     /// # Returns
     ///
-    /// Temperature-compensated SOC percentage using default parameters
+    /// * `Ok(soc)` - Temperature-compensated SOC percentage using default parameters
+    /// * `Err(Error::NonFiniteInput)` - The voltage is NaN or infinite
+    /// * `Err(Error::InvalidTemperature)` - The temperature is NaN or infinite
+    /// * `Err(Error::TemperatureOutOfRange)` - The temperature lies outside
+    ///   the [operating range](Self::operating_temperature_range)
+    #[cfg(not(feature = "no-float"))]
     pub fn estimate_soc_with_temp(&self, voltage: f32, temperature: f32) -> Result<f32, Error> {
-        let base_soc = self.curve.voltage_to_soc(voltage)?;
+        let base_soc = self.raw_soc_f32(voltage)?;
+        self.check_temperature_f64(temperature.into())?;
 
         // Always apply temperature compensation with default parameters
-        let compensated = default_temperature_compensation_fixed(
-            Fixed::from_num(base_soc),
-            Fixed::from_num(temperature),
-        );
-
-        Ok(compensated
-            .clamp(Fixed::ZERO, Fixed::from_num(100))
-            .to_num::<f32>())
+        let compensated = default_temperature_compensation(base_soc, temperature);
+        self.finish(voltage, base_soc, compensated, logging::FLAG_TEMPERATURE)
     }
 
     /// Estimate SOC using configuration settings with fixed-point arithmetic
@@ -220,83 +863,590 @@ impl SocEstimator {
     ///
     /// # Returns
     ///
-    /// Compensated SOC percentage as fixed-point value
+    /// * `Ok(soc)` - Compensated SOC percentage as fixed-point value
+    /// * `Err(Error::TemperatureOutOfRange)` - Temperature compensation is
+    ///   enabled and the temperature lies outside the
+    ///   [operating range](Self::operating_temperature_range)
+    /// * `Err(Error::NumericalError)` - The compensation overflowed the
+    ///   fixed-point range (e.g., an absurd temperature or coefficient)
+    /// * `Err(Error)` - The curve lookup failed
     pub fn estimate_soc_compensated_fixed(
         &self,
         voltage: Fixed,
         temperature: Fixed,
     ) -> Result<Fixed, Error> {
-        let base_soc = self.curve.voltage_to_soc_fixed(voltage)?;
+        let base_soc = self.lookup(voltage)?;
+        self.compensate(voltage, base_soc, temperature, true)
+    }
+
+    /// [`estimate_soc_compensated_fixed()`](Self::estimate_soc_compensated_fixed)
+    /// without the aging compensation
+    ///
+    /// For callers that derate by their own state of health, such as the
+    /// per-cell SOH of a [`PackEstimator`](crate::PackEstimator).
+    pub(crate) fn estimate_soc_temperature_compensated_fixed(
+        &self,
+        voltage: Fixed,
+        temperature: Fixed,
+    ) -> Result<Fixed, Error> {
+        let base_soc = self.lookup(voltage)?;
+        self.compensate(voltage, base_soc, temperature, false)
+    }
+
+    /// Like
+    /// [`estimate_soc_compensated_fixed()`](Self::estimate_soc_compensated_fixed),
+    /// but reports the measurement that caused an error
+    ///
+    /// The error carries an [`ErrorContext::Measurement`](crate::ErrorContext::Measurement)
+    /// with the voltage as passed in (before divider and calibration
+    /// scaling), so field logs show what the estimator was given.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, ErrorContext, Fixed, SocEstimator};
+    ///
+    /// let estimator = SocEstimator::with_temperature_compensation(
+    ///     BatteryChemistry::LiPo,
+    ///     Fixed::from_num(25),
+    ///     Fixed::from_num(0.005),
+    /// );
+    ///
+    /// let detail = estimator
+    ///     .estimate_soc_compensated_detail(Fixed::from_num(3.7), Fixed::from_num(75))
+    ///     .unwrap_err();
+    /// assert_eq!(
+    ///     detail.context,
+    ///     ErrorContext::Measurement { voltage_mv: 3700, temperature_cdeg: 7500 }
+    /// );
+    /// assert_eq!(
+    ///     detail.to_string(),
+    ///     "Temperature outside operating range (3700 mV, 75.00 °C)"
+    /// );
+    /// ```
+    pub fn estimate_soc_compensated_detail(
+        &self,
+        voltage: Fixed,
+        temperature: Fixed,
+    ) -> Result<Fixed, ErrorDetail> {
+        self.estimate_soc_compensated_fixed(voltage, temperature)
+            .map_err(|error| error.at_measurement(voltage, temperature))
+    }
+
+    /// [`estimate_soc_compensated_fixed()`](Self::estimate_soc_compensated_fixed)
+    /// starting the curve lookup from the segment of a previous estimate
+    ///
+    /// `segment` is updated to the segment of `voltage`; see
+    /// [`Curve::voltage_to_soc_near()`].
+    pub(crate) fn estimate_soc_compensated_near(
+        &self,
+        voltage: Fixed,
+        temperature: Fixed,
+        segment: &mut usize,
+    ) -> Result<Fixed, Error> {
+        let scaled = self.config.scale_voltage(voltage);
+        self.check_voltage_range(scaled)?;
+        let base_soc = self.curve.voltage_to_soc_near(scaled, segment)?;
+        self.compensate(voltage, base_soc, temperature, true)
+    }
+
+    /// Configured compensation and output policy applied to a curve SOC
+    ///
+    /// Aging compensation is skipped when `aging` is false.
+    fn compensate(
+        &self,
+        voltage: Fixed,
+        base_soc: Fixed,
+        temperature: Fixed,
+        aging: bool,
+    ) -> Result<Fixed, Error> {
         let mut soc = base_soc;
+        let mut flags = 0;
 
         if self.config.is_temperature_compensation_enabled() {
-            soc = compensate_temperature_fixed(
+            self.check_temperature(temperature)?;
+            soc = compensate_temperature_limited(
                 soc,
                 temperature,
                 self.config.nominal_temperature,
                 self.config.temperature_coefficient,
-            );
+                &self.config.limits,
+            )
+            .inspect_err(|_| logging::overflow("temperature", soc))?;
+            flags |= logging::FLAG_TEMPERATURE;
         }
 
-        if self.config.is_aging_compensation_enabled() {
-            soc = compensate_aging_fixed(soc, self.config.age_years, self.config.aging_factor);
+        if aging && self.config.is_aging_compensation_enabled() {
+            soc = compensate_aging_limited(
+                soc,
+                self.config.age_years,
+                self.config.aging_factor,
+                &self.config.limits,
+            )
+            .inspect_err(|_| logging::overflow("aging", soc))?;
+            flags |= logging::FLAG_AGING;
         }
 
-        Ok(soc.clamp(Fixed::ZERO, Fixed::from_num(100)))
+        self.finish(voltage, base_soc, soc, flags)
+    }
+
+    /// Output policy and estimate record shared by the single-voltage
+    /// estimates of every precision
+    ///
+    /// `voltage` is the measured voltage, `base_soc` the curve SOC and
+    /// `flags` the [`logging`] flags of the compensation applied to `soc`.
+    #[inline]
+    fn finish<T: compensation::Precision + logging::Logged>(
+        &self,
+        voltage: T,
+        base_soc: T,
+        soc: T,
+        flags: u8,
+    ) -> Result<T, Error> {
+        let result = self.config.apply_output_policy(soc);
+        logging::estimate(voltage, base_soc, soc, &result, flags);
+        result
     }
 
     /// Estimate SOC (using configuration settings)
+    ///
+    /// The compensation runs in `f32`; results can differ from
+    /// [`estimate_soc_compensated_fixed()`](Self::estimate_soc_compensated_fixed)
+    /// by the fixed-point rounding.
+    #[cfg(not(feature = "no-float"))]
     pub fn estimate_soc_compensated(&self, voltage: f32, temperature: f32) -> Result<f32, Error> {
-        let result = self.estimate_soc_compensated_fixed(
-            Fixed::from_num(voltage),
-            Fixed::from_num(temperature),
-        )?;
-        Ok(result.to_num::<f32>())
-    }
+        let base_soc = self.raw_soc_f32(voltage)?;
+        let mut soc = base_soc;
+        let mut flags = 0;
+        if !temperature.is_finite() {
+            return Err(Error::InvalidTemperature);
+        }
 
-    /// Get voltage range
-    pub const fn voltage_range(&self) -> (f32, f32) {
-        self.curve.voltage_range()
-    }
+        if self.config.is_temperature_compensation_enabled() {
+            self.check_temperature_f64(temperature.into())?;
+            soc = temperature_compensated(
+                soc,
+                temperature,
+                self.config_f32.nominal_temperature,
+                self.config_f32.temperature_coefficient,
+                &self.config.limits,
+            );
+            flags |= logging::FLAG_TEMPERATURE;
+        }
 
-    /// Get voltage range as fixed-point values
-    pub fn voltage_range_fixed(&self) -> (Fixed, Fixed) {
-        self.curve.voltage_range_fixed()
-    }
+        if self.config.is_aging_compensation_enabled() {
+            soc = aging_compensated(
+                soc,
+                self.config_f32.age_years,
+                self.config_f32.aging_factor,
+                &self.config.limits,
+            );
+            flags |= logging::FLAG_AGING;
+        }
 
-    /// Update configuration
-    #[inline]
-    pub fn update_config(&mut self, config: EstimatorConfig) {
-        self.config = config;
+        self.finish(voltage, base_soc, soc, flags)
     }
 
-    /// Get current configuration
-    #[inline]
-    pub const fn config(&self) -> &EstimatorConfig {
-        &self.config
+    /// Estimate SOC in double precision (without temperature compensation)
+    ///
+    /// Intended for host-side users such as log replay or simulation. The
+    /// voltage is neither truncated to millivolts nor converted to
+    /// fixed-point, so results are continuous in the input voltage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, SocEstimator};
+    ///
+    /// let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+    ///
+    /// let soc = estimator.estimate_soc_f64(3.75).unwrap();
+    /// assert!((soc - 60.0).abs() < 1e-9);
+    /// ```
+    #[cfg(not(feature = "no-float"))]
+    pub fn estimate_soc_f64(&self, voltage: f64) -> Result<f64, Error> {
+        self.estimate_soc_f64_on(self.curve, voltage)
+    }
+
+    /// [`estimate_soc_f64()`](Self::estimate_soc_f64) on `curve` instead of
+    /// the estimator's curve
+    ///
+    /// For bindings that own their curve and cannot lend it for `'static`.
+    #[cfg(not(feature = "no-float"))]
+    pub(crate) fn estimate_soc_f64_on(&self, curve: &Curve, voltage: f64) -> Result<f64, Error> {
+        let soc = self.raw_soc_f64(curve, voltage)?;
+        self.finish(voltage, soc, soc, 0)
+    }
+
+    /// Estimate SOC from a typed voltage (without temperature compensation)
+    ///
+    /// Same as [`estimate_soc_fixed()`](Self::estimate_soc_fixed), but the
+    /// unit types make it impossible to pass a temperature by mistake.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, Fixed, Percent, SocEstimator, Volts};
+    ///
+    /// let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+    ///
+    /// let soc = estimator.estimate(Volts::from_millivolts(3750)).unwrap();
+    /// assert_eq!(soc, Percent::new(Fixed::from_num(60)));
+    /// ```
+    pub fn estimate(&self, voltage: Volts) -> Result<Percent, Error> {
+        self.estimate_soc_fixed(voltage.raw()).map(Percent::new)
+    }
+
+    /// Estimate SOC from a typed voltage and temperature with default
+    /// temperature compensation
+    ///
+    /// Typed variant of
+    /// [`estimate_soc_with_temp_fixed()`](Self::estimate_soc_with_temp_fixed).
+    pub fn estimate_with_temp(
+        &self,
+        voltage: Volts,
+        temperature: Celsius,
+    ) -> Result<Percent, Error> {
+        self.estimate_soc_with_temp_fixed(voltage.raw(), temperature.raw())
+            .map(Percent::new)
+    }
+
+    /// Estimate SOC from a typed voltage and temperature (using configuration settings)
+    ///
+    /// Typed variant of
+    /// [`estimate_soc_compensated_fixed()`](Self::estimate_soc_compensated_fixed).
+    pub fn estimate_compensated(
+        &self,
+        voltage: Volts,
+        temperature: Celsius,
+    ) -> Result<Percent, Error> {
+        self.estimate_soc_compensated_fixed(voltage.raw(), temperature.raw())
+            .map(Percent::new)
+    }
+
+    /// Estimate SOC from millivolts as permille using integers only
+    ///
+    /// Takes the battery voltage in millivolts and returns SOC in permille
+    /// (0 to 1000), without `fixed` or floating-point types in the
+    /// signature. The measurement-path scaling of the configuration is
+    /// applied; temperature and aging compensation are not.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, SocEstimator};
+    ///
+    /// let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+    ///
+    /// assert_eq!(estimator.estimate_soc_permille(3_750).unwrap(), 600);
+    /// ```
+    pub fn estimate_soc_permille(&self, voltage_mv: u16) -> Result<u16, Error> {
+        let voltage_mv = self.config.scale_voltage_mv(voltage_mv);
+        if self.is_strict() {
+            let (low, high) = self.curve.voltage_range_mv();
+            if !(low..=high).contains(&voltage_mv) {
+                return Err(Error::VoltageOutOfRange);
+            }
+        }
+        let soc = self.curve.voltage_mv_to_soc_permille(voltage_mv)?;
+        match self.config.output_policy {
+            OutputPolicy::Clamp => Ok(soc.min(1000)),
+            OutputPolicy::Reject if soc > 1000 => Err(Error::SocOutOfRange),
+            OutputPolicy::Reject | OutputPolicy::Raw => Ok(soc),
+        }
+    }
+
+    /// Estimate SOC in double precision with default temperature compensation
+    ///
+    /// Double-precision variant of
+    /// [`estimate_soc_with_temp()`](Self::estimate_soc_with_temp); always
+    /// uses a nominal temperature of 25°C and a coefficient of 0.005.
+    #[cfg(not(feature = "no-float"))]
+    pub fn estimate_soc_with_temp_f64(&self, voltage: f64, temperature: f64) -> Result<f64, Error> {
+        let base_soc = self.raw_soc_f64(self.curve, voltage)?;
+        self.check_temperature_f64(temperature)?;
+        let compensated = temperature_compensated(
+            base_soc,
+            temperature,
+            25.0,
+            0.005,
+            &CompensationLimits::DEFAULT,
+        );
+        self.finish(voltage, base_soc, compensated, logging::FLAG_TEMPERATURE)
+    }
+
+    /// Estimate SOC in double precision (using configuration settings)
+    ///
+    /// Double-precision variant of
+    /// [`estimate_soc_compensated()`](Self::estimate_soc_compensated).
+    #[cfg(not(feature = "no-float"))]
+    pub fn estimate_soc_compensated_f64(
+        &self,
+        voltage: f64,
+        temperature: f64,
+    ) -> Result<f64, Error> {
+        self.estimate_soc_compensated_f64_on(self.curve, voltage, temperature)
+    }
+
+    /// [`estimate_soc_compensated_f64()`](Self::estimate_soc_compensated_f64)
+    /// on `curve` instead of the estimator's curve
+    #[cfg(not(feature = "no-float"))]
+    pub(crate) fn estimate_soc_compensated_f64_on(
+        &self,
+        curve: &Curve,
+        voltage: f64,
+        temperature: f64,
+    ) -> Result<f64, Error> {
+        let base_soc = self.raw_soc_f64(curve, voltage)?;
+        let mut soc = base_soc;
+        let mut flags = 0;
+        if !temperature.is_finite() {
+            return Err(Error::InvalidTemperature);
+        }
+
+        if self.config.is_temperature_compensation_enabled() {
+            self.check_temperature_f64(temperature)?;
+            soc = temperature_compensated(
+                soc,
+                temperature,
+                self.config.nominal_temperature.to_num::<f64>(),
+                self.config.temperature_coefficient.to_num::<f64>(),
+                &self.config.limits,
+            );
+            flags |= logging::FLAG_TEMPERATURE;
+        }
+
+        if self.config.is_aging_compensation_enabled() {
+            soc = aging_compensated(
+                soc,
+                self.config.age_years.to_num::<f64>(),
+                self.config.aging_factor.to_num::<f64>(),
+                &self.config.limits,
+            );
+            flags |= logging::FLAG_AGING;
+        }
+
+        self.finish(voltage, base_soc, soc, flags)
+    }
+
+    /// Estimate SOC from a voltage measured under load
+    ///
+    /// Corrects the terminal voltage for the drop across the configured
+    /// internal resistance before the curve lookup. Negative currents mean
+    /// discharging, so a discharging battery reads higher than its terminal
+    /// voltage. With zero internal resistance this equals
+    /// [`estimate_soc_fixed()`](Self::estimate_soc_fixed).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, Fixed, SocEstimator};
+    ///
+    /// let estimator = SocEstimator::builder()
+    ///     .chemistry(BatteryChemistry::LiPo)
+    ///     .internal_resistance(Fixed::from_num(0.125))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// // 1 A discharge sags a 3.75 V cell to 3.625 V
+    /// let soc = estimator
+    ///     .estimate_soc_under_load_fixed(Fixed::from_num(3.625), Fixed::from_num(-1000))
+    ///     .unwrap();
+    /// assert_eq!(soc, Fixed::from_num(60));
+    /// ```
+    pub fn estimate_soc_under_load_fixed(
+        &self,
+        voltage: Fixed,
+        current_ma: Fixed,
+    ) -> Result<Fixed, Error> {
+        let scaled = self.config.scale_voltage(voltage);
+        let sag = (current_ma / Fixed::from_bits(1000 << 16))
+            .saturating_mul(self.config.internal_resistance);
+        let corrected = scaled.saturating_sub(sag);
+        self.check_voltage_range(corrected)?;
+        let soc = self.curve.voltage_to_soc_fixed(corrected)?;
+        self.finish(voltage, soc, soc, 0)
+    }
+
+    /// Remaining charge in mAh at a given SOC
+    ///
+    /// Returns `None` if no capacity is configured.
+    pub fn remaining_capacity_mah(&self, soc: Fixed) -> Option<Fixed> {
+        if self.config.capacity_mah <= Fixed::ZERO {
+            return None;
+        }
+        let soc = soc.clamp(Fixed::ZERO, Fixed::from_bits(100 << 16));
+        let bits = self.config.capacity_mah.to_bits() as i64 * soc.to_bits() as i64 / (100 << 16);
+        Some(Fixed::from_bits(bits as i32))
+    }
+
+    /// Curve lookup of a measured voltage after the measurement-path scaling
+    ///
+    /// Returns the raw curve value; callers apply the output policy.
+    #[inline]
+    fn lookup(&self, voltage: Fixed) -> Result<Fixed, Error> {
+        let voltage = self.config.scale_voltage(voltage);
+        self.check_voltage_range(voltage)?;
+        self.curve.voltage_to_soc_fixed(voltage)
+    }
+
+    /// Double-precision variant of [`lookup()`](Self::lookup) on `curve`
+    #[cfg(not(feature = "no-float"))]
+    #[inline]
+    fn raw_soc_f64(&self, curve: &Curve, voltage: f64) -> Result<f64, Error> {
+        if !voltage.is_finite() {
+            return Err(Error::NonFiniteInput);
+        }
+        let voltage = self.config.scale_voltage_f64(voltage);
+        if self.config.is_strict_voltage_range_enabled() && curve.len() >= 2 {
+            let (low, high) = curve.voltage_range_mv();
+            // Rounded to whole millivolts like `check_voltage_range()`
+            if !(low as f64 - 0.5..high as f64 + 0.5).contains(&(voltage * 1000.0)) {
+                return Err(Error::VoltageOutOfRange);
+            }
+        }
+        curve.voltage_to_soc_f64(voltage)
+    }
+
+    /// Single-precision lookup of a measured voltage
+    #[cfg(not(feature = "no-float"))]
+    #[inline]
+    fn raw_soc_f32(&self, voltage: f32) -> Result<f32, Error> {
+        if !voltage.is_finite() {
+            return Err(Error::NonFiniteInput);
+        }
+        let voltage_mv = self.config_f32.scale_voltage(voltage) * 1000.0;
+        logging::voltage_out_of_range(voltage_mv as i32, self.curve);
+        if self.is_strict() {
+            let (low, high) = self.curve.voltage_range_mv();
+            // Rounded to whole millivolts like `check_voltage_range()`
+            if !(low as f32 - 0.5..high as f32 + 0.5).contains(&voltage_mv) {
+                return Err(Error::VoltageOutOfRange);
+            }
+        }
+        self.curve.voltage_to_soc_f32(voltage_mv / 1000.0)
+    }
+
+    /// Logs a scaled voltage outside the curve range, and rejects it in
+    /// strict mode
+    #[inline]
+    fn check_voltage_range(&self, voltage: Fixed) -> Result<(), Error> {
+        logging::voltage_out_of_range(voltage.saturating_mul_int(1000).to_num(), self.curve);
+        if self.is_strict() {
+            instrument::ops(1, 0);
+            let voltage_mv = voltage
+                .saturating_mul(Fixed::from_bits(1000 << 16))
+                .saturating_round()
+                .to_num::<i32>();
+            let (low, high) = self.curve.voltage_range_mv();
+            if !(low as i32..=high as i32).contains(&voltage_mv) {
+                return Err(Error::VoltageOutOfRange);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reject temperatures outside the operating range
+    #[inline]
+    fn check_temperature(&self, temperature: Fixed) -> Result<(), Error> {
+        let (min, max) = self.temperature_range;
+        if (min..=max).contains(&temperature) {
+            Ok(())
+        } else {
+            Err(Error::TemperatureOutOfRange)
+        }
+    }
+
+    /// [`check_temperature()`](Self::check_temperature) for the float
+    /// paths, which also reject non-finite temperatures
+    #[cfg(not(feature = "no-float"))]
+    fn check_temperature_f64(&self, temperature: f64) -> Result<(), Error> {
+        if !temperature.is_finite() {
+            return Err(Error::InvalidTemperature);
+        }
+        let (min, max) = self.temperature_range;
+        if (min.to_num::<f64>()..=max.to_num::<f64>()).contains(&temperature) {
+            Ok(())
+        } else {
+            Err(Error::TemperatureOutOfRange)
+        }
+    }
+
+    /// Whether out-of-range voltages are rejected; curves that cannot be
+    /// interpolated report [`Error::InvalidCurve`] from the lookup instead
+    #[inline]
+    fn is_strict(&self) -> bool {
+        self.config.is_strict_voltage_range_enabled() && self.curve.len() >= 2
+    }
+
+    /// Get the voltage curve used for estimation
+    #[inline]
+    pub const fn curve(&self) -> &'static Curve {
+        self.curve
+    }
+
+    /// Get voltage range
+    #[cfg(not(feature = "no-float"))]
+    pub const fn voltage_range(&self) -> (f32, f32) {
+        self.curve.voltage_range()
+    }
+
+    /// Get voltage range as fixed-point values
+    pub fn voltage_range_fixed(&self) -> (Fixed, Fixed) {
+        self.curve.voltage_range_fixed()
+    }
+
+    /// Update configuration
+    #[inline]
+    pub fn update_config(&mut self, config: EstimatorConfig) {
+        self.config = config;
+        #[cfg(not(feature = "no-float"))]
+        {
+            self.config_f32 = ConfigF32::new(&config);
+        }
+    }
+
+    /// Get current configuration
+    #[inline]
+    pub const fn config(&self) -> &EstimatorConfig {
+        &self.config
     }
 
     /// Enable temperature compensation
     pub fn enable_temperature_compensation(&mut self, nominal_temp: Fixed, coefficient: Fixed) {
-        self.config = self
-            .config
-            .with_temperature_compensation()
-            .with_nominal_temperature(nominal_temp)
-            .with_temperature_coefficient(coefficient);
+        self.update_config(
+            self.config
+                .with_temperature_compensation()
+                .with_nominal_temperature(nominal_temp)
+                .with_temperature_coefficient(coefficient),
+        );
     }
 
     /// Enable aging compensation
     pub fn enable_aging_compensation(&mut self, age_years: Fixed, aging_factor: Fixed) {
-        self.config = self
-            .config
-            .with_aging_compensation()
-            .with_age_years(age_years)
-            .with_aging_factor(aging_factor);
+        self.update_config(
+            self.config
+                .with_aging_compensation()
+                .with_age_years(age_years)
+                .with_aging_factor(aging_factor),
+        );
     }
 
     /// Disable all compensation
+    ///
+    /// The measurement-path scaling, capacity, internal resistance and
+    /// output policy are kept.
     pub fn disable_all_compensation(&mut self) {
-        self.config = EstimatorConfig::default();
+        self.update_config(EstimatorConfig {
+            divider_ratio: self.config.divider_ratio,
+            input_gain: self.config.input_gain,
+            input_offset: self.config.input_offset,
+            capacity_mah: self.config.capacity_mah,
+            internal_resistance: self.config.internal_resistance,
+            output_policy: self.config.output_policy,
+            limits: self.config.limits,
+            ..EstimatorConfig::default()
+        });
     }
 }
 
@@ -353,10 +1503,180 @@ impl SocEstimator {
     }
 }
 
+/// Fluent, validating builder for [`SocEstimator`]
+///
+/// Created with [`SocEstimator::builder()`]. Settings that are not given
+/// keep their [`EstimatorConfig::default()`] values; compensation is only
+/// enabled by the corresponding builder method.
+#[derive(Debug, Clone, Copy)]
+pub struct SocEstimatorBuilder {
+    curve: Option<&'static Curve>,
+    config: EstimatorConfig,
+    curve_temperature_range: (Fixed, Fixed),
+    temperature_range: Option<(Fixed, Fixed)>,
+}
+
+impl SocEstimatorBuilder {
+    /// Creates a builder with no curve selected
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            curve: None,
+            config: EstimatorConfig::default(),
+            curve_temperature_range: DEFAULT_TEMPERATURE_RANGE,
+            temperature_range: None,
+        }
+    }
+
+    /// Use the built-in curve of a chemistry
+    #[inline]
+    pub const fn chemistry(mut self, chemistry: BatteryChemistry) -> Self {
+        self.curve = Some(Curve::for_chemistry(chemistry));
+        self.curve_temperature_range = chemistry.operating_temperature_range();
+        self
+    }
+
+    /// Use a custom curve
+    #[inline]
+    pub const fn curve(mut self, curve: &'static Curve) -> Self {
+        self.curve = Some(curve);
+        self.curve_temperature_range = DEFAULT_TEMPERATURE_RANGE;
+        self
+    }
+
+    /// Start from an existing configuration
+    #[inline]
+    pub const fn config(mut self, config: EstimatorConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Enable temperature compensation
+    #[inline]
+    pub fn temperature_compensation(mut self, nominal_temp: Fixed, coefficient: Fixed) -> Self {
+        self.config = self
+            .config
+            .with_temperature_compensation()
+            .with_nominal_temperature(nominal_temp)
+            .with_temperature_coefficient(coefficient);
+        self
+    }
+
+    /// Enable aging compensation
+    #[inline]
+    pub fn aging_compensation(mut self, age_years: Fixed, aging_factor: Fixed) -> Self {
+        self.config = self
+            .config
+            .with_aging_compensation()
+            .with_age_years(age_years)
+            .with_aging_factor(aging_factor);
+        self
+    }
+
+    /// Set the voltage divider ratio
+    #[inline]
+    pub fn divider_ratio(mut self, ratio: Fixed) -> Self {
+        self.config = self.config.with_divider_ratio(ratio);
+        self
+    }
+
+    /// Set the rated battery capacity in mAh
+    #[inline]
+    pub fn capacity_mah(mut self, capacity_mah: Fixed) -> Self {
+        self.config = self.config.with_capacity_mah(capacity_mah);
+        self
+    }
+
+    /// Set the internal resistance in ohms
+    #[inline]
+    pub fn internal_resistance(mut self, resistance: Fixed) -> Self {
+        self.config = self.config.with_internal_resistance(resistance);
+        self
+    }
+
+    /// Set the handling of SOC results outside 0–100%
+    #[inline]
+    pub const fn output_policy(mut self, policy: OutputPolicy) -> Self {
+        self.config = self.config.with_output_policy(policy);
+        self
+    }
+
+    /// Reject voltages outside the curve range
+    #[inline]
+    pub const fn strict_voltage_range(mut self) -> Self {
+        self.config = self.config.with_strict_voltage_range();
+        self
+    }
+
+    /// Set the operating temperature range in °C
+    ///
+    /// Overrides the range of the selected chemistry; see
+    /// [`SocEstimator::with_operating_temperature_range()`].
+    #[inline]
+    pub const fn operating_temperature_range(mut self, min: Fixed, max: Fixed) -> Self {
+        self.temperature_range = Some((min, max));
+        self
+    }
+
+    /// Validate the settings and create the estimator
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(estimator)` - The configured estimator
+    /// * `Err(Error::InvalidCurve)` - The selected curve cannot be interpolated
+    /// * `Err(Error::DuplicateVoltage)` / `Err(Error::NonMonotonicSoc)` - The
+    ///   selected curve fails [`Curve::validate_sorted()`]
+    /// * `Err(Error::InvalidConfig)` - No chemistry or curve was selected, or
+    ///   the operating temperature range is empty
+    /// * `Err(Error)` - A setting is out of range (see
+    ///   [`EstimatorConfig::validate()`])
+    pub fn build(self) -> Result<SocEstimator, Error> {
+        let curve = self.curve.ok_or(Error::InvalidConfig)?;
+        curve.validate_sorted()?;
+        self.config.validate()?;
+
+        let temperature_range = self
+            .temperature_range
+            .unwrap_or(self.curve_temperature_range);
+        if temperature_range.0 > temperature_range.1 {
+            return Err(Error::InvalidConfig);
+        }
+
+        Ok(SocEstimator {
+            curve,
+            config: self.config,
+            temperature_range,
+            #[cfg(not(feature = "no-float"))]
+            config_f32: ConfigF32::new(&self.config),
+        })
+    }
+}
+
+// Non-const Default implementation
+impl Default for SocEstimatorBuilder {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SocEstimate for SocEstimator {
+    #[inline]
+    fn estimate_soc_fixed(&self, voltage: Fixed) -> Result<Fixed, Error> {
+        SocEstimator::estimate_soc_fixed(self, voltage)
+    }
+
+    #[inline]
+    fn voltage_range_fixed(&self) -> (Fixed, Fixed) {
+        SocEstimator::voltage_range_fixed(self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_estimator_basic() {
         let estimator = SocEstimator::new(BatteryChemistry::LiPo);
@@ -390,6 +1710,7 @@ mod tests {
         assert!(soc > Fixed::from_num(45.0) && soc < Fixed::from_num(55.0));
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_estimator_with_temp() {
         let estimator = SocEstimator::new(BatteryChemistry::LiPo);
@@ -431,6 +1752,7 @@ mod tests {
         assert!(hot_soc >= base_soc);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_estimator_custom_curve() {
         use crate::CurvePoint;
@@ -448,6 +1770,7 @@ mod tests {
         assert_eq!(estimator.estimate_soc(4.0).unwrap(), 100.0);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_estimator_all_battery_types() {
         // Test all battery chemistries
@@ -463,6 +1786,7 @@ mod tests {
         assert!(conservative.estimate_soc(3.77).is_ok());
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_estimator_voltage_range() {
         let estimator = SocEstimator::new(BatteryChemistry::LiPo);
@@ -481,6 +1805,7 @@ mod tests {
         assert_eq!(max, Fixed::from_num(4.2));
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_estimator_estimate_soc_compensated() {
         let config = EstimatorConfig::default()
@@ -559,6 +1884,7 @@ mod tests {
         assert_eq!(config.aging_factor, Fixed::from_num(0.02));
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_estimator_with_config_lipo410() {
         // Test with_config using Lipo410Full340Cutoff to cover line 137
@@ -578,6 +1904,7 @@ mod tests {
         assert!((soc - 50.0).abs() < 1.0);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_estimate_soc_compensated_with_temp_only() {
         // Test temperature compensation in estimate_soc_compensated
@@ -639,6 +1966,7 @@ mod tests {
         assert_eq!(estimator.config().aging_factor, Fixed::from_num(0.03));
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_estimator_convenience_constructors() {
         // Test with_temperature_compensation
@@ -686,9 +2014,11 @@ mod tests {
         );
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_estimate_soc_with_temp_clamping() {
-        let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+        let estimator = SocEstimator::new(BatteryChemistry::LiPo)
+            .with_operating_temperature_range(Fixed::from_num(-100), Fixed::from_num(60));
 
         // Test that temperature compensation is clamped to valid range
         let result = estimator.estimate_soc_with_temp(3.7, -100.0);
@@ -698,6 +2028,7 @@ mod tests {
         assert!((0.0..=100.0).contains(&soc));
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_estimator_copy() {
         let estimator1 = SocEstimator::new(BatteryChemistry::LiPo);
@@ -708,9 +2039,22 @@ mod tests {
         assert!(estimator2.estimate_soc(3.7).is_ok());
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_estimator_extreme_temperatures() {
-        let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+        // Beyond the rated range of the chemistry the model is not applied
+        let rated = SocEstimator::new(BatteryChemistry::LiPo);
+        assert_eq!(
+            rated.estimate_soc_with_temp(3.7, -40.0),
+            Err(Error::TemperatureOutOfRange)
+        );
+        assert_eq!(
+            rated.estimate_soc_with_temp(3.7, 80.0),
+            Err(Error::TemperatureOutOfRange)
+        );
+
+        let estimator =
+            rated.with_operating_temperature_range(Fixed::from_num(-40), Fixed::from_num(85));
 
         // Test extreme cold
         let cold_result = estimator.estimate_soc_with_temp(3.7, -40.0);
@@ -762,4 +2106,834 @@ mod tests {
         // SOC should be approximately 60% at 3.75V for LiPo
         assert!(soc > Fixed::from_num(55.0) && soc < Fixed::from_num(65.0));
     }
+
+    #[test]
+    fn test_estimate_soc_batch() {
+        let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+        let samples = [
+            Fixed::from_num(3.70),
+            Fixed::from_num(3.80),
+            Fixed::from_num(3.75),
+            Fixed::from_num(3.75),
+        ];
+
+        let estimate = estimator.estimate_soc_batch(&samples).unwrap();
+        assert_eq!(estimate.count, 4);
+        assert_eq!(estimate.min_voltage, Fixed::from_num(3.70));
+        assert_eq!(estimate.max_voltage, Fixed::from_num(3.80));
+        assert!((estimate.mean_voltage - Fixed::from_num(3.75)).abs() < Fixed::from_num(0.0001));
+        assert_eq!(
+            estimate.soc,
+            estimator.estimate_soc_fixed(estimate.mean_voltage).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_estimate_soc_batch_large_buffer() {
+        // 4096 samples at 4.2V would overflow an I16F16 accumulator
+        let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+        let samples = [Fixed::from_num(4.25); 4096];
+
+        let estimate = estimator.estimate_soc_batch(&samples).unwrap();
+        assert_eq!(estimate.mean_voltage, Fixed::from_num(4.25));
+        assert_eq!(estimate.soc, Fixed::from_num(100));
+    }
+
+    #[test]
+    fn test_estimate_soc_slice_matches_single() {
+        let config = EstimatorConfig::default().with_divider_ratio(Fixed::from_num(2));
+        let estimator = SocEstimator::with_config(BatteryChemistry::LiFePO4, config);
+
+        // Slow sweep down, a jump back up, then out of range
+        let mut voltages = [Fixed::ZERO; 64];
+        for (i, voltage) in voltages.iter_mut().enumerate() {
+            *voltage = Fixed::from_num(1.7) - Fixed::from_num(i as i32) / 200;
+        }
+        voltages[40] = Fixed::from_num(1.68);
+        voltages[63] = Fixed::from_num(3);
+        let mut socs = [Fixed::ZERO; 64];
+
+        estimator.estimate_soc_slice(&voltages, &mut socs).unwrap();
+        for (&voltage, &soc) in voltages.iter().zip(&socs) {
+            assert_eq!(soc, estimator.estimate_soc_fixed(voltage).unwrap());
+        }
+    }
+
+    #[cfg(feature = "cmsis-dsp")]
+    #[test]
+    fn test_estimate_soc_slice_cmsis_matches_portable() {
+        let mut voltages = [Fixed::ZERO; 48];
+        for (i, voltage) in voltages.iter_mut().enumerate() {
+            *voltage = Fixed::from_num(1.45) + Fixed::from_num(i as i32) / 100;
+        }
+        let default = EstimatorConfig::default();
+        let configs = [
+            (default, 0),
+            (default.with_input_offset(Fixed::from_num(-0.05)), 0),
+            (default.with_divider_ratio(Fixed::from_num(2)), 4),
+            (
+                default
+                    .with_input_gain(Fixed::from_num(1.013))
+                    .with_divider_ratio(Fixed::from_num(2)),
+                8,
+            ),
+            (default.with_output_policy(OutputPolicy::Raw), 0),
+        ];
+        for (config, max_bits) in configs {
+            let estimator = SocEstimator::with_config(BatteryChemistry::LiFePO4, config);
+            let mut portable = [Fixed::ZERO; 48];
+            estimator
+                .estimate_soc_slice(&voltages, &mut portable)
+                .unwrap();
+            let mut cmsis = [Fixed::ZERO; 48];
+            let (rest, _) = estimator
+                .estimate_soc_slice_cmsis(&voltages, &mut cmsis)
+                .unwrap();
+            assert!(rest.is_empty());
+
+            // Lossy scaling reads a sample up to a few LSBs low, which the
+            // curve slope turns into a slightly lower SOC
+            for (&expected, &actual) in portable.iter().zip(&cmsis) {
+                let diff = (expected - actual).to_bits();
+                assert!(
+                    (0..=max_bits * 2000).contains(&diff),
+                    "{expected} vs {actual}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_estimate_soc_slice_errors() {
+        let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+        let mut socs = [Fixed::ZERO; 2];
+        assert_eq!(
+            estimator.estimate_soc_slice(&[Fixed::from_num(3.7)], &mut socs),
+            Err(Error::InvalidConfig)
+        );
+        assert_eq!(estimator.estimate_soc_slice(&[], &mut []), Ok(()));
+
+        static SINGLE: Curve = Curve::new(&[crate::CurvePoint::new(3.0, 0.0)]);
+        let estimator = SocEstimator::with_custom_curve(&SINGLE);
+        assert_eq!(
+            estimator.estimate_soc_slice(&[], &mut []),
+            Err(Error::InvalidCurve)
+        );
+    }
+
+    #[test]
+    fn test_estimate_soc_batch_errors() {
+        let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+        assert_eq!(estimator.estimate_soc_batch(&[]), Err(Error::EmptyInput));
+
+        static SINGLE: Curve = Curve::new(&[crate::CurvePoint::new(3.0, 0.0)]);
+        let estimator = SocEstimator::with_custom_curve(&SINGLE);
+        assert_eq!(
+            estimator.estimate_soc_batch(&[Fixed::from_num(3.0)]),
+            Err(Error::InvalidCurve)
+        );
+    }
+
+    #[cfg(not(feature = "no-float"))]
+    #[test]
+    fn test_measurement_scaling() {
+        let direct = SocEstimator::new(BatteryChemistry::LiPo);
+        let config = EstimatorConfig::default()
+            .with_divider_ratio(Fixed::from_num(2))
+            .with_input_gain(Fixed::from_num(0.5))
+            .with_input_offset(Fixed::from_num(0.125));
+        let scaled = SocEstimator::with_config(BatteryChemistry::LiPo, config);
+
+        // (3.5 * 0.5 + 0.125) * 2 = 3.75
+        assert_eq!(
+            config.scale_voltage(Fixed::from_num(3.5)),
+            Fixed::from_num(3.75)
+        );
+        assert_eq!(
+            scaled.estimate_soc_fixed(Fixed::from_num(3.5)).unwrap(),
+            direct.estimate_soc_fixed(Fixed::from_num(3.75)).unwrap()
+        );
+        assert_eq!(
+            scaled.estimate_soc(3.5).unwrap(),
+            direct.estimate_soc(3.75).unwrap()
+        );
+        assert_eq!(
+            scaled
+                .estimate_soc_compensated_fixed(Fixed::from_num(3.5), Fixed::from_num(25))
+                .unwrap(),
+            direct.estimate_soc_fixed(Fixed::from_num(3.75)).unwrap()
+        );
+
+        let batch = scaled
+            .estimate_soc_batch(&[Fixed::from_num(3.5); 4])
+            .unwrap();
+        assert_eq!(batch.mean_voltage, Fixed::from_num(3.5));
+        assert_eq!(batch.soc, Fixed::from_num(60));
+    }
+
+    #[test]
+    fn test_disable_compensation_keeps_scaling() {
+        let config = EstimatorConfig::default()
+            .with_temperature_compensation()
+            .with_divider_ratio(Fixed::from_num(3));
+        let mut estimator = SocEstimator::with_config(BatteryChemistry::LiPo, config);
+
+        estimator.disable_all_compensation();
+        assert!(!estimator.config().is_temperature_compensation_enabled());
+        assert_eq!(estimator.config().divider_ratio, Fixed::from_num(3));
+    }
+
+    #[cfg(not(feature = "no-float"))]
+    #[test]
+    fn test_estimate_soc_f64() {
+        let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+
+        let soc = estimator.estimate_soc_f64(3.75).unwrap();
+        assert!((soc - 60.0).abs() < 1e-9);
+        // Agrees with the fixed-point path on millivolt-exact inputs
+        assert_eq!(
+            estimator.estimate_soc_f64(3.875).unwrap(),
+            estimator
+                .estimate_soc_fixed(Fixed::from_num(3.875))
+                .unwrap()
+                .to_num::<f64>()
+        );
+        assert_eq!(estimator.estimate_soc_f64(5.0).unwrap(), 100.0);
+    }
+
+    #[cfg(not(feature = "no-float"))]
+    #[test]
+    fn test_estimate_soc_f32_follows_config() {
+        let mut estimator = SocEstimator::new(BatteryChemistry::LiPo);
+        assert!((estimator.estimate_soc(3.75).unwrap() - 60.0).abs() < 1e-4);
+        // Interpolated in `f32`, not rounded to whole millivolts
+        assert!(estimator.estimate_soc(3.7505).unwrap() > estimator.estimate_soc(3.75).unwrap());
+
+        estimator.update_config(EstimatorConfig::default().with_divider_ratio(Fixed::from_num(2)));
+        assert!((estimator.estimate_soc(1.875).unwrap() - 60.0).abs() < 1e-4);
+
+        estimator.enable_temperature_compensation(Fixed::from_num(25), Fixed::from_num(0.005));
+        let soc = estimator.estimate_soc_compensated(1.875, 15.0).unwrap();
+        assert!((soc - 57.0).abs() < 0.01);
+        assert_eq!(
+            estimator,
+            SocEstimator::with_config(BatteryChemistry::LiPo, *estimator.config())
+        );
+    }
+
+    #[cfg(not(feature = "no-float"))]
+    #[test]
+    fn test_estimate_soc_compensated_f64() {
+        let estimator = SocEstimator::with_all_compensation(
+            BatteryChemistry::LiPo,
+            Fixed::from_num(25),
+            Fixed::from_num(0.0078125),
+            Fixed::from_num(2),
+            Fixed::from_num(0.0625),
+        );
+
+        // 60% * (1 - 10 * 0.0078125) * (1 - 2 * 0.0625)
+        let soc = estimator.estimate_soc_compensated_f64(3.75, 15.0).unwrap();
+        assert!((soc - 60.0 * 0.921875 * 0.875).abs() < 1e-9);
+
+        let soc = estimator.estimate_soc_with_temp_f64(3.75, 25.0).unwrap();
+        assert!((soc - 60.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_compensation_limits_from_config() {
+        // -20 °C at 0.005/°C is a 22.5% loss, floored at 5%
+        let limits = CompensationLimits {
+            max_cold_loss: Fixed::from_num(0.05),
+            ..CompensationLimits::DEFAULT
+        };
+        let config = EstimatorConfig::default()
+            .with_temperature_compensation()
+            .with_compensation_limits(limits);
+        let estimator = SocEstimator::with_config(BatteryChemistry::LiPo, config);
+
+        let soc = estimator
+            .estimate_soc_compensated_fixed(Fixed::from_num(3.75), Fixed::from_num(-20))
+            .unwrap();
+        assert!((soc - Fixed::from_num(57)).abs() < Fixed::from_num(0.01));
+
+        #[cfg(not(feature = "no-float"))]
+        {
+            let soc = estimator.estimate_soc_compensated(3.75, -20.0).unwrap();
+            assert!((soc - 57.0).abs() < 0.01);
+            let soc = estimator.estimate_soc_compensated_f64(3.75, -20.0).unwrap();
+            assert!((soc - 57.0).abs() < 0.01);
+        }
+
+        let invalid = EstimatorConfig::default().with_compensation_limits(CompensationLimits {
+            max_cold_loss: Fixed::from_num(1.5),
+            ..CompensationLimits::DEFAULT
+        });
+        assert_eq!(invalid.validate(), Err(Error::InvalidConfig));
+    }
+
+    #[test]
+    fn test_estimate_soc_permille() {
+        let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+        assert_eq!(estimator.estimate_soc_permille(3_750).unwrap(), 600);
+        assert_eq!(estimator.estimate_soc_permille(3_875).unwrap(), 812);
+        assert_eq!(estimator.estimate_soc_permille(5_000).unwrap(), 1000);
+        assert_eq!(estimator.estimate_soc_permille(0).unwrap(), 0);
+
+        // Divider in front of the ADC
+        let config = EstimatorConfig::default().with_divider_ratio(Fixed::from_num(2));
+        let scaled = SocEstimator::with_config(BatteryChemistry::LiPo, config);
+        assert_eq!(scaled.estimate_soc_permille(1_875).unwrap(), 600);
+    }
+
+    #[test]
+    fn test_estimate_typed_units() {
+        let estimator = SocEstimator::with_temperature_compensation(
+            BatteryChemistry::LiPo,
+            Fixed::from_num(25),
+            Fixed::from_num(0.005),
+        );
+        let voltage = Volts::new(Fixed::from_num(3.75));
+
+        assert_eq!(
+            estimator.estimate(voltage).unwrap().raw(),
+            estimator.estimate_soc_fixed(voltage.raw()).unwrap()
+        );
+        assert_eq!(
+            estimator
+                .estimate_compensated(voltage, Celsius::new(Fixed::ZERO))
+                .unwrap()
+                .raw(),
+            estimator
+                .estimate_soc_compensated_fixed(voltage.raw(), Fixed::ZERO)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_builder_matches_constructor() {
+        let built = SocEstimator::builder()
+            .chemistry(BatteryChemistry::LiPo)
+            .temperature_compensation(Fixed::from_num(25), Fixed::from_num(0.005))
+            .build()
+            .unwrap();
+        let direct = SocEstimator::with_temperature_compensation(
+            BatteryChemistry::LiPo,
+            Fixed::from_num(25),
+            Fixed::from_num(0.005),
+        );
+
+        assert_eq!(
+            built
+                .estimate_soc_compensated_fixed(Fixed::from_num(3.75), Fixed::ZERO)
+                .unwrap(),
+            direct
+                .estimate_soc_compensated_fixed(Fixed::from_num(3.75), Fixed::ZERO)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_builder_validation() {
+        static REVERSED: Curve = Curve::new(&[
+            crate::CurvePoint::new(4.0, 100.0),
+            crate::CurvePoint::new(3.0, 0.0),
+        ]);
+
+        assert_eq!(
+            SocEstimator::builder().build().err(),
+            Some(Error::InvalidConfig)
+        );
+        assert_eq!(
+            SocEstimator::builder().curve(&REVERSED).build().err(),
+            Some(Error::InvalidCurve)
+        );
+        assert_eq!(
+            SocEstimator::builder()
+                .chemistry(BatteryChemistry::LiPo)
+                .divider_ratio(Fixed::ZERO)
+                .build()
+                .err(),
+            Some(Error::InvalidConfig)
+        );
+        assert_eq!(
+            SocEstimator::builder()
+                .chemistry(BatteryChemistry::LiPo)
+                .internal_resistance(Fixed::from_num(-0.1))
+                .build()
+                .err(),
+            Some(Error::InvalidConfig)
+        );
+    }
+
+    #[test]
+    fn test_config_validate() {
+        assert_eq!(EstimatorConfig::default().validate(), Ok(()));
+        assert_eq!(
+            EstimatorConfig::default()
+                .with_nominal_temperature(Fixed::from_num(-60))
+                .validate(),
+            Ok(())
+        );
+
+        assert_eq!(
+            EstimatorConfig::default()
+                .with_temperature_coefficient(Fixed::from_num(0.1))
+                .with_aging_factor(Fixed::ONE)
+                .validate(),
+            Ok(())
+        );
+
+        let default = EstimatorConfig::default();
+        let invalid = [
+            (
+                default.with_temperature_coefficient(Fixed::from_num(-0.005)),
+                Error::TemperatureCoefficientOutOfRange,
+            ),
+            (
+                default.with_temperature_coefficient(Fixed::from_num(0.5)),
+                Error::TemperatureCoefficientOutOfRange,
+            ),
+            (
+                default.with_aging_factor(Fixed::from_num(-0.02)),
+                Error::AgingFactorOutOfRange,
+            ),
+            (
+                default.with_aging_factor(Fixed::from_num(2)),
+                Error::AgingFactorOutOfRange,
+            ),
+            (
+                default.with_nominal_temperature(Fixed::from_num(-61)),
+                Error::NominalTemperatureOutOfRange,
+            ),
+            (
+                default.with_nominal_temperature(Fixed::from_num(121)),
+                Error::NominalTemperatureOutOfRange,
+            ),
+            (default.with_input_gain(Fixed::ZERO), Error::InvalidConfig),
+            (
+                default.with_age_years(Fixed::from_num(-1)),
+                Error::InvalidConfig,
+            ),
+        ];
+        for (config, error) in invalid {
+            assert_eq!(config.validate(), Err(error));
+        }
+    }
+
+    #[test]
+    fn test_config_equality() {
+        let config = EstimatorConfig::default()
+            .with_temperature_compensation()
+            .with_capacity_mah(Fixed::from_num(2200));
+
+        assert_eq!(config, config.with_capacity_mah(Fixed::from_num(2200)));
+        assert_ne!(config, config.with_aging_compensation());
+        assert_ne!(config, EstimatorConfig::default());
+
+        let estimator = SocEstimator::with_config(BatteryChemistry::LiPo, config);
+        assert_eq!(
+            estimator,
+            SocEstimator::with_config(BatteryChemistry::LiPo, config)
+        );
+        assert_ne!(
+            estimator,
+            SocEstimator::with_config(BatteryChemistry::LiIon, config)
+        );
+    }
+
+    #[test]
+    fn test_remaining_capacity_mah() {
+        let estimator = SocEstimator::builder()
+            .chemistry(BatteryChemistry::LiPo)
+            .capacity_mah(Fixed::from_num(5000))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            estimator.remaining_capacity_mah(Fixed::from_num(60)),
+            Some(Fixed::from_num(3000))
+        );
+        assert_eq!(
+            SocEstimator::new(BatteryChemistry::LiPo).remaining_capacity_mah(Fixed::from_num(60)),
+            None
+        );
+    }
+
+    #[cfg(not(feature = "no-float"))]
+    #[test]
+    fn test_compensated_overflow_is_reported() {
+        let estimator = SocEstimator::with_temperature_compensation(
+            BatteryChemistry::LiPo,
+            Fixed::from_num(25),
+            Fixed::from_num(10),
+        )
+        .with_operating_temperature_range(Fixed::MIN, Fixed::MAX);
+
+        assert_eq!(
+            estimator
+                .estimate_soc_compensated_fixed(Fixed::from_num(3.75), Fixed::from_num(-30000)),
+            Err(Error::NumericalError)
+        );
+        assert_eq!(
+            estimator.estimate_soc_compensated(3.75, f32::NAN),
+            Err(Error::InvalidTemperature)
+        );
+        // Voltages above the I16F16 millivolt range saturate to full
+        assert_eq!(
+            estimator.estimate_soc_fixed(Fixed::from_num(100)).unwrap(),
+            Fixed::from_num(100)
+        );
+    }
+
+    #[cfg(not(feature = "no-float"))]
+    #[test]
+    fn test_output_policy() {
+        // 4.25V at 45°C reads 105% before the policy is applied
+        let estimate = |policy| {
+            let config = EstimatorConfig::default()
+                .with_temperature_compensation()
+                .with_output_policy(policy);
+            let estimator = SocEstimator::with_config(BatteryChemistry::LiPo, config);
+            (
+                estimator
+                    .estimate_soc_compensated_fixed(Fixed::from_num(4.25), Fixed::from_num(45)),
+                estimator.estimate_soc_compensated_f64(4.25, 45.0),
+            )
+        };
+
+        let (fixed, float) = estimate(OutputPolicy::Clamp);
+        assert_eq!(fixed, Ok(Fixed::from_num(100)));
+        assert_eq!(float, Ok(100.0));
+
+        let (fixed, float) = estimate(OutputPolicy::Reject);
+        assert_eq!(fixed, Err(Error::SocOutOfRange));
+        assert_eq!(float, Err(Error::SocOutOfRange));
+
+        let (fixed, float) = estimate(OutputPolicy::Raw);
+        assert!(fixed.unwrap() > Fixed::from_num(104.9));
+        assert!(float.unwrap() > 104.9);
+    }
+
+    #[cfg(not(feature = "no-float"))]
+    #[test]
+    fn test_non_finite_float_inputs() {
+        let estimator = SocEstimator::with_all_compensation(
+            BatteryChemistry::LiPo,
+            Fixed::from_num(25),
+            Fixed::from_num(0.005),
+            Fixed::from_num(2),
+            Fixed::from_num(0.02),
+        );
+
+        for voltage in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            let err = Err(Error::NonFiniteInput);
+            assert_eq!(estimator.estimate_soc(voltage), err);
+            assert_eq!(estimator.estimate_soc_with_temp(voltage, 25.0), err);
+            assert_eq!(estimator.estimate_soc_compensated(voltage, 25.0), err);
+
+            let voltage = voltage as f64;
+            let err = Err(Error::NonFiniteInput);
+            assert_eq!(estimator.estimate_soc_f64(voltage), err);
+            assert_eq!(estimator.estimate_soc_with_temp_f64(voltage, 25.0), err);
+            assert_eq!(estimator.estimate_soc_compensated_f64(voltage, 25.0), err);
+        }
+
+        for temperature in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            let err = Err(Error::InvalidTemperature);
+            assert_eq!(estimator.estimate_soc_with_temp(3.75, temperature), err);
+            assert_eq!(estimator.estimate_soc_compensated(3.75, temperature), err);
+
+            let temperature = temperature as f64;
+            let err = Err(Error::InvalidTemperature);
+            assert_eq!(estimator.estimate_soc_with_temp_f64(3.75, temperature), err);
+            assert_eq!(
+                estimator.estimate_soc_compensated_f64(3.75, temperature),
+                err
+            );
+        }
+
+        // Finite voltages far outside the fixed-point range saturate
+        assert_eq!(estimator.estimate_soc(1e30), Ok(100.0));
+        assert_eq!(estimator.estimate_soc(-1e30), Ok(0.0));
+    }
+
+    #[cfg(not(feature = "no-float"))]
+    #[test]
+    fn test_strict_voltage_range() {
+        let lenient = SocEstimator::new(BatteryChemistry::LiPo);
+        let strict = SocEstimator::builder()
+            .chemistry(BatteryChemistry::LiPo)
+            .strict_voltage_range()
+            .build()
+            .unwrap();
+        assert!(strict.config().is_strict_voltage_range_enabled());
+        assert!(!lenient.config().is_strict_voltage_range_enabled());
+
+        let (min, max) = strict.voltage_range_fixed();
+        let below = min - Fixed::from_num(0.01);
+        let above = max + Fixed::from_num(0.01);
+
+        // The curve end points themselves are in range
+        assert_eq!(strict.estimate_soc_f64(4.2), Ok(100.0));
+        assert_eq!(
+            strict.estimate_soc_fixed(min),
+            lenient.estimate_soc_fixed(min)
+        );
+        assert_eq!(
+            strict.estimate_soc_fixed(max),
+            lenient.estimate_soc_fixed(max)
+        );
+        assert_eq!(lenient.estimate_soc_fixed(above), Ok(Fixed::from_num(100)));
+
+        for voltage in [below, above] {
+            assert_eq!(
+                strict.estimate_soc_fixed(voltage),
+                Err(Error::VoltageOutOfRange)
+            );
+            assert_eq!(
+                strict.estimate_soc_compensated_fixed(voltage, Fixed::from_num(25)),
+                Err(Error::VoltageOutOfRange)
+            );
+            assert_eq!(
+                strict.estimate_soc(voltage.to_num::<f32>()),
+                Err(Error::VoltageOutOfRange)
+            );
+            assert_eq!(
+                strict.estimate_soc_f64(voltage.to_num::<f64>()),
+                Err(Error::VoltageOutOfRange)
+            );
+            assert_eq!(
+                strict.estimate_soc_permille(voltage.saturating_mul_int(1000).to_num::<u16>()),
+                Err(Error::VoltageOutOfRange)
+            );
+        }
+        assert_eq!(strict.estimate_soc(f32::NAN), Err(Error::NonFiniteInput));
+
+        // Slices stop at the first out-of-range sample
+        let voltages = [Fixed::from_num(3.75), above];
+        let mut socs = [Fixed::ZERO; 2];
+        assert_eq!(
+            strict.estimate_soc_slice(&voltages, &mut socs),
+            Err(Error::VoltageOutOfRange)
+        );
+        assert_eq!(socs[0], Fixed::from_num(60));
+
+        // A divider configured wrongly reads out of range for every sample
+        let config = strict.config().with_divider_ratio(Fixed::from_num(2));
+        let miswired = SocEstimator::with_config(BatteryChemistry::LiPo, config);
+        assert_eq!(
+            miswired.estimate_soc_fixed(Fixed::from_num(3.75)),
+            Err(Error::VoltageOutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_operating_temperature_range() {
+        let voltage = Fixed::from_num(3.75);
+        let estimator = SocEstimator::with_temperature_compensation(
+            BatteryChemistry::LiPo,
+            Fixed::from_num(25),
+            Fixed::from_num(0.005),
+        );
+        assert_eq!(
+            estimator.operating_temperature_range(),
+            BatteryChemistry::LiPo.operating_temperature_range()
+        );
+
+        // The limits themselves are in range
+        for temperature in [-20, 60] {
+            let temperature = Fixed::from_num(temperature);
+            assert!(estimator
+                .estimate_soc_compensated_fixed(voltage, temperature)
+                .is_ok());
+            assert!(estimator
+                .estimate_soc_with_temp_fixed(voltage, temperature)
+                .is_ok());
+        }
+
+        for temperature in [-200.0, -21.0, 61.0] {
+            let fixed = Fixed::from_num(temperature);
+            let err = Error::TemperatureOutOfRange;
+            assert_eq!(
+                estimator.estimate_soc_compensated_fixed(voltage, fixed),
+                Err(err)
+            );
+            assert_eq!(
+                estimator.estimate_soc_with_temp_fixed(voltage, fixed),
+                Err(err)
+            );
+            #[cfg(not(feature = "no-float"))]
+            {
+                assert_eq!(
+                    estimator.estimate_soc_compensated(3.75, temperature),
+                    Err(err)
+                );
+                assert_eq!(
+                    estimator.estimate_soc_with_temp(3.75, temperature),
+                    Err(err)
+                );
+                assert_eq!(
+                    estimator.estimate_soc_compensated_f64(3.75, temperature.into()),
+                    Err(err)
+                );
+                assert_eq!(
+                    estimator.estimate_soc_with_temp_f64(3.75, temperature.into()),
+                    Err(err)
+                );
+            }
+        }
+
+        // Without temperature compensation the temperature is not used
+        let uncompensated = SocEstimator::new(BatteryChemistry::LiPo);
+        assert_eq!(
+            uncompensated.estimate_soc_compensated_fixed(voltage, Fixed::from_num(-200)),
+            Ok(Fixed::from_num(60))
+        );
+
+        // Custom curves and explicit ranges
+        let custom = SocEstimator::with_custom_curve(Curve::for_chemistry(BatteryChemistry::LiPo));
+        assert_eq!(
+            custom.operating_temperature_range(),
+            (Fixed::from_num(-40), Fixed::from_num(85))
+        );
+        let built = SocEstimator::builder()
+            .operating_temperature_range(Fixed::from_num(0), Fixed::from_num(45))
+            .chemistry(BatteryChemistry::LiFePO4)
+            .build()
+            .unwrap();
+        assert_eq!(
+            built.operating_temperature_range(),
+            (Fixed::from_num(0), Fixed::from_num(45))
+        );
+        assert_eq!(
+            SocEstimator::builder()
+                .chemistry(BatteryChemistry::LiFePO4)
+                .build()
+                .unwrap()
+                .operating_temperature_range(),
+            BatteryChemistry::LiFePO4.operating_temperature_range()
+        );
+        assert_eq!(
+            SocEstimator::builder()
+                .chemistry(BatteryChemistry::LiPo)
+                .operating_temperature_range(Fixed::from_num(45), Fixed::from_num(0))
+                .build(),
+            Err(Error::InvalidConfig)
+        );
+    }
+
+    #[cfg(not(feature = "no-float"))]
+    #[test]
+    fn test_output_policy_uncompensated_custom_curve() {
+        static OVERFULL: Curve = Curve::new(&[
+            crate::CurvePoint::from_raw(3000, 0),
+            crate::CurvePoint::from_raw(4000, 1100),
+        ]);
+        let estimator = |policy| {
+            SocEstimator::builder()
+                .curve(&OVERFULL)
+                .output_policy(policy)
+                .build()
+                .unwrap()
+        };
+
+        assert_eq!(
+            estimator(OutputPolicy::Clamp).estimate_soc_permille(4000),
+            Ok(1000)
+        );
+        assert_eq!(
+            estimator(OutputPolicy::Reject).estimate_soc(4.0),
+            Err(Error::SocOutOfRange)
+        );
+        assert_eq!(
+            estimator(OutputPolicy::Raw).estimate_soc_fixed(Fixed::from_num(4)),
+            Ok(Fixed::from_num(110))
+        );
+    }
+
+    #[test]
+    fn test_every_estimate_respects_output_policy() {
+        // Reads up to 110% before compensation, 115% at 45°C
+        static OVERFULL: Curve = Curve::new(&[
+            crate::CurvePoint::from_raw(3000, 0),
+            crate::CurvePoint::from_raw(4000, 1100),
+        ]);
+        const SOC_FULL: Fixed = Fixed::from_bits(100 << 16);
+
+        for policy in [OutputPolicy::Clamp, OutputPolicy::Reject] {
+            let estimator = SocEstimator::builder()
+                .curve(&OVERFULL)
+                .temperature_compensation(Fixed::from_num(25), Fixed::from_num(0.005))
+                .aging_compensation(Fixed::from_num(1), Fixed::from_num(0.02))
+                .internal_resistance(Fixed::from_num(0.1))
+                .output_policy(policy)
+                .build()
+                .unwrap();
+            let check = |soc: Result<Fixed, Error>| match soc {
+                Ok(soc) => assert!((Fixed::ZERO..=SOC_FULL).contains(&soc), "{policy:?} {soc}"),
+                Err(error) => {
+                    assert_eq!(policy, OutputPolicy::Reject);
+                    assert_eq!(error, Error::SocOutOfRange);
+                }
+            };
+
+            for millivolts in (2_500..=4_500).step_by(125) {
+                let voltage = Fixed::from_num(millivolts) / 1000;
+                for celsius in [-20, 25, 45, 85] {
+                    let temperature = Fixed::from_num(celsius);
+                    check(estimator.estimate_soc_fixed(voltage));
+                    check(estimator.estimate_soc_with_temp_fixed(voltage, temperature));
+                    check(estimator.estimate_soc_compensated_fixed(voltage, temperature));
+                    check(estimator.estimate_soc_under_load_fixed(voltage, Fixed::from_num(-500)));
+                    check(
+                        estimator
+                            .estimate_soc_batch(&[voltage])
+                            .map(|batch| batch.soc),
+                    );
+                    check(estimator.estimate(Volts::new(voltage)).map(Percent::raw));
+                    check(
+                        estimator
+                            .estimate_compensated(Volts::new(voltage), Celsius::new(temperature))
+                            .map(Percent::raw),
+                    );
+                    check(
+                        estimator
+                            .estimate_soc_permille(millivolts)
+                            .map(|permille| Fixed::from_num(permille) / 10),
+                    );
+                    let mut socs = [Fixed::ZERO];
+                    check(
+                        estimator
+                            .estimate_soc_slice(&[voltage], &mut socs)
+                            .map(|()| socs[0]),
+                    );
+
+                    #[cfg(not(feature = "no-float"))]
+                    {
+                        let f32_voltage = voltage.to_num::<f32>();
+                        let f64_voltage = voltage.to_num::<f64>();
+                        let f32_celsius = celsius as f32;
+                        let f64_celsius = celsius as f64;
+                        let from_f32 = |soc: Result<f32, Error>| soc.map(Fixed::from_num);
+                        let from_f64 = |soc: Result<f64, Error>| soc.map(Fixed::from_num);
+                        check(from_f32(estimator.estimate_soc(f32_voltage)));
+                        check(from_f32(
+                            estimator.estimate_soc_with_temp(f32_voltage, f32_celsius),
+                        ));
+                        check(from_f32(
+                            estimator.estimate_soc_compensated(f32_voltage, f32_celsius),
+                        ));
+                        check(from_f64(estimator.estimate_soc_f64(f64_voltage)));
+                        check(from_f64(
+                            estimator.estimate_soc_with_temp_f64(f64_voltage, f64_celsius),
+                        ));
+                        check(from_f64(
+                            estimator.estimate_soc_compensated_f64(f64_voltage, f64_celsius),
+                        ));
+                    }
+                }
+            }
+        }
+    }
 }
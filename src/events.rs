@@ -0,0 +1,114 @@
+//! Gauge events and event sinks
+//!
+//! The [`SocTracker`](crate::SocTracker) can report notable changes of the
+//! battery state as [`GaugeEvent`]s through an [`EventSink`]. The sink is
+//! passed per call, so it can be a closure, a channel sender, or a struct
+//! that forwards events into an RTIC/Embassy task without the tracker
+//! having to own it.
+
+use crate::{Error, Fixed};
+
+/// Direction in which a threshold was crossed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Crossing {
+    /// SOC fell below the threshold
+    Falling,
+    /// SOC rose to or above the threshold
+    Rising,
+}
+
+/// Notable change of the battery state reported by the tracker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GaugeEvent {
+    /// The tracked SOC crossed a configured threshold
+    ThresholdCrossed {
+        /// Threshold in percent that was crossed
+        threshold: Fixed,
+        /// Tracked SOC after the crossing
+        soc: Fixed,
+        /// Direction of the crossing
+        direction: Crossing,
+    },
+    /// A sample was rejected by the plausibility check
+    SensorFault {
+        /// The rejected raw voltage
+        voltage: Fixed,
+    },
+    /// The tracked SOC reached 100%
+    Full,
+    /// The tracked SOC reached 0%
+    Empty,
+    /// A gauging period failed for a reason other than the plausibility
+    /// check, e.g. the voltage read or the curve lookup
+    ///
+    /// Reported by `GaugeTask::run()` (`async` feature), which otherwise
+    /// has no way to surface errors.
+    SampleFailed {
+        /// Why the period failed
+        error: Error,
+    },
+}
+
+/// Receiver of [`GaugeEvent`]s
+///
+/// Implemented for every `FnMut(GaugeEvent)` closure, so simple cases need
+/// no extra type.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{EventSink, GaugeEvent};
+///
+/// struct LowBatteryFlag(bool);
+///
+/// impl EventSink for LowBatteryFlag {
+///     fn on_event(&mut self, event: GaugeEvent) {
+///         if let GaugeEvent::Empty = event {
+///             self.0 = true;
+///         }
+///     }
+/// }
+///
+/// let mut flag = LowBatteryFlag(false);
+/// flag.on_event(GaugeEvent::Empty);
+/// assert!(flag.0);
+/// ```
+pub trait EventSink {
+    /// Called once for every event, in the order the events occurred
+    fn on_event(&mut self, event: GaugeEvent);
+}
+
+impl<F: FnMut(GaugeEvent)> EventSink for F {
+    #[inline]
+    fn on_event(&mut self, event: GaugeEvent) {
+        self(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_closure_sink() {
+        let mut count = 0;
+        let mut sink = |_event: GaugeEvent| count += 1;
+
+        sink.on_event(GaugeEvent::Full);
+        sink.on_event(GaugeEvent::Empty);
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_event_equality() {
+        let a = GaugeEvent::ThresholdCrossed {
+            threshold: Fixed::from_num(20),
+            soc: Fixed::from_num(19.5),
+            direction: Crossing::Falling,
+        };
+        assert_eq!(a, a);
+        assert_ne!(a, GaugeEvent::Full);
+        assert_ne!(Crossing::Falling, Crossing::Rising);
+    }
+}
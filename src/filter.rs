@@ -0,0 +1,248 @@
+//! Voltage pre-filters
+//!
+//! Raw ADC voltage readings are noisy. Averaging a buffer (see
+//! [`SocEstimator::estimate_soc_batch()`](crate::SocEstimator::estimate_soc_batch))
+//! rejects noise but adds latency proportional to the window length. The
+//! [`KalmanFilter`] in this module is a one-dimensional Kalman filter on the
+//! voltage itself: it adapts its gain to the estimated uncertainty, so it
+//! settles quickly after start-up and then rejects noise as well as a long
+//! average would.
+
+use crate::{instrument, Fixed};
+
+/// Millivolts per volt, used to keep variances in a well-resolved range
+const MV_PER_V: Fixed = Fixed::from_bits(1000 << 16);
+
+/// Scalar Kalman filter for battery voltage
+///
+/// Models the voltage as a random walk: each update adds the process
+/// noise to the estimate variance, then blends in the new measurement
+/// according to the measurement noise.
+///
+/// Variances are expressed in **mV²** rather than V² so that typical
+/// values (e.g., 10 mV measurement noise = 100 mV²) have plenty of
+/// resolution in 16.16 fixed-point.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{Fixed, KalmanFilter};
+///
+/// // 0.5 mV drift per sample, 10 mV ADC noise
+/// let mut filter = KalmanFilter::new(Fixed::from_num(0.25), Fixed::from_num(100));
+///
+/// let first = filter.update(Fixed::from_num(3.70));
+/// assert_eq!(first, Fixed::from_num(3.70));
+///
+/// // A single noisy spike only moves the estimate partially
+/// let filtered = filter.update(Fixed::from_num(3.75));
+/// assert!(filtered > Fixed::from_num(3.70) && filtered < Fixed::from_num(3.75));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KalmanFilter {
+    /// Process noise variance per update in mV²
+    process_noise: Fixed,
+    /// Measurement noise variance in mV²
+    measurement_noise: Fixed,
+    /// Current voltage estimate, `None` until the first measurement
+    estimate: Option<Fixed>,
+    /// Variance of the current estimate in mV²
+    covariance: Fixed,
+}
+
+impl KalmanFilter {
+    /// Creates a new filter
+    ///
+    /// # Arguments
+    ///
+    /// * `process_noise` - Expected voltage drift variance per update in mV²
+    /// * `measurement_noise` - Measurement noise variance in mV²
+    ///
+    /// Negative values are treated as 0. A zero measurement noise turns the
+    /// filter into a pass-through.
+    pub fn new(process_noise: Fixed, measurement_noise: Fixed) -> Self {
+        Self {
+            process_noise: process_noise.max(Fixed::ZERO),
+            measurement_noise: measurement_noise.max(Fixed::ZERO),
+            estimate: None,
+            covariance: Fixed::ZERO,
+        }
+    }
+
+    /// Feeds a new voltage measurement and returns the filtered voltage
+    ///
+    /// The first measurement initializes the estimate directly with a
+    /// variance equal to the measurement noise.
+    pub fn update(&mut self, voltage: Fixed) -> Fixed {
+        let estimate = match self.estimate {
+            Some(estimate) => estimate,
+            None => {
+                self.estimate = Some(voltage);
+                self.covariance = self.measurement_noise;
+                return voltage;
+            }
+        };
+
+        instrument::ops(3, 2);
+
+        // Predict
+        let predicted_cov = self.covariance.saturating_add(self.process_noise);
+
+        // Correct
+        let denominator = predicted_cov.saturating_add(self.measurement_noise);
+        let gain = if denominator > Fixed::ZERO {
+            predicted_cov / denominator
+        } else {
+            Fixed::ONE
+        };
+
+        let innovation_mv = voltage.saturating_sub(estimate).saturating_mul(MV_PER_V);
+        let correction = gain.saturating_mul(innovation_mv) / MV_PER_V;
+        let filtered = estimate.saturating_add(correction);
+
+        self.estimate = Some(filtered);
+        self.covariance = Fixed::ONE
+            .saturating_sub(gain)
+            .saturating_mul(predicted_cov);
+        filtered
+    }
+
+    /// Returns the current voltage estimate, if any
+    #[inline]
+    pub const fn estimate(&self) -> Option<Fixed> {
+        self.estimate
+    }
+
+    /// Returns the variance of the current estimate in mV²
+    #[inline]
+    pub const fn covariance(&self) -> Fixed {
+        self.covariance
+    }
+
+    /// Returns the process noise variance in mV²
+    #[inline]
+    pub const fn process_noise(&self) -> Fixed {
+        self.process_noise
+    }
+
+    /// Returns the measurement noise variance in mV²
+    #[inline]
+    pub const fn measurement_noise(&self) -> Fixed {
+        self.measurement_noise
+    }
+
+    /// Restores a previously observed estimate and variance
+    ///
+    /// Used to resume filtering after a power cycle without going through
+    /// the initial settling phase again.
+    #[inline]
+    pub fn set_state(&mut self, estimate: Fixed, covariance: Fixed) {
+        self.estimate = Some(estimate);
+        self.covariance = covariance.max(Fixed::ZERO);
+    }
+
+    /// Forgets the current estimate
+    #[inline]
+    pub fn reset(&mut self) {
+        self.estimate = None;
+        self.covariance = Fixed::ZERO;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kalman_first_sample() {
+        let mut filter = KalmanFilter::new(Fixed::ONE, Fixed::from_num(100));
+        assert_eq!(filter.estimate(), None);
+
+        assert_eq!(filter.update(Fixed::from_num(3.7)), Fixed::from_num(3.7));
+        assert_eq!(filter.covariance(), Fixed::from_num(100));
+    }
+
+    #[test]
+    fn test_kalman_rejects_noise() {
+        let mut filter = KalmanFilter::new(Fixed::from_num(0.1), Fixed::from_num(100));
+
+        // Alternating ±20 mV noise around 3.75V
+        let mut last = Fixed::ZERO;
+        for i in 0..200 {
+            let noise = if i % 2 == 0 { 0.02 } else { -0.02 };
+            last = filter.update(Fixed::from_num(3.75 + noise));
+        }
+
+        let error = (last - Fixed::from_num(3.75)).abs();
+        assert!(error < Fixed::from_num(0.005), "error = {}", error);
+    }
+
+    #[test]
+    fn test_kalman_tracks_step_change() {
+        // After a 100 mV step the filter must converge within a bounded
+        // number of samples even though its variance has collapsed
+        let mut filter = KalmanFilter::new(Fixed::ONE, Fixed::from_num(25));
+        filter.update(Fixed::from_num(3.6));
+        for _ in 0..50 {
+            filter.update(Fixed::from_num(3.6));
+        }
+
+        let mut steps = 0;
+        while (filter.update(Fixed::from_num(3.7)) - Fixed::from_num(3.7)).abs()
+            > Fixed::from_num(0.005)
+        {
+            steps += 1;
+            assert!(steps < 64, "filter did not settle");
+        }
+    }
+
+    #[test]
+    fn test_kalman_covariance_converges() {
+        let mut filter = KalmanFilter::new(Fixed::ONE, Fixed::from_num(100));
+        for _ in 0..100 {
+            filter.update(Fixed::from_num(3.7));
+        }
+
+        // Steady state for q=1, r=100 is about 9.5 mV²
+        let cov = filter.covariance();
+        assert!(
+            cov > Fixed::from_num(8) && cov < Fixed::from_num(11),
+            "cov = {}",
+            cov
+        );
+    }
+
+    #[test]
+    fn test_kalman_zero_noise_passthrough() {
+        let mut filter = KalmanFilter::new(Fixed::ZERO, Fixed::ZERO);
+        filter.update(Fixed::from_num(3.6));
+        assert_eq!(filter.update(Fixed::from_num(3.9)), Fixed::from_num(3.9));
+
+        let mut filter = KalmanFilter::new(Fixed::from_num(-1), Fixed::from_num(-1));
+        assert_eq!(filter.process_noise(), Fixed::ZERO);
+        assert_eq!(filter.measurement_noise(), Fixed::ZERO);
+        filter.update(Fixed::from_num(3.6));
+        assert_eq!(filter.update(Fixed::from_num(3.9)), Fixed::from_num(3.9));
+    }
+
+    #[test]
+    fn test_kalman_set_state_and_reset() {
+        let mut filter = KalmanFilter::new(Fixed::ONE, Fixed::from_num(100));
+        filter.set_state(Fixed::from_num(3.8), Fixed::from_num(4));
+        assert_eq!(filter.estimate(), Some(Fixed::from_num(3.8)));
+        assert_eq!(filter.covariance(), Fixed::from_num(4));
+
+        filter.reset();
+        assert_eq!(filter.estimate(), None);
+        assert_eq!(filter.update(Fixed::from_num(3.5)), Fixed::from_num(3.5));
+    }
+
+    #[test]
+    fn test_kalman_extreme_step_saturates() {
+        let mut filter = KalmanFilter::new(Fixed::ONE, Fixed::from_num(100));
+        filter.update(Fixed::MAX);
+        let filtered = filter.update(Fixed::MIN);
+        assert!(filtered < Fixed::MAX);
+        assert!(filter.covariance() >= Fixed::ZERO);
+    }
+}
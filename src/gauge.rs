@@ -0,0 +1,338 @@
+//! Standard data model of TI Impedance Track gauges
+//!
+//! Firmware written against a BQ27441-style fuel gauge reads a fixed set
+//! of "standard commands": SOC, state of health, remaining and full charge
+//! capacity, average current and a flags word. [`GaugeDataModel`] carries
+//! the same values in the same units, filled from this crate's
+//! estimators, so code that used to talk to a gauge IC can switch to a
+//! software gauge by replacing the register reads.
+
+use crate::sbs::{deci_kelvin, saturate_i16, saturate_u16};
+use crate::{Fixed, PackReport, SocTracker};
+
+/// 100% as a fixed-point constant
+const SOC_FULL: Fixed = Fixed::from_bits(100 << 16);
+
+/// Standard command codes backed by [`GaugeDataModel`]
+///
+/// Each command addresses a little-endian word: the host reads the low
+/// byte at the command code and the high byte at the code plus one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum GaugeCommand {
+    /// Temperature in 0.1 K
+    Temperature = 0x02,
+    /// Voltage in mV
+    Voltage = 0x04,
+    /// Status flags (`FLAG_*` constants of [`GaugeDataModel`])
+    Flags = 0x06,
+    /// Remaining capacity in mAh
+    RemainingCapacity = 0x0C,
+    /// Full charge capacity in mAh
+    FullChargeCapacity = 0x0E,
+    /// Average current in mA (signed)
+    AverageCurrent = 0x10,
+    /// Average power in mW (signed)
+    AveragePower = 0x18,
+    /// SOC in percent
+    StateOfCharge = 0x1C,
+    /// State of health in percent
+    StateOfHealth = 0x20,
+}
+
+impl GaugeCommand {
+    /// Looks up the command for a standard command code
+    ///
+    /// Returns `None` for codes not backed by [`GaugeDataModel`].
+    pub const fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0x02 => Some(GaugeCommand::Temperature),
+            0x04 => Some(GaugeCommand::Voltage),
+            0x06 => Some(GaugeCommand::Flags),
+            0x0C => Some(GaugeCommand::RemainingCapacity),
+            0x0E => Some(GaugeCommand::FullChargeCapacity),
+            0x10 => Some(GaugeCommand::AverageCurrent),
+            0x18 => Some(GaugeCommand::AveragePower),
+            0x1C => Some(GaugeCommand::StateOfCharge),
+            0x20 => Some(GaugeCommand::StateOfHealth),
+            _ => None,
+        }
+    }
+}
+
+/// Snapshot of the standard gauge outputs
+///
+/// Values use the units of the BQ27441 standard commands and saturate to
+/// the range of their register. State of health scales the design
+/// capacity into the full charge capacity, as on the gauge.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{Fixed, GaugeDataModel};
+///
+/// let data = GaugeDataModel::new(
+///     Fixed::from_num(3.8),   // voltage
+///     Fixed::from_num(-200),  // average current in mA
+///     Fixed::from_num(25),    // temperature in °C
+///     Fixed::from_num(70),    // SOC in percent
+///     Fixed::from_num(90),    // SOH in percent
+///     Fixed::from_num(1000),  // design capacity in mAh
+/// )
+/// .with_soc_alarms(Fixed::from_num(10), Fixed::from_num(2));
+///
+/// assert_eq!(data.full_charge_capacity, 900);
+/// assert_eq!(data.remaining_capacity, 630);
+/// assert_eq!(data.average_power, -760);
+/// assert!(data.has_flag(GaugeDataModel::FLAG_DSG));
+/// assert!(!data.has_flag(GaugeDataModel::FLAG_SOC1));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GaugeDataModel {
+    /// Temperature in 0.1 K
+    pub temperature: u16,
+    /// Voltage in mV
+    pub voltage: u16,
+    /// Status flags (`FLAG_*` constants)
+    pub flags: u16,
+    /// Remaining capacity in mAh
+    pub remaining_capacity: u16,
+    /// Full charge capacity in mAh
+    pub full_charge_capacity: u16,
+    /// Average current in mA, negative when discharging
+    pub average_current: i16,
+    /// Average power in mW, negative when discharging
+    pub average_power: i16,
+    /// SOC in percent (0 to 100)
+    pub state_of_charge: u16,
+    /// State of health in percent (0 to 100)
+    pub state_of_health: u16,
+}
+
+impl GaugeDataModel {
+    /// Full charge detected
+    pub const FLAG_FC: u16 = 0x0200;
+    /// Battery presence (always set)
+    pub const FLAG_BAT_DET: u16 = 0x0008;
+    /// SOC at or below the first alarm threshold
+    pub const FLAG_SOC1: u16 = 0x0004;
+    /// SOC at or below the final alarm threshold
+    pub const FLAG_SOCF: u16 = 0x0002;
+    /// Discharging
+    pub const FLAG_DSG: u16 = 0x0001;
+
+    /// Builds the data model from gauge outputs
+    ///
+    /// The SOC alarm flags are left clear; see
+    /// [`with_soc_alarms()`](Self::with_soc_alarms).
+    ///
+    /// # Arguments
+    ///
+    /// * `voltage` - Battery voltage in volts
+    /// * `average_current_ma` - Average current in mA, negative when discharging
+    /// * `temperature` - Battery temperature in °C
+    /// * `soc` - SOC in percent (clamped to 0-100)
+    /// * `soh` - State of health in percent (clamped to 0-100)
+    /// * `design_capacity_mah` - Design capacity in mAh (0 if unknown)
+    pub fn new(
+        voltage: Fixed,
+        average_current_ma: Fixed,
+        temperature: Fixed,
+        soc: Fixed,
+        soh: Fixed,
+        design_capacity_mah: Fixed,
+    ) -> Self {
+        let soc = soc.clamp(Fixed::ZERO, SOC_FULL);
+        let soh = soh.clamp(Fixed::ZERO, SOC_FULL);
+        let design_capacity_mah = design_capacity_mah.max(Fixed::ZERO);
+        let full_bits = design_capacity_mah.to_bits() as i64 * soh.to_bits() as i64 / (100 << 16);
+        let remaining_bits = full_bits * soc.to_bits() as i64 / (100 << 16);
+        // V * mA = mW
+        let power_bits = (voltage.to_bits() as i64 * average_current_ma.to_bits() as i64) >> 16;
+
+        let mut flags = Self::FLAG_BAT_DET;
+        if average_current_ma < Fixed::ZERO {
+            flags |= Self::FLAG_DSG;
+        }
+        if soc >= SOC_FULL {
+            flags |= Self::FLAG_FC;
+        }
+
+        Self {
+            temperature: deci_kelvin(temperature),
+            voltage: saturate_u16(voltage.to_bits() as i64 * 1000),
+            flags,
+            remaining_capacity: saturate_u16(remaining_bits),
+            full_charge_capacity: saturate_u16(full_bits),
+            average_current: saturate_i16(average_current_ma),
+            average_power: ((power_bits + 0x8000) >> 16).clamp(i16::MIN as i64, i16::MAX as i64)
+                as i16,
+            state_of_charge: saturate_u16(soc.to_bits() as i64),
+            state_of_health: saturate_u16(soh.to_bits() as i64),
+        }
+    }
+
+    /// Builds the data model from a tracker's state
+    ///
+    /// Uses the tracked SOC and temperature and takes the configured
+    /// capacity as the design capacity. SOC1 and SOCF follow the tracker's
+    /// low and critical SOC thresholds.
+    ///
+    /// # Returns
+    ///
+    /// `None` until the tracker has processed its first sample.
+    pub fn from_tracker(
+        tracker: &SocTracker,
+        voltage: Fixed,
+        average_current_ma: Fixed,
+        soh: Fixed,
+    ) -> Option<Self> {
+        let config = tracker.config();
+        let data = Self::new(
+            voltage,
+            average_current_ma,
+            tracker.temperature(),
+            tracker.soc()?,
+            soh,
+            config.capacity_mah,
+        );
+        Some(data.with_soc_alarms(config.low_soc_threshold, config.critical_soc_threshold))
+    }
+
+    /// Builds the data model from a pack report
+    ///
+    /// Uses the pack SOC, SOH and temperature.
+    ///
+    /// # Arguments
+    ///
+    /// * `report` - Report from [`PackEstimator::report()`](crate::PackEstimator::report)
+    /// * `pack_voltage` - Pack voltage in volts
+    /// * `average_current_ma` - Average pack current in mA
+    /// * `design_capacity_mah` - Pack design capacity in mAh
+    pub fn from_pack_report<const CELLS: usize>(
+        report: &PackReport<CELLS>,
+        pack_voltage: Fixed,
+        average_current_ma: Fixed,
+        design_capacity_mah: Fixed,
+    ) -> Self {
+        Self::new(
+            pack_voltage,
+            average_current_ma,
+            report.temperature,
+            report.soc,
+            report.soh,
+            design_capacity_mah,
+        )
+    }
+
+    /// Set the SOC1 and SOCF flags from alarm thresholds in percent
+    pub fn with_soc_alarms(mut self, soc1_threshold: Fixed, socf_threshold: Fixed) -> Self {
+        let soc = Fixed::from_num(self.state_of_charge);
+        self.flags &= !(Self::FLAG_SOC1 | Self::FLAG_SOCF);
+        if soc <= soc1_threshold {
+            self.flags |= Self::FLAG_SOC1;
+        }
+        if soc <= socf_threshold {
+            self.flags |= Self::FLAG_SOCF;
+        }
+        self
+    }
+
+    /// Word returned for a read of `command`
+    ///
+    /// Signed registers are returned in two's complement.
+    pub const fn read_word(&self, command: GaugeCommand) -> u16 {
+        match command {
+            GaugeCommand::Temperature => self.temperature,
+            GaugeCommand::Voltage => self.voltage,
+            GaugeCommand::Flags => self.flags,
+            GaugeCommand::RemainingCapacity => self.remaining_capacity,
+            GaugeCommand::FullChargeCapacity => self.full_charge_capacity,
+            GaugeCommand::AverageCurrent => self.average_current as u16,
+            GaugeCommand::AveragePower => self.average_power as u16,
+            GaugeCommand::StateOfCharge => self.state_of_charge,
+            GaugeCommand::StateOfHealth => self.state_of_health,
+        }
+    }
+
+    /// Returns `true` if all bits of `flag` are set
+    #[inline]
+    pub const fn has_flag(&self, flag: u16) -> bool {
+        self.flags & flag == flag
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BatteryChemistry, PackEstimator, SocEstimator, TrackerConfig};
+
+    #[test]
+    fn test_data_model_values() {
+        let data = GaugeDataModel::new(
+            Fixed::from_num(4.2),
+            Fixed::from_num(500),
+            Fixed::from_num(30),
+            Fixed::from_num(100),
+            Fixed::from_num(120),
+            Fixed::from_num(2000),
+        );
+
+        assert_eq!(data.temperature, 3032);
+        assert_eq!(data.voltage, 4200);
+        assert_eq!(data.state_of_health, 100);
+        assert_eq!(data.full_charge_capacity, 2000);
+        assert_eq!(data.remaining_capacity, 2000);
+        assert_eq!(data.average_current, 500);
+        assert_eq!(data.average_power, 2100);
+        assert_eq!(
+            data.flags,
+            GaugeDataModel::FLAG_BAT_DET | GaugeDataModel::FLAG_FC
+        );
+
+        let empty = data.with_soc_alarms(Fixed::from_num(10), Fixed::from_num(2));
+        assert!(!empty.has_flag(GaugeDataModel::FLAG_SOC1));
+
+        let low = GaugeDataModel::new(
+            Fixed::from_num(3.3),
+            Fixed::from_num(-100),
+            Fixed::from_num(25),
+            Fixed::from_num(1),
+            Fixed::from_num(80),
+            Fixed::from_num(2000),
+        )
+        .with_soc_alarms(Fixed::from_num(10), Fixed::from_num(2));
+        assert!(low.has_flag(GaugeDataModel::FLAG_SOC1 | GaugeDataModel::FLAG_SOCF));
+        assert!(low.has_flag(GaugeDataModel::FLAG_DSG));
+        assert_eq!(low.remaining_capacity, 16);
+    }
+
+    #[test]
+    fn test_data_model_sources() {
+        let config = TrackerConfig::default().with_capacity_mah(Fixed::from_num(1000));
+        let mut tracker = SocTracker::new(SocEstimator::new(BatteryChemistry::LiPo), config);
+        let voltage = Fixed::from_num(3.75);
+        let soh = Fixed::from_num(100);
+
+        assert_eq!(
+            GaugeDataModel::from_tracker(&tracker, voltage, Fixed::ZERO, soh),
+            None
+        );
+        tracker.update(voltage, Fixed::ZERO, Fixed::ONE).unwrap();
+        let data = GaugeDataModel::from_tracker(&tracker, voltage, Fixed::ZERO, soh).unwrap();
+        assert_eq!(data.state_of_charge, 60);
+        assert_eq!(data.remaining_capacity, 600);
+
+        let pack = PackEstimator::<2>::new(SocEstimator::new(BatteryChemistry::LiPo));
+        let report = pack.report(&[voltage; 2]).unwrap();
+        let data = GaugeDataModel::from_pack_report(
+            &report,
+            Fixed::from_num(7.5),
+            Fixed::ZERO,
+            Fixed::from_num(1000),
+        );
+        assert_eq!(data.voltage, 7500);
+        assert_eq!(data.state_of_charge, 60);
+        assert_eq!(data.state_of_health, 100);
+    }
+}
@@ -0,0 +1,208 @@
+//! Runtime-editable curve backed by `heapless::Vec`
+//!
+//! Enabled with the `heapless` feature. [`Curve`](crate::Curve) is built once
+//! from a fixed 32-slot array; [`HeaplessCurve`] instead lets firmware push
+//! and pop points at runtime (e.g., while learning a curve from calibration
+//! cycles) with a capacity chosen at compile time.
+
+#[cfg(not(feature = "no-float"))]
+use crate::curve::volts_from_f32;
+use crate::curve::{interpolate_points, validate_points};
+use crate::{CurvePoint, Error, ErrorDetail, Fixed, SocEstimate};
+use heapless::Vec;
+
+/// A voltage-to-SOC curve with up to `N` points stored in a `heapless::Vec`
+///
+/// Points must be pushed in order of increasing voltage; use
+/// [`validate_sorted()`](Self::validate_sorted) to check a curve assembled
+/// at runtime. Interpolation behaves like
+/// [`Curve::voltage_to_soc_fixed()`](crate::Curve::voltage_to_soc_fixed).
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{CurvePoint, Fixed, HeaplessCurve};
+///
+/// let mut curve = HeaplessCurve::<8>::new();
+/// curve.push(CurvePoint::new(3.0, 0.0)).unwrap();
+/// curve.push(CurvePoint::new(4.0, 100.0)).unwrap();
+///
+/// assert_eq!(curve.voltage_to_soc_fixed(Fixed::from_num(3.5)).unwrap(), Fixed::from_num(50));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaplessCurve<const N: usize> {
+    points: Vec<CurvePoint, N>,
+}
+
+impl<const N: usize> HeaplessCurve<N> {
+    /// Creates an empty curve
+    #[inline]
+    pub const fn new() -> Self {
+        Self { points: Vec::new() }
+    }
+
+    /// Creates a curve from a slice of points
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(curve)` - All points fit
+    /// * `Err(Error::InvalidCurve)` - More than `N` points were given
+    pub fn from_points(points: &[CurvePoint]) -> Result<Self, Error> {
+        Vec::from_slice(points)
+            .map(|points| Self { points })
+            .map_err(|_| Error::InvalidCurve)
+    }
+
+    /// Appends a point, returning it back if the curve is full
+    #[inline]
+    pub fn push(&mut self, point: CurvePoint) -> Result<(), CurvePoint> {
+        self.points.push(point)
+    }
+
+    /// Removes and returns the last point
+    #[inline]
+    pub fn pop(&mut self) -> Option<CurvePoint> {
+        self.points.pop()
+    }
+
+    /// Removes all points
+    #[inline]
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
+
+    /// Returns the points in order
+    #[inline]
+    pub fn points(&self) -> &[CurvePoint] {
+        &self.points
+    }
+
+    /// Returns the number of points
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Returns `true` if the curve has no points
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Returns the maximum number of points (`N`)
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Checks that the curve has at least 2 points with strictly
+    /// increasing voltages
+    #[inline]
+    pub fn validate_sorted(&self) -> Result<(), Error> {
+        self.validate_sorted_detail().map_err(Error::from)
+    }
+
+    /// Like [`validate_sorted()`](Self::validate_sorted), but reports the
+    /// index of the first point that breaks the voltage order
+    #[inline]
+    pub fn validate_sorted_detail(&self) -> Result<(), ErrorDetail> {
+        validate_points(&self.points)
+    }
+
+    /// Converts a voltage to SOC percentage using fixed-point arithmetic
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(soc)` - SOC percentage as fixed-point
+    /// * `Err(Error::InvalidCurve)` - Curve has fewer than 2 points
+    /// * `Err(Error::NumericalError)` - Points out of order
+    #[inline]
+    pub fn voltage_to_soc_fixed(&self, voltage: Fixed) -> Result<Fixed, Error> {
+        interpolate_points(&self.points, voltage)
+    }
+
+    /// Converts a voltage in volts to SOC percentage
+    ///
+    /// Returns [`Error::NonFiniteInput`] for a NaN or infinite voltage.
+    #[cfg(not(feature = "no-float"))]
+    pub fn voltage_to_soc(&self, voltage: f32) -> Result<f32, Error> {
+        self.voltage_to_soc_fixed(volts_from_f32(voltage)?)
+            .map(|soc| soc.to_num::<f32>())
+    }
+
+    /// Returns the voltage of the first and last point as fixed-point values
+    ///
+    /// Both are zero for an empty curve.
+    pub fn voltage_range_fixed(&self) -> (Fixed, Fixed) {
+        match (self.points.first(), self.points.last()) {
+            (Some(first), Some(last)) => (first.voltage_fixed(), last.voltage_fixed()),
+            _ => (Fixed::ZERO, Fixed::ZERO),
+        }
+    }
+}
+
+// Non-const Default implementation
+impl<const N: usize> Default for HeaplessCurve<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> SocEstimate for HeaplessCurve<N> {
+    #[inline]
+    fn estimate_soc_fixed(&self, voltage: Fixed) -> Result<Fixed, Error> {
+        self.voltage_to_soc_fixed(voltage)
+    }
+
+    #[inline]
+    fn voltage_range_fixed(&self) -> (Fixed, Fixed) {
+        HeaplessCurve::voltage_range_fixed(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Curve;
+
+    #[test]
+    fn test_heapless_curve_matches_curve() {
+        let points = [
+            CurvePoint::new(3.0, 0.0),
+            CurvePoint::new(3.5, 50.0),
+            CurvePoint::new(4.0, 100.0),
+        ];
+        let fixed = Curve::new(&points);
+        let dynamic = HeaplessCurve::<4>::from_points(&points).unwrap();
+
+        for mv in (2_900..=4_100).step_by(25) {
+            let voltage = Fixed::from_num(mv) / Fixed::from_num(1000);
+            assert_eq!(
+                dynamic.voltage_to_soc_fixed(voltage),
+                fixed.voltage_to_soc_fixed(voltage)
+            );
+        }
+        assert_eq!(dynamic.voltage_range_fixed(), fixed.voltage_range_fixed());
+    }
+
+    #[test]
+    fn test_heapless_curve_push_pop() {
+        let mut curve = HeaplessCurve::<2>::new();
+        assert_eq!(curve.voltage_to_soc(3.5), Err(Error::InvalidCurve));
+
+        curve.push(CurvePoint::new(3.0, 0.0)).unwrap();
+        curve.push(CurvePoint::new(4.0, 100.0)).unwrap();
+        let extra = CurvePoint::new(4.2, 100.0);
+        assert_eq!(curve.push(extra), Err(extra));
+        assert_eq!(curve.validate_sorted(), Ok(()));
+
+        assert_eq!(curve.pop(), Some(CurvePoint::new(4.0, 100.0)));
+        curve.push(CurvePoint::new(2.5, 0.0)).unwrap();
+        assert_eq!(
+            curve.validate_sorted_detail(),
+            Err(Error::InvalidCurve.at_curve_point(1))
+        );
+        assert!(HeaplessCurve::<2>::from_points(&[extra; 3]).is_err());
+    }
+}
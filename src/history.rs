@@ -0,0 +1,235 @@
+//! SOC history ring buffer
+//!
+//! Trend displays ("-3 %/h") and rate-of-change checks need a few past SOC
+//! values. [`SocHistory`] stores up to [`MAX_HISTORY_LEN`] timestamped SOC
+//! samples in a fixed-size ring buffer, overwriting the oldest entry once
+//! full. A minimum sample interval keeps a fast update loop from filling
+//! the buffer within a few seconds.
+
+use crate::Fixed;
+
+/// Maximum number of entries stored in an [`SocHistory`]
+pub const MAX_HISTORY_LEN: usize = 32;
+
+/// Milliseconds per hour, used for rate-of-change conversion
+const MS_PER_HOUR: i64 = 3_600_000;
+
+/// A single timestamped SOC sample
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// Timestamp in milliseconds (wraps after about 49 days)
+    pub timestamp_ms: u32,
+    /// SOC percentage at `timestamp_ms`
+    pub soc: Fixed,
+}
+
+/// Fixed-size ring buffer of timestamped SOC samples
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{Fixed, SocHistory};
+///
+/// // Keep at most one sample per minute
+/// let mut history = SocHistory::new(60_000);
+///
+/// history.record(0, Fixed::from_num(80));
+/// history.record(10_000, Fixed::from_num(79)); // too soon, ignored
+/// history.record(1_800_000, Fixed::from_num(75));
+///
+/// assert_eq!(history.len(), 2);
+/// // Lost 5% in half an hour
+/// assert_eq!(history.rate_per_hour(), Some(Fixed::from_num(-10)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SocHistory {
+    /// Entry storage (fixed size for memory efficiency)
+    entries: [HistoryEntry; MAX_HISTORY_LEN],
+    /// Index of the oldest entry
+    head: u8,
+    /// Number of valid entries
+    len: u8,
+    /// Minimum time between two recorded entries in milliseconds
+    interval_ms: u32,
+}
+
+impl SocHistory {
+    /// Creates an empty history
+    ///
+    /// # Arguments
+    ///
+    /// * `interval_ms` - Minimum time between two recorded entries in
+    ///   milliseconds (0 records every sample)
+    pub const fn new(interval_ms: u32) -> Self {
+        Self {
+            entries: [HistoryEntry {
+                timestamp_ms: 0,
+                soc: Fixed::ZERO,
+            }; MAX_HISTORY_LEN],
+            head: 0,
+            len: 0,
+            interval_ms,
+        }
+    }
+
+    /// Records a sample if at least the configured interval has passed
+    ///
+    /// Timestamps are compared with wrapping arithmetic, so a wrap of the
+    /// millisecond counter is handled transparently.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the sample was stored
+    pub fn record(&mut self, timestamp_ms: u32, soc: Fixed) -> bool {
+        if let Some(latest) = self.latest() {
+            if timestamp_ms.wrapping_sub(latest.timestamp_ms) < self.interval_ms {
+                return false;
+            }
+        }
+
+        let entry = HistoryEntry { timestamp_ms, soc };
+        if (self.len as usize) < MAX_HISTORY_LEN {
+            let index = (self.head as usize + self.len as usize) % MAX_HISTORY_LEN;
+            self.entries[index] = entry;
+            self.len += 1;
+        } else {
+            let head = self.head as usize % MAX_HISTORY_LEN;
+            self.entries[head] = entry;
+            self.head = ((head + 1) % MAX_HISTORY_LEN) as u8;
+        }
+        true
+    }
+
+    /// Returns the entry at `index`, where 0 is the oldest entry
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<HistoryEntry> {
+        if index >= self.len as usize {
+            return None;
+        }
+        Some(self.entries[(self.head as usize + index) % MAX_HISTORY_LEN])
+    }
+
+    /// Returns the oldest stored entry
+    #[inline]
+    pub fn oldest(&self) -> Option<HistoryEntry> {
+        self.get(0)
+    }
+
+    /// Returns the most recent stored entry
+    #[inline]
+    pub fn latest(&self) -> Option<HistoryEntry> {
+        match self.len {
+            0 => None,
+            len => self.get(len as usize - 1),
+        }
+    }
+
+    /// Iterates over the stored entries from oldest to newest
+    pub fn iter(&self) -> impl Iterator<Item = HistoryEntry> + '_ {
+        (0..self.len as usize).filter_map(move |i| self.get(i))
+    }
+
+    /// Average rate of change between the oldest and newest entry in %/h
+    ///
+    /// Negative while discharging. Returns `None` with fewer than two
+    /// entries or if both entries share the same timestamp.
+    pub fn rate_per_hour(&self) -> Option<Fixed> {
+        let oldest = self.oldest()?;
+        let latest = self.latest()?;
+        let elapsed_ms = latest.timestamp_ms.wrapping_sub(oldest.timestamp_ms);
+        if elapsed_ms == 0 {
+            return None;
+        }
+
+        let delta_bits = latest.soc.to_bits() as i64 - oldest.soc.to_bits() as i64;
+        let rate_bits = delta_bits * MS_PER_HOUR / elapsed_ms as i64;
+        let rate_bits = rate_bits.clamp(i32::MIN as i64, i32::MAX as i64) as i32;
+        Some(Fixed::from_bits(rate_bits))
+    }
+
+    /// Returns the number of stored entries
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    /// Returns `true` if no entries are stored
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the minimum interval between recorded entries in milliseconds
+    #[inline]
+    pub const fn interval_ms(&self) -> u32 {
+        self.interval_ms
+    }
+
+    /// Removes all entries
+    #[inline]
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_empty() {
+        let history = SocHistory::new(0);
+        assert!(history.is_empty());
+        assert_eq!(history.oldest(), None);
+        assert_eq!(history.latest(), None);
+        assert_eq!(history.rate_per_hour(), None);
+    }
+
+    #[test]
+    fn test_history_wraps_oldest_out() {
+        let mut history = SocHistory::new(0);
+        for i in 0..(MAX_HISTORY_LEN as u32 + 5) {
+            assert!(history.record(i * 1000, Fixed::from_num(i)));
+        }
+
+        assert_eq!(history.len(), MAX_HISTORY_LEN);
+        assert_eq!(history.oldest().unwrap().soc, Fixed::from_num(5));
+        assert_eq!(
+            history.latest().unwrap().soc,
+            Fixed::from_num(MAX_HISTORY_LEN + 4)
+        );
+        assert!(history
+            .iter()
+            .zip(history.iter().skip(1))
+            .all(|(a, b)| a.timestamp_ms < b.timestamp_ms));
+    }
+
+    #[test]
+    fn test_history_interval() {
+        let mut history = SocHistory::new(1000);
+        assert!(history.record(0, Fixed::from_num(50)));
+        assert!(!history.record(999, Fixed::from_num(49)));
+        assert!(history.record(1000, Fixed::from_num(49)));
+        assert_eq!(history.len(), 2);
+    }
+
+    #[test]
+    fn test_history_timestamp_wrap() {
+        let mut history = SocHistory::new(1000);
+        history.record(u32::MAX - 499, Fixed::from_num(60));
+        assert!(history.record(500, Fixed::from_num(61)));
+
+        // +1% in 1 s = +3600 %/h
+        assert_eq!(history.rate_per_hour(), Some(Fixed::from_num(3600)));
+    }
+
+    #[test]
+    fn test_history_clear() {
+        let mut history = SocHistory::new(0);
+        history.record(0, Fixed::from_num(10));
+        history.clear();
+        assert!(history.is_empty());
+        assert_eq!(history.iter().count(), 0);
+    }
+}
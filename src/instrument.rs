@@ -0,0 +1,193 @@
+//! Operation counting for verifying the performance claims on a target
+//!
+//! With the `instrument` feature, the fixed-point estimation paths count
+//! their curve lookups and the fixed-point multiplications and divisions
+//! they perform: curve interpolation, measurement scaling, compensation,
+//! the Kalman pre-filter and the tracker update. [`measure()`] reports the
+//! counts of one call, and [`measure_cycles()`] adds the cycles elapsed on
+//! a user-supplied counter such as the Cortex-M DWT cycle counter.
+//!
+//! The counters are global and updated with plain atomic loads and stores,
+//! which every target supports; counts from code running concurrently
+//! (e.g., an estimate in an interrupt during a measurement) can mix or get
+//! lost. Without the feature the hooks compile to nothing and [`measure()`]
+//! is not available.
+//!
+//! # Examples
+//!
+//! ```
+//! use battery_estimator::{instrument, BatteryChemistry, Fixed, SocEstimator};
+//!
+//! let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+//! let (soc, ops) = instrument::measure(|| estimator.estimate_soc_fixed(Fixed::from_num(3.75)));
+//!
+//! assert!(soc.is_ok());
+//! assert_eq!(ops.lookups, 1);
+//! assert!(ops.divisions == 0 && ops.multiplications <= 4);
+//! ```
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+static MULTIPLICATIONS: AtomicU32 = AtomicU32::new(0);
+static DIVISIONS: AtomicU32 = AtomicU32::new(0);
+static LOOKUPS: AtomicU32 = AtomicU32::new(0);
+
+/// Operations counted since the last [`reset()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct OpCounts {
+    /// Fixed-point and wide-integer multiplications
+    pub multiplications: u32,
+    /// Fixed-point and wide-integer divisions
+    pub divisions: u32,
+    /// Curve lookups (segment search and interpolation)
+    pub lookups: u32,
+}
+
+/// Operation counts and elapsed cycles of one measured call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Measurement {
+    /// Operations performed by the call
+    pub ops: OpCounts,
+    /// Difference of the cycle counter across the call (wrapping)
+    pub cycles: u32,
+}
+
+/// Clears all counters
+#[inline]
+pub fn reset() {
+    MULTIPLICATIONS.store(0, Ordering::Relaxed);
+    DIVISIONS.store(0, Ordering::Relaxed);
+    LOOKUPS.store(0, Ordering::Relaxed);
+}
+
+/// Returns the operations counted since the last [`reset()`]
+#[inline]
+pub fn counts() -> OpCounts {
+    OpCounts {
+        multiplications: MULTIPLICATIONS.load(Ordering::Relaxed),
+        divisions: DIVISIONS.load(Ordering::Relaxed),
+        lookups: LOOKUPS.load(Ordering::Relaxed),
+    }
+}
+
+/// Runs `f` on cleared counters and returns its result with the counts
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{
+///     instrument, BatteryChemistry, Fixed, SocEstimator, SocTracker, TrackerConfig,
+/// };
+///
+/// let estimator = SocEstimator::with_all_compensation(
+///     BatteryChemistry::LiPo,
+///     Fixed::from_num(25),
+///     Fixed::from_num(0.005),
+///     Fixed::from_num(2),
+///     Fixed::from_num(0.02),
+/// );
+/// let voltage = Fixed::from_num(3.75);
+///
+/// let (_, plain) = instrument::measure(|| estimator.estimate_soc_fixed(voltage));
+/// let (_, compensated) = instrument::measure(|| {
+///     estimator.estimate_soc_compensated_fixed(voltage, Fixed::from_num(35))
+/// });
+/// assert_eq!((plain.lookups, compensated.lookups), (1, 1));
+/// assert!(compensated.multiplications > plain.multiplications);
+///
+/// // A tracker update adds the coulomb counting and slew limiting
+/// let mut tracker = SocTracker::new(estimator, TrackerConfig::default());
+/// tracker.update(voltage, Fixed::ZERO, Fixed::ONE).unwrap();
+/// let (_, update) = instrument::measure(|| tracker.update(voltage, Fixed::from_num(-500), Fixed::ONE));
+/// assert_eq!(update.lookups, 1);
+/// assert!(update.divisions > compensated.divisions);
+/// ```
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, OpCounts) {
+    reset();
+    let result = f();
+    (result, counts())
+}
+
+/// Like [`measure()`], also reading `cycle_counter` before and after `f`
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{instrument, BatteryChemistry, Fixed, SocEstimator};
+///
+/// // Stand-in for a hardware counter such as `DWT::cycle_count()`
+/// let mut now = 0u32;
+/// let clock = || {
+///     now += 100;
+///     now
+/// };
+///
+/// let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+/// let (_, measurement) =
+///     instrument::measure_cycles(clock, || estimator.estimate_soc_fixed(Fixed::from_num(3.75)));
+/// assert_eq!(measurement.cycles, 100);
+/// ```
+pub fn measure_cycles<R>(
+    mut cycle_counter: impl FnMut() -> u32,
+    f: impl FnOnce() -> R,
+) -> (R, Measurement) {
+    reset();
+    let start = cycle_counter();
+    let result = f();
+    let cycles = cycle_counter().wrapping_sub(start);
+    (
+        result,
+        Measurement {
+            ops: counts(),
+            cycles,
+        },
+    )
+}
+
+/// Adds to a counter without a read-modify-write atomic
+#[inline]
+fn add(counter: &AtomicU32, n: u32) {
+    counter.store(
+        counter.load(Ordering::Relaxed).wrapping_add(n),
+        Ordering::Relaxed,
+    );
+}
+
+/// Counts `multiplications` and `divisions` (hook for the estimation paths)
+#[inline(always)]
+pub(crate) fn ops(multiplications: u32, divisions: u32) {
+    add(&MULTIPLICATIONS, multiplications);
+    add(&DIVISIONS, divisions);
+}
+
+/// Counts one curve lookup (hook for the estimation paths)
+#[inline(always)]
+pub(crate) fn lookup() {
+    add(&LOOKUPS, 1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The counters are shared with tests running in parallel, so only
+    // lower bounds hold here; the doc examples check exact counts
+    #[test]
+    fn test_measure_hooks() {
+        let (value, ops) = measure(|| {
+            super::ops(2, 1);
+            lookup();
+            7
+        });
+        assert_eq!(value, 7);
+        assert!(ops.multiplications >= 2 && ops.divisions >= 1 && ops.lookups >= 1);
+
+        let mut now = u32::MAX - 5;
+        let clock = || {
+            now = now.wrapping_add(10);
+            now
+        };
+        let (_, measurement) = measure_cycles(clock, || ());
+        assert_eq!(measurement.cycles, 10);
+    }
+}
@@ -0,0 +1,322 @@
+//! Reusable checks of the estimation invariants
+//!
+//! The crate's own tests rely on three properties of every estimator:
+//!
+//! - **Monotonicity**: a higher voltage never reads as a lower SOC
+//! - **Boundedness**: every estimate lies within 0–100%
+//! - **Nominal identity**: at the nominal temperature and zero age,
+//!   compensation leaves the SOC unchanged
+//!
+//! A custom curve or configuration can break them, e.g. with a curve whose
+//! SOC falls between two points, a curve above 100% read with
+//! [`OutputPolicy::Raw`](crate::OutputPolicy::Raw), or a nominal
+//! temperature outside the operating range. The functions here sweep a
+//! measured voltage range of a configured [`SocEstimator`] and report the
+//! first [`Violation`], so integrators can run the same checks in their
+//! own CI. They need neither `std` nor `proptest`.
+//!
+//! # Examples
+//!
+//! ```
+//! use battery_estimator::invariants::{check_all, MILLIVOLT};
+//! use battery_estimator::{Curve, CurvePoint, EstimatorConfig, Fixed, SocEstimator};
+//!
+//! static CURVE: Curve = Curve::new(&[
+//!     CurvePoint::new(3.0, 0.0),
+//!     CurvePoint::new(3.6, 40.0),
+//!     CurvePoint::new(4.1, 100.0),
+//! ]);
+//! let mut estimator = SocEstimator::with_custom_curve(&CURVE);
+//! estimator.update_config(
+//!     EstimatorConfig::default()
+//!         .with_temperature_compensation()
+//!         .with_aging_compensation()
+//!         .with_age_years(Fixed::from_num(2)),
+//! );
+//!
+//! for temperature in [-20, 0, 25, 60] {
+//!     check_all(
+//!         &estimator,
+//!         Fixed::from_num(2.8)..=Fixed::from_num(4.3),
+//!         MILLIVOLT,
+//!         Fixed::from_num(temperature),
+//!     )
+//!     .unwrap();
+//! }
+//! ```
+
+use crate::{Error, Fixed, SocEstimator};
+use core::ops::RangeInclusive;
+
+/// 100% as a fixed-point constant
+const SOC_FULL: Fixed = Fixed::from_bits(100 << 16);
+
+/// The fixed-point value nearest to one millivolt, the resolution of every
+/// curve lookup, as a sweep step
+pub const MILLIVOLT: Fixed = Fixed::from_bits(66);
+
+/// First input found to break an invariant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Violation {
+    /// The SOC at `voltage` is below the SOC one step lower
+    NotMonotonic {
+        /// Measured voltage (volts)
+        voltage: Fixed,
+        /// SOC at `voltage` (percent)
+        soc: Fixed,
+        /// SOC one step below `voltage` (percent)
+        previous_soc: Fixed,
+    },
+    /// The SOC at `voltage` is outside 0–100%
+    OutOfBounds {
+        /// Measured voltage (volts)
+        voltage: Fixed,
+        /// The estimate (percent)
+        soc: Fixed,
+    },
+    /// Compensation at nominal conditions changed the SOC at `voltage`
+    NotIdentity {
+        /// Measured voltage (volts)
+        voltage: Fixed,
+        /// Uncompensated SOC (percent)
+        soc: Fixed,
+        /// Compensated SOC (percent)
+        compensated: Fixed,
+    },
+    /// Estimation failed at `voltage`
+    Estimation {
+        /// Measured voltage (volts)
+        voltage: Fixed,
+        /// The error returned by the estimator
+        error: Error,
+    },
+}
+
+/// Measured voltages from the start of `voltages` up to its end, `step`
+/// apart
+///
+/// Empty if the range is empty or `step` is not positive.
+fn sweep(voltages: RangeInclusive<Fixed>, step: Fixed) -> impl Iterator<Item = Fixed> {
+    let (start, end) = voltages.into_inner();
+    let first = (start <= end && step > Fixed::ZERO).then_some(start);
+    core::iter::successors(first, move |voltage| {
+        voltage.checked_add(step).filter(|next| *next <= end)
+    })
+}
+
+/// Compensated estimate at `voltage`, with errors as a [`Violation`]
+fn estimate(
+    estimator: &SocEstimator,
+    voltage: Fixed,
+    temperature: Fixed,
+) -> Result<Fixed, Violation> {
+    estimator
+        .estimate_soc_compensated_fixed(voltage, temperature)
+        .map_err(|error| Violation::Estimation { voltage, error })
+}
+
+/// Checks that compensated estimates never decrease with voltage
+///
+/// Sweeps `voltages` (measured volts, before divider and calibration
+/// scaling) in steps of `step` at a fixed `temperature`.
+///
+/// # Returns
+///
+/// * `Ok(())` - Every estimate is at least the one a step below
+/// * `Err(Violation::NotMonotonic)` - The first decrease
+/// * `Err(Violation::Estimation)` - Estimation failed, e.g. in strict
+///   voltage range mode or outside the operating temperature range
+pub fn check_monotonic(
+    estimator: &SocEstimator,
+    voltages: RangeInclusive<Fixed>,
+    step: Fixed,
+    temperature: Fixed,
+) -> Result<(), Violation> {
+    let mut previous_soc = None;
+    for voltage in sweep(voltages, step) {
+        let soc = estimate(estimator, voltage, temperature)?;
+        if let Some(previous_soc) = previous_soc.filter(|previous| soc < *previous) {
+            return Err(Violation::NotMonotonic {
+                voltage,
+                soc,
+                previous_soc,
+            });
+        }
+        previous_soc = Some(soc);
+    }
+    Ok(())
+}
+
+/// Checks that compensated estimates stay within 0–100%
+///
+/// Sweeps like [`check_monotonic()`]. Holds by construction with the
+/// default [`OutputPolicy::Clamp`](crate::OutputPolicy::Clamp); with
+/// [`Reject`](crate::OutputPolicy::Reject) an out-of-range SOC is reported
+/// as an [`Error::SocOutOfRange`] estimation failure.
+///
+/// # Returns
+///
+/// * `Ok(())` - Every estimate is within 0–100%
+/// * `Err(Violation::OutOfBounds)` - The first estimate outside 0–100%
+/// * `Err(Violation::Estimation)` - Estimation failed
+pub fn check_bounded(
+    estimator: &SocEstimator,
+    voltages: RangeInclusive<Fixed>,
+    step: Fixed,
+    temperature: Fixed,
+) -> Result<(), Violation> {
+    for voltage in sweep(voltages, step) {
+        let soc = estimate(estimator, voltage, temperature)?;
+        if !(Fixed::ZERO..=SOC_FULL).contains(&soc) {
+            return Err(Violation::OutOfBounds { voltage, soc });
+        }
+    }
+    Ok(())
+}
+
+/// Checks that compensation at nominal conditions is the identity
+///
+/// Compares the compensated estimate at the configured nominal temperature
+/// and an age of zero years with the uncompensated estimate, over the
+/// sweep. The other settings (coefficients, limits, scaling, output
+/// policy) are taken from the estimator.
+///
+/// # Returns
+///
+/// * `Ok(())` - Both estimates agree at every voltage
+/// * `Err(Violation::NotIdentity)` - The first voltage where they differ
+/// * `Err(Violation::Estimation)` - Estimation failed, e.g. because the
+///   nominal temperature is outside the operating range
+pub fn check_nominal_identity(
+    estimator: &SocEstimator,
+    voltages: RangeInclusive<Fixed>,
+    step: Fixed,
+) -> Result<(), Violation> {
+    let mut config = *estimator.config();
+    config.age_years = Fixed::ZERO;
+    let mut nominal = *estimator;
+    nominal.update_config(config);
+
+    for voltage in sweep(voltages, step) {
+        let soc = nominal
+            .estimate_soc_fixed(voltage)
+            .map_err(|error| Violation::Estimation { voltage, error })?;
+        let compensated = estimate(&nominal, voltage, config.nominal_temperature)?;
+        if compensated != soc {
+            return Err(Violation::NotIdentity {
+                voltage,
+                soc,
+                compensated,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Runs every check of this module over the same sweep
+///
+/// Monotonicity and boundedness are checked at `temperature`, nominal
+/// identity at the configured nominal temperature.
+pub fn check_all(
+    estimator: &SocEstimator,
+    voltages: RangeInclusive<Fixed>,
+    step: Fixed,
+    temperature: Fixed,
+) -> Result<(), Violation> {
+    check_monotonic(estimator, voltages.clone(), step, temperature)?;
+    check_bounded(estimator, voltages.clone(), step, temperature)?;
+    check_nominal_identity(estimator, voltages, step)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BatteryChemistry, Curve, CurvePoint, EstimatorConfig, OutputPolicy};
+
+    fn volts(value: f32) -> Fixed {
+        Fixed::from_num(value)
+    }
+
+    #[test]
+    fn test_builtin_chemistries_hold_invariants() {
+        let config = EstimatorConfig::default()
+            .with_temperature_compensation()
+            .with_aging_compensation()
+            .with_age_years(Fixed::from_num(3));
+        for chemistry in [
+            BatteryChemistry::LiPo,
+            BatteryChemistry::LiFePO4,
+            BatteryChemistry::LiIon,
+            BatteryChemistry::Lipo410Full340Cutoff,
+        ] {
+            let estimator = SocEstimator::with_config(chemistry, config);
+            for temperature in [-20, 0, 25, 45, 60] {
+                assert_eq!(
+                    check_all(
+                        &estimator,
+                        volts(2.5)..=volts(4.5),
+                        MILLIVOLT,
+                        Fixed::from_num(temperature)
+                    ),
+                    Ok(())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_violations_are_reported() {
+        static FALLING: Curve = Curve::new(&[
+            CurvePoint::from_raw(3_000, 0),
+            CurvePoint::from_raw(3_500, 600),
+            CurvePoint::from_raw(3_600, 500),
+            CurvePoint::from_raw(4_000, 1000),
+        ]);
+        let estimator = SocEstimator::with_custom_curve(&FALLING);
+        assert!(matches!(
+            check_monotonic(&estimator, volts(3.0)..=volts(4.0), MILLIVOLT, volts(25.0)),
+            Err(Violation::NotMonotonic { voltage, .. }) if voltage > volts(3.5)
+        ));
+
+        static OVERFULL: Curve = Curve::new(&[
+            CurvePoint::from_raw(3_000, 0),
+            CurvePoint::from_raw(4_000, 1100),
+        ]);
+        let mut estimator = SocEstimator::with_custom_curve(&OVERFULL);
+        assert_eq!(
+            check_bounded(&estimator, volts(3.0)..=volts(4.0), MILLIVOLT, volts(25.0)),
+            Ok(())
+        );
+        estimator.update_config(EstimatorConfig::default().with_output_policy(OutputPolicy::Raw));
+        assert!(matches!(
+            check_bounded(&estimator, volts(3.0)..=volts(4.0), MILLIVOLT, volts(25.0)),
+            Err(Violation::OutOfBounds { soc, .. }) if soc > Fixed::from_num(100)
+        ));
+
+        let estimator = SocEstimator::with_config(
+            BatteryChemistry::LiPo,
+            EstimatorConfig::default()
+                .with_temperature_compensation()
+                .with_nominal_temperature(Fixed::from_num(80)),
+        );
+        assert_eq!(
+            check_nominal_identity(&estimator, volts(3.0)..=volts(4.0), MILLIVOLT),
+            Err(Violation::Estimation {
+                voltage: volts(3.0),
+                error: Error::TemperatureOutOfRange
+            })
+        );
+    }
+
+    #[test]
+    fn test_sweep() {
+        assert_eq!(sweep(volts(3.0)..=volts(3.01), MILLIVOLT).count(), 10);
+        assert_eq!(sweep(volts(3.0)..=volts(3.0), MILLIVOLT).count(), 1);
+        assert_eq!(sweep(volts(3.0)..=volts(2.0), MILLIVOLT).count(), 0);
+        assert_eq!(sweep(volts(3.0)..=volts(4.0), Fixed::ZERO).count(), 0);
+        assert_eq!(
+            sweep(Fixed::MAX - MILLIVOLT..=Fixed::MAX, MILLIVOLT).count(),
+            2
+        );
+    }
+}
@@ -0,0 +1,586 @@
+//! JSON provisioning format
+//!
+//! The `serde` implementations of the core types store fixed-point values
+//! as raw I16F16 bits, which suits compact binary formats but not files
+//! edited by people or generated by provisioning servers. This module
+//! defines a separate JSON document in natural units that servers and
+//! devices can exchange, and converts it to and from the estimator types.
+//!
+//! # Schema (version 1)
+//!
+//! ```json
+//! {
+//!   "schema_version": 1,
+//!   "chemistry": {
+//!     "name": "pack-18650",
+//!     "curve": [[3.0, 0.0], [3.6, 40.0], [4.2, 100.0]]
+//!   },
+//!   "config": {
+//!     "temperature_compensation": { "nominal_temperature": 25.0, "coefficient": 0.005 },
+//!     "aging_compensation": { "age_years": 2.0, "aging_factor": 0.02 },
+//!     "divider_ratio": 2.0,
+//!     "input_gain": 1.0,
+//!     "input_offset": 0.0,
+//!     "capacity_mah": 2500.0,
+//!     "internal_resistance": 0.05,
+//!     "output_policy": "Clamp"
+//!   }
+//! }
+//! ```
+//!
+//! - `schema_version` (required) - Must be `1`
+//! - `chemistry.name` (required) - A [`BatteryChemistry`] variant name, or a
+//!   free label when `curve` is given
+//! - `chemistry.curve` (optional) - `[voltage, soc]` pairs in volts and
+//!   percent, ordered by increasing voltage, at most
+//!   [`MAX_CURVE_POINTS`](crate::MAX_CURVE_POINTS)
+//! - `config` (optional) - Every field is optional and defaults to
+//!   [`EstimatorConfig::default()`]; a compensation is enabled by giving
+//!   its object
+//!
+//! Unknown fields are rejected, so typos fail loudly instead of silently
+//! falling back to defaults.
+//!
+//! For live status, [`MqttReport`] renders a flat JSON object following
+//! common home-automation conventions, ready to publish as an MQTT state
+//! payload.
+
+use std::io;
+use std::string::String;
+use std::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use crate::sbs::saturate_u16;
+use crate::{
+    BatteryChemistry, Curve, CurvePoint, Error, EstimatorConfig, Fixed, OutputPolicy, SocEstimator,
+    SocTracker,
+};
+
+/// Schema version written by this crate
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Converts a JSON number into fixed-point, rejecting values out of range
+fn to_fixed(value: f64) -> Result<Fixed, Error> {
+    Fixed::checked_from_num(value).ok_or(Error::InvalidConfig)
+}
+
+/// Battery chemistry: a built-in curve or a named custom curve
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChemistryDescriptor {
+    /// Chemistry name, or a free label for a custom curve
+    pub name: String,
+    /// Custom curve as `[voltage, soc]` pairs, `None` for a built-in chemistry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub curve: Option<Vec<[f32; 2]>>,
+}
+
+impl ChemistryDescriptor {
+    /// Descriptor of a built-in chemistry
+    pub fn builtin(chemistry: BatteryChemistry) -> Self {
+        Self {
+            name: std::format!("{:?}", chemistry),
+            curve: None,
+        }
+    }
+
+    /// Descriptor of a custom curve
+    pub fn custom(name: &str, curve: &Curve) -> Self {
+        Self {
+            name: name.into(),
+            curve: Some(
+                curve
+                    .points()
+                    .iter()
+                    .map(|point| [point.voltage(), point.soc()])
+                    .collect(),
+            ),
+        }
+    }
+
+    /// The described curve
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(curve)` - The built-in or validated custom curve
+    /// * `Err(Error::InvalidConfig)` - No curve is given and the name is not
+    ///   a built-in chemistry
+    /// * `Err(Error::InvalidCurve)` - The custom curve has too many points or
+    ///   is not ordered by increasing voltage
+    pub fn to_curve(&self) -> Result<Curve, Error> {
+        match &self.curve {
+            None => Ok(*Curve::for_chemistry(self.name.parse()?)),
+            Some(pairs) => {
+                if pairs.len() > crate::MAX_CURVE_POINTS {
+                    return Err(Error::InvalidCurve);
+                }
+                let points: Vec<CurvePoint> = pairs
+                    .iter()
+                    .map(|&[voltage, soc]| CurvePoint::new(voltage, soc))
+                    .collect();
+                let curve = Curve::new(&points);
+                curve.validate_sorted()?;
+                Ok(curve)
+            }
+        }
+    }
+}
+
+/// Temperature compensation settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemperatureCompensation {
+    /// Nominal temperature in °C
+    pub nominal_temperature: f64,
+    /// Compensation coefficient (percentage change per °C)
+    pub coefficient: f64,
+}
+
+/// Aging compensation settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AgingCompensation {
+    /// Battery age in years
+    pub age_years: f64,
+    /// Capacity loss per year as a fraction
+    pub aging_factor: f64,
+}
+
+/// [`EstimatorConfig`] in natural units
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigDescriptor {
+    /// Temperature compensation, `None` to disable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature_compensation: Option<TemperatureCompensation>,
+    /// Aging compensation, `None` to disable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aging_compensation: Option<AgingCompensation>,
+    /// Battery voltage / ADC pin voltage
+    pub divider_ratio: f64,
+    /// Gain correction applied to the measured voltage
+    pub input_gain: f64,
+    /// Offset correction in volts added after the gain
+    pub input_offset: f64,
+    /// Rated capacity in mAh, zero if unknown
+    pub capacity_mah: f64,
+    /// Internal resistance in ohms, zero to ignore voltage sag
+    pub internal_resistance: f64,
+    /// Handling of SOC results outside 0–100%
+    pub output_policy: OutputPolicy,
+}
+
+impl Default for ConfigDescriptor {
+    fn default() -> Self {
+        Self::from(&EstimatorConfig::default())
+    }
+}
+
+impl From<&EstimatorConfig> for ConfigDescriptor {
+    fn from(config: &EstimatorConfig) -> Self {
+        Self {
+            temperature_compensation: config.is_temperature_compensation_enabled().then(|| {
+                TemperatureCompensation {
+                    nominal_temperature: config.nominal_temperature.to_num(),
+                    coefficient: config.temperature_coefficient.to_num(),
+                }
+            }),
+            aging_compensation: config
+                .is_aging_compensation_enabled()
+                .then(|| AgingCompensation {
+                    age_years: config.age_years.to_num(),
+                    aging_factor: config.aging_factor.to_num(),
+                }),
+            divider_ratio: config.divider_ratio.to_num(),
+            input_gain: config.input_gain.to_num(),
+            input_offset: config.input_offset.to_num(),
+            capacity_mah: config.capacity_mah.to_num(),
+            internal_resistance: config.internal_resistance.to_num(),
+            output_policy: config.output_policy,
+        }
+    }
+}
+
+impl ConfigDescriptor {
+    /// The described configuration
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(config)` - The validated configuration
+    /// * `Err(Error::InvalidConfig)` - A value does not fit the fixed-point
+    ///   range or fails [`EstimatorConfig::validate()`]
+    pub fn to_config(&self) -> Result<EstimatorConfig, Error> {
+        let mut config = EstimatorConfig::default()
+            .with_divider_ratio(to_fixed(self.divider_ratio)?)
+            .with_input_gain(to_fixed(self.input_gain)?)
+            .with_input_offset(to_fixed(self.input_offset)?)
+            .with_capacity_mah(to_fixed(self.capacity_mah)?)
+            .with_internal_resistance(to_fixed(self.internal_resistance)?)
+            .with_output_policy(self.output_policy);
+
+        if let Some(temperature) = self.temperature_compensation {
+            config = config
+                .with_temperature_compensation()
+                .with_nominal_temperature(to_fixed(temperature.nominal_temperature)?)
+                .with_temperature_coefficient(to_fixed(temperature.coefficient)?);
+        }
+        if let Some(aging) = self.aging_compensation {
+            config = config
+                .with_aging_compensation()
+                .with_age_years(to_fixed(aging.age_years)?)
+                .with_aging_factor(to_fixed(aging.aging_factor)?);
+        }
+
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+/// A complete estimator description
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::EstimatorDocument;
+///
+/// let json = r#"{
+///     "schema_version": 1,
+///     "chemistry": { "name": "LiPo" },
+///     "config": { "temperature_compensation": { "nominal_temperature": 25.0, "coefficient": 0.005 } }
+/// }"#;
+///
+/// let document = EstimatorDocument::from_json(json).unwrap();
+/// let built = document.build().unwrap();
+/// assert!(built.config().is_temperature_compensation_enabled());
+///
+/// let restored = EstimatorDocument::from_json(&document.to_json()).unwrap();
+/// assert_eq!(restored, document);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EstimatorDocument {
+    /// Format version, [`SCHEMA_VERSION`]
+    pub schema_version: u32,
+    /// Chemistry or custom curve
+    pub chemistry: ChemistryDescriptor,
+    /// Estimator configuration
+    #[serde(default)]
+    pub config: ConfigDescriptor,
+}
+
+impl EstimatorDocument {
+    /// Describes an estimator
+    ///
+    /// The curve is written as a custom curve named `name`, or by its
+    /// chemistry name if it is one of the built-in curves.
+    pub fn from_estimator(name: &str, estimator: &SocEstimator) -> Self {
+        let chemistry = [
+            BatteryChemistry::LiPo,
+            BatteryChemistry::LiFePO4,
+            BatteryChemistry::LiIon,
+            BatteryChemistry::Lipo410Full340Cutoff,
+        ]
+        .into_iter()
+        .find(|&chemistry| Curve::for_chemistry(chemistry) == estimator.curve());
+
+        Self {
+            schema_version: SCHEMA_VERSION,
+            chemistry: match chemistry {
+                Some(chemistry) => ChemistryDescriptor::builtin(chemistry),
+                None => ChemistryDescriptor::custom(name, estimator.curve()),
+            },
+            config: ConfigDescriptor::from(estimator.config()),
+        }
+    }
+
+    /// Parses a document
+    ///
+    /// Malformed JSON, unknown fields and unsupported schema versions are
+    /// reported as [`io::ErrorKind::InvalidData`].
+    pub fn from_json(json: &str) -> io::Result<Self> {
+        serde_json::from_str::<Self>(json)?.check_version()
+    }
+
+    /// Reads a document, e.g. from a file
+    pub fn from_reader<R: io::Read>(reader: R) -> io::Result<Self> {
+        serde_json::from_reader::<_, Self>(reader)?.check_version()
+    }
+
+    /// Rejects documents of other schema versions
+    fn check_version(self) -> io::Result<Self> {
+        if self.schema_version != SCHEMA_VERSION {
+            return Err(Error::InvalidConfig.into());
+        }
+        Ok(self)
+    }
+
+    /// Pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        // Serializing plain structs of numbers and strings cannot fail
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Creates the described estimator
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(built)` - The curve and configuration; see
+    ///   [`OwnedEstimator::estimator()`]
+    /// * `Err(Error)` - See [`ChemistryDescriptor::to_curve()`] and
+    ///   [`ConfigDescriptor::to_config()`]
+    pub fn build(&self) -> Result<OwnedEstimator, Error> {
+        let config = self.config.to_config()?;
+        let chemistry = match self.chemistry.curve {
+            None => Some(self.chemistry.name.parse()?),
+            Some(_) => None,
+        };
+        Ok(OwnedEstimator {
+            curve: self.chemistry.to_curve()?,
+            chemistry,
+            config,
+        })
+    }
+}
+
+/// Estimator built from an [`EstimatorDocument`], owning its curve
+///
+/// [`SocEstimator`] borrows its curve for `'static`, which a curve read at
+/// runtime does not have. This keeps the curve next to the configuration
+/// instead; [`estimator()`](Self::estimator) borrows it once the value is
+/// stored for the rest of the program, e.g. in a `static`
+/// [`OnceLock`](std::sync::OnceLock).
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::OnceLock;
+///
+/// use battery_estimator::{EstimatorDocument, Fixed, OwnedEstimator};
+///
+/// static BUILT: OnceLock<OwnedEstimator> = OnceLock::new();
+///
+/// let json = r#"{ "schema_version": 1, "chemistry": { "name": "cell-a", "curve": [[3.0, 0.0], [4.0, 100.0]] } }"#;
+/// let document = EstimatorDocument::from_json(json).unwrap();
+/// let estimator = BUILT.get_or_init(|| document.build().unwrap()).estimator();
+///
+/// assert_eq!(estimator.estimate_soc_fixed(Fixed::from_num(3.5)), Ok(Fixed::from_num(50)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OwnedEstimator {
+    curve: Curve,
+    /// Built-in chemistry of `curve`, for its operating temperature range
+    chemistry: Option<BatteryChemistry>,
+    config: EstimatorConfig,
+}
+
+impl OwnedEstimator {
+    /// The described curve
+    #[inline]
+    pub const fn curve(&self) -> &Curve {
+        &self.curve
+    }
+
+    /// The described configuration, already validated
+    #[inline]
+    pub const fn config(&self) -> &EstimatorConfig {
+        &self.config
+    }
+
+    /// The estimator, borrowing the owned curve
+    ///
+    /// Built-in chemistries keep their operating temperature range; custom
+    /// curves get the default range of
+    /// [`SocEstimator::with_custom_curve()`].
+    pub fn estimator(&'static self) -> SocEstimator {
+        match self.chemistry {
+            Some(chemistry) => SocEstimator::with_config(chemistry, self.config),
+            None => {
+                let mut estimator = SocEstimator::with_custom_curve(&self.curve);
+                estimator.update_config(self.config);
+                estimator
+            }
+        }
+    }
+}
+
+/// Battery state as a flat JSON object for MQTT
+///
+/// Field names follow Home Assistant's device classes (`battery` in
+/// percent, `voltage` in volts, `temperature` in °C, `battery_charging`
+/// and `battery_low` as booleans), so a single state topic can feed
+/// several sensors through `value_json.<field>` templates.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{Fixed, MqttReport};
+///
+/// let report = MqttReport::new(
+///     Fixed::from_num(3.87),  // voltage
+///     Fixed::from_num(21.5),  // temperature in °C
+///     Fixed::from_num(72.6),  // SOC in percent
+///     false,                  // charging
+///     false,                  // low
+/// );
+///
+/// assert_eq!(
+///     report.to_json(),
+///     r#"{"battery":73,"voltage":3.87,"temperature":21.5,"battery_charging":false,"battery_low":false}"#
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MqttReport {
+    /// SOC in whole percent (0 to 100)
+    pub battery: u8,
+    /// Voltage in volts, rounded to millivolts
+    pub voltage: f64,
+    /// Temperature in °C, rounded to 0.1 °C
+    pub temperature: f64,
+    /// The battery is charging
+    pub battery_charging: bool,
+    /// The SOC is below the low threshold
+    pub battery_low: bool,
+}
+
+impl MqttReport {
+    /// Creates a report
+    ///
+    /// # Arguments
+    ///
+    /// * `voltage` - Battery voltage in volts
+    /// * `temperature` - Battery temperature in °C
+    /// * `soc` - SOC in percent (rounded and clamped to 0-100)
+    /// * `charging` - Whether the battery is charging
+    /// * `low` - Whether the SOC is below the low threshold
+    pub fn new(voltage: Fixed, temperature: Fixed, soc: Fixed, charging: bool, low: bool) -> Self {
+        let soc = soc.clamp(Fixed::ZERO, Fixed::from_num(100));
+        let voltage_mv = saturate_u16(voltage.to_bits() as i64 * 1000);
+        let temperature_tenth = temperature.saturating_mul_int(10).saturating_round();
+        Self {
+            battery: saturate_u16(soc.to_bits() as i64) as u8,
+            voltage: voltage_mv as f64 / 1000.0,
+            temperature: temperature_tenth.to_num::<f64>() / 10.0,
+            battery_charging: charging,
+            battery_low: low,
+        }
+    }
+
+    /// Creates a report from a tracker's state
+    ///
+    /// `battery_low` follows the tracker's low SOC threshold and
+    /// `battery_charging` is set for a positive current.
+    ///
+    /// # Returns
+    ///
+    /// `None` until the tracker has processed its first sample.
+    pub fn from_tracker(tracker: &SocTracker, voltage: Fixed, current_ma: Fixed) -> Option<Self> {
+        let soc = tracker.soc()?;
+        Some(Self::new(
+            voltage,
+            tracker.temperature(),
+            soc,
+            current_ma > Fixed::ZERO,
+            soc < tracker.config().low_soc_threshold,
+        ))
+    }
+
+    /// Compact JSON payload
+    pub fn to_json(&self) -> String {
+        // Serializing plain numbers and booleans cannot fail
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::OnceLock;
+
+    use super::*;
+
+    #[test]
+    fn test_document_custom_curve() {
+        let json = r#"{
+            "schema_version": 1,
+            "chemistry": { "name": "cell-a", "curve": [[3.0, 0.0], [3.5, 40.0], [4.0, 100.0]] },
+            "config": {
+                "aging_compensation": { "age_years": 1.0, "aging_factor": 0.1 },
+                "capacity_mah": 1200,
+                "output_policy": "Reject"
+            }
+        }"#;
+
+        static BUILT: OnceLock<OwnedEstimator> = OnceLock::new();
+        let document = EstimatorDocument::from_json(json).unwrap();
+        let estimator = BUILT.get_or_init(|| document.build().unwrap()).estimator();
+        assert_eq!(estimator.curve().len(), 3);
+        assert!(estimator.config().is_aging_compensation_enabled());
+        assert_eq!(estimator.config().capacity_mah, Fixed::from_num(1200));
+        assert_eq!(estimator.config().output_policy, OutputPolicy::Reject);
+
+        let described = EstimatorDocument::from_estimator("cell-a", &estimator);
+        assert_eq!(described.chemistry, document.chemistry);
+        assert_eq!(described.config.to_config(), Ok(*estimator.config()));
+    }
+
+    #[test]
+    fn test_document_builtin_round_trip() {
+        let estimator = SocEstimator::new(BatteryChemistry::LiFePO4);
+        let document = EstimatorDocument::from_estimator("unused", &estimator);
+        assert_eq!(document.chemistry.name, "LiFePO4");
+        assert_eq!(document.chemistry.curve, None);
+
+        static BUILT: OnceLock<OwnedEstimator> = OnceLock::new();
+        let restored = EstimatorDocument::from_json(&document.to_json()).unwrap();
+        assert_eq!(
+            BUILT.get_or_init(|| restored.build().unwrap()).estimator(),
+            estimator
+        );
+    }
+
+    #[test]
+    fn test_document_errors() {
+        let unknown_field =
+            r#"{ "schema_version": 1, "chemistry": { "name": "LiPo" }, "extra": 1 }"#;
+        assert!(EstimatorDocument::from_json(unknown_field).is_err());
+
+        let wrong_version = r#"{ "schema_version": 2, "chemistry": { "name": "LiPo" } }"#;
+        assert!(EstimatorDocument::from_json(wrong_version).is_err());
+
+        let unknown = r#"{ "schema_version": 1, "chemistry": { "name": "NiMH" } }"#;
+        let document = EstimatorDocument::from_json(unknown).unwrap();
+        assert_eq!(document.build(), Err(Error::InvalidConfig));
+
+        let unsorted = r#"{ "schema_version": 1, "chemistry": { "name": "x", "curve": [[4.0, 100.0], [3.0, 0.0]] } }"#;
+        let document = EstimatorDocument::from_json(unsorted).unwrap();
+        assert_eq!(document.build(), Err(Error::InvalidCurve));
+
+        let negative = r#"{ "schema_version": 1, "chemistry": { "name": "LiPo" }, "config": { "capacity_mah": -1 } }"#;
+        let document = EstimatorDocument::from_json(negative).unwrap();
+        assert_eq!(document.build(), Err(Error::InvalidConfig));
+    }
+
+    #[test]
+    fn test_mqtt_report() {
+        let mut tracker = SocTracker::new(
+            SocEstimator::new(BatteryChemistry::LiPo),
+            crate::TrackerConfig::default(),
+        );
+        let voltage = Fixed::from_num(3.4);
+        assert_eq!(
+            MqttReport::from_tracker(&tracker, voltage, Fixed::ZERO),
+            None
+        );
+
+        tracker.set_temperature(Fixed::from_num(-5.25));
+        tracker.update(voltage, Fixed::ZERO, Fixed::ONE).unwrap();
+        let report = MqttReport::from_tracker(&tracker, voltage, Fixed::from_num(-300)).unwrap();
+        assert_eq!(report.voltage, 3.4);
+        assert_eq!(report.temperature, -5.3);
+        assert!(!report.battery_charging);
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["battery"], report.battery);
+        assert_eq!(json["battery_low"], report.battery_low);
+    }
+}
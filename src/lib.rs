@@ -20,6 +20,7 @@
 //! ## Quick Start
 //!
 //! ```rust
+//! # #[cfg(not(feature = "no-float"))] {
 //! use battery_estimator::{BatteryChemistry, SocEstimator};
 //!
 //! // Create estimator for a standard LiPo battery
@@ -30,6 +31,7 @@
 //!     Ok(soc) => println!("Battery SOC: {:.1}%", soc),
 //!     Err(e) => println!("Error estimating SOC: {}", e),
 //! }
+//! # }
 //! ```
 //!
 //! ## Fixed-Point API
@@ -64,6 +66,7 @@
 //! ## Temperature Compensation
 //!
 //! ```rust
+//! # #[cfg(not(feature = "no-float"))] {
 //! use battery_estimator::{BatteryChemistry, SocEstimator, Fixed};
 //!
 //! // Create estimator with temperature compensation
@@ -78,6 +81,7 @@
 //!     Ok(soc) => println!("Temperature-compensated SOC: {:.1}%", soc),
 //!     Err(e) => println!("Error: {}", e),
 //! }
+//! # }
 //! ```
 //!
 //! ## Custom Voltage Curves
@@ -99,36 +103,203 @@
 //! ## Module Structure
 //!
 //! - [`SocEstimator`] - Main estimator struct for SOC calculations
+//! - [`SocEstimate`] - Trait for swapping estimators behind `dyn`
 //! - [`EstimatorConfig`] - Configuration for SOC estimator (compensation settings)
+//! - [`LiteEstimator`] - Reduced-precision (I8F8) estimator for 8/16-bit MCUs
+//! - [`SocEstimatorBuilder`] - Validating builder for [`SocEstimator`]
 //! - [`BatteryChemistry`] - Supported battery types
 //! - [`Curve`] - Voltage-SOC curve representation
+//! - [`SizedCurve`] - [`Curve`] with a point capacity chosen at compile time
 //! - [`CurvePoint`] - Individual voltage-SOC data point
+//! - [`BorrowedCurve`] - Zero-copy curve over a borrowed slice of points
+//! - [`CompactCurve`] - Delta-encoded curve for embedding many curves in flash
+//! - [`AccuracyBound`] - Worst-case SOC error of a curve lookup, for documenting gauge accuracy
 //! - [`Fixed`] - Fixed-point type alias (I16F16)
 //! - [`Error`] - Error types for estimation failures
+//! - [`Result`] - Result alias with [`Error`] as the error type
+//! - [`ErrorDetail`] - An [`Error`] with the value that caused it
 //! - [`compensate_temperature`] - Temperature compensation function
 //! - [`compensate_aging`] - Aging compensation function
+//! - [`CompensationLimits`] - Bounds of the compensation model shared by every API
+//! - [`SocQuantizer`] - Bar/step SOC output with hysteresis
+//! - [`SocRounding`] - Floor/nearest/ceil rounding of reported SOC to a number of decimals
+//! - [`KalmanFilter`] - Scalar Kalman pre-filter for raw voltage readings
+//! - [`SocTracker`] - Stateful SOC tracking with coulomb counting and slew limiting
+//! - [`VoltageSource`] - Provider of voltage readings for [`SocTracker::sample()`]
+//! - [`Calibration`] - Per-device voltage gain/offset correction, storable in flash
+//! - [`AdcConverter`] - Raw ADC counts to battery volts (reference, resolution, divider, gain/offset)
+//! - [`EventSink`] - Receiver for tracker [`GaugeEvent`]s
+//! - [`SocHistory`] - Ring buffer of timestamped SOC samples
+//! - [`PackEstimator`] - SOC estimation for series packs of identical cells
+//! - [`GaugeCrossCheck`] - Cross-validation against a MAX1704x hardware fuel gauge
+//! - [`SbsRegisters`] - Smart Battery Data Specification register snapshot
+//! - [`BleBatteryLevelStatus`] - Bluetooth LE Battery Service characteristic values
+//! - [`GaugeDataModel`] - BQ27441-style standard gauge outputs
+//! - [`RegisterFile`] - Address-to-word register view for emulating a gauge over I2C/SMBus
+//! - [`DischargeSimulator`] - Synthetic voltage traces for tuning and regression tests
+//! - [`ReplayHarness`] - Log replay with error statistics against a reference SOC
+//! - `instrument` - Operation counts and cycle measurement (`instrument` feature)
+//! - [`budget`] - Compile-time RAM budgets of the main types
+//! - [`invariants`] - Monotonicity, boundedness and nominal-identity checks for custom curves and configurations
+//! - [`Volts`], [`Celsius`], [`Percent`] - Unit newtypes for typed estimation
 
 #![no_std]
+#![cfg_attr(all(feature = "simd", nightly), feature(portable_simd))]
 #![deny(missing_docs, unsafe_code)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(all(
+    feature = "no-float",
+    any(
+        feature = "uom",
+        feature = "json",
+        feature = "toml",
+        feature = "python",
+        feature = "wasm"
+    )
+))]
+compile_error!(
+    "`no-float` conflicts with the float-based `uom`, `json`, `toml`, `python` and `wasm` features"
+);
+
+mod accuracy;
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
+mod ble;
+mod borrowed_curve;
+mod calibration;
+#[cfg(all(feature = "cmsis-dsp", any(target_arch = "arm", test)))]
+mod cmsis;
+mod compact_curve;
 mod compensation;
+mod crosscheck;
+#[cfg(feature = "std")]
+mod csv;
 mod curve;
+#[cfg(all(feature = "uom", not(feature = "no-float")))]
+mod dimensional;
 mod error;
 mod estimator;
+mod events;
+mod filter;
+mod gauge;
+#[cfg(feature = "heapless")]
+mod heapless_curve;
+mod history;
+#[cfg(all(feature = "json", not(feature = "no-float")))]
+mod json;
+mod lite;
+mod logging;
+mod pack;
+mod persist;
+#[cfg(all(feature = "python", not(feature = "no-float")))]
+mod python;
+mod quantize;
+mod registers;
+mod replay;
+mod sbs;
+mod sim;
+#[cfg(all(feature = "simd", nightly))]
+mod simd;
+mod source;
+#[cfg(feature = "async")]
+mod task;
+#[cfg(feature = "telemetry")]
+mod telemetry;
+#[cfg(all(feature = "toml", not(feature = "no-float")))]
+mod toml_config;
+mod tracker;
 mod types;
+#[cfg(feature = "ufmt")]
+mod ufmt_impls;
+mod units;
+#[cfg(feature = "alloc")]
+mod vec_curve;
+#[cfg(all(feature = "wasm", not(feature = "no-float")))]
+mod wasm;
 
+pub use accuracy::AccuracyBound;
+pub use ble::{ble_battery_level, BleBatteryLevelStatus, BleChargeLevel, BleChargeState};
+pub use borrowed_curve::BorrowedCurve;
+pub use calibration::Calibration;
+pub use compact_curve::{compact_curve_len, CompactCurve};
+#[cfg(not(feature = "no-float"))]
 pub use compensation::{
-    compensate_aging, compensate_aging_fixed, compensate_temperature, compensate_temperature_fixed,
-    default_temperature_compensation, default_temperature_compensation_fixed,
+    compensate_aging, compensate_temperature, default_temperature_compensation,
+};
+pub use compensation::{
+    compensate_aging_checked, compensate_aging_fixed, compensate_aging_limited,
+    compensate_temperature_checked, compensate_temperature_fixed, compensate_temperature_limited,
+    default_temperature_compensation_fixed, CompensationLimits,
+};
+pub use crosscheck::{CrossCheckResult, GaugeCrossCheck, Max1704xReading};
+#[cfg(feature = "std")]
+pub use csv::CsvColumns;
+pub use curve::{Curve, SizedCurve, MAX_CURVE_POINTS};
+pub use error::{Error, ErrorContext, ErrorDetail, Result};
+pub use estimator::{
+    BatchEstimate, EstimatorConfig, OutputPolicy, SocEstimate, SocEstimator, SocEstimatorBuilder,
 };
-pub use curve::{Curve, MAX_CURVE_POINTS};
-pub use error::Error;
-pub use estimator::{EstimatorConfig, SocEstimator};
+pub use events::{Crossing, EventSink, GaugeEvent};
+pub use filter::KalmanFilter;
+pub use gauge::{GaugeCommand, GaugeDataModel};
+#[cfg(feature = "heapless")]
+pub use heapless_curve::HeaplessCurve;
+pub use history::{HistoryEntry, SocHistory, MAX_HISTORY_LEN};
+#[cfg(all(feature = "json", not(feature = "no-float")))]
+pub use json::{
+    AgingCompensation, ChemistryDescriptor, ConfigDescriptor, EstimatorDocument, MqttReport,
+    OwnedEstimator, TemperatureCompensation, SCHEMA_VERSION,
+};
+pub use lite::{LiteCoefficient, LiteConfig, LiteEstimator, LiteFixed};
+pub use pack::{
+    detect_series_cells, CellUsage, PackConfig, PackEstimate, PackEstimator, PackReport,
+    PackSocPolicy, PackTopology,
+};
+pub use quantize::{soc_bars, soc_quantized, RoundingMode, SocQuantizer, SocRounding};
+pub use registers::RegisterFile;
+pub use replay::{ErrorStats, LogRecord, ReplayHarness, ReplayTarget};
+pub use sbs::{SbsCommand, SbsRegisters};
+pub use sim::{DischargeSimulator, LoadProfile, SimConfig, SimSample};
+#[cfg(feature = "adc")]
+pub use source::AdcVoltageSource;
+#[cfg(feature = "async")]
+pub use source::AsyncVoltageSource;
+pub use source::{AdcConverter, VoltageSource};
+#[cfg(feature = "async")]
+pub use task::{GaugeTask, GaugeTimer};
+#[cfg(feature = "telemetry")]
+pub use telemetry::TelemetryReport;
+#[cfg(all(feature = "toml", not(feature = "no-float")))]
+pub use toml_config::{Deployment, DeploymentFile, PackDescriptor};
+pub use tracker::{SocTracker, TrackerConfig, VoltageStats};
 pub use types::{BatteryChemistry, CurvePoint, Fixed};
+pub use units::{Celsius, Percent, Volts};
+#[cfg(feature = "alloc")]
+pub use vec_curve::VecCurve;
 
 // Re-export the fixed type for convenience
 pub use fixed::types::I16F16;
 
+pub mod budget;
+#[cfg(feature = "instrument")]
+pub mod instrument;
+pub mod invariants;
+/// No-op estimation hooks without the `instrument` feature
+#[cfg(not(feature = "instrument"))]
+mod instrument {
+    #[inline(always)]
+    pub(crate) fn ops(_multiplications: u32, _divisions: u32) {}
+    #[inline(always)]
+    pub(crate) fn lookup() {}
+}
+#[cfg(feature = "proptest")]
+pub mod strategies;
+
 /// Prelude module for convenient imports
 ///
 /// This module re-exports the most commonly used types and functions:
@@ -137,11 +308,12 @@ pub use fixed::types::I16F16;
 /// use battery_estimator::prelude::*;
 /// ```
 pub mod prelude {
+    #[cfg(not(feature = "no-float"))]
+    pub use crate::{compensate_aging, compensate_temperature, default_temperature_compensation};
     pub use crate::{
-        compensate_aging, compensate_aging_fixed, compensate_temperature,
-        compensate_temperature_fixed, default_temperature_compensation,
+        compensate_aging_fixed, compensate_temperature_fixed,
         default_temperature_compensation_fixed, BatteryChemistry, Curve, CurvePoint, Error,
-        EstimatorConfig, Fixed, SocEstimator,
+        EstimatorConfig, Fixed, SocEstimate, SocEstimator, SocQuantizer, SocTracker, TrackerConfig,
     };
 }
 
@@ -151,6 +323,7 @@ mod tests {
     extern crate alloc;
     use alloc::string::ToString;
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_prelude_exports() {
         // Test that prelude exports are accessible
@@ -179,6 +352,7 @@ mod tests {
         );
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_basic_usage() {
         let estimator = SocEstimator::new(BatteryChemistry::LiPo);
@@ -192,6 +366,7 @@ mod tests {
         assert!(soc_fixed > Fixed::from_num(40.0) && soc_fixed < Fixed::from_num(60.0));
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_temperature_compensation_usage() {
         let estimator = SocEstimator::with_temperature_compensation(
@@ -208,6 +383,7 @@ mod tests {
         assert!(soc_cold < soc_normal);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_aging_compensation_usage() {
         let estimator = SocEstimator::with_aging_compensation(
@@ -223,6 +399,7 @@ mod tests {
         assert!(soc > 0.0 && soc < 100.0);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_all_battery_chemistries() {
         // Test all battery chemistry types
@@ -252,6 +429,7 @@ mod tests {
         assert!((value.to_num::<f32>() - 3.7).abs() < 0.001);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_export() {
         // Test that Curve is properly exported
@@ -278,6 +456,7 @@ mod tests {
         assert_eq!(config.nominal_temperature, Fixed::from_num(30.0));
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_custom_curve() {
         const CUSTOM: Curve = Curve::new(&[
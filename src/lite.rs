@@ -0,0 +1,523 @@
+//! Reduced-precision estimation profile for 8/16-bit MCUs
+//!
+//! [`SocEstimator`](crate::SocEstimator) works in I16F16 throughout, which
+//! means 32-bit registers and 64-bit intermediate products. On AVR or
+//! MSP430 class parts, [`LiteEstimator`] trades precision for size and
+//! speed: voltages, temperatures and SOC values are [`LiteFixed`] (I8F8,
+//! 2 bytes, steps of 1/256), compensation coefficients are
+//! [`LiteCoefficient`] (I1F15), and the compensation math stays within 32
+//! bits. The configuration takes 10 bytes instead of 40.
+//!
+//! The curve lookup is the integer permille path of
+//! [`Curve::voltage_mv_to_soc_permille()`], so the SOC is within 0.5% of
+//! the full-precision estimate: voltages are resolved to 3.9 mV and the
+//! result is rounded to 1/256 %.
+
+use crate::{BatteryChemistry, CompensationLimits, Curve, Error, EstimatorConfig, OutputPolicy};
+use fixed::types::{I1F15, I8F8};
+
+/// Fixed-point type of the reduced-precision profile (8 integer and 8
+/// fractional bits, range -128 to 127.996)
+pub type LiteFixed = I8F8;
+
+/// Fixed-point type of the compensation coefficients (range -1 to 0.99997)
+pub type LiteCoefficient = I1F15;
+
+/// Temperature compensation flag
+const FLAG_TEMPERATURE: u8 = 0x01;
+/// Aging compensation flag
+const FLAG_AGING: u8 = 0x02;
+
+/// Configuration of a [`LiteEstimator`]
+///
+/// The reduced-precision counterpart of [`EstimatorConfig`], without the
+/// measurement-path scaling: voltages are taken as battery voltages.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{LiteCoefficient, LiteConfig, LiteFixed};
+///
+/// let config = LiteConfig::default()
+///     .with_temperature_compensation()
+///     .with_nominal_temperature(LiteFixed::from_num(20));
+///
+/// assert!(config.is_temperature_compensation_enabled());
+/// assert_eq!(config.temperature_coefficient, LiteCoefficient::from_num(0.005));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LiteConfig {
+    /// Nominal temperature (°C)
+    pub nominal_temperature: LiteFixed,
+    /// Temperature compensation coefficient (capacity change per °C)
+    pub temperature_coefficient: LiteCoefficient,
+    /// Battery age (years)
+    pub age_years: LiteFixed,
+    /// Aging factor (capacity loss per year)
+    pub aging_factor: LiteCoefficient,
+    /// Handling of SOC results outside 0–100%
+    pub output_policy: OutputPolicy,
+    /// Compensation flags
+    flags: u8,
+}
+
+impl LiteConfig {
+    /// Default configuration, matching [`EstimatorConfig::default()`]
+    #[inline]
+    pub const fn default() -> Self {
+        Self {
+            nominal_temperature: LiteFixed::from_bits(25 << 8),
+            temperature_coefficient: LiteCoefficient::from_bits(164), // 0.005
+            age_years: LiteFixed::ZERO,
+            aging_factor: LiteCoefficient::from_bits(655), // 0.02
+            output_policy: OutputPolicy::Clamp,
+            flags: 0,
+        }
+    }
+
+    /// Enable temperature compensation
+    #[inline]
+    pub const fn with_temperature_compensation(mut self) -> Self {
+        self.flags |= FLAG_TEMPERATURE;
+        self
+    }
+
+    /// Enable aging compensation
+    #[inline]
+    pub const fn with_aging_compensation(mut self) -> Self {
+        self.flags |= FLAG_AGING;
+        self
+    }
+
+    /// Set nominal temperature
+    #[inline]
+    pub const fn with_nominal_temperature(mut self, temp: LiteFixed) -> Self {
+        self.nominal_temperature = temp;
+        self
+    }
+
+    /// Set temperature coefficient
+    #[inline]
+    pub const fn with_temperature_coefficient(mut self, coeff: LiteCoefficient) -> Self {
+        self.temperature_coefficient = coeff;
+        self
+    }
+
+    /// Set battery age
+    #[inline]
+    pub const fn with_age_years(mut self, years: LiteFixed) -> Self {
+        self.age_years = years;
+        self
+    }
+
+    /// Set aging factor
+    #[inline]
+    pub const fn with_aging_factor(mut self, factor: LiteCoefficient) -> Self {
+        self.aging_factor = factor;
+        self
+    }
+
+    /// Set the handling of SOC results outside 0–100%
+    ///
+    /// [`OutputPolicy::Raw`] results saturate to the [`LiteFixed`] range.
+    #[inline]
+    pub const fn with_output_policy(mut self, policy: OutputPolicy) -> Self {
+        self.output_policy = policy;
+        self
+    }
+
+    /// Returns `true` if temperature compensation is enabled
+    #[inline]
+    pub const fn is_temperature_compensation_enabled(self) -> bool {
+        (self.flags & FLAG_TEMPERATURE) != 0
+    }
+
+    /// Returns `true` if aging compensation is enabled
+    #[inline]
+    pub const fn is_aging_compensation_enabled(self) -> bool {
+        (self.flags & FLAG_AGING) != 0
+    }
+
+    /// Converts a full-precision configuration, saturating values outside
+    /// the narrower ranges
+    ///
+    /// The measurement-path scaling and the compensation limits have no lite
+    /// counterpart and are dropped; lite estimates use
+    /// [`CompensationLimits::DEFAULT`](crate::CompensationLimits::DEFAULT).
+    pub fn from_config(config: &EstimatorConfig) -> Self {
+        Self {
+            nominal_temperature: LiteFixed::saturating_from_num(config.nominal_temperature),
+            temperature_coefficient: LiteCoefficient::saturating_from_num(
+                config.temperature_coefficient,
+            ),
+            age_years: LiteFixed::saturating_from_num(config.age_years),
+            aging_factor: LiteCoefficient::saturating_from_num(config.aging_factor),
+            output_policy: config.output_policy,
+            flags: (config.is_temperature_compensation_enabled() as u8 * FLAG_TEMPERATURE)
+                | (config.is_aging_compensation_enabled() as u8 * FLAG_AGING),
+        }
+    }
+}
+
+// Non-const Default implementation
+impl Default for LiteConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::default()
+    }
+}
+
+/// SOC estimator of the reduced-precision profile
+///
+/// Results outside 0-100% are handled by the configured
+/// [`OutputPolicy`], clamped by default.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{BatteryChemistry, Fixed, LiteEstimator, LiteFixed, SocEstimator};
+///
+/// let lite = LiteEstimator::new(BatteryChemistry::LiPo);
+/// let full = SocEstimator::new(BatteryChemistry::LiPo);
+///
+/// let soc = lite.estimate_soc(LiteFixed::from_num(3.75)).unwrap();
+/// let reference = full.estimate_soc_fixed(Fixed::from_num(3.75)).unwrap();
+/// assert!((Fixed::from(soc) - reference).abs() <= Fixed::from_num(0.5));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LiteEstimator {
+    curve: &'static Curve,
+    config: LiteConfig,
+}
+
+impl LiteEstimator {
+    /// Creates an estimator for a built-in chemistry without compensation
+    #[inline]
+    pub const fn new(chemistry: BatteryChemistry) -> Self {
+        Self::with_config(chemistry, LiteConfig::default())
+    }
+
+    /// Creates an estimator for a built-in chemistry
+    #[inline]
+    pub const fn with_config(chemistry: BatteryChemistry, config: LiteConfig) -> Self {
+        Self {
+            curve: Curve::for_chemistry(chemistry),
+            config,
+        }
+    }
+
+    /// Creates an estimator for a custom curve without compensation
+    #[inline]
+    pub const fn with_custom_curve(curve: &'static Curve) -> Self {
+        Self {
+            curve,
+            config: LiteConfig::default(),
+        }
+    }
+
+    /// Replaces the configuration
+    #[inline]
+    pub const fn update_config(&mut self, config: LiteConfig) {
+        self.config = config;
+    }
+
+    /// Returns the configuration
+    #[inline]
+    pub const fn config(&self) -> &LiteConfig {
+        &self.config
+    }
+
+    /// Returns the voltage curve
+    #[inline]
+    pub const fn curve(&self) -> &'static Curve {
+        self.curve
+    }
+
+    /// Estimates SOC without compensation
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(soc)` - SOC percentage (0 to 100 unless the policy is
+    ///   [`OutputPolicy::Raw`])
+    /// * `Err(Error::InvalidCurve)` - Curve has fewer than 2 points
+    /// * `Err(Error::SocOutOfRange)` - The curve exceeds 100% and the policy
+    ///   is [`OutputPolicy::Reject`]
+    #[inline]
+    pub const fn estimate_soc(&self, voltage: LiteFixed) -> Result<LiteFixed, Error> {
+        match self.lookup_permille(voltage) {
+            Ok(permille) => self.apply_output_policy(permille),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Estimates SOC with the configured temperature and aging compensation
+    ///
+    /// Follows [`compensate_temperature_checked()`](crate::compensate_temperature_checked)
+    /// and [`compensate_aging_checked()`](crate::compensate_aging_checked)
+    /// with the same [`CompensationLimits::DEFAULT`]: above the nominal
+    /// temperature the change is halved and capped at +5%, below it is
+    /// floored at -30%, and aging removes at most 50%. The
+    /// [`OutputPolicy`] is applied to the compensated SOC, so a full cell
+    /// in warm conditions reads 100% by default.
+    pub const fn estimate_soc_compensated(
+        &self,
+        voltage: LiteFixed,
+        temperature: LiteFixed,
+    ) -> Result<LiteFixed, Error> {
+        let mut permille = match self.lookup_permille(voltage) {
+            // Far above any LiteFixed result even after the largest losses,
+            // and small enough for the Q15 products to fit in 32 bits
+            Ok(permille) if permille > LITE_MAX_COMPENSATED_PERMILLE => {
+                LITE_MAX_COMPENSATED_PERMILLE
+            }
+            Ok(permille) => permille,
+            Err(error) => return Err(error),
+        };
+        let config = &self.config;
+
+        if config.is_temperature_compensation_enabled() {
+            // I8F8 * I1F15 >> 8 leaves a Q15 fraction
+            let delta = temperature.to_bits() as i32 - config.nominal_temperature.to_bits() as i32;
+            let mut change = (delta * config.temperature_coefficient.to_bits() as i32) >> 8;
+            if delta >= 0 {
+                // Any change beyond ±1.0 ends up at the warm cap or cold
+                // floor anyway; clamping first keeps the product within 32 bits
+                if change > Q15_ONE {
+                    change = Q15_ONE;
+                } else if change < -Q15_ONE {
+                    change = -Q15_ONE;
+                }
+                change = (change * Q15_WARM_SCALE) >> 15;
+                if change > Q15_MAX_WARM_GAIN {
+                    change = Q15_MAX_WARM_GAIN;
+                }
+            }
+            if change < -Q15_MAX_COLD_LOSS {
+                change = -Q15_MAX_COLD_LOSS;
+            }
+            permille = match permille.checked_mul(Q15_ONE + change) {
+                Some(product) => product >> 15,
+                // A negative coefficient below the nominal temperature
+                // raises the SOC without bound
+                None => LITE_MAX_COMPENSATED_PERMILLE,
+            };
+        }
+
+        if config.is_aging_compensation_enabled()
+            && config.age_years.to_bits() >= 0
+            && config.aging_factor.to_bits() >= 0
+        {
+            let mut loss =
+                (config.age_years.to_bits() as i32 * config.aging_factor.to_bits() as i32) >> 8;
+            if loss > Q15_MAX_AGING_LOSS {
+                loss = Q15_MAX_AGING_LOSS;
+            }
+            permille = (permille * (Q15_ONE - loss)) >> 15;
+        }
+
+        self.apply_output_policy(permille)
+    }
+
+    /// Applies the [`OutputPolicy`] to an SOC in permille
+    const fn apply_output_policy(&self, permille: i32) -> Result<LiteFixed, Error> {
+        let in_range = permille >= 0 && permille <= 1000;
+        match self.config.output_policy {
+            OutputPolicy::Clamp => Ok(permille_to_lite(permille, 0, 1000)),
+            OutputPolicy::Reject if !in_range => Err(Error::SocOutOfRange),
+            OutputPolicy::Reject => Ok(permille_to_lite(permille, 0, 1000)),
+            OutputPolicy::Raw => Ok(permille_to_lite(
+                permille,
+                LITE_MIN_PERMILLE,
+                LITE_MAX_PERMILLE,
+            )),
+        }
+    }
+
+    /// Curve lookup in permille
+    const fn lookup_permille(&self, voltage: LiteFixed) -> Result<i32, Error> {
+        let voltage_mv = (voltage.to_bits() as i32 * 1000) >> 8;
+        let voltage_mv = if voltage_mv < 0 { 0 } else { voltage_mv as u16 };
+        match self.curve.voltage_mv_to_soc_permille(voltage_mv) {
+            Ok(permille) => Ok(permille as i32),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+/// 1.0 as a Q15 fraction
+const Q15_ONE: i32 = 1 << 15;
+/// [`CompensationLimits::DEFAULT`] as Q15 fractions
+const Q15_WARM_SCALE: i32 = q15(CompensationLimits::DEFAULT.warm_scale);
+const Q15_MAX_WARM_GAIN: i32 = q15(CompensationLimits::DEFAULT.max_warm_gain);
+const Q15_MAX_COLD_LOSS: i32 = q15(CompensationLimits::DEFAULT.max_cold_loss);
+const Q15_MAX_AGING_LOSS: i32 = q15(CompensationLimits::DEFAULT.max_aging_loss);
+
+/// Converts an I16F16 fraction to Q15
+const fn q15(value: crate::Fixed) -> i32 {
+    value.to_bits() >> 1
+}
+
+/// Lowest SOC permille representable as a [`LiteFixed`] percentage
+const LITE_MIN_PERMILLE: i32 = -1279;
+/// Highest SOC permille representable as a [`LiteFixed`] percentage
+const LITE_MAX_PERMILLE: i32 = 1279;
+
+/// Cap on the permille fed to the compensation math
+const LITE_MAX_COMPENSATED_PERMILLE: i32 = 10 * LITE_MAX_PERMILLE;
+
+/// Converts SOC permille clamped to `min..=max` to a rounded [`LiteFixed`]
+/// percentage
+const fn permille_to_lite(permille: i32, min: i32, max: i32) -> LiteFixed {
+    let permille = if permille < min {
+        min
+    } else if permille > max {
+        max
+    } else {
+        permille
+    };
+    LiteFixed::from_bits(((permille * 256 + 5) / 10) as i16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CurvePoint, Fixed, SocEstimator};
+
+    fn within_half_percent(lite: LiteFixed, full: Fixed) -> bool {
+        (Fixed::from(lite) - full).abs() <= Fixed::from_num(0.5)
+    }
+
+    #[test]
+    fn test_lite_matches_full_precision() {
+        for chemistry in [
+            BatteryChemistry::LiPo,
+            BatteryChemistry::LiFePO4,
+            BatteryChemistry::LiIon,
+            BatteryChemistry::Lipo410Full340Cutoff,
+        ] {
+            let lite = LiteEstimator::new(chemistry);
+            let full = SocEstimator::new(chemistry);
+            for bits in (2 * 256..=5 * 256).step_by(1) {
+                let voltage = LiteFixed::from_bits(bits);
+                let soc = lite.estimate_soc(voltage).unwrap();
+                let reference = full.estimate_soc_fixed(voltage.into()).unwrap();
+                assert!(
+                    within_half_percent(soc, reference),
+                    "{voltage}: {soc} vs {reference}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_lite_compensation_matches_full_precision() {
+        let config = EstimatorConfig::default()
+            .with_temperature_compensation()
+            .with_aging_compensation()
+            .with_age_years(Fixed::from_num(3));
+        let full = SocEstimator::with_config(BatteryChemistry::LiIon, config);
+        let lite =
+            LiteEstimator::with_config(BatteryChemistry::LiIon, LiteConfig::from_config(&config));
+        assert!(lite.config().is_aging_compensation_enabled());
+
+        for temperature in [-20, 0, 25, 40, 60] {
+            for bits in (3 * 256..=4 * 256 + 64).step_by(3) {
+                let voltage = LiteFixed::from_bits(bits);
+                let temperature = LiteFixed::from_num(temperature);
+                let soc = lite.estimate_soc_compensated(voltage, temperature).unwrap();
+                let reference = full
+                    .estimate_soc_compensated_fixed(voltage.into(), temperature.into())
+                    .unwrap();
+                assert!(
+                    within_half_percent(soc, reference),
+                    "{voltage} {temperature}: {soc} vs {reference}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_lite_is_smaller() {
+        assert_eq!(core::mem::size_of::<LiteConfig>(), 10);
+        assert!(core::mem::size_of::<LiteConfig>() * 4 <= core::mem::size_of::<EstimatorConfig>());
+        assert_eq!(
+            LiteEstimator::new(BatteryChemistry::LiPo).estimate_soc(LiteFixed::MAX),
+            Ok(LiteFixed::from_num(100))
+        );
+        assert_eq!(
+            LiteEstimator::new(BatteryChemistry::LiPo).estimate_soc(LiteFixed::MIN),
+            Ok(LiteFixed::ZERO)
+        );
+    }
+
+    #[test]
+    fn test_lite_output_policy() {
+        // 4.2V at 45°C reads 105% before the policy is applied
+        let estimate = |policy| {
+            let config = LiteConfig::default()
+                .with_temperature_compensation()
+                .with_output_policy(policy);
+            LiteEstimator::with_config(BatteryChemistry::LiPo, config)
+                .estimate_soc_compensated(LiteFixed::from_num(4.2), LiteFixed::from_num(45))
+        };
+
+        assert_eq!(estimate(OutputPolicy::Clamp), Ok(LiteFixed::from_num(100)));
+        assert_eq!(estimate(OutputPolicy::Reject), Err(Error::SocOutOfRange));
+        assert!(estimate(OutputPolicy::Raw).unwrap() > LiteFixed::from_num(104.5));
+
+        let config = EstimatorConfig::default().with_output_policy(OutputPolicy::Reject);
+        assert_eq!(
+            LiteConfig::from_config(&config).output_policy,
+            OutputPolicy::Reject
+        );
+    }
+
+    #[test]
+    fn test_lite_compensation_extreme_curve() {
+        static CURVE: Curve = Curve::new(&[
+            CurvePoint::from_raw(3_000, 0),
+            CurvePoint::from_raw(4_000, u16::MAX),
+        ]);
+        let config = LiteConfig::default()
+            .with_temperature_compensation()
+            .with_output_policy(OutputPolicy::Raw);
+        let mut lite = LiteEstimator::with_custom_curve(&CURVE);
+        lite.update_config(config);
+
+        let soc = lite
+            .estimate_soc_compensated(LiteFixed::from_num(4), LiteFixed::from_num(45))
+            .unwrap();
+        assert_eq!(
+            soc,
+            LiteFixed::from_bits(((LITE_MAX_PERMILLE * 256 + 5) / 10) as i16)
+        );
+    }
+
+    #[test]
+    fn test_lite_compensation_extreme_coefficient() {
+        let config = LiteConfig::default()
+            .with_temperature_compensation()
+            .with_temperature_coefficient(LiteCoefficient::MAX)
+            .with_output_policy(OutputPolicy::Raw);
+        let lite = LiteEstimator::with_config(BatteryChemistry::LiPo, config);
+        let voltage = LiteFixed::from_num(3.75);
+        let nominal = lite.estimate_soc(voltage).unwrap();
+
+        // The warm gain is capped at +5% however large the coefficient
+        let warm = lite
+            .estimate_soc_compensated(voltage, LiteFixed::MAX)
+            .unwrap();
+        assert!(within_half_percent(
+            warm,
+            Fixed::from(nominal) * Fixed::from_num(1.05)
+        ));
+
+        let cold = lite
+            .estimate_soc_compensated(voltage, LiteFixed::MIN)
+            .unwrap();
+        assert!(within_half_percent(
+            cold,
+            Fixed::from(nominal) * Fixed::from_num(0.7)
+        ));
+    }
+}
@@ -0,0 +1,293 @@
+//! Estimate and anomaly logging
+//!
+//! With the `defmt` feature, every single-voltage estimate of a
+//! [`SocEstimator`] in fixed-point, `f32` or `f64` (and with it every
+//! [`SocTracker`] update) emits one `debug` record with the measured
+//! voltage in mV, the raw curve SOC, the compensated SOC and the final
+//! SOC in tenths of a percent, and a flags byte:
+//!
+//! - `0x01` - Temperature compensation applied
+//! - `0x02` - Aging compensation applied
+//! - `0x04` - The output policy clamped the SOC
+//!
+//! Estimates rejected by the output policy are logged as `warn` records
+//! with the error instead of the final SOC. The integer permille estimate
+//! and the per-sample results of slice estimates are not logged.
+//!
+//! With the `log` feature, anomalies are reported through the `log`
+//! facade: voltages leaving the curve range (`warn`, with an `info` record
+//! when they return), samples rejected as implausible (`warn`) and
+//! compensation overflows (`error`).
+//!
+//! Without the features the calls compile to nothing.
+//!
+//! [`SocEstimator`]: crate::SocEstimator
+//! [`SocTracker`]: crate::SocTracker
+
+use crate::{Curve, Error, Fixed};
+#[cfg(feature = "log")]
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Temperature compensation was applied
+pub(crate) const FLAG_TEMPERATURE: u8 = 0x01;
+/// Aging compensation was applied
+pub(crate) const FLAG_AGING: u8 = 0x02;
+/// The output policy clamped the SOC
+pub(crate) const FLAG_CLAMPED: u8 = 0x04;
+
+/// Converts a fixed-point value to thousandths
+#[cfg(feature = "defmt")]
+fn milli(value: Fixed) -> i32 {
+    ((value.to_bits() as i64 * 1000) >> 16) as i32
+}
+
+/// Converts a fixed-point percentage to tenths
+#[cfg(feature = "defmt")]
+fn tenths(value: Fixed) -> i32 {
+    ((value.to_bits() as i64 * 10) >> 16) as i32
+}
+
+/// A voltage or SOC type of the estimate record
+pub(crate) trait Logged: Copy + PartialEq {
+    /// The value in thousandths
+    #[cfg(feature = "defmt")]
+    fn milli(self) -> i32;
+    /// The value in tenths
+    #[cfg(feature = "defmt")]
+    fn tenths(self) -> i32;
+}
+
+impl Logged for Fixed {
+    #[cfg(feature = "defmt")]
+    fn milli(self) -> i32 {
+        milli(self)
+    }
+    #[cfg(feature = "defmt")]
+    fn tenths(self) -> i32 {
+        tenths(self)
+    }
+}
+
+#[cfg(not(feature = "no-float"))]
+impl Logged for f32 {
+    #[cfg(feature = "defmt")]
+    fn milli(self) -> i32 {
+        (self * 1000.0) as i32
+    }
+    #[cfg(feature = "defmt")]
+    fn tenths(self) -> i32 {
+        (self * 10.0) as i32
+    }
+}
+
+#[cfg(not(feature = "no-float"))]
+impl Logged for f64 {
+    #[cfg(feature = "defmt")]
+    fn milli(self) -> i32 {
+        (self * 1000.0) as i32
+    }
+    #[cfg(feature = "defmt")]
+    fn tenths(self) -> i32 {
+        (self * 10.0) as i32
+    }
+}
+
+/// Logs one estimate
+///
+/// `flags` holds the compensation flags; the clamp flag is added when
+/// `result` differs from `compensated`.
+#[inline(always)]
+pub(crate) fn estimate<T: Logged>(
+    voltage: T,
+    raw: T,
+    compensated: T,
+    result: &Result<T, Error>,
+    flags: u8,
+) {
+    let flags = match *result {
+        Ok(soc) if soc != compensated => flags | FLAG_CLAMPED,
+        _ => flags,
+    };
+
+    #[cfg(feature = "defmt")]
+    match *result {
+        Ok(soc) => defmt::debug!(
+            "estimate voltage={=i32}mV raw={=i32} compensated={=i32} soc={=i32} flags={=u8:#04x}",
+            voltage.milli(),
+            raw.tenths(),
+            compensated.tenths(),
+            soc.tenths(),
+            flags
+        ),
+        Err(error) => defmt::warn!(
+            "estimate rejected voltage={=i32}mV raw={=i32} compensated={=i32} flags={=u8:#04x} error={}",
+            voltage.milli(),
+            raw.tenths(),
+            compensated.tenths(),
+            flags,
+            error
+        ),
+    }
+    #[cfg(not(feature = "defmt"))]
+    let _ = (voltage, raw, compensated, result, flags);
+}
+
+/// Whether the last looked-up voltage was outside its curve's range
+#[cfg(feature = "log")]
+static OUT_OF_RANGE: AtomicBool = AtomicBool::new(false);
+
+/// Logs a voltage leaving the curve's range, for which the SOC saturates,
+/// and its return into the range
+///
+/// Lookups at the sample rate would otherwise repeat the warning for as
+/// long as the voltage stays outside. The state is shared by all
+/// estimators, so interleaved lookups on curves in and out of range log
+/// each change.
+#[inline(always)]
+pub(crate) fn voltage_out_of_range(voltage_mv: i32, curve: &Curve) {
+    #[cfg(feature = "log")]
+    range_transition(&OUT_OF_RANGE, voltage_mv, curve);
+    #[cfg(not(feature = "log"))]
+    let _ = (voltage_mv, curve);
+}
+
+/// [`voltage_out_of_range()`] with the state in `out_of_range`
+///
+/// Only loads and stores the flag, which every target supports; a race
+/// between threads can at worst repeat or drop one record.
+#[cfg(feature = "log")]
+fn range_transition(out_of_range: &AtomicBool, voltage_mv: i32, curve: &Curve) {
+    let (Some(first), Some(last)) = (curve.points().first(), curve.points().last()) else {
+        return;
+    };
+    let outside = voltage_mv < first.voltage_mv as i32 || voltage_mv > last.voltage_mv as i32;
+    if outside == out_of_range.load(Ordering::Relaxed) {
+        return;
+    }
+    out_of_range.store(outside, Ordering::Relaxed);
+    if outside {
+        log::warn!(
+            "battery voltage {voltage_mv} mV outside the curve range {}-{} mV",
+            first.voltage_mv,
+            last.voltage_mv
+        );
+    } else {
+        log::info!(
+            "battery voltage {voltage_mv} mV back inside the curve range {}-{} mV",
+            first.voltage_mv,
+            last.voltage_mv
+        );
+    }
+}
+
+/// Logs a sample rejected by the tracker's plausibility check
+#[inline(always)]
+pub(crate) fn implausible_sample(voltage: Fixed, previous: Fixed, max_step: Fixed) {
+    #[cfg(feature = "log")]
+    log::warn!(
+        "implausible battery voltage {voltage} V after {previous} V (maximum step {max_step} V), sample ignored"
+    );
+    #[cfg(not(feature = "log"))]
+    let _ = (voltage, previous, max_step);
+}
+
+/// Logs a compensation that overflowed the fixed-point range
+#[inline(always)]
+pub(crate) fn overflow(compensation: &str, soc: Fixed) {
+    #[cfg(feature = "log")]
+    log::error!("{compensation} compensation of {soc}% SOC overflowed");
+    #[cfg(not(feature = "log"))]
+    let _ = (compensation, soc);
+}
+
+/// Formats the SOC and voltage fields in tenths of a percent and mV
+#[cfg(feature = "defmt")]
+impl defmt::Format for crate::GaugeEvent {
+    fn format(&self, f: defmt::Formatter) {
+        match *self {
+            crate::GaugeEvent::ThresholdCrossed {
+                threshold,
+                soc,
+                direction,
+            } => defmt::write!(
+                f,
+                "ThresholdCrossed {{ threshold: {=i32}, soc: {=i32}, direction: {} }}",
+                tenths(threshold),
+                tenths(soc),
+                direction
+            ),
+            crate::GaugeEvent::SensorFault { voltage } => {
+                defmt::write!(f, "SensorFault {{ voltage: {=i32}mV }}", milli(voltage))
+            }
+            crate::GaugeEvent::Full => defmt::write!(f, "Full"),
+            crate::GaugeEvent::Empty => defmt::write!(f, "Empty"),
+            crate::GaugeEvent::SampleFailed { error } => {
+                defmt::write!(f, "SampleFailed {{ error: {} }}", error)
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "log", feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::BatteryChemistry;
+    use std::string::{String, ToString};
+    use std::sync::Mutex;
+    use std::vec::Vec;
+
+    static RECORDS: Mutex<Vec<(log::Level, String)>> = Mutex::new(Vec::new());
+
+    struct Recorder;
+
+    impl log::Log for Recorder {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            let message = record.args().to_string();
+            RECORDS.lock().unwrap().push((record.level(), message));
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn test_anomalies_logged() {
+        let _ = log::set_logger(&Recorder);
+        log::set_max_level(log::LevelFilter::Info);
+
+        // Local state, as other tests run lookups in parallel
+        let curve = Curve::for_chemistry(BatteryChemistry::LiPo);
+        let state = AtomicBool::new(false);
+        for voltage_mv in [4_517, 4_518, 4_519, 4_123, 4_124, 4_531] {
+            range_transition(&state, voltage_mv, curve);
+        }
+        overflow("temperature", Fixed::from_num(50));
+
+        let records = RECORDS.lock().unwrap();
+        let logged = |level: log::Level, text: &str| {
+            records
+                .iter()
+                .filter(|(l, message)| *l == level && message.contains(text))
+                .count()
+        };
+        assert_eq!(
+            logged(
+                log::Level::Warn,
+                "4517 mV outside the curve range 3200-4200 mV"
+            ),
+            1
+        );
+        assert_eq!(logged(log::Level::Warn, "4518 mV"), 0);
+        assert_eq!(logged(log::Level::Warn, "4519 mV"), 0);
+        assert_eq!(
+            logged(log::Level::Info, "4123 mV back inside the curve range"),
+            1
+        );
+        assert_eq!(logged(log::Level::Info, "4124 mV"), 0);
+        assert_eq!(logged(log::Level::Warn, "4531 mV"), 1);
+        assert!(logged(log::Level::Error, "50% SOC") > 0);
+    }
+}
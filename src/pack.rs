@@ -0,0 +1,1675 @@
+//! Multi-cell battery pack estimation
+//!
+//! Curves describe a single cell. A series pack of `CELLS` cells can be
+//! estimated either from the per-cell voltages (when a balancer or AFE
+//! reports them) or from the total pack voltage alone. The
+//! [`PackEstimator`] applies the per-cell curve in both cases, so the
+//! application never has to divide the pack voltage by hand.
+//!
+//! Cells wired in parallel behave like one cell of larger capacity. An
+//! xSyP pack is described by a [`PackTopology`]: `CELLS` fixes the series
+//! count, and the parallel count and cell capacity make capacity-based
+//! outputs (remaining mAh, time-to-empty) scale correctly.
+//!
+//! Remaining energy (Wh) integrates the cell curve from the current SOC
+//! down to empty, so the voltage sag towards the end of discharge is
+//! accounted for instead of assuming a constant nominal voltage.
+//!
+//! Pack parameters are often provisioned in manufacturing. [`PackConfig`]
+//! converts to and from a small checksummed byte blob for storage in
+//! flash, and implements `serde` traits with the `serde` feature.
+//!
+//! Cells in one pack age at different rates. The estimator keeps a
+//! state-of-health (SOH, remaining capacity in percent of nominal) per
+//! cell group and scales each cell's SOC by it, which replaces the
+//! estimator-wide aging compensation with a per-cell one. Feeding
+//! [`PackEstimator::record_usage()`] with the pack current and cell
+//! temperatures tracks each cell's charge throughput and heat exposure
+//! and lowers its SOH accordingly, so cells age independently.
+
+use crate::persist::{crc8, get_fixed, put_fixed};
+use crate::{Error, ErrorDetail, Fixed, SocEstimate, SocEstimator};
+
+/// 100% as a fixed-point constant
+const SOC_FULL: Fixed = Fixed::from_bits(100 << 16);
+
+/// Milliamp-hours per amp-hour
+const MAH_PER_AH: Fixed = Fixed::from_bits(1000 << 16);
+
+/// Format version written into serialized pack configurations
+const CONFIG_VERSION: u8 = 2;
+
+/// Seconds per hour, used to convert mA·s into mAh
+const SECONDS_PER_HOUR: i128 = 3600;
+
+/// Pack topology in xSyP notation
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{Fixed, PackTopology};
+///
+/// // 4S2P pack of 3000 mAh cells
+/// let topology = PackTopology::new(4, 2, Fixed::from_num(3000));
+///
+/// assert_eq!(topology.cell_count(), 8);
+/// assert_eq!(topology.pack_capacity_mah(), Fixed::from_num(6000));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PackTopology {
+    /// Number of cell groups in series
+    pub series: u8,
+    /// Number of cells in each parallel group (values below 1 count as 1)
+    pub parallel: u8,
+    /// Nominal capacity of a single cell in mAh (0 disables capacity outputs)
+    pub cell_capacity_mah: Fixed,
+}
+
+impl PackTopology {
+    /// Creates a topology of `series` groups of `parallel` cells each
+    #[inline]
+    pub const fn new(series: u8, parallel: u8, cell_capacity_mah: Fixed) -> Self {
+        Self {
+            series,
+            parallel,
+            cell_capacity_mah,
+        }
+    }
+
+    /// Total number of cells in the pack
+    #[inline]
+    pub const fn cell_count(&self) -> u16 {
+        let parallel = if self.parallel == 0 { 1 } else { self.parallel };
+        self.series as u16 * parallel as u16
+    }
+
+    /// Nominal capacity of one series group in mAh (cell capacity times
+    /// parallel count), which is also the nominal pack capacity
+    ///
+    /// Saturates at the largest representable value (about 32 Ah).
+    #[inline]
+    pub fn pack_capacity_mah(&self) -> Fixed {
+        self.cell_capacity_mah
+            .max(Fixed::ZERO)
+            .saturating_mul(Fixed::from_num(self.parallel.max(1)))
+    }
+}
+
+/// Pack configuration
+///
+/// The series count of [`topology`](Self::topology) is overwritten with
+/// `CELLS` when the configuration is handed to a [`PackEstimator`].
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{Fixed, PackConfig};
+///
+/// // 2P pack of 3000 mAh cells
+/// let config = PackConfig::default()
+///     .with_cell_capacity_mah(Fixed::from_num(3000))
+///     .with_parallel(2);
+///
+/// assert_eq!(config.topology.pack_capacity_mah(), Fixed::from_num(6000));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PackConfig {
+    /// Pack topology (series/parallel counts and cell capacity)
+    pub topology: PackTopology,
+    /// Cell voltage spread in volts above which the pack is reported as
+    /// imbalanced (0 disables the warning)
+    pub imbalance_threshold: Fixed,
+    /// How the pack SOC is derived from the cell SOCs
+    pub soc_policy: PackSocPolicy,
+    /// SOH loss in percent per equivalent full cycle (one capacity
+    /// discharged and recharged)
+    pub cycle_fade: Fixed,
+    /// SOH loss in percent per 1000 °C·h spent above the estimator's
+    /// nominal temperature
+    pub thermal_fade: Fixed,
+}
+
+impl PackConfig {
+    /// Default configuration
+    ///
+    /// - Topology 1S1P: one cell per group (plain series pack)
+    /// - Capacity outputs disabled (cell capacity 0 mAh)
+    /// - Imbalance threshold: 50 mV
+    /// - Pack SOC policy: [`PackSocPolicy::Average`]
+    /// - Cycle fade: 0.02% per cycle (80% SOH after 1000 cycles)
+    /// - Thermal fade: 0.05% per 1000 °C·h (~4%/year at 10 °C above nominal)
+    #[inline]
+    pub const fn default() -> Self {
+        Self {
+            topology: PackTopology::new(1, 1, Fixed::ZERO),
+            imbalance_threshold: Fixed::from_bits(3277), // 0.05
+            soc_policy: PackSocPolicy::Average,
+            cycle_fade: Fixed::from_bits(1311),   // 0.02
+            thermal_fade: Fixed::from_bits(3277), // 0.05
+        }
+    }
+
+    /// Size of a serialized configuration in bytes
+    pub const SERIALIZED_SIZE: usize = 25;
+
+    /// Serialize the configuration into a fixed-size byte blob
+    ///
+    /// Layout (all multi-byte values little-endian, fixed-point values as
+    /// raw I16F16 bits):
+    ///
+    /// | Offset | Size | Content |
+    /// |--------|------|---------|
+    /// | 0 | 1 | Format version |
+    /// | 1 | 1 | Series count |
+    /// | 2 | 1 | Parallel count |
+    /// | 3 | 1 | SOC policy (0: average, 1: weakest cell, 2: weighted) |
+    /// | 4 | 4 | Cell capacity in mAh |
+    /// | 8 | 4 | Imbalance threshold in volts |
+    /// | 12 | 4 | Weakest-cell weight (weighted policy only) |
+    /// | 16 | 4 | Cycle fade in percent per cycle |
+    /// | 20 | 4 | Thermal fade in percent per 1000 °C·h |
+    /// | 24 | 1 | CRC-8 (poly 0x07) over all preceding bytes |
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{Fixed, PackConfig, PackTopology};
+    ///
+    /// let config = PackConfig::default()
+    ///     .with_topology(PackTopology::new(4, 2, Fixed::from_num(3000)));
+    ///
+    /// let blob = config.to_bytes();
+    /// assert_eq!(PackConfig::from_bytes(&blob), Ok(config));
+    /// ```
+    pub fn to_bytes(&self) -> [u8; Self::SERIALIZED_SIZE] {
+        let mut blob = [0u8; Self::SERIALIZED_SIZE];
+        blob[0] = CONFIG_VERSION;
+        blob[1] = self.topology.series;
+        blob[2] = self.topology.parallel;
+        let (policy, weight) = match self.soc_policy {
+            PackSocPolicy::Average => (0, Fixed::ZERO),
+            PackSocPolicy::WeakestCell => (1, Fixed::ZERO),
+            PackSocPolicy::Weighted(weight) => (2, weight),
+        };
+        blob[3] = policy;
+        put_fixed(&mut blob, 4, self.topology.cell_capacity_mah);
+        put_fixed(&mut blob, 8, self.imbalance_threshold);
+        put_fixed(&mut blob, 12, weight);
+        put_fixed(&mut blob, 16, self.cycle_fade);
+        put_fixed(&mut blob, 20, self.thermal_fade);
+
+        let last = Self::SERIALIZED_SIZE - 1;
+        blob[last] = crc8(&blob[..last]);
+        blob
+    }
+
+    /// Deserialize a configuration produced by [`to_bytes()`](Self::to_bytes)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(config)` - The stored configuration
+    /// * `Err(Error::InvalidState)` - Checksum, version, or policy check
+    ///   failed (e.g., unprovisioned flash)
+    pub fn from_bytes(blob: &[u8; Self::SERIALIZED_SIZE]) -> Result<Self, Error> {
+        let last = Self::SERIALIZED_SIZE - 1;
+        if blob[0] != CONFIG_VERSION || blob[last] != crc8(&blob[..last]) {
+            return Err(Error::InvalidState);
+        }
+
+        let soc_policy = match blob[3] {
+            0 => PackSocPolicy::Average,
+            1 => PackSocPolicy::WeakestCell,
+            2 => PackSocPolicy::Weighted(get_fixed(blob, 12)),
+            _ => return Err(Error::InvalidState),
+        };
+
+        Ok(Self {
+            topology: PackTopology::new(blob[1], blob[2], get_fixed(blob, 4)),
+            imbalance_threshold: get_fixed(blob, 8),
+            soc_policy,
+            cycle_fade: get_fixed(blob, 16),
+            thermal_fade: get_fixed(blob, 20),
+        })
+    }
+
+    /// Set the pack topology
+    #[inline]
+    pub fn with_topology(mut self, topology: PackTopology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    /// Set the number of cells in each parallel group
+    #[inline]
+    pub fn with_parallel(mut self, parallel: u8) -> Self {
+        self.topology.parallel = parallel;
+        self
+    }
+
+    /// Set the nominal capacity of a single cell
+    #[inline]
+    pub fn with_cell_capacity_mah(mut self, capacity_mah: Fixed) -> Self {
+        self.topology.cell_capacity_mah = capacity_mah;
+        self
+    }
+
+    /// Set the cell voltage spread that triggers the imbalance warning
+    #[inline]
+    pub fn with_imbalance_threshold(mut self, threshold: Fixed) -> Self {
+        self.imbalance_threshold = threshold;
+        self
+    }
+
+    /// Set how the pack SOC is derived from the cell SOCs
+    #[inline]
+    pub fn with_soc_policy(mut self, policy: PackSocPolicy) -> Self {
+        self.soc_policy = policy;
+        self
+    }
+
+    /// Set the SOH loss per equivalent full cycle in percent
+    #[inline]
+    pub fn with_cycle_fade(mut self, fade: Fixed) -> Self {
+        self.cycle_fade = fade;
+        self
+    }
+
+    /// Set the SOH loss per 1000 °C·h above nominal temperature in percent
+    #[inline]
+    pub fn with_thermal_fade(mut self, fade: Fixed) -> Self {
+        self.thermal_fade = fade;
+        self
+    }
+}
+
+// Non-const Default implementation
+impl Default for PackConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::default()
+    }
+}
+
+/// How the pack SOC is derived from the cell SOCs
+///
+/// In a series pack every cell carries the same current, so the pack is
+/// empty as soon as its weakest cell is. Averaging the cells therefore
+/// overestimates the remaining runtime of an imbalanced pack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PackSocPolicy {
+    /// Mean of all cell SOCs
+    Average,
+    /// SOC of the weakest cell
+    WeakestCell,
+    /// Blend of the weakest cell and the mean
+    ///
+    /// The value is the weight of the weakest cell (0.0-1.0): 0.0 behaves
+    /// like [`Average`](Self::Average), 1.0 like
+    /// [`WeakestCell`](Self::WeakestCell).
+    Weighted(Fixed),
+}
+
+/// Accumulated usage of one cell group
+///
+/// Recorded by [`PackEstimator::record_usage()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CellUsage {
+    /// Charge moved in or out of the group in mA·s, as 48.16 fixed-point
+    /// bits so that small samples are not truncated away
+    throughput_mas: u64,
+    /// Temperature excess over nominal integrated over time in °C·s,
+    /// as 16.16 fixed-point bits
+    heat_exposure: u64,
+}
+
+impl CellUsage {
+    /// Charge moved in or out of the group in mAh
+    #[inline]
+    pub const fn throughput_mah(&self) -> u64 {
+        (self.throughput_mas >> 16) / SECONDS_PER_HOUR as u64
+    }
+
+    /// Time-integrated temperature excess over nominal in °C·h
+    #[inline]
+    pub const fn heat_exposure_degree_hours(&self) -> u64 {
+        (self.heat_exposure >> 16) / SECONDS_PER_HOUR as u64
+    }
+}
+
+/// Result of a per-cell pack estimation
+///
+/// Produced by [`PackEstimator::estimate_soc_cells()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackEstimate<const CELLS: usize> {
+    /// Pack SOC percentage as fixed-point
+    pub soc: Fixed,
+    /// SOC percentage of each cell as fixed-point
+    pub cell_soc: [Fixed; CELLS],
+    /// Lowest cell voltage as fixed-point
+    pub min_cell_voltage: Fixed,
+    /// Highest cell voltage as fixed-point
+    pub max_cell_voltage: Fixed,
+    /// Difference between the highest and lowest cell SOC in percent
+    pub soc_spread: Fixed,
+    /// `true` if the cell voltage spread exceeds the configured threshold
+    pub imbalanced: bool,
+}
+
+impl<const CELLS: usize> PackEstimate<CELLS> {
+    /// Difference between the highest and lowest cell voltage
+    #[inline]
+    pub fn voltage_spread(&self) -> Fixed {
+        self.max_cell_voltage.saturating_sub(self.min_cell_voltage)
+    }
+}
+
+/// Complete pack status produced in one call
+///
+/// Produced by [`PackEstimator::report()`]. Plain `Copy` data, ready to be
+/// packed into a telemetry frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackReport<const CELLS: usize> {
+    /// Pack SOC percentage as fixed-point
+    pub soc: Fixed,
+    /// SOC percentage of each cell as fixed-point
+    pub cell_soc: [Fixed; CELLS],
+    /// Lowest cell voltage as fixed-point
+    pub min_cell_voltage: Fixed,
+    /// Highest cell voltage as fixed-point
+    pub max_cell_voltage: Fixed,
+    /// Difference between the highest and lowest cell voltage
+    pub imbalance: Fixed,
+    /// Pack temperature in °C
+    pub temperature: Fixed,
+    /// Pack state of health in percent
+    pub soh: Fixed,
+    /// Status flags (`FLAG_*` constants)
+    pub flags: u8,
+}
+
+impl<const CELLS: usize> PackReport<CELLS> {
+    /// The cell voltage spread exceeds the imbalance threshold
+    pub const FLAG_IMBALANCED: u8 = 0x01;
+    /// At least one cell is empty
+    pub const FLAG_CELL_EMPTY: u8 = 0x02;
+    /// At least one cell is full
+    pub const FLAG_CELL_FULL: u8 = 0x04;
+
+    /// Returns `true` if all bits of `flag` are set
+    #[inline]
+    pub const fn has_flag(&self, flag: u8) -> bool {
+        self.flags & flag == flag
+    }
+}
+
+/// SOC estimator for a series pack of `CELLS` identical cells
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{BatteryChemistry, Fixed, PackEstimator, SocEstimator};
+///
+/// // 3S LiPo pack
+/// let pack = PackEstimator::<3>::new(SocEstimator::new(BatteryChemistry::LiPo));
+///
+/// let soc = pack.estimate_soc_pack_voltage(Fixed::from_num(11.25)).unwrap();
+/// assert_eq!(soc, Fixed::from_num(60.0));
+///
+/// let cells = [Fixed::from_num(3.75); 3];
+/// let estimate = pack.estimate_soc_cells(&cells).unwrap();
+/// assert_eq!(estimate.soc, soc);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct PackEstimator<const CELLS: usize> {
+    /// Per-cell estimator
+    estimator: SocEstimator,
+    config: PackConfig,
+    /// Cell temperature used for compensated estimation
+    temperature: Fixed,
+    /// State of health of each cell group in percent of nominal capacity
+    cell_soh: [Fixed; CELLS],
+    /// SOH of each cell group before any recorded usage
+    cell_soh_baseline: [Fixed; CELLS],
+    /// Accumulated usage of each cell group
+    cell_usage: [CellUsage; CELLS],
+}
+
+impl<const CELLS: usize> PackEstimator<CELLS> {
+    /// Rejects series counts that do not fit a [`PackTopology`] at compile time
+    const NON_EMPTY: () = assert!(
+        CELLS > 0 && CELLS <= u8::MAX as usize,
+        "a pack needs between 1 and 255 cells in series"
+    );
+
+    /// Create a new pack estimator from a per-cell estimator
+    ///
+    /// The temperature starts at the estimator's nominal temperature.
+    pub const fn new(estimator: SocEstimator) -> Self {
+        Self::with_config(estimator, PackConfig::default())
+    }
+
+    /// Create a new pack estimator with a custom configuration
+    ///
+    /// The series count of the topology is set to `CELLS`.
+    pub const fn with_config(estimator: SocEstimator, mut config: PackConfig) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::NON_EMPTY;
+        config.topology.series = CELLS as u8;
+        Self {
+            temperature: estimator.config().nominal_temperature,
+            estimator,
+            config,
+            cell_soh: [SOC_FULL; CELLS],
+            cell_soh_baseline: [SOC_FULL; CELLS],
+            cell_usage: [CellUsage {
+                throughput_mas: 0,
+                heat_exposure: 0,
+            }; CELLS],
+        }
+    }
+
+    /// Number of cells in series
+    #[inline]
+    pub const fn cells(&self) -> usize {
+        CELLS
+    }
+
+    /// Estimate pack SOC from the total pack voltage
+    ///
+    /// The pack voltage is divided evenly across the cells, which assumes a
+    /// balanced pack. Use [`estimate_soc_cells()`](Self::estimate_soc_cells)
+    /// when per-cell voltages are available. The SOC is scaled by the
+    /// [pack SOH](Self::pack_soh).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(soc)` - Pack SOC percentage as fixed-point
+    /// * `Err(Error::PackMismatch)` - The voltage is implausible for `CELLS`
+    ///   cells (see [`validate_pack_voltage()`](Self::validate_pack_voltage))
+    /// * `Err(Error)` - The curve lookup failed
+    pub fn estimate_soc_pack_voltage(&self, pack_voltage: Fixed) -> Result<Fixed, Error> {
+        self.validate_pack_voltage(pack_voltage)?;
+        let cell_voltage = Self::cell_voltage(pack_voltage);
+        let soc = self.cell_soc(cell_voltage, self.temperature)?;
+        Ok(percent_of(soc, self.pack_soh()))
+    }
+
+    /// Produce a complete [`PackReport`] from individual cell voltages
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, Fixed, PackEstimator, PackReport, SocEstimator};
+    ///
+    /// let pack = PackEstimator::<2>::new(SocEstimator::new(BatteryChemistry::LiPo));
+    /// let report = pack
+    ///     .report(&[Fixed::from_num(3.75), Fixed::from_num(4.25)])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(report.soc, Fixed::from_num(80));
+    /// assert_eq!(report.imbalance, Fixed::from_num(0.5));
+    /// assert!(report.has_flag(PackReport::<2>::FLAG_IMBALANCED));
+    /// assert!(report.has_flag(PackReport::<2>::FLAG_CELL_FULL));
+    /// ```
+    pub fn report(&self, cell_voltages: &[Fixed; CELLS]) -> Result<PackReport<CELLS>, Error> {
+        let estimate = self.estimate_soc_cells(cell_voltages)?;
+
+        let mut flags = 0;
+        if estimate.imbalanced {
+            flags |= PackReport::<CELLS>::FLAG_IMBALANCED;
+        }
+        if estimate.cell_soc.iter().any(|&soc| soc <= Fixed::ZERO) {
+            flags |= PackReport::<CELLS>::FLAG_CELL_EMPTY;
+        }
+        if estimate.cell_soc.iter().any(|&soc| soc >= SOC_FULL) {
+            flags |= PackReport::<CELLS>::FLAG_CELL_FULL;
+        }
+
+        Ok(PackReport {
+            soc: estimate.soc,
+            cell_soc: estimate.cell_soc,
+            min_cell_voltage: estimate.min_cell_voltage,
+            max_cell_voltage: estimate.max_cell_voltage,
+            imbalance: estimate.voltage_spread(),
+            temperature: self.temperature,
+            soh: self.pack_soh(),
+            flags,
+        })
+    }
+
+    /// Check that a pack voltage is physically possible for this pack
+    ///
+    /// The per-cell voltage must lie between 80% of the curve's lowest
+    /// voltage (deeply discharged cells) and 110% of its highest voltage
+    /// (measurement tolerance). Anything outside that window means the
+    /// series count or chemistry does not match the connected pack.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, Error, Fixed, PackEstimator, SocEstimator};
+    ///
+    /// let pack = PackEstimator::<3>::new(SocEstimator::new(BatteryChemistry::LiPo));
+    ///
+    /// assert_eq!(pack.validate_pack_voltage(Fixed::from_num(11.1)), Ok(()));
+    /// // Full 4S pack connected to a 3S configuration
+    /// assert_eq!(
+    ///     pack.validate_pack_voltage(Fixed::from_num(16.8)),
+    ///     Err(Error::PackMismatch)
+    /// );
+    /// ```
+    pub fn validate_pack_voltage(&self, pack_voltage: Fixed) -> Result<(), Error> {
+        self.validate_pack_voltage_detail(pack_voltage)
+            .map_err(Error::from)
+    }
+
+    /// Like [`validate_pack_voltage()`](Self::validate_pack_voltage), but
+    /// reports the rejected pack voltage
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, ErrorContext, Fixed, PackEstimator, SocEstimator};
+    ///
+    /// let pack = PackEstimator::<3>::new(SocEstimator::new(BatteryChemistry::LiPo));
+    ///
+    /// let detail = pack.validate_pack_voltage_detail(Fixed::from_num(16.5)).unwrap_err();
+    /// assert_eq!(detail.context, ErrorContext::Voltage(Fixed::from_num(16.5)));
+    /// ```
+    pub fn validate_pack_voltage_detail(&self, pack_voltage: Fixed) -> Result<(), ErrorDetail> {
+        if !is_plausible_cell_voltage(&self.estimator, Self::cell_voltage(pack_voltage)) {
+            return Err(Error::PackMismatch.at_voltage(pack_voltage));
+        }
+        Ok(())
+    }
+
+    /// Estimate pack SOC from individual cell voltages
+    ///
+    /// Each cell SOC is scaled by that cell's SOH, then the pack SOC is
+    /// derived from the cell SOCs according to the configured
+    /// [`PackSocPolicy`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(estimate)` - Pack SOC, per-cell SOC, and imbalance information
+    /// * `Err(Error)` - A cell curve lookup failed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, Fixed, PackEstimator, SocEstimator};
+    ///
+    /// let pack = PackEstimator::<2>::new(SocEstimator::new(BatteryChemistry::LiPo));
+    /// let estimate = pack
+    ///     .estimate_soc_cells(&[Fixed::from_num(3.75), Fixed::from_num(3.875)])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(estimate.cell_soc[0], Fixed::from_num(60.0));
+    /// assert_eq!(estimate.voltage_spread(), Fixed::from_num(0.125));
+    /// assert!(estimate.imbalanced);
+    /// ```
+    pub fn estimate_soc_cells(
+        &self,
+        cell_voltages: &[Fixed; CELLS],
+    ) -> Result<PackEstimate<CELLS>, Error> {
+        self.estimate_soc_cells_with_temperatures(cell_voltages, &[self.temperature; CELLS])
+    }
+
+    /// Estimate pack SOC from individual cell voltages and temperatures
+    ///
+    /// Like [`estimate_soc_cells()`](Self::estimate_soc_cells), but each
+    /// cell is compensated with its own temperature instead of the shared
+    /// pack temperature. Edge cells of a pack routinely run 10 °C colder
+    /// than the center ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, Fixed, PackEstimator, SocEstimator};
+    ///
+    /// let estimator = SocEstimator::with_temperature_compensation(
+    ///     BatteryChemistry::LiPo,
+    ///     Fixed::from_num(25),
+    ///     Fixed::from_num(0.005),
+    /// );
+    /// let pack = PackEstimator::<2>::new(estimator);
+    ///
+    /// let estimate = pack
+    ///     .estimate_soc_cells_with_temperatures(
+    ///         &[Fixed::from_num(3.75); 2],
+    ///         &[Fixed::from_num(25), Fixed::from_num(15)],
+    ///     )
+    ///     .unwrap();
+    /// assert!(estimate.cell_soc[1] < estimate.cell_soc[0]);
+    /// ```
+    pub fn estimate_soc_cells_with_temperatures(
+        &self,
+        cell_voltages: &[Fixed; CELLS],
+        cell_temperatures: &[Fixed; CELLS],
+    ) -> Result<PackEstimate<CELLS>, Error> {
+        let mut cell_soc = [Fixed::ZERO; CELLS];
+        let mut sum: i64 = 0;
+        let mut min_cell_voltage = Fixed::MAX;
+        let mut max_cell_voltage = Fixed::MIN;
+        let mut min_soc = Fixed::MAX;
+        let mut max_soc = Fixed::MIN;
+        for (i, soc) in cell_soc.iter_mut().enumerate() {
+            let voltage = cell_voltages[i];
+            *soc = percent_of(
+                self.cell_soc(voltage, cell_temperatures[i])?,
+                self.cell_soh[i],
+            );
+            sum += soc.to_bits() as i64;
+            min_cell_voltage = min_cell_voltage.min(voltage);
+            max_cell_voltage = max_cell_voltage.max(voltage);
+            min_soc = min_soc.min(*soc);
+            max_soc = max_soc.max(*soc);
+        }
+
+        let mean_soc = Fixed::from_bits((sum / CELLS as i64) as i32);
+        let soc = match self.config.soc_policy {
+            PackSocPolicy::Average => mean_soc,
+            PackSocPolicy::WeakestCell => min_soc,
+            PackSocPolicy::Weighted(weight) => {
+                let weight = weight.clamp(Fixed::ZERO, Fixed::ONE);
+                mean_soc - weight.saturating_mul(mean_soc - min_soc)
+            }
+        };
+
+        let threshold = self.config.imbalance_threshold;
+        Ok(PackEstimate {
+            soc,
+            cell_soc,
+            min_cell_voltage,
+            max_cell_voltage,
+            soc_spread: max_soc.saturating_sub(min_soc),
+            imbalanced: threshold > Fixed::ZERO
+                && max_cell_voltage.saturating_sub(min_cell_voltage) > threshold,
+        })
+    }
+
+    /// Remaining pack charge in mAh at the given pack SOC
+    ///
+    /// Returns `None` if no cell capacity is configured.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, Fixed, PackConfig, PackEstimator, SocEstimator};
+    ///
+    /// // 3S2P pack of 2500 mAh cells
+    /// let config = PackConfig::default()
+    ///     .with_cell_capacity_mah(Fixed::from_num(2500))
+    ///     .with_parallel(2);
+    /// let pack = PackEstimator::<3>::with_config(SocEstimator::new(BatteryChemistry::LiPo), config);
+    ///
+    /// assert_eq!(
+    ///     pack.remaining_mah(Fixed::from_num(60)),
+    ///     Some(Fixed::from_num(3000))
+    /// );
+    /// ```
+    pub fn remaining_mah(&self, soc: Fixed) -> Option<Fixed> {
+        let capacity = self.config.topology.pack_capacity_mah();
+        if capacity <= Fixed::ZERO {
+            return None;
+        }
+        Some(percent_of(capacity, soc.clamp(Fixed::ZERO, SOC_FULL)))
+    }
+
+    /// Remaining pack energy in Wh at the given pack SOC
+    ///
+    /// Assumes all cell groups sit at `soc`. Use
+    /// [`remaining_energy_wh_cells()`](Self::remaining_energy_wh_cells)
+    /// to account for an imbalanced pack.
+    ///
+    /// Returns `None` if no cell capacity is configured or the curve is
+    /// invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, Fixed, PackConfig, PackEstimator, SocEstimator};
+    ///
+    /// // 3S1P pack of 2000 mAh cells
+    /// let config = PackConfig::default().with_cell_capacity_mah(Fixed::from_num(2000));
+    /// let pack = PackEstimator::<3>::with_config(SocEstimator::new(BatteryChemistry::LiPo), config);
+    ///
+    /// // Roughly 3S * 2 Ah * 3.8V average
+    /// let energy = pack.remaining_energy_wh(Fixed::from_num(100)).unwrap();
+    /// assert!(energy > Fixed::from_num(22) && energy < Fixed::from_num(23));
+    /// ```
+    pub fn remaining_energy_wh(&self, soc: Fixed) -> Option<Fixed> {
+        let group = self.group_energy_wh(soc.clamp(Fixed::ZERO, SOC_FULL))?;
+        Some(group.saturating_mul(Fixed::from_num(CELLS)))
+    }
+
+    /// Remaining pack energy in Wh from a per-cell estimate
+    ///
+    /// Sums the energy of every cell group at its own SOC.
+    ///
+    /// Returns `None` if no cell capacity is configured or the curve is
+    /// invalid.
+    pub fn remaining_energy_wh_cells(&self, estimate: &PackEstimate<CELLS>) -> Option<Fixed> {
+        let mut total = Fixed::ZERO;
+        for &soc in &estimate.cell_soc {
+            total = total.saturating_add(self.group_energy_wh(soc.clamp(Fixed::ZERO, SOC_FULL))?);
+        }
+        Some(total)
+    }
+
+    /// Energy of one series group at `soc` in Wh
+    fn group_energy_wh(&self, soc: Fixed) -> Option<Fixed> {
+        let capacity = self.config.topology.pack_capacity_mah();
+        if capacity <= Fixed::ZERO {
+            return None;
+        }
+        let integral = self.estimator.curve().voltage_integral(soc).ok()?;
+        Some(percent_of(capacity / MAH_PER_AH, integral))
+    }
+
+    /// Estimated time until empty in hours at a constant pack current
+    ///
+    /// `current_ma` follows the tracker convention (**negative when
+    /// discharging**). Returns `None` if no cell capacity is configured or
+    /// the pack is not discharging.
+    pub fn time_to_empty_hours(&self, soc: Fixed, current_ma: Fixed) -> Option<Fixed> {
+        if current_ma >= Fixed::ZERO {
+            return None;
+        }
+        let remaining = self.remaining_mah(soc)?;
+        Some(remaining.saturating_div(current_ma.saturating_neg()))
+    }
+
+    /// Charge in mAh each cell group should bleed to rebalance the pack
+    ///
+    /// Passive balancing can only remove charge, so every group is bled
+    /// down to the SOC of the weakest one. The amounts are based on the
+    /// per-cell SOCs of `estimate` and the capacity of one parallel group.
+    /// All entries are zero while the pack is not
+    /// [`imbalanced`](PackEstimate::imbalanced).
+    ///
+    /// Returns `None` if no cell capacity is configured.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, Fixed, PackConfig, PackEstimator, SocEstimator};
+    ///
+    /// let config = PackConfig::default().with_cell_capacity_mah(Fixed::from_num(2000));
+    /// let pack = PackEstimator::<2>::with_config(SocEstimator::new(BatteryChemistry::LiPo), config);
+    ///
+    /// // 60% and 81.25%
+    /// let estimate = pack
+    ///     .estimate_soc_cells(&[Fixed::from_num(3.75), Fixed::from_num(3.875)])
+    ///     .unwrap();
+    /// let bleed = pack.balancing_recommendation(&estimate).unwrap();
+    ///
+    /// assert_eq!(bleed[0], Fixed::ZERO);
+    /// assert_eq!(bleed[1], Fixed::from_num(425));
+    /// ```
+    pub fn balancing_recommendation(
+        &self,
+        estimate: &PackEstimate<CELLS>,
+    ) -> Option<[Fixed; CELLS]> {
+        let capacity = self.config.topology.pack_capacity_mah();
+        if capacity <= Fixed::ZERO {
+            return None;
+        }
+
+        let mut bleed_mah = [Fixed::ZERO; CELLS];
+        if !estimate.imbalanced {
+            return Some(bleed_mah);
+        }
+
+        let min_soc = estimate
+            .cell_soc
+            .iter()
+            .copied()
+            .min()
+            .unwrap_or(Fixed::ZERO);
+        for (bleed, &soc) in bleed_mah.iter_mut().zip(&estimate.cell_soc) {
+            *bleed = percent_of(capacity, soc.saturating_sub(min_soc).max(Fixed::ZERO));
+        }
+        Some(bleed_mah)
+    }
+
+    /// Returns the pack configuration
+    #[inline]
+    pub const fn config(&self) -> &PackConfig {
+        &self.config
+    }
+
+    /// Returns the pack topology
+    #[inline]
+    pub const fn topology(&self) -> &PackTopology {
+        &self.config.topology
+    }
+
+    /// Update the pack configuration
+    ///
+    /// The series count of the topology is set to `CELLS`.
+    #[inline]
+    pub fn update_config(&mut self, mut config: PackConfig) {
+        config.topology.series = CELLS as u8;
+        self.config = config;
+        for cell in 0..CELLS {
+            self.refresh_cell_soh(cell);
+        }
+    }
+
+    /// Set the state of health of one cell group in percent (clamped to 0-100)
+    ///
+    /// The value is the SOH before any usage recorded with
+    /// [`record_usage()`](Self::record_usage); recorded usage keeps
+    /// lowering it. Out-of-range indices are ignored.
+    #[inline]
+    pub fn set_cell_soh(&mut self, cell: usize, soh: Fixed) {
+        if let Some(slot) = self.cell_soh_baseline.get_mut(cell) {
+            *slot = soh.clamp(Fixed::ZERO, SOC_FULL);
+            self.refresh_cell_soh(cell);
+        }
+    }
+
+    /// Record pack usage for per-cell aging
+    ///
+    /// Every cell group carries the full pack current. Charge throughput
+    /// counts against [`cycle_fade`](PackConfig::cycle_fade) (requires a
+    /// configured cell capacity), time spent above the estimator's nominal
+    /// temperature against [`thermal_fade`](PackConfig::thermal_fade).
+    ///
+    /// # Arguments
+    ///
+    /// * `current_ma` - Pack current in mA (sign is ignored)
+    /// * `cell_temperatures` - Temperature of each cell group in °C
+    /// * `dt` - Time since the previous call in seconds
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, Fixed, PackConfig, PackEstimator, SocEstimator};
+    ///
+    /// let config = PackConfig::default().with_cell_capacity_mah(Fixed::from_num(1000));
+    /// let mut pack = PackEstimator::<2>::with_config(SocEstimator::new(BatteryChemistry::LiPo), config);
+    ///
+    /// // One hour at 1 A; the second cell runs 20 °C hotter
+    /// let temperatures = [Fixed::from_num(25), Fixed::from_num(45)];
+    /// for _ in 0..60 {
+    ///     pack.record_usage(Fixed::from_num(1000), &temperatures, Fixed::from_num(60));
+    /// }
+    ///
+    /// assert_eq!(pack.cell_usage()[0].throughput_mah(), 1000);
+    /// assert_eq!(pack.cell_usage()[1].heat_exposure_degree_hours(), 20);
+    /// assert_eq!(pack.most_degraded_cell(), 1);
+    /// ```
+    pub fn record_usage(
+        &mut self,
+        current_ma: Fixed,
+        cell_temperatures: &[Fixed; CELLS],
+        dt: Fixed,
+    ) {
+        let dt_bits = dt.max(Fixed::ZERO).to_bits() as u64;
+        let charge = (current_ma.to_bits().unsigned_abs() as u64 * dt_bits) >> 16;
+        let nominal = self.estimator.config().nominal_temperature;
+
+        for (cell, &temperature) in cell_temperatures.iter().enumerate() {
+            let excess = temperature.saturating_sub(nominal).max(Fixed::ZERO);
+            let usage = &mut self.cell_usage[cell];
+            usage.throughput_mas = usage.throughput_mas.saturating_add(charge);
+            usage.heat_exposure = usage
+                .heat_exposure
+                .saturating_add((excess.to_bits() as u64 * dt_bits) >> 16);
+            self.refresh_cell_soh(cell);
+        }
+    }
+
+    /// Returns the accumulated usage of every cell group
+    #[inline]
+    pub const fn cell_usage(&self) -> &[CellUsage; CELLS] {
+        &self.cell_usage
+    }
+
+    /// Recomputes the SOH of one cell group from its baseline and usage
+    fn refresh_cell_soh(&mut self, cell: usize) {
+        let usage = self.cell_usage[cell];
+        let mut loss: i128 = 0;
+
+        // Equivalent full cycles = throughput / (2 * capacity)
+        let capacity = self.config.topology.pack_capacity_mah().to_bits() as i128;
+        if capacity > 0 {
+            loss += usage.throughput_mas as i128
+                * self.config.cycle_fade.max(Fixed::ZERO).to_bits() as i128
+                / (2 * SECONDS_PER_HOUR * capacity);
+        }
+        loss += usage.heat_exposure as i128
+            * self.config.thermal_fade.max(Fixed::ZERO).to_bits() as i128
+            / (SECONDS_PER_HOUR * 1000 * (1 << 16));
+
+        let loss = Fixed::from_bits(loss.min(SOC_FULL.to_bits() as i128) as i32);
+        self.cell_soh[cell] = (self.cell_soh_baseline[cell] - loss).max(Fixed::ZERO);
+    }
+
+    /// Returns the state of health of every cell group in percent
+    #[inline]
+    pub const fn cell_soh(&self) -> &[Fixed; CELLS] {
+        &self.cell_soh
+    }
+
+    /// Pack state of health in percent
+    ///
+    /// A series pack can only deliver the capacity of its most degraded
+    /// cell group, so this is the lowest cell SOH.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, Fixed, PackEstimator, SocEstimator};
+    ///
+    /// let mut pack = PackEstimator::<3>::new(SocEstimator::new(BatteryChemistry::LiPo));
+    /// pack.set_cell_soh(0, Fixed::from_num(95));
+    /// pack.set_cell_soh(2, Fixed::from_num(88));
+    ///
+    /// assert_eq!(pack.pack_soh(), Fixed::from_num(88));
+    /// assert_eq!(pack.most_degraded_cell(), 2);
+    /// ```
+    #[inline]
+    pub fn pack_soh(&self) -> Fixed {
+        self.cell_soh[self.most_degraded_cell()]
+    }
+
+    /// Index of the cell group with the lowest SOH (the first one on ties)
+    pub fn most_degraded_cell(&self) -> usize {
+        let mut index = 0;
+        for (i, &soh) in self.cell_soh.iter().enumerate() {
+            if soh < self.cell_soh[index] {
+                index = i;
+            }
+        }
+        index
+    }
+
+    /// Set the cell temperature (°C) used for compensated estimation
+    #[inline]
+    pub fn set_temperature(&mut self, temperature: Fixed) {
+        self.temperature = temperature;
+    }
+
+    /// Returns the cell temperature used for compensated estimation
+    #[inline]
+    pub const fn temperature(&self) -> Fixed {
+        self.temperature
+    }
+
+    /// Returns the per-cell estimator
+    #[inline]
+    pub const fn estimator(&self) -> &SocEstimator {
+        &self.estimator
+    }
+
+    /// Pack voltage divided evenly across the series cells
+    #[inline]
+    fn cell_voltage(pack_voltage: Fixed) -> Fixed {
+        Fixed::from_bits((pack_voltage.to_bits() as i64 / CELLS as i64) as i32)
+    }
+
+    /// SOC of a single cell with the estimator's temperature compensation
+    /// applied
+    ///
+    /// The estimator's aging compensation is skipped: per-cell SOH, applied
+    /// by the callers, replaces it.
+    #[inline]
+    fn cell_soc(&self, voltage: Fixed, temperature: Fixed) -> Result<Fixed, Error> {
+        self.estimator
+            .estimate_soc_temperature_compensated_fixed(voltage, temperature)
+    }
+}
+
+/// Pack-level view: voltages are whole-pack voltages
+impl<const CELLS: usize> SocEstimate for PackEstimator<CELLS> {
+    #[inline]
+    fn estimate_soc_fixed(&self, voltage: Fixed) -> Result<Fixed, Error> {
+        self.estimate_soc_pack_voltage(voltage)
+    }
+
+    fn voltage_range_fixed(&self) -> (Fixed, Fixed) {
+        let (min, max) = self.estimator.voltage_range_fixed();
+        let cells = Fixed::from_num(CELLS);
+        (min.saturating_mul(cells), max.saturating_mul(cells))
+    }
+}
+
+/// Infers the series cell count from a measured pack voltage
+///
+/// Returns the smallest count between 1 and `max_cells` for which the
+/// per-cell voltage is plausible for the estimator's chemistry. Unlike
+/// [`PackEstimator::validate_pack_voltage()`], the upper bound is only
+/// 2.5% above the full-charge voltage, since a looser bound would mistake
+/// e.g. a 6S pack at 3.7V per cell for a 5S pack. Like RC chargers, this
+/// prefers fewer, fuller cells when two counts fit, so detection is only
+/// reliable for packs that are not deeply discharged.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{detect_series_cells, BatteryChemistry, Fixed, SocEstimator};
+///
+/// let lipo = SocEstimator::new(BatteryChemistry::LiPo);
+///
+/// assert_eq!(detect_series_cells(&lipo, Fixed::from_num(3.9), 6), Some(1));
+/// assert_eq!(detect_series_cells(&lipo, Fixed::from_num(11.4), 6), Some(3));
+/// assert_eq!(detect_series_cells(&lipo, Fixed::from_num(22.2), 6), Some(6));
+/// // Too high for 6S
+/// assert_eq!(detect_series_cells(&lipo, Fixed::from_num(30.0), 6), None);
+/// ```
+pub fn detect_series_cells(
+    estimator: &SocEstimator,
+    pack_voltage: Fixed,
+    max_cells: u8,
+) -> Option<u8> {
+    (1..=max_cells).find(|&cells| {
+        let cell_voltage = Fixed::from_bits((pack_voltage.to_bits() as i64 / cells as i64) as i32);
+        let (min, max) = estimator.voltage_range_fixed();
+        let cell_voltage = estimator.config().scale_voltage(cell_voltage);
+        cell_voltage >= min - min / Fixed::from_num(5)
+            && cell_voltage <= max.saturating_add(max / Fixed::from_num(40))
+    })
+}
+
+/// Returns `true` if a measured cell voltage lies between 80% of the
+/// curve's lowest and 110% of its highest voltage
+fn is_plausible_cell_voltage(estimator: &SocEstimator, cell_voltage: Fixed) -> bool {
+    let (min, max) = estimator.voltage_range_fixed();
+    let cell_voltage = estimator.config().scale_voltage(cell_voltage);
+
+    let lower = min - min / Fixed::from_num(5);
+    let upper = max.saturating_add(max / Fixed::from_num(10));
+    cell_voltage >= lower && cell_voltage <= upper
+}
+
+/// `percent` % of `value`, computed in 64 bits so that e.g. 60% of
+/// 5000 mAh is exactly 3000 mAh
+#[inline]
+fn percent_of(value: Fixed, percent: Fixed) -> Fixed {
+    let bits = value.to_bits() as i64 * percent.to_bits() as i64 / SOC_FULL.to_bits() as i64;
+    Fixed::from_bits(bits.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BatteryChemistry;
+
+    fn lipo_pack<const CELLS: usize>() -> PackEstimator<CELLS> {
+        PackEstimator::new(SocEstimator::new(BatteryChemistry::LiPo))
+    }
+
+    #[test]
+    fn test_pack_voltage_matches_single_cell() {
+        let single = SocEstimator::new(BatteryChemistry::LiPo);
+        let pack = lipo_pack::<4>();
+
+        assert_eq!(pack.cells(), 4);
+        assert_eq!(
+            pack.estimate_soc_pack_voltage(Fixed::from_num(15.5))
+                .unwrap(),
+            single.estimate_soc_fixed(Fixed::from_num(3.875)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_pack_cells_average_and_extremes() {
+        let pack = lipo_pack::<2>();
+        let estimate = pack
+            .estimate_soc_cells(&[Fixed::from_num(3.75), Fixed::from_num(4.25)])
+            .unwrap();
+
+        // 60% and 100%
+        assert_eq!(estimate.soc, Fixed::from_num(80));
+        assert_eq!(estimate.min_cell_voltage, Fixed::from_num(3.75));
+        assert_eq!(estimate.max_cell_voltage, Fixed::from_num(4.25));
+    }
+
+    #[test]
+    fn test_pack_temperature() {
+        let estimator = SocEstimator::with_temperature_compensation(
+            BatteryChemistry::LiPo,
+            Fixed::from_num(25),
+            Fixed::from_num(0.005),
+        );
+        let mut pack = PackEstimator::<3>::new(estimator);
+        assert_eq!(pack.temperature(), Fixed::from_num(25));
+
+        let warm = pack
+            .estimate_soc_pack_voltage(Fixed::from_num(11.25))
+            .unwrap();
+        pack.set_temperature(Fixed::from_num(0));
+        let cold = pack
+            .estimate_soc_pack_voltage(Fixed::from_num(11.25))
+            .unwrap();
+        assert!(cold < warm);
+    }
+
+    #[test]
+    fn test_pack_parallel_capacity() {
+        let config = PackConfig::default()
+            .with_cell_capacity_mah(Fixed::from_num(2000))
+            .with_parallel(3);
+        let pack =
+            PackEstimator::<4>::with_config(SocEstimator::new(BatteryChemistry::LiPo), config);
+
+        assert_eq!(
+            pack.config().topology.pack_capacity_mah(),
+            Fixed::from_num(6000)
+        );
+        assert_eq!(
+            pack.remaining_mah(Fixed::from_num(50)),
+            Some(Fixed::from_num(3000))
+        );
+        assert_eq!(
+            pack.remaining_mah(Fixed::from_num(150)),
+            Some(Fixed::from_num(6000))
+        );
+
+        // 3000 mAh at 1500 mA discharge
+        assert_eq!(
+            pack.time_to_empty_hours(Fixed::from_num(50), Fixed::from_num(-1500)),
+            Some(Fixed::from_num(2))
+        );
+        assert_eq!(
+            pack.time_to_empty_hours(Fixed::from_num(50), Fixed::ZERO),
+            None
+        );
+        assert_eq!(
+            pack.time_to_empty_hours(Fixed::from_num(50), Fixed::from_num(500)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_pack_capacity_disabled() {
+        let mut pack = lipo_pack::<2>();
+        assert_eq!(pack.remaining_mah(Fixed::from_num(50)), None);
+        assert_eq!(
+            pack.time_to_empty_hours(Fixed::from_num(50), Fixed::from_num(-100)),
+            None
+        );
+
+        // Zero parallel count is treated as a single cell per group
+        pack.update_config(
+            PackConfig::default()
+                .with_cell_capacity_mah(Fixed::from_num(1000))
+                .with_parallel(0),
+        );
+        assert_eq!(
+            pack.config().topology.pack_capacity_mah(),
+            Fixed::from_num(1000)
+        );
+    }
+
+    #[test]
+    fn test_pack_cell_soc_and_imbalance() {
+        let mut pack = lipo_pack::<3>();
+        let cells = [
+            Fixed::from_num(3.75),
+            Fixed::from_num(3.78),
+            Fixed::from_num(3.875),
+        ];
+
+        let estimate = pack.estimate_soc_cells(&cells).unwrap();
+        assert_eq!(estimate.cell_soc[0], Fixed::from_num(60));
+        assert_eq!(estimate.cell_soc[2], Fixed::from_num(81.25));
+        assert_eq!(estimate.soc_spread, Fixed::from_num(21.25));
+        assert_eq!(estimate.voltage_spread(), Fixed::from_num(0.125));
+        assert!(estimate.imbalanced);
+
+        pack.update_config(PackConfig::default().with_imbalance_threshold(Fixed::from_num(0.2)));
+        assert!(!pack.estimate_soc_cells(&cells).unwrap().imbalanced);
+
+        pack.update_config(PackConfig::default().with_imbalance_threshold(Fixed::ZERO));
+        assert!(!pack.estimate_soc_cells(&cells).unwrap().imbalanced);
+    }
+
+    #[test]
+    fn test_pack_extreme_spread_saturates() {
+        let pack = lipo_pack::<4>();
+        let cells = [Fixed::MAX, Fixed::MIN, Fixed::ZERO, Fixed::from_num(3.7)];
+
+        let estimate = pack.estimate_soc_cells(&cells).unwrap();
+        assert_eq!(estimate.voltage_spread(), Fixed::MAX);
+        assert_eq!(estimate.soc_spread, Fixed::from_num(100));
+        assert!(estimate.imbalanced);
+
+        let report = pack.report(&cells).unwrap();
+        assert_eq!(report.imbalance, Fixed::MAX);
+        assert!(report.has_flag(PackReport::<4>::FLAG_IMBALANCED));
+    }
+
+    #[test]
+    fn test_pack_balanced_cells() {
+        let pack = lipo_pack::<4>();
+        let estimate = pack
+            .estimate_soc_cells(&[Fixed::from_num(3.75); 4])
+            .unwrap();
+        assert_eq!(estimate.soc_spread, Fixed::ZERO);
+        assert!(!estimate.imbalanced);
+    }
+
+    #[test]
+    fn test_pack_soc_policies() {
+        let cells = [Fixed::from_num(3.75), Fixed::from_num(4.25)];
+        let mut pack = lipo_pack::<2>();
+
+        // 60% and 100%
+        assert_eq!(
+            pack.estimate_soc_cells(&cells).unwrap().soc,
+            Fixed::from_num(80)
+        );
+
+        pack.update_config(PackConfig::default().with_soc_policy(PackSocPolicy::WeakestCell));
+        assert_eq!(
+            pack.estimate_soc_cells(&cells).unwrap().soc,
+            Fixed::from_num(60)
+        );
+
+        pack.update_config(
+            PackConfig::default().with_soc_policy(PackSocPolicy::Weighted(Fixed::from_num(0.5))),
+        );
+        assert_eq!(
+            pack.estimate_soc_cells(&cells).unwrap().soc,
+            Fixed::from_num(70)
+        );
+
+        // Out-of-range weights are clamped
+        pack.update_config(
+            PackConfig::default().with_soc_policy(PackSocPolicy::Weighted(Fixed::from_num(2))),
+        );
+        assert_eq!(
+            pack.estimate_soc_cells(&cells).unwrap().soc,
+            Fixed::from_num(60)
+        );
+    }
+
+    #[test]
+    fn test_pack_balancing_recommendation() {
+        let config = PackConfig::default()
+            .with_cell_capacity_mah(Fixed::from_num(1000))
+            .with_parallel(2);
+        let pack =
+            PackEstimator::<3>::with_config(SocEstimator::new(BatteryChemistry::LiPo), config);
+
+        // 60%, 100%, 81.25%
+        let estimate = pack
+            .estimate_soc_cells(&[
+                Fixed::from_num(3.75),
+                Fixed::from_num(4.25),
+                Fixed::from_num(3.875),
+            ])
+            .unwrap();
+        let bleed = pack.balancing_recommendation(&estimate).unwrap();
+        assert_eq!(
+            bleed,
+            [Fixed::ZERO, Fixed::from_num(800), Fixed::from_num(425)]
+        );
+    }
+
+    #[test]
+    fn test_pack_balancing_not_needed() {
+        let config = PackConfig::default().with_cell_capacity_mah(Fixed::from_num(1000));
+        let pack =
+            PackEstimator::<2>::with_config(SocEstimator::new(BatteryChemistry::LiPo), config);
+
+        // 10 mV apart is within the default 50 mV threshold
+        let estimate = pack
+            .estimate_soc_cells(&[Fixed::from_num(3.75), Fixed::from_num(3.76)])
+            .unwrap();
+        assert_eq!(
+            pack.balancing_recommendation(&estimate),
+            Some([Fixed::ZERO; 2])
+        );
+
+        assert_eq!(lipo_pack::<2>().balancing_recommendation(&estimate), None);
+    }
+
+    #[test]
+    fn test_pack_cell_soh() {
+        let mut pack = lipo_pack::<2>();
+        assert_eq!(pack.pack_soh(), Fixed::from_num(100));
+        assert_eq!(pack.most_degraded_cell(), 0);
+
+        pack.set_cell_soh(1, Fixed::from_num(80));
+        pack.set_cell_soh(5, Fixed::from_num(10)); // ignored
+        assert_eq!(
+            pack.cell_soh(),
+            &[Fixed::from_num(100), Fixed::from_num(80)]
+        );
+        assert_eq!(pack.most_degraded_cell(), 1);
+        assert_eq!(pack.pack_soh(), Fixed::from_num(80));
+
+        // 100% and 100% -> cell 1 holds only 80% of nominal
+        let estimate = pack
+            .estimate_soc_cells(&[Fixed::from_num(4.25); 2])
+            .unwrap();
+        assert_eq!(
+            estimate.cell_soc,
+            [Fixed::from_num(100), Fixed::from_num(80)]
+        );
+        assert_eq!(
+            pack.estimate_soc_pack_voltage(Fixed::from_num(8.5))
+                .unwrap(),
+            Fixed::from_num(80)
+        );
+    }
+
+    #[test]
+    fn test_pack_cell_soh_clamped() {
+        let mut pack = lipo_pack::<2>();
+        pack.set_cell_soh(0, Fixed::from_num(120));
+        pack.set_cell_soh(1, Fixed::from_num(-5));
+        assert_eq!(pack.cell_soh(), &[Fixed::from_num(100), Fixed::ZERO]);
+    }
+
+    #[test]
+    fn test_pack_cell_soh_replaces_estimator_aging() {
+        // A 5-year-old estimator derates by 10%, but the cell's own SOH
+        // is what counts, so SOH is not applied on top of the aging
+        let estimator = SocEstimator::with_aging_compensation(
+            BatteryChemistry::LiPo,
+            Fixed::from_num(5),
+            Fixed::from_num(0.02),
+        );
+        let derated = estimator
+            .estimate_soc_compensated_fixed(Fixed::from_num(4.25), Fixed::from_num(25))
+            .unwrap();
+        assert!((derated - Fixed::from_num(90)).abs() < Fixed::from_num(0.01));
+
+        let mut pack = PackEstimator::<2>::new(estimator);
+        pack.set_cell_soh(1, Fixed::from_num(80));
+        let estimate = pack
+            .estimate_soc_cells(&[Fixed::from_num(4.25); 2])
+            .unwrap();
+        assert_eq!(
+            estimate.cell_soc,
+            [Fixed::from_num(100), Fixed::from_num(80)]
+        );
+    }
+
+    #[test]
+    fn test_pack_voltage_validation() {
+        let pack = lipo_pack::<3>();
+
+        // LiPo curve spans 3.2-4.2V per cell
+        assert_eq!(pack.validate_pack_voltage(Fixed::from_num(9.6)), Ok(()));
+        assert_eq!(pack.validate_pack_voltage(Fixed::from_num(7.8)), Ok(()));
+        assert_eq!(pack.validate_pack_voltage(Fixed::from_num(13.8)), Ok(()));
+        assert_eq!(
+            pack.validate_pack_voltage(Fixed::from_num(7.5)),
+            Err(Error::PackMismatch)
+        );
+        assert_eq!(
+            pack.estimate_soc_pack_voltage(Fixed::from_num(14.0)),
+            Err(Error::PackMismatch)
+        );
+        assert_eq!(
+            pack.estimate_soc_pack_voltage(Fixed::ZERO),
+            Err(Error::PackMismatch)
+        );
+    }
+
+    #[test]
+    fn test_pack_topology() {
+        let topology = PackTopology::new(2, 0, Fixed::from_num(1500));
+        assert_eq!(topology.cell_count(), 2);
+        assert_eq!(topology.pack_capacity_mah(), Fixed::from_num(1500));
+
+        // The series count always follows CELLS
+        let config =
+            PackConfig::default().with_topology(PackTopology::new(7, 3, Fixed::from_num(2000)));
+        let mut pack =
+            PackEstimator::<4>::with_config(SocEstimator::new(BatteryChemistry::LiPo), config);
+        assert_eq!(
+            pack.topology(),
+            &PackTopology::new(4, 3, Fixed::from_num(2000))
+        );
+        assert_eq!(pack.topology().cell_count(), 12);
+
+        pack.update_config(config);
+        assert_eq!(pack.topology().series, 4);
+    }
+
+    #[test]
+    fn test_pack_per_cell_temperatures() {
+        let estimator = SocEstimator::with_temperature_compensation(
+            BatteryChemistry::LiPo,
+            Fixed::from_num(25),
+            Fixed::from_num(0.005),
+        );
+        let mut pack = PackEstimator::<3>::new(estimator);
+        let cells = [Fixed::from_num(3.75); 3];
+        let temperatures = [
+            Fixed::from_num(15),
+            Fixed::from_num(25),
+            Fixed::from_num(15),
+        ];
+
+        let estimate = pack
+            .estimate_soc_cells_with_temperatures(&cells, &temperatures)
+            .unwrap();
+        assert_eq!(estimate.cell_soc[0], estimate.cell_soc[2]);
+        assert!(estimate.cell_soc[0] < estimate.cell_soc[1]);
+
+        // Uniform temperatures match the shared pack temperature
+        pack.set_temperature(Fixed::from_num(15));
+        assert_eq!(
+            pack.estimate_soc_cells(&cells).unwrap(),
+            pack.estimate_soc_cells_with_temperatures(&cells, &[Fixed::from_num(15); 3])
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_pack_energy() {
+        use crate::{Curve, CurvePoint};
+        static LINEAR: Curve =
+            Curve::new(&[CurvePoint::new(3.0, 0.0), CurvePoint::new(4.0, 100.0)]);
+
+        let config = PackConfig::default()
+            .with_cell_capacity_mah(Fixed::from_num(2000))
+            .with_parallel(2);
+        let pack =
+            PackEstimator::<2>::with_config(SocEstimator::with_custom_curve(&LINEAR), config);
+
+        // 2S * 4 Ah * 3.5V mean
+        assert_eq!(
+            pack.remaining_energy_wh(Fixed::from_num(100)),
+            Some(Fixed::from_num(28))
+        );
+        // 2S * 4 Ah * 0.5 * 3.25V mean
+        assert_eq!(
+            pack.remaining_energy_wh(Fixed::from_num(50)),
+            Some(Fixed::from_num(13))
+        );
+        assert_eq!(pack.remaining_energy_wh(Fixed::ZERO), Some(Fixed::ZERO));
+
+        // Cells at 100% and 50%
+        let estimate = pack
+            .estimate_soc_cells(&[Fixed::from_num(4.0), Fixed::from_num(3.5)])
+            .unwrap();
+        assert_eq!(
+            pack.remaining_energy_wh_cells(&estimate),
+            Some(Fixed::from_num(20.5))
+        );
+
+        assert_eq!(
+            lipo_pack::<2>().remaining_energy_wh(Fixed::from_num(50)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_detect_series_cells() {
+        let lipo = SocEstimator::new(BatteryChemistry::LiPo);
+        for cells in 1..=6u8 {
+            for cell_voltage in [3.7, 3.9, 4.2] {
+                let pack_voltage = Fixed::from_num(cell_voltage) * Fixed::from_num(cells);
+                assert_eq!(
+                    detect_series_cells(&lipo, pack_voltage, 6),
+                    Some(cells),
+                    "{}S at {}V",
+                    cells,
+                    cell_voltage
+                );
+            }
+        }
+
+        assert_eq!(detect_series_cells(&lipo, Fixed::from_num(1.0), 6), None);
+        assert_eq!(detect_series_cells(&lipo, Fixed::from_num(16.8), 3), None);
+        assert_eq!(detect_series_cells(&lipo, Fixed::from_num(3.7), 0), None);
+
+        let lifepo4 = SocEstimator::new(BatteryChemistry::LiFePO4);
+        assert_eq!(
+            detect_series_cells(&lifepo4, Fixed::from_num(13.2), 8),
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn test_pack_config_bytes_roundtrip() {
+        let configs = [
+            PackConfig::default(),
+            PackConfig::default()
+                .with_topology(PackTopology::new(6, 3, Fixed::from_num(2500)))
+                .with_soc_policy(PackSocPolicy::WeakestCell),
+            PackConfig::default()
+                .with_imbalance_threshold(Fixed::from_num(0.03))
+                .with_soc_policy(PackSocPolicy::Weighted(Fixed::from_num(0.75))),
+        ];
+        for config in configs {
+            assert_eq!(PackConfig::from_bytes(&config.to_bytes()), Ok(config));
+        }
+    }
+
+    #[test]
+    fn test_pack_config_bytes_rejects_corruption() {
+        let blob = PackConfig::default().to_bytes();
+
+        assert_eq!(
+            PackConfig::from_bytes(&[0xFF; PackConfig::SERIALIZED_SIZE]),
+            Err(Error::InvalidState)
+        );
+
+        let mut corrupted = blob;
+        corrupted[4] ^= 0x01;
+        assert_eq!(PackConfig::from_bytes(&corrupted), Err(Error::InvalidState));
+
+        // Unknown policy with a valid checksum
+        let mut unknown = blob;
+        unknown[3] = 9;
+        let last = PackConfig::SERIALIZED_SIZE - 1;
+        unknown[last] = crc8(&unknown[..last]);
+        assert_eq!(PackConfig::from_bytes(&unknown), Err(Error::InvalidState));
+    }
+
+    #[test]
+    fn test_pack_cycle_aging() {
+        let config = PackConfig::default()
+            .with_cell_capacity_mah(Fixed::from_num(1000))
+            .with_cycle_fade(Fixed::from_num(0.5));
+        let mut pack =
+            PackEstimator::<2>::with_config(SocEstimator::new(BatteryChemistry::LiPo), config);
+        let nominal = [Fixed::from_num(25); 2];
+
+        // Ten full cycles: 20 Ah of throughput at 2 A, charge and discharge
+        for i in 0..20 {
+            let current = if i % 2 == 0 { -2000 } else { 2000 };
+            for _ in 0..30 {
+                pack.record_usage(Fixed::from_num(current), &nominal, Fixed::from_num(60));
+            }
+        }
+
+        assert_eq!(pack.cell_usage()[0].throughput_mah(), 20_000);
+        assert_eq!(pack.cell_usage()[0].heat_exposure_degree_hours(), 0);
+        assert_eq!(pack.cell_soh(), &[Fixed::from_num(95); 2]);
+
+        // Baseline SOH is reduced by the recorded usage
+        pack.set_cell_soh(1, Fixed::from_num(90));
+        assert_eq!(pack.cell_soh()[1], Fixed::from_num(85));
+    }
+
+    #[test]
+    fn test_pack_usage_small_samples() {
+        let mut pack = lipo_pack::<2>();
+        let nominal = [Fixed::from_num(25); 2];
+
+        // 1.5625 mA·s per sample must not be truncated to 1
+        let dt = Fixed::from_num(1) / 64;
+        for _ in 0..2304 {
+            pack.record_usage(Fixed::from_num(100), &nominal, dt);
+        }
+        assert_eq!(pack.cell_usage()[0].throughput_mah(), 1);
+
+        // The most negative current does not overflow when negated
+        pack.record_usage(Fixed::MIN, &[Fixed::MAX, Fixed::MIN], Fixed::ONE);
+        assert_eq!(pack.cell_usage()[0].throughput_mah(), 10);
+    }
+
+    #[test]
+    fn test_pack_thermal_aging() {
+        let config = PackConfig::default().with_thermal_fade(Fixed::from_num(1));
+        let mut pack =
+            PackEstimator::<3>::with_config(SocEstimator::new(BatteryChemistry::LiPo), config);
+        let temperatures = [
+            Fixed::from_num(20),
+            Fixed::from_num(35),
+            Fixed::from_num(45),
+        ];
+
+        // 100 hours; no capacity configured, so no cycle aging
+        for _ in 0..100 {
+            pack.record_usage(Fixed::from_num(1000), &temperatures, Fixed::from_num(3600));
+        }
+
+        let usage = pack.cell_usage();
+        assert_eq!(usage[0].heat_exposure_degree_hours(), 0);
+        assert_eq!(usage[1].heat_exposure_degree_hours(), 1000);
+        assert_eq!(usage[2].heat_exposure_degree_hours(), 2000);
+        assert_eq!(
+            pack.cell_soh(),
+            &[
+                Fixed::from_num(100),
+                Fixed::from_num(99),
+                Fixed::from_num(98)
+            ]
+        );
+        assert_eq!(pack.most_degraded_cell(), 2);
+    }
+
+    #[test]
+    fn test_pack_report() {
+        let mut pack = lipo_pack::<3>();
+        pack.set_cell_soh(1, Fixed::from_num(90));
+        pack.set_temperature(Fixed::from_num(30));
+
+        let report = pack
+            .report(&[
+                Fixed::from_num(3.75),
+                Fixed::from_num(3.76),
+                Fixed::from_num(3.2),
+            ])
+            .unwrap();
+        assert_eq!(report.cell_soc[2], Fixed::ZERO);
+        assert_eq!(report.min_cell_voltage, Fixed::from_num(3.2));
+        assert_eq!(report.temperature, Fixed::from_num(30));
+        assert_eq!(report.soh, Fixed::from_num(90));
+        assert_eq!(
+            report.flags,
+            PackReport::<3>::FLAG_IMBALANCED | PackReport::<3>::FLAG_CELL_EMPTY
+        );
+        assert!(!report.has_flag(PackReport::<3>::FLAG_CELL_FULL));
+
+        let balanced = pack.report(&[Fixed::from_num(3.75); 3]).unwrap();
+        assert_eq!(balanced.flags, 0);
+        assert_eq!(balanced.imbalance, Fixed::ZERO);
+    }
+}
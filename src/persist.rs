@@ -0,0 +1,80 @@
+//! Byte-level helpers for persisted blobs
+//!
+//! Tracker state, pack configuration and calibration all serialize to small
+//! versioned blobs: fixed-point fields as little-endian bits and a trailing
+//! CRC-8 over everything before it.
+
+use crate::Fixed;
+
+/// Writes a fixed-point value as little-endian bits at `offset`
+#[inline]
+pub(crate) fn put_fixed(blob: &mut [u8], offset: usize, value: Fixed) {
+    blob[offset..offset + 4].copy_from_slice(&value.to_bits().to_le_bytes());
+}
+
+/// Reads a fixed-point value from little-endian bits at `offset`
+#[inline]
+pub(crate) fn get_fixed(blob: &[u8], offset: usize) -> Fixed {
+    let mut bytes = [0u8; 4];
+    bytes.copy_from_slice(&blob[offset..offset + 4]);
+    Fixed::from_bits(i32::from_le_bytes(bytes))
+}
+
+/// Writes a 64-bit value as little-endian bytes at `offset`
+#[inline]
+pub(crate) fn put_i64(blob: &mut [u8], offset: usize, value: i64) {
+    blob[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+}
+
+/// Reads a 64-bit value from little-endian bytes at `offset`
+#[inline]
+pub(crate) fn get_i64(blob: &[u8], offset: usize) -> i64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&blob[offset..offset + 8]);
+    i64::from_le_bytes(bytes)
+}
+
+/// CRC-8 with polynomial 0x07 (SMBus PEC), initial value 0
+pub(crate) fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    for &byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc8_known_value() {
+        // CRC-8/SMBUS check value
+        assert_eq!(crc8(b"123456789"), 0xF4);
+    }
+
+    #[test]
+    fn test_fixed_round_trip() {
+        let mut blob = [0u8; 6];
+        put_fixed(&mut blob, 1, Fixed::from_num(-3.25));
+        assert_eq!(blob[0], 0);
+        assert_eq!(blob[5], 0);
+        assert_eq!(get_fixed(&blob, 1), Fixed::from_num(-3.25));
+    }
+
+    #[test]
+    fn test_i64_round_trip() {
+        let mut blob = [0u8; 10];
+        put_i64(&mut blob, 1, -(1 << 40) - 7);
+        assert_eq!(blob[0], 0);
+        assert_eq!(blob[9], 0);
+        assert_eq!(get_i64(&blob, 1), -(1 << 40) - 7);
+    }
+}
@@ -0,0 +1,407 @@
+//! Python bindings
+//!
+//! Exposes [`SocEstimator`], [`Curve`] and [`DischargeSimulator`] to Python
+//! through `pyo3`, so field logs can be replayed, synthetic traces generated
+//! and curves or compensation tuned in notebooks with the same code that
+//! runs on the device. Build the extension module with
+//! [maturin](https://www.maturin.rs) and the `python` feature:
+//!
+//! ```text
+//! maturin develop --features python,pyo3/extension-module
+//! ```
+//!
+//! ```python
+//! import battery_estimator as be
+//!
+//! est = be.SocEstimator("LiPo")
+//! est.enable_temperature_compensation(25.0, 0.005)
+//! socs = est.estimate_soc_many([3.71, 3.70, 3.69], temperature=10.0)
+//!
+//! sim = be.DischargeSimulator("LiPo", load_ma=500.0, capacity_mah=2000.0)
+//! for time_ms, voltage, current_ma, soc in sim:
+//!     ...
+//! ```
+//!
+//! Values cross the boundary as `float`. Estimation uses the double-precision
+//! API; errors are raised as `ValueError`.
+
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::{
+    BatteryChemistry, Curve, CurvePoint, DischargeSimulator, Error, Fixed, LoadProfile, SimConfig,
+    SocEstimator,
+};
+
+/// Converts a crate error into a Python `ValueError`
+fn to_py_err(error: Error) -> PyErr {
+    PyValueError::new_err(error.to_string())
+}
+
+/// Converts a Python float into fixed-point, rejecting values out of range
+fn to_fixed(value: f64) -> Result<Fixed, Error> {
+    Fixed::checked_from_num(value).ok_or(Error::InvalidConfig)
+}
+
+/// Voltage-to-SOC curve
+///
+/// Constructed from `(voltage, soc)` tuples in volts and percent, ordered
+/// by increasing voltage.
+#[pyclass(name = "Curve", module = "battery_estimator", frozen, from_py_object)]
+#[derive(Clone, Copy)]
+struct PyCurve {
+    curve: Curve,
+}
+
+#[pymethods]
+impl PyCurve {
+    #[new]
+    fn new(points: Vec<(f32, f32)>) -> PyResult<Self> {
+        if points.len() > crate::MAX_CURVE_POINTS {
+            return Err(to_py_err(Error::InvalidCurve));
+        }
+        let points: Vec<CurvePoint> = points.into_iter().map(CurvePoint::from).collect();
+        let curve = Curve::new(&points);
+        curve.validate_sorted().map_err(to_py_err)?;
+        Ok(Self { curve })
+    }
+
+    /// Built-in curve of a chemistry ("LiPo", "LiFePO4", "LiIon",
+    /// "Lipo410Full340Cutoff")
+    #[staticmethod]
+    fn for_chemistry(chemistry: &str) -> PyResult<Self> {
+        let chemistry = chemistry.parse::<BatteryChemistry>().map_err(to_py_err)?;
+        Ok(Self {
+            curve: *Curve::for_chemistry(chemistry),
+        })
+    }
+
+    /// Points as `(voltage, soc)` tuples
+    fn points(&self) -> Vec<(f32, f32)> {
+        self.curve
+            .points()
+            .iter()
+            .map(|point| (point.voltage(), point.soc()))
+            .collect()
+    }
+
+    /// `(min, max)` voltage covered by the curve
+    fn voltage_range(&self) -> (f32, f32) {
+        self.curve.voltage_range()
+    }
+
+    /// SOC in percent at a voltage
+    fn voltage_to_soc(&self, voltage: f64) -> PyResult<f64> {
+        self.curve.voltage_to_soc_f64(voltage).map_err(to_py_err)
+    }
+
+    fn __len__(&self) -> usize {
+        self.curve.len()
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.curve == other.curve
+    }
+}
+
+/// Curve of a `chemistry` argument: a chemistry name or a `Curve`
+fn extract_curve(chemistry: &Bound<'_, PyAny>) -> PyResult<Curve> {
+    if let Ok(curve) = chemistry.extract::<PyCurve>() {
+        return Ok(curve.curve);
+    }
+    let name: &str = chemistry.extract()?;
+    let chemistry = name.parse::<BatteryChemistry>().map_err(to_py_err)?;
+    Ok(*Curve::for_chemistry(chemistry))
+}
+
+/// Voltage-based SOC estimator
+///
+/// Created from a chemistry name or a `Curve`.
+#[pyclass(
+    name = "SocEstimator",
+    module = "battery_estimator",
+    skip_from_py_object
+)]
+#[derive(Clone, Copy)]
+struct PySocEstimator {
+    /// The estimation curve, owned so custom curves need no `'static` copy
+    curve: Curve,
+    /// Configuration only; lookups use `curve`
+    estimator: SocEstimator,
+}
+
+#[pymethods]
+impl PySocEstimator {
+    /// `chemistry` is a chemistry name or a `Curve`
+    #[new]
+    fn new(chemistry: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if let Ok(curve) = chemistry.extract::<PyCurve>() {
+            // Custom-curve defaults; the built-in curve is never looked up
+            let placeholder = Curve::for_chemistry(BatteryChemistry::LiPo);
+            return Ok(Self {
+                curve: curve.curve,
+                estimator: SocEstimator::with_custom_curve(placeholder),
+            });
+        }
+        let name: &str = chemistry.extract()?;
+        let estimator = SocEstimator::new(name.parse::<BatteryChemistry>().map_err(to_py_err)?);
+        Ok(Self {
+            curve: *estimator.curve(),
+            estimator,
+        })
+    }
+
+    /// The curve used for estimation
+    fn curve(&self) -> PyCurve {
+        PyCurve { curve: self.curve }
+    }
+
+    /// `(min, max)` voltage covered by the curve
+    fn voltage_range(&self) -> (f32, f32) {
+        self.curve.voltage_range()
+    }
+
+    /// Enable temperature compensation
+    fn enable_temperature_compensation(
+        &mut self,
+        nominal_temp: f64,
+        coefficient: f64,
+    ) -> PyResult<()> {
+        let nominal_temp = to_fixed(nominal_temp).map_err(to_py_err)?;
+        let coefficient = to_fixed(coefficient).map_err(to_py_err)?;
+        self.estimator
+            .enable_temperature_compensation(nominal_temp, coefficient);
+        self.estimator.config().validate().map_err(to_py_err)
+    }
+
+    /// Enable aging compensation
+    fn enable_aging_compensation(&mut self, age_years: f64, aging_factor: f64) -> PyResult<()> {
+        let age_years = to_fixed(age_years).map_err(to_py_err)?;
+        let aging_factor = to_fixed(aging_factor).map_err(to_py_err)?;
+        self.estimator
+            .enable_aging_compensation(age_years, aging_factor);
+        self.estimator.config().validate().map_err(to_py_err)
+    }
+
+    /// Disable temperature and aging compensation
+    fn disable_all_compensation(&mut self) {
+        self.estimator.disable_all_compensation();
+    }
+
+    /// SOC in percent at a voltage, without compensation
+    fn estimate_soc(&self, voltage: f64) -> PyResult<f64> {
+        self.estimator
+            .estimate_soc_f64_on(&self.curve, voltage)
+            .map_err(to_py_err)
+    }
+
+    /// SOC in percent using the configured compensation
+    fn estimate_soc_compensated(&self, voltage: f64, temperature: f64) -> PyResult<f64> {
+        self.estimator
+            .estimate_soc_compensated_f64_on(&self.curve, voltage, temperature)
+            .map_err(to_py_err)
+    }
+
+    /// SOC of every voltage in a log, using the configured compensation
+    #[pyo3(signature = (voltages, temperature = None))]
+    fn estimate_soc_many(
+        &self,
+        voltages: Vec<f64>,
+        temperature: Option<f64>,
+    ) -> PyResult<Vec<f64>> {
+        let temperature =
+            temperature.unwrap_or_else(|| self.estimator.config().nominal_temperature.to_num());
+        voltages
+            .into_iter()
+            .map(|voltage| self.estimate_soc_compensated(voltage, temperature))
+            .collect()
+    }
+}
+
+/// Load of a simulation: `load_ma` throughout, or `load_ma` while idle and
+/// `active_ma` for the first `active_ms` of every `period_ms`
+fn load_profile(
+    load_ma: f64,
+    active_ma: Option<f64>,
+    period_ms: u32,
+    active_ms: u32,
+) -> Result<LoadProfile, Error> {
+    let load_ma = to_fixed(load_ma)?;
+    Ok(match active_ma {
+        Some(active_ma) => LoadProfile::Pulsed {
+            idle_ma: load_ma,
+            active_ma: to_fixed(active_ma)?,
+            period_ms,
+            active_ms,
+        },
+        None => LoadProfile::Constant(load_ma),
+    })
+}
+
+/// Synthetic voltage trace of a cell under load
+///
+/// Iterating yields `(time_ms, voltage, current_ma, soc)` tuples until the
+/// cell is empty; a charging load runs forever, so bound it with
+/// `itertools.islice`. Unset parameters keep the `SimConfig` defaults.
+#[pyclass(
+    name = "DischargeSimulator",
+    module = "battery_estimator",
+    skip_from_py_object
+)]
+#[derive(Clone)]
+struct PyDischargeSimulator {
+    simulator: DischargeSimulator,
+}
+
+#[pymethods]
+impl PyDischargeSimulator {
+    /// `chemistry` is a chemistry name or a `Curve`; `load_ma` is positive
+    /// when discharging
+    #[new]
+    #[pyo3(signature = (
+        chemistry,
+        load_ma,
+        *,
+        active_ma = None,
+        period_ms = 1000,
+        active_ms = 0,
+        capacity_mah = None,
+        initial_soc = None,
+        temperature = None,
+        internal_resistance = None,
+        noise = None,
+        interval_ms = None,
+        seed = None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        chemistry: &Bound<'_, PyAny>,
+        load_ma: f64,
+        active_ma: Option<f64>,
+        period_ms: u32,
+        active_ms: u32,
+        capacity_mah: Option<f64>,
+        initial_soc: Option<f64>,
+        temperature: Option<f64>,
+        internal_resistance: Option<f64>,
+        noise: Option<f64>,
+        interval_ms: Option<u32>,
+        seed: Option<u32>,
+    ) -> PyResult<Self> {
+        let curve = extract_curve(chemistry)?;
+        let load = load_profile(load_ma, active_ma, period_ms, active_ms).map_err(to_py_err)?;
+
+        let mut config = SimConfig::default();
+        let fields = [
+            (capacity_mah, &mut config.capacity_mah),
+            (initial_soc, &mut config.initial_soc),
+            (temperature, &mut config.temperature),
+            (internal_resistance, &mut config.internal_resistance),
+            (noise, &mut config.noise),
+        ];
+        for (value, field) in fields {
+            if let Some(value) = value {
+                *field = to_fixed(value).map_err(to_py_err)?;
+            }
+        }
+        config.interval_ms = interval_ms.unwrap_or(config.interval_ms);
+        config.seed = seed.unwrap_or(config.seed);
+
+        let simulator =
+            DischargeSimulator::with_custom_curve(&curve, config, load).map_err(to_py_err)?;
+        Ok(Self { simulator })
+    }
+
+    /// True SOC in percent
+    fn soc(&self) -> f64 {
+        self.simulator.soc().to_num()
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> Option<(u64, f64, f64, f64)> {
+        self.simulator.next().map(|sample| {
+            (
+                sample.time_ms,
+                sample.voltage.to_num(),
+                sample.current_ma.to_num(),
+                sample.soc.to_num(),
+            )
+        })
+    }
+}
+
+/// The `battery_estimator` Python module
+#[pymodule]
+fn battery_estimator(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyCurve>()?;
+    module.add_class::<PySocEstimator>()?;
+    module.add_class::<PyDischargeSimulator>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimator_owns_custom_curve() {
+        let curve = Curve::new(&[CurvePoint::new(3.0, 0.0), CurvePoint::new(4.0, 100.0)]);
+        let estimator = PySocEstimator {
+            curve,
+            estimator: SocEstimator::with_custom_curve(Curve::for_chemistry(
+                BatteryChemistry::LiPo,
+            )),
+        };
+        assert_eq!(estimator.voltage_range(), (3.0, 4.0));
+        assert!((estimator.estimate_soc(3.5).unwrap() - 50.0).abs() < 1e-9);
+        assert!((estimator.estimate_soc_compensated(3.5, 25.0).unwrap() - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_load_profile() {
+        assert_eq!(
+            load_profile(250.0, None, 1000, 0),
+            Ok(LoadProfile::Constant(Fixed::from_num(250)))
+        );
+        assert_eq!(
+            load_profile(10.0, Some(500.0), 2000, 100),
+            Ok(LoadProfile::Pulsed {
+                idle_ma: Fixed::from_num(10),
+                active_ma: Fixed::from_num(500),
+                period_ms: 2000,
+                active_ms: 100,
+            })
+        );
+        assert_eq!(
+            load_profile(f64::INFINITY, None, 1000, 0),
+            Err(Error::InvalidConfig)
+        );
+    }
+
+    #[test]
+    fn test_simulator_iterates_samples() {
+        let curve = *Curve::for_chemistry(BatteryChemistry::LiPo);
+        let load = LoadProfile::Constant(Fixed::from_num(1000));
+        let config = SimConfig::default().with_interval_ms(60_000);
+        let mut simulator = PyDischargeSimulator {
+            simulator: DischargeSimulator::with_custom_curve(&curve, config, load).unwrap(),
+        };
+        assert_eq!(simulator.soc(), 100.0);
+        let full = Fixed::from_num(4.2).to_num::<f64>();
+        assert_eq!(simulator.__next__(), Some((0, full, -1000.0, 100.0)));
+        assert_eq!(core::iter::from_fn(|| simulator.__next__()).count(), 60);
+    }
+
+    #[test]
+    fn test_to_fixed() {
+        assert_eq!(to_fixed(25.0), Ok(Fixed::from_num(25)));
+        assert_eq!(to_fixed(f64::NAN), Err(Error::InvalidConfig));
+        assert_eq!(to_fixed(1e9), Err(Error::InvalidConfig));
+    }
+}
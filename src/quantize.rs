@@ -0,0 +1,551 @@
+//! Quantized SOC output for bar-style battery indicators
+//!
+//! Battery icons rarely show a continuous percentage. This module converts
+//! SOC values into a small number of discrete levels (bars or fixed steps)
+//! and optionally applies hysteresis so the displayed level does not
+//! oscillate when the SOC sits right on a step boundary. [`SocRounding`]
+//! rounds numeric SOC readouts to a chosen number of decimals.
+
+use crate::{Error, Fixed};
+
+/// 100% as a fixed-point constant
+const SOC_FULL: Fixed = Fixed::from_bits(100 << 16);
+
+/// Converts an SOC percentage into a number of bars (stateless)
+///
+/// The SOC range 0-100% is divided into `bars` equal steps and the result
+/// is rounded down, so a bar is only shown once its step is fully reached.
+/// Use [`SocQuantizer`] if the output needs hysteresis.
+///
+/// # Arguments
+///
+/// * `soc` - SOC percentage as fixed-point (clamped to 0-100)
+/// * `bars` - Total number of bars of the indicator
+///
+/// # Returns
+///
+/// Number of bars to display (0 to `bars`). Returns 0 if `bars` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{soc_bars, Fixed};
+///
+/// assert_eq!(soc_bars(Fixed::from_num(100.0), 4), 4);
+/// assert_eq!(soc_bars(Fixed::from_num(60.0), 4), 2);
+/// assert_eq!(soc_bars(Fixed::from_num(10.0), 4), 0);
+/// ```
+#[inline]
+pub fn soc_bars(soc: Fixed, bars: u8) -> u8 {
+    if bars == 0 {
+        return 0;
+    }
+    level_for(soc, bar_step(bars), bars)
+}
+
+/// Rounds an SOC percentage down to a multiple of `step` (stateless)
+///
+/// # Arguments
+///
+/// * `soc` - SOC percentage as fixed-point (clamped to 0-100)
+/// * `step` - Step size in percent (e.g., 5.0 for 5% steps)
+///
+/// # Returns
+///
+/// Quantized SOC percentage. A non-positive `step` returns the clamped SOC.
+///
+/// Steps such as 0.1% are not exact in fixed-point. Values within the
+/// accumulated rounding of the step (half an LSB per step) of a boundary
+/// count as reaching it, and the result is a multiple of the fixed-point
+/// `step`.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{soc_quantized, Fixed};
+///
+/// let soc = soc_quantized(Fixed::from_num(57.3), Fixed::from_num(5.0));
+/// assert_eq!(soc, Fixed::from_num(55.0));
+/// ```
+#[inline]
+pub fn soc_quantized(soc: Fixed, step: Fixed) -> Fixed {
+    let soc = soc.clamp(Fixed::ZERO, SOC_FULL);
+    if step <= Fixed::ZERO {
+        return soc;
+    }
+    let bits = floor_steps(soc, step) * step.to_bits() as i64;
+    Fixed::from_bits(bits.min(SOC_FULL.to_bits() as i64) as i32)
+}
+
+/// Stateful SOC quantizer with per-step hysteresis
+///
+/// Keeps track of the last reported level and only moves to a new level
+/// once the SOC has crossed the step boundary by more than the configured
+/// hysteresis. This prevents a 4-bar icon from flickering between 2 and 3
+/// bars when the SOC hovers around 50%.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{Fixed, SocQuantizer};
+///
+/// let mut bars = SocQuantizer::bars(4).with_hysteresis(Fixed::from_num(2.0));
+///
+/// assert_eq!(bars.update(Fixed::from_num(51.0)), 2);
+/// // Small dip below the 50% boundary keeps 2 bars
+/// assert_eq!(bars.update(Fixed::from_num(49.0)), 2);
+/// // Clearly below the boundary drops to 1 bar
+/// assert_eq!(bars.update(Fixed::from_num(47.0)), 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SocQuantizer {
+    /// Step size in percent
+    step: Fixed,
+    /// Highest level (number of steps in 0-100%)
+    levels: u8,
+    /// Distance past a boundary required before the level changes
+    hysteresis: Fixed,
+    /// Last reported level, `None` until the first update
+    level: Option<u8>,
+}
+
+impl SocQuantizer {
+    /// Creates a quantizer reporting `bars` equally sized bars
+    ///
+    /// Hysteresis defaults to a quarter of a bar. A value of 0 bars is
+    /// treated as 1 bar.
+    pub fn bars(bars: u8) -> Self {
+        let bars = bars.max(1);
+        let step = bar_step(bars);
+        Self {
+            step,
+            levels: bars,
+            hysteresis: step / Fixed::from_num(4),
+            level: None,
+        }
+    }
+
+    /// Creates a quantizer reporting SOC in multiples of `step` percent
+    ///
+    /// Hysteresis defaults to a quarter of a step. Steps larger than 100%
+    /// are treated as 100%.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(quantizer)` - The quantizer
+    /// * `Err(Error::InvalidConfig)` - The step is finer than 100/255 %,
+    ///   so the levels would not fit in a `u8`; use [`soc_quantized()`]
+    ///   for finer steps
+    pub fn steps(step: Fixed) -> Result<Self, Error> {
+        if step < bar_step(u8::MAX) {
+            return Err(Error::InvalidConfig);
+        }
+        let step = step.min(SOC_FULL);
+        Ok(Self {
+            step,
+            levels: step_levels(step),
+            hysteresis: step / Fixed::from_num(4),
+            level: None,
+        })
+    }
+
+    /// Sets the hysteresis band in percent (negative values are treated as 0)
+    pub fn with_hysteresis(mut self, hysteresis: Fixed) -> Self {
+        self.hysteresis = hysteresis.max(Fixed::ZERO);
+        self
+    }
+
+    /// Feeds a new SOC value and returns the level to display
+    ///
+    /// The first update after construction or [`reset()`](Self::reset)
+    /// reports the plain rounded-down level.
+    pub fn update(&mut self, soc: Fixed) -> u8 {
+        let soc = soc.clamp(Fixed::ZERO, SOC_FULL);
+        let raw = level_for(soc, self.step, self.levels);
+
+        let level = match self.level {
+            // The ends of the range cannot be overshot, so they are always
+            // reachable regardless of the hysteresis band
+            None => raw,
+            Some(_) if soc == SOC_FULL || soc == Fixed::ZERO => raw,
+            Some(prev) if raw > prev => {
+                // Rising: the boundary must be exceeded by the hysteresis
+                level_for(soc - self.hysteresis, self.step, self.levels).max(prev)
+            }
+            Some(prev) if raw < prev => {
+                // Falling: the boundary must be undershot by the hysteresis
+                level_for(soc + self.hysteresis, self.step, self.levels).min(prev)
+            }
+            Some(prev) => prev,
+        };
+
+        self.level = Some(level);
+        level
+    }
+
+    /// Returns the last reported level, if any
+    #[inline]
+    pub const fn level(&self) -> Option<u8> {
+        self.level
+    }
+
+    /// Returns the last reported level as an SOC percentage
+    pub fn quantized_soc(&self) -> Option<Fixed> {
+        self.level
+            .map(|level| (self.step * Fixed::from_num(level)).min(SOC_FULL))
+    }
+
+    /// Returns the highest level this quantizer can report
+    #[inline]
+    pub const fn levels(&self) -> u8 {
+        self.levels
+    }
+
+    /// Returns the step size in percent
+    #[inline]
+    pub const fn step(&self) -> Fixed {
+        self.step
+    }
+
+    /// Forgets the last reported level
+    #[inline]
+    pub fn reset(&mut self) {
+        self.level = None;
+    }
+}
+
+/// Direction in which [`SocRounding`] rounds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RoundingMode {
+    /// Round down, so the gauge never reports more charge than estimated
+    Floor,
+    /// Round to the nearest value, halves upwards (default)
+    #[default]
+    Nearest,
+    /// Round up, e.g. so a nearly full battery reads 100%
+    Ceil,
+}
+
+/// Rounding of reported SOC to a number of decimal places
+///
+/// Stateless and independent of the estimator, so it applies equally to
+/// [`SocEstimator`](crate::SocEstimator), [`SocTracker`](crate::SocTracker)
+/// or pack estimates just before they are displayed or reported.
+/// Decimal steps such as 0.1% are not exact in fixed-point; the result is
+/// the fixed-point value nearest to the rounded decimal, i.e. the same as
+/// `Fixed::from_num()` of it. Inputs within half an LSB of a step count as
+/// on the step, so rounding an already rounded value changes nothing.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{Fixed, RoundingMode, SocRounding};
+///
+/// let soc = Fixed::from_num(99.6);
+///
+/// // Safety-critical products never over-report
+/// assert_eq!(SocRounding::new(RoundingMode::Floor, 0).round(soc), Fixed::from_num(99));
+/// // Consumer gauges show a nearly full battery as full
+/// assert_eq!(SocRounding::new(RoundingMode::Ceil, 0).round(soc), Fixed::from_num(100));
+///
+/// let tenths = SocRounding::new(RoundingMode::Nearest, 1);
+/// assert_eq!(tenths.round(Fixed::from_num(57.26)), Fixed::from_num(57.3));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SocRounding {
+    /// Rounding direction
+    mode: RoundingMode,
+    /// Decimal places kept, at most [`MAX_DECIMALS`](Self::MAX_DECIMALS)
+    decimals: u8,
+}
+
+impl SocRounding {
+    /// Most decimal places kept; finer steps are below the fixed-point
+    /// resolution of about 0.000015%
+    pub const MAX_DECIMALS: u8 = 4;
+
+    /// Creates a rounding to `decimals` decimal places
+    ///
+    /// More than [`MAX_DECIMALS`](Self::MAX_DECIMALS) places are treated
+    /// as `MAX_DECIMALS`.
+    #[inline]
+    pub const fn new(mode: RoundingMode, decimals: u8) -> Self {
+        let decimals = if decimals > Self::MAX_DECIMALS {
+            Self::MAX_DECIMALS
+        } else {
+            decimals
+        };
+        Self { mode, decimals }
+    }
+
+    /// Returns the rounding direction
+    #[inline]
+    pub const fn mode(&self) -> RoundingMode {
+        self.mode
+    }
+
+    /// Returns the number of decimal places kept
+    #[inline]
+    pub const fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    /// Rounds an SOC percentage
+    ///
+    /// Values outside 0–100% are rounded the same way, not clamped;
+    /// results beyond the fixed-point range saturate.
+    pub const fn round(&self, soc: Fixed) -> Fixed {
+        const ONE: i64 = 1 << 16;
+        let scale: i64 = match self.decimals {
+            0 => 1,
+            1 => 10,
+            2 => 100,
+            3 => 1_000,
+            _ => 10_000,
+        };
+        // SOC in units of the last decimal, scaled by 2^16; a stored step
+        // lies within half an LSB, i.e. `scale / 2`, of its exact value
+        let scaled = soc.to_bits() as i64 * scale;
+        let units = match self.mode {
+            RoundingMode::Floor => (scaled + scale / 2).div_euclid(ONE),
+            RoundingMode::Nearest => (scaled + ONE / 2).div_euclid(ONE),
+            RoundingMode::Ceil => (scaled - scale / 2 + ONE - 1).div_euclid(ONE),
+        };
+        let bits = (units * ONE * 2 + scale).div_euclid(scale * 2);
+        if bits > i32::MAX as i64 {
+            Fixed::MAX
+        } else if bits < i32::MIN as i64 {
+            Fixed::MIN
+        } else {
+            Fixed::from_bits(bits as i32)
+        }
+    }
+}
+
+/// Step size for `bars` equally sized bars
+#[inline]
+fn bar_step(bars: u8) -> Fixed {
+    SOC_FULL / Fixed::from_num(bars)
+}
+
+/// Number of whole steps of size `step` in 0-100%, saturated to `u8`
+#[inline]
+fn step_levels(step: Fixed) -> u8 {
+    (SOC_FULL / step).to_num::<i32>().clamp(0, u8::MAX as i32) as u8
+}
+
+/// Rounded-down level of `soc` for the given step, bounded to `0..=levels`
+#[inline]
+fn level_for(soc: Fixed, step: Fixed, levels: u8) -> u8 {
+    let soc = soc.clamp(Fixed::ZERO, SOC_FULL);
+    floor_steps(soc, step).clamp(0, levels as i64) as u8
+}
+
+/// Number of whole steps of a positive `step` in a non-negative `soc`
+///
+/// `step` is off by up to half an LSB, which adds up over many steps.
+/// Dividing by `step - 0.5 LSB` counts values within that accumulated
+/// error of a boundary as reaching it, so that 100% always maps to the top
+/// level and 57.3% is 573 steps of 0.1%.
+#[inline]
+fn floor_steps(soc: Fixed, step: Fixed) -> i64 {
+    (2 * soc.to_bits() as i64 + 2) / (2 * step.to_bits() as i64 - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_soc_rounding_modes() {
+        let round =
+            |mode, decimals, soc: f64| SocRounding::new(mode, decimals).round(Fixed::from_num(soc));
+        assert_eq!(round(RoundingMode::Floor, 0, 99.99), Fixed::from_num(99));
+        assert_eq!(round(RoundingMode::Nearest, 0, 99.5), Fixed::from_num(100));
+        assert_eq!(round(RoundingMode::Nearest, 0, 99.49), Fixed::from_num(99));
+        assert_eq!(round(RoundingMode::Ceil, 0, 99.01), Fixed::from_num(100));
+        assert_eq!(round(RoundingMode::Ceil, 0, 100.0), Fixed::from_num(100));
+        assert_eq!(round(RoundingMode::Floor, 0, -0.5), Fixed::from_num(-1));
+        assert_eq!(round(RoundingMode::Ceil, 0, -0.5), Fixed::ZERO);
+
+        // 57.2 is not exact in fixed-point but still counts as on the step
+        assert_eq!(round(RoundingMode::Floor, 1, 57.2), Fixed::from_num(57.2));
+        assert_eq!(round(RoundingMode::Ceil, 1, 57.2), Fixed::from_num(57.2));
+        assert_eq!(
+            round(RoundingMode::Floor, 2, 57.219),
+            Fixed::from_num(57.21)
+        );
+        assert_eq!(round(RoundingMode::Ceil, 2, 57.211), Fixed::from_num(57.22));
+
+        assert_eq!(
+            SocRounding::new(RoundingMode::Floor, 9).decimals(),
+            SocRounding::MAX_DECIMALS
+        );
+        assert_eq!(
+            SocRounding::new(RoundingMode::Ceil, 0).round(Fixed::MAX),
+            Fixed::MAX
+        );
+        assert_eq!(
+            SocRounding::default(),
+            SocRounding::new(RoundingMode::Nearest, 0)
+        );
+    }
+
+    #[test]
+    fn test_soc_rounding_is_idempotent() {
+        for mode in [
+            RoundingMode::Floor,
+            RoundingMode::Nearest,
+            RoundingMode::Ceil,
+        ] {
+            for decimals in 0..=SocRounding::MAX_DECIMALS {
+                let rounding = SocRounding::new(mode, decimals);
+                for bits in (0..=100 << 16).step_by(997) {
+                    let once = rounding.round(Fixed::from_bits(bits));
+                    assert_eq!(rounding.round(once), once, "{mode:?} {decimals} {bits}");
+                    let error = (once - Fixed::from_bits(bits)).abs();
+                    assert!(error.to_num::<f64>() <= 10f64.powi(-(decimals as i32)) + 1e-4);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_soc_bars_boundaries() {
+        assert_eq!(soc_bars(Fixed::ZERO, 4), 0);
+        assert_eq!(soc_bars(Fixed::from_num(24.9), 4), 0);
+        assert_eq!(soc_bars(Fixed::from_num(25.0), 4), 1);
+        assert_eq!(soc_bars(Fixed::from_num(50.0), 4), 2);
+        assert_eq!(soc_bars(Fixed::from_num(99.9), 4), 3);
+        assert_eq!(soc_bars(Fixed::from_num(100.0), 4), 4);
+    }
+
+    #[test]
+    fn test_soc_bars_uneven_division() {
+        // 100 / 3 is not exact in fixed-point
+        assert_eq!(soc_bars(Fixed::from_num(100.0), 3), 3);
+        assert_eq!(soc_bars(Fixed::from_num(66.7), 3), 2);
+        assert_eq!(soc_bars(Fixed::from_num(33.4), 3), 1);
+    }
+
+    #[test]
+    fn test_soc_bars_out_of_range() {
+        assert_eq!(soc_bars(Fixed::from_num(-10.0), 4), 0);
+        assert_eq!(soc_bars(Fixed::from_num(150.0), 4), 4);
+        assert_eq!(soc_bars(Fixed::from_num(50.0), 0), 0);
+    }
+
+    #[test]
+    fn test_soc_quantized() {
+        let step = Fixed::from_num(5.0);
+        assert_eq!(
+            soc_quantized(Fixed::from_num(57.3), step),
+            Fixed::from_num(55.0)
+        );
+        assert_eq!(
+            soc_quantized(Fixed::from_num(100.0), step),
+            Fixed::from_num(100.0)
+        );
+        assert_eq!(soc_quantized(Fixed::from_num(4.9), step), Fixed::ZERO);
+        assert_eq!(soc_quantized(Fixed::from_num(-1.0), step), Fixed::ZERO);
+    }
+
+    #[test]
+    fn test_soc_quantized_fine_step() {
+        let step = Fixed::from_num(0.1);
+        let soc = soc_quantized(Fixed::from_num(57.3), step);
+        assert!(
+            (soc - Fixed::from_num(57.3)).abs() < Fixed::from_num(0.01),
+            "{soc}"
+        );
+        let soc = soc_quantized(Fixed::from_num(57.25), step);
+        assert!(
+            (soc - Fixed::from_num(57.2)).abs() < Fixed::from_num(0.01),
+            "{soc}"
+        );
+        assert_eq!(
+            soc_quantized(Fixed::from_num(100.0), step),
+            Fixed::from_num(100.0)
+        );
+        assert_eq!(soc_quantized(Fixed::from_num(0.05), step), Fixed::ZERO);
+    }
+
+    #[test]
+    fn test_soc_quantized_invalid_step() {
+        let soc = Fixed::from_num(42.0);
+        assert_eq!(soc_quantized(soc, Fixed::ZERO), soc);
+        assert_eq!(soc_quantized(soc, Fixed::from_num(-5.0)), soc);
+    }
+
+    #[test]
+    fn test_quantizer_hysteresis_prevents_oscillation() {
+        let mut q = SocQuantizer::bars(4).with_hysteresis(Fixed::from_num(2.0));
+
+        assert_eq!(q.update(Fixed::from_num(51.0)), 2);
+        for soc in [49.0, 50.5, 48.5, 51.5, 49.5] {
+            assert_eq!(q.update(Fixed::from_num(soc)), 2);
+        }
+
+        assert_eq!(q.update(Fixed::from_num(47.5)), 1);
+        // Coming back up needs 52% to reach 2 bars again
+        assert_eq!(q.update(Fixed::from_num(51.0)), 1);
+        assert_eq!(q.update(Fixed::from_num(52.5)), 2);
+    }
+
+    #[test]
+    fn test_quantizer_large_jump() {
+        let mut q = SocQuantizer::bars(4);
+
+        assert_eq!(q.update(Fixed::from_num(10.0)), 0);
+        assert_eq!(q.update(Fixed::from_num(100.0)), 4);
+        assert_eq!(q.update(Fixed::ZERO), 0);
+    }
+
+    #[test]
+    fn test_quantizer_steps() {
+        let mut q = SocQuantizer::steps(Fixed::from_num(10.0))
+            .unwrap()
+            .with_hysteresis(Fixed::ONE);
+
+        assert_eq!(q.levels(), 10);
+        assert_eq!(q.update(Fixed::from_num(73.0)), 7);
+        assert_eq!(q.quantized_soc(), Some(Fixed::from_num(70.0)));
+
+        assert_eq!(q.update(Fixed::from_num(69.5)), 7);
+        assert_eq!(q.update(Fixed::from_num(68.5)), 6);
+        assert_eq!(q.quantized_soc(), Some(Fixed::from_num(60.0)));
+    }
+
+    #[test]
+    fn test_quantizer_reset() {
+        let mut q = SocQuantizer::bars(5);
+
+        assert_eq!(q.level(), None);
+        assert_eq!(q.quantized_soc(), None);
+
+        q.update(Fixed::from_num(80.0));
+        assert_eq!(q.level(), Some(4));
+
+        q.reset();
+        assert_eq!(q.level(), None);
+    }
+
+    #[test]
+    fn test_quantizer_degenerate_config() {
+        let q = SocQuantizer::bars(0);
+        assert_eq!(q.levels(), 1);
+
+        assert_eq!(SocQuantizer::steps(Fixed::ZERO), Err(Error::InvalidConfig));
+        assert_eq!(
+            SocQuantizer::steps(Fixed::from_num(0.1)),
+            Err(Error::InvalidConfig)
+        );
+
+        let q = SocQuantizer::steps(bar_step(u8::MAX)).unwrap();
+        assert_eq!(q.levels(), u8::MAX);
+
+        let q = SocQuantizer::steps(Fixed::from_num(200.0)).unwrap();
+        assert_eq!(q.levels(), 1);
+        assert_eq!(q.step(), Fixed::from_num(100.0));
+    }
+}
@@ -0,0 +1,113 @@
+//! Virtual fuel-gauge register file
+//!
+//! Hosts written for a hardware fuel gauge read 16-bit registers by
+//! address over I2C or SMBus. [`RegisterFile`] maps such an address to the
+//! word the gauge would return, so firmware implementing the slave side
+//! can answer from a register snapshot ([`GaugeDataModel`] for
+//! BQ27441-style standard commands, [`SbsRegisters`] for smart battery
+//! commands) rebuilt after each estimator update.
+
+use crate::{GaugeCommand, GaugeDataModel, SbsCommand, SbsRegisters};
+
+/// Read access to gauge registers by address
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{Fixed, GaugeDataModel, RegisterFile};
+///
+/// let data = GaugeDataModel::new(
+///     Fixed::from_num(3.8),
+///     Fixed::from_num(-200),
+///     Fixed::from_num(25),
+///     Fixed::from_num(70),
+///     Fixed::from_num(100),
+///     Fixed::from_num(1000),
+/// );
+///
+/// // I2C slave handler: the host wrote command 0x1C (StateOfCharge)
+/// assert_eq!(data.read_register(0x1C), Some(70));
+/// assert_eq!(data.read_register_bytes(0x04), Some([0xD8, 0x0E])); // 3800 mV
+/// // Unmapped addresses are left to the driver (e.g., NACK)
+/// assert_eq!(data.read_register(0x7F), None);
+/// ```
+pub trait RegisterFile {
+    /// Word at `address`, or `None` if the address is not mapped
+    fn read_register(&self, address: u8) -> Option<u16>;
+
+    /// Word at `address` in bus order (low byte first)
+    ///
+    /// Both SMBus read-word and the BQ27441 standard commands transfer the
+    /// low byte first.
+    #[inline]
+    fn read_register_bytes(&self, address: u8) -> Option<[u8; 2]> {
+        self.read_register(address).map(u16::to_le_bytes)
+    }
+}
+
+impl<R: RegisterFile + ?Sized> RegisterFile for &R {
+    #[inline]
+    fn read_register(&self, address: u8) -> Option<u16> {
+        (**self).read_register(address)
+    }
+}
+
+impl RegisterFile for GaugeDataModel {
+    #[inline]
+    fn read_register(&self, address: u8) -> Option<u16> {
+        GaugeCommand::from_code(address).map(|command| self.read_word(command))
+    }
+}
+
+impl RegisterFile for SbsRegisters {
+    #[inline]
+    fn read_register(&self, address: u8) -> Option<u16> {
+        SbsCommand::from_code(address).map(|command| self.read_word(command))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Fixed;
+
+    #[test]
+    fn test_register_addresses() {
+        let data = GaugeDataModel::new(
+            Fixed::from_num(3.6),
+            Fixed::from_num(-300),
+            Fixed::from_num(25),
+            Fixed::from_num(40),
+            Fixed::from_num(90),
+            Fixed::from_num(2000),
+        );
+        assert_eq!(data.read_register(0x02), Some(2982));
+        assert_eq!(data.read_register(0x06), Some(data.flags));
+        assert_eq!(data.read_register(0x0E), Some(1800));
+        assert_eq!(data.read_register(0x10), Some((-300i16) as u16));
+        assert_eq!(data.read_register(0x20), Some(90));
+        assert_eq!(data.read_register(0x03), None);
+
+        let sbs = SbsRegisters::new(
+            Fixed::from_num(3.6),
+            Fixed::from_num(-300),
+            Fixed::from_num(25),
+            Fixed::from_num(40),
+            Fixed::from_num(2000),
+        );
+        let registers: &dyn RegisterFile = &sbs;
+        assert_eq!(registers.read_register(0x0D), Some(40));
+        assert_eq!(registers.read_register_bytes(0x09), Some([0x10, 0x0E]));
+        assert_eq!(registers.read_register(0x02), None);
+    }
+
+    #[test]
+    fn test_command_codes() {
+        assert_eq!(
+            GaugeCommand::from_code(0x1C),
+            Some(GaugeCommand::StateOfCharge)
+        );
+        assert_eq!(GaugeCommand::from_code(0x00), None);
+        assert_eq!(GaugeCommand::AveragePower as u8, 0x18);
+    }
+}
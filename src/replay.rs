@@ -0,0 +1,304 @@
+//! Log replay and accuracy statistics
+//!
+//! A [`ReplayHarness`] feeds recorded or simulated measurements through
+//! an estimator, tracker or any other [`ReplayTarget`] and compares every
+//! result with the log's reference SOC (e.g., from a lab coulomb counter).
+//! Replaying the same log through differently configured targets gives an
+//! apples-to-apples comparison of algorithm options.
+
+use crate::{Error, Fixed, SimSample, SocEstimator, SocTracker};
+
+/// One recorded measurement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LogRecord {
+    /// Timestamp in milliseconds
+    pub time_ms: u64,
+    /// Battery voltage in volts
+    pub voltage: Fixed,
+    /// Battery current in mA (positive = charging, negative = discharging)
+    pub current_ma: Fixed,
+    /// Battery temperature in °C
+    pub temperature: Fixed,
+    /// Reference SOC in percent
+    pub reference_soc: Fixed,
+}
+
+impl LogRecord {
+    /// Creates a record from a simulated sample, with its true SOC as reference
+    pub fn from_sim(sample: SimSample, temperature: Fixed) -> Self {
+        Self {
+            time_ms: sample.time_ms,
+            voltage: sample.voltage,
+            current_ma: sample.current_ma,
+            temperature,
+            reference_soc: sample.soc,
+        }
+    }
+}
+
+/// Algorithm under test
+///
+/// Implemented for [`SocTracker`], [`SocEstimator`] and every
+/// `FnMut(&LogRecord, Fixed) -> Result<Fixed, Error>` closure, which covers
+/// any other estimator.
+pub trait ReplayTarget {
+    /// Processes one record and returns the estimated SOC in percent
+    ///
+    /// `dt` is the time since the previous record in seconds (0 for the
+    /// first record).
+    fn process(&mut self, record: &LogRecord, dt: Fixed) -> Result<Fixed, Error>;
+}
+
+impl ReplayTarget for SocTracker {
+    #[inline]
+    fn process(&mut self, record: &LogRecord, dt: Fixed) -> Result<Fixed, Error> {
+        self.set_temperature(record.temperature);
+        self.update(record.voltage, record.current_ma, dt)
+    }
+}
+
+impl ReplayTarget for SocEstimator {
+    #[inline]
+    fn process(&mut self, record: &LogRecord, _dt: Fixed) -> Result<Fixed, Error> {
+        self.estimate_soc_compensated_fixed(record.voltage, record.temperature)
+    }
+}
+
+impl<F: FnMut(&LogRecord, Fixed) -> Result<Fixed, Error>> ReplayTarget for F {
+    #[inline]
+    fn process(&mut self, record: &LogRecord, dt: Fixed) -> Result<Fixed, Error> {
+        self(record, dt)
+    }
+}
+
+/// Error statistics of a replay, in percentage points of SOC
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ErrorStats {
+    /// Number of records compared with the reference
+    pub count: u32,
+    /// Number of records the target returned an error for
+    pub failures: u32,
+    /// Mean signed error (estimate minus reference)
+    pub bias: Fixed,
+    /// Mean absolute error
+    pub mean_abs_error: Fixed,
+    /// Root-mean-square error
+    pub rms_error: Fixed,
+    /// Largest absolute error
+    pub max_abs_error: Fixed,
+    /// Timestamp of the record with the largest absolute error
+    pub max_error_time_ms: u64,
+}
+
+/// Replays logs through a [`ReplayTarget`] and accumulates error statistics
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{
+///     BatteryChemistry, DischargeSimulator, Fixed, LoadProfile, LogRecord, ReplayHarness,
+///     SimConfig, SocEstimator, SocTracker, TrackerConfig,
+/// };
+///
+/// let config = SimConfig::default()
+///     .with_interval_ms(10_000)
+///     .with_noise(Fixed::from_num(0.02));
+/// let load = LoadProfile::Constant(Fixed::from_num(500));
+/// let log = DischargeSimulator::new(BatteryChemistry::LiPo, config, load)
+///     .map(|sample| LogRecord::from_sim(sample, Fixed::from_num(25)));
+///
+/// let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+/// let tracker = SocTracker::new(
+///     estimator,
+///     TrackerConfig::default().with_capacity_mah(Fixed::from_num(1000)),
+/// );
+///
+/// let raw = ReplayHarness::new(estimator).run(log.clone());
+/// let tracked = ReplayHarness::new(tracker).run(log);
+/// assert!(tracked.rms_error < raw.rms_error);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReplayHarness<T> {
+    target: T,
+    last_time_ms: Option<u64>,
+    count: u32,
+    failures: u32,
+    /// Sum of signed errors in fixed-point bits
+    error_sum: i64,
+    /// Sum of absolute errors in fixed-point bits
+    abs_error_sum: i64,
+    /// Sum of squared errors in squared fixed-point bits
+    squared_error_sum: u128,
+    max_abs_error: Fixed,
+    max_error_time_ms: u64,
+}
+
+impl<T: ReplayTarget> ReplayHarness<T> {
+    /// Creates a harness around `target`
+    pub fn new(target: T) -> Self {
+        Self {
+            target,
+            last_time_ms: None,
+            count: 0,
+            failures: 0,
+            error_sum: 0,
+            abs_error_sum: 0,
+            squared_error_sum: 0,
+            max_abs_error: Fixed::ZERO,
+            max_error_time_ms: 0,
+        }
+    }
+
+    /// Processes one record and accumulates its error
+    ///
+    /// The time step is derived from the timestamps and saturates at the
+    /// fixed-point range; timestamps going backwards give a step of 0.
+    ///
+    /// # Returns
+    ///
+    /// The target's result; failures are counted but not compared.
+    pub fn feed(&mut self, record: &LogRecord) -> Result<Fixed, Error> {
+        let elapsed_ms = self
+            .last_time_ms
+            .map_or(0, |last| record.time_ms.saturating_sub(last));
+        self.last_time_ms = Some(record.time_ms);
+        let dt = if elapsed_ms >= (i16::MAX as u64 + 1) * 1000 {
+            Fixed::MAX
+        } else {
+            Fixed::from_bits(((elapsed_ms << 16) / 1000) as i32)
+        };
+
+        let result = self.target.process(record, dt);
+        match result {
+            Ok(soc) => {
+                let error = soc.to_bits() as i64 - record.reference_soc.to_bits() as i64;
+                let abs_error = error.unsigned_abs();
+                self.count += 1;
+                self.error_sum += error;
+                self.abs_error_sum += abs_error as i64;
+                self.squared_error_sum += abs_error as u128 * abs_error as u128;
+
+                let abs_error = Fixed::from_bits(abs_error.min(i32::MAX as u64) as i32);
+                if abs_error > self.max_abs_error || self.count == 1 {
+                    self.max_abs_error = abs_error;
+                    self.max_error_time_ms = record.time_ms;
+                }
+            }
+            Err(_) => self.failures += 1,
+        }
+        result
+    }
+
+    /// Replays all `records` and returns the statistics so far
+    pub fn run<I: IntoIterator<Item = LogRecord>>(&mut self, records: I) -> ErrorStats {
+        for record in records {
+            let _ = self.feed(&record);
+        }
+        self.stats()
+    }
+
+    /// Statistics of all records fed so far
+    pub fn stats(&self) -> ErrorStats {
+        let count = self.count.max(1) as i64;
+        let to_fixed =
+            |bits: i64| Fixed::from_bits(bits.clamp(i32::MIN as i64, i32::MAX as i64) as i32);
+        let mean_square = self.squared_error_sum / count as u128;
+        ErrorStats {
+            count: self.count,
+            failures: self.failures,
+            bias: to_fixed(self.error_sum / count),
+            mean_abs_error: to_fixed(self.abs_error_sum / count),
+            rms_error: to_fixed(mean_square.isqrt().min(i64::MAX as u128) as i64),
+            max_abs_error: self.max_abs_error,
+            max_error_time_ms: self.max_error_time_ms,
+        }
+    }
+
+    /// Get the target
+    #[inline]
+    pub fn target(&self) -> &T {
+        &self.target
+    }
+
+    /// Consumes the harness and returns the target
+    #[inline]
+    pub fn into_target(self) -> T {
+        self.target
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BatteryChemistry, TrackerConfig};
+
+    fn record(time_ms: u64, reference_soc: i32) -> LogRecord {
+        LogRecord {
+            time_ms,
+            voltage: Fixed::from_num(3.75),
+            current_ma: Fixed::ZERO,
+            temperature: Fixed::from_num(25),
+            reference_soc: Fixed::from_num(reference_soc),
+        }
+    }
+
+    #[test]
+    fn test_error_statistics() {
+        // The estimator reads 60% at 3.75 V
+        let mut harness = ReplayHarness::new(SocEstimator::new(BatteryChemistry::LiPo));
+        let stats = harness.run([record(0, 57), record(1000, 64), record(2000, 60)]);
+
+        assert_eq!(stats.count, 3);
+        assert_eq!(stats.failures, 0);
+        // Errors +3, -4, 0
+        assert_eq!(stats.bias, Fixed::from_num(-1) / 3);
+        assert_eq!(stats.mean_abs_error, Fixed::from_num(7) / 3);
+        assert_eq!(stats.max_abs_error, Fixed::from_num(4));
+        assert_eq!(stats.max_error_time_ms, 1000);
+        let expected_rms = (Fixed::from_num(25) / 3).sqrt();
+        assert!((stats.rms_error - expected_rms).abs() <= Fixed::from_bits(2));
+    }
+
+    #[test]
+    fn test_time_steps_and_failures() {
+        let mut steps = [Fixed::ZERO; 4];
+        let mut index = 0;
+        let target = |record: &LogRecord, dt: Fixed| {
+            steps[index] = dt;
+            index += 1;
+            if record.reference_soc < Fixed::ZERO {
+                Err(Error::SensorFault)
+            } else {
+                Ok(record.reference_soc)
+            }
+        };
+        let mut harness = ReplayHarness::new(target);
+        let stats = harness.run([
+            record(5_000, 50),
+            record(5_250, -1),
+            record(4_000, 50),
+            record(100_004_000, 50),
+        ]);
+
+        assert_eq!(stats.count, 3);
+        assert_eq!(stats.failures, 1);
+        assert_eq!(stats.rms_error, Fixed::ZERO);
+        assert_eq!(
+            steps,
+            [Fixed::ZERO, Fixed::from_num(0.25), Fixed::ZERO, Fixed::MAX]
+        );
+    }
+
+    #[test]
+    fn test_tracker_target_uses_temperature() {
+        let tracker = SocTracker::new(
+            SocEstimator::new(BatteryChemistry::LiPo),
+            TrackerConfig::default(),
+        );
+        let mut harness = ReplayHarness::new(tracker);
+        let mut cold = record(0, 60);
+        cold.temperature = Fixed::from_num(-10);
+        harness.feed(&cold).unwrap();
+        assert_eq!(harness.target().temperature(), Fixed::from_num(-10));
+    }
+}
@@ -0,0 +1,322 @@
+//! Smart Battery Data Specification (SBS) register mapping
+//!
+//! Converts estimator outputs into the 16-bit words of the Smart Battery
+//! Data Specification, so firmware with an SMBus slave driver can answer a
+//! host that expects a smart battery. Only the word-sized commands derived
+//! from a voltage-based gauge are provided; the driver answers the rest.
+
+use crate::{Fixed, SocTracker};
+
+/// 100% as a fixed-point constant
+const SOC_FULL: Fixed = Fixed::from_bits(100 << 16);
+
+/// SBS command codes backed by [`SbsRegisters`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum SbsCommand {
+    /// Pack temperature in 0.1 K
+    Temperature = 0x08,
+    /// Pack voltage in mV
+    Voltage = 0x09,
+    /// Current in mA (signed, positive when charging)
+    Current = 0x0A,
+    /// SOC in percent of the full charge capacity
+    RelativeStateOfCharge = 0x0D,
+    /// Remaining capacity in mAh
+    RemainingCapacity = 0x0F,
+    /// Full charge capacity in mAh
+    FullChargeCapacity = 0x10,
+    /// Minutes until empty at the present current (65535 if not discharging)
+    RunTimeToEmpty = 0x11,
+    /// Alarm and status bits (`STATUS_*` constants of [`SbsRegisters`])
+    BatteryStatus = 0x16,
+}
+
+impl SbsCommand {
+    /// Looks up the command for an SMBus command code
+    ///
+    /// Returns `None` for codes not backed by [`SbsRegisters`].
+    pub const fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0x08 => Some(SbsCommand::Temperature),
+            0x09 => Some(SbsCommand::Voltage),
+            0x0A => Some(SbsCommand::Current),
+            0x0D => Some(SbsCommand::RelativeStateOfCharge),
+            0x0F => Some(SbsCommand::RemainingCapacity),
+            0x10 => Some(SbsCommand::FullChargeCapacity),
+            0x11 => Some(SbsCommand::RunTimeToEmpty),
+            0x16 => Some(SbsCommand::BatteryStatus),
+            _ => None,
+        }
+    }
+}
+
+/// Snapshot of the SBS registers
+///
+/// Build a snapshot after each gauge update and answer SMBus word reads
+/// from it with [`read_word()`](Self::read_word). All values saturate to
+/// the range of their register.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{Fixed, SbsCommand, SbsRegisters};
+///
+/// let registers = SbsRegisters::new(
+///     Fixed::from_num(3.75),  // voltage
+///     Fixed::from_num(-500),  // current in mA (discharging)
+///     Fixed::from_num(25),    // temperature in °C
+///     Fixed::from_num(60),    // SOC in percent
+///     Fixed::from_num(2000),  // full charge capacity in mAh
+/// );
+///
+/// assert_eq!(registers.read_word(SbsCommand::Voltage), 3750);
+/// assert_eq!(registers.read_word(SbsCommand::RelativeStateOfCharge), 60);
+/// // 1200 mAh left at 500 mA
+/// assert_eq!(registers.read_word(SbsCommand::RunTimeToEmpty), 144);
+/// assert!(registers.has_status(SbsRegisters::STATUS_DISCHARGING));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SbsRegisters {
+    /// Temperature in 0.1 K
+    pub temperature: u16,
+    /// Voltage in mV
+    pub voltage_mv: u16,
+    /// Current in mA, positive when charging
+    pub current_ma: i16,
+    /// SOC in percent (0 to 100)
+    pub relative_soc: u16,
+    /// Remaining capacity in mAh
+    pub remaining_mah: u16,
+    /// Full charge capacity in mAh
+    pub full_charge_mah: u16,
+    /// Minutes until empty, 65535 if not discharging
+    pub run_time_to_empty: u16,
+    /// Alarm and status bits (`STATUS_*` constants)
+    pub battery_status: u16,
+}
+
+impl SbsRegisters {
+    /// The SOC fell below the remaining-capacity alarm threshold
+    pub const STATUS_REMAINING_CAPACITY_ALARM: u16 = 0x0200;
+    /// The SOC fell below the terminate-discharge threshold
+    pub const STATUS_TERMINATE_DISCHARGE_ALARM: u16 = 0x0800;
+    /// The battery is discharging
+    pub const STATUS_DISCHARGING: u16 = 0x0040;
+    /// The battery is full
+    pub const STATUS_FULLY_CHARGED: u16 = 0x0020;
+    /// The battery is empty
+    pub const STATUS_FULLY_DISCHARGED: u16 = 0x0010;
+
+    /// Value of [`SbsCommand::RunTimeToEmpty`] when not discharging
+    pub const RUN_TIME_NOT_DISCHARGING: u16 = u16::MAX;
+
+    /// Builds the registers from gauge outputs
+    ///
+    /// Alarm bits are left clear; [`from_tracker()`](Self::from_tracker)
+    /// derives them from the tracker's SOC thresholds.
+    ///
+    /// # Arguments
+    ///
+    /// * `voltage` - Battery voltage in volts
+    /// * `current_ma` - Current in mA, negative when discharging
+    /// * `temperature` - Battery temperature in °C
+    /// * `soc` - SOC in percent (clamped to 0-100)
+    /// * `full_charge_mah` - Full charge capacity in mAh (0 if unknown)
+    pub fn new(
+        voltage: Fixed,
+        current_ma: Fixed,
+        temperature: Fixed,
+        soc: Fixed,
+        full_charge_mah: Fixed,
+    ) -> Self {
+        let soc = soc.clamp(Fixed::ZERO, SOC_FULL);
+        let full_charge_mah = full_charge_mah.max(Fixed::ZERO);
+        let remaining_bits = full_charge_mah.to_bits() as i64 * soc.to_bits() as i64 / (100 << 16);
+        let remaining_mah = saturate_u16(remaining_bits);
+
+        let run_time_to_empty = if current_ma < Fixed::ZERO {
+            // mAh / mA in minutes, computed on the raw bits
+            let minutes = remaining_bits * 60 / (-(current_ma.to_bits() as i64));
+            saturate_u16(minutes << 16).min(Self::RUN_TIME_NOT_DISCHARGING - 1)
+        } else {
+            Self::RUN_TIME_NOT_DISCHARGING
+        };
+
+        let mut battery_status = 0;
+        if current_ma < Fixed::ZERO {
+            battery_status |= Self::STATUS_DISCHARGING;
+        }
+        if soc >= SOC_FULL {
+            battery_status |= Self::STATUS_FULLY_CHARGED;
+        }
+        if soc <= Fixed::ZERO {
+            battery_status |= Self::STATUS_FULLY_DISCHARGED;
+        }
+
+        Self {
+            temperature: deci_kelvin(temperature),
+            voltage_mv: saturate_u16(voltage.to_bits() as i64 * 1000),
+            current_ma: saturate_i16(current_ma),
+            relative_soc: saturate_u16(soc.to_bits() as i64),
+            remaining_mah,
+            full_charge_mah: saturate_u16(full_charge_mah.to_bits() as i64),
+            run_time_to_empty,
+            battery_status,
+        }
+    }
+
+    /// Builds the registers from a tracker's state
+    ///
+    /// Uses the tracked SOC and temperature and the configured capacity.
+    /// The remaining-capacity alarm is raised below the tracker's low SOC
+    /// threshold and the terminate-discharge alarm below its critical
+    /// threshold.
+    ///
+    /// # Returns
+    ///
+    /// `None` until the tracker has processed its first sample.
+    pub fn from_tracker(tracker: &SocTracker, voltage: Fixed, current_ma: Fixed) -> Option<Self> {
+        let soc = tracker.soc()?;
+        let config = tracker.config();
+        let mut registers = Self::new(
+            voltage,
+            current_ma,
+            tracker.temperature(),
+            soc,
+            config.capacity_mah,
+        );
+
+        if soc < config.low_soc_threshold {
+            registers.battery_status |= Self::STATUS_REMAINING_CAPACITY_ALARM;
+        }
+        if soc < config.critical_soc_threshold {
+            registers.battery_status |= Self::STATUS_TERMINATE_DISCHARGE_ALARM;
+        }
+        Some(registers)
+    }
+
+    /// Word returned for an SMBus read of `command`
+    ///
+    /// Signed registers are returned in two's complement.
+    pub const fn read_word(&self, command: SbsCommand) -> u16 {
+        match command {
+            SbsCommand::Temperature => self.temperature,
+            SbsCommand::Voltage => self.voltage_mv,
+            SbsCommand::Current => self.current_ma as u16,
+            SbsCommand::RelativeStateOfCharge => self.relative_soc,
+            SbsCommand::RemainingCapacity => self.remaining_mah,
+            SbsCommand::FullChargeCapacity => self.full_charge_mah,
+            SbsCommand::RunTimeToEmpty => self.run_time_to_empty,
+            SbsCommand::BatteryStatus => self.battery_status,
+        }
+    }
+
+    /// Returns `true` if all bits of `status` are set
+    #[inline]
+    pub const fn has_status(&self, status: u16) -> bool {
+        self.battery_status & status == status
+    }
+}
+
+/// 16.16 fixed-point bits rounded to the nearest integer, saturated to `u16`
+#[inline]
+pub(crate) fn saturate_u16(bits: i64) -> u16 {
+    ((bits + 0x8000) >> 16).clamp(0, u16::MAX as i64) as u16
+}
+
+/// Value rounded to the nearest integer, saturated to `i16`
+#[inline]
+pub(crate) fn saturate_i16(value: Fixed) -> i16 {
+    value
+        .saturating_round()
+        .to_num::<i32>()
+        .clamp(i16::MIN as i32, i16::MAX as i32) as i16
+}
+
+/// Temperature in °C as a 0.1 K register value
+#[inline]
+pub(crate) fn deci_kelvin(temperature: Fixed) -> u16 {
+    // (t + 273.15) * 10
+    saturate_u16(temperature.to_bits() as i64 * 10 + (27315 << 16) / 10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BatteryChemistry, SocEstimator, TrackerConfig};
+
+    #[test]
+    fn test_registers_from_values() {
+        let registers = SbsRegisters::new(
+            Fixed::from_num(4.2),
+            Fixed::from_num(250),
+            Fixed::from_num(-10),
+            Fixed::from_num(105),
+            Fixed::from_num(3000),
+        );
+
+        assert_eq!(registers.read_word(SbsCommand::Voltage), 4200);
+        assert_eq!(registers.read_word(SbsCommand::Current), 250);
+        assert_eq!(registers.read_word(SbsCommand::Temperature), 2632);
+        assert_eq!(registers.read_word(SbsCommand::RelativeStateOfCharge), 100);
+        assert_eq!(registers.read_word(SbsCommand::RemainingCapacity), 3000);
+        assert_eq!(
+            registers.read_word(SbsCommand::RunTimeToEmpty),
+            SbsRegisters::RUN_TIME_NOT_DISCHARGING
+        );
+        assert_eq!(
+            registers.read_word(SbsCommand::BatteryStatus),
+            SbsRegisters::STATUS_FULLY_CHARGED
+        );
+
+        let discharging = SbsRegisters::new(
+            Fixed::from_num(3.2),
+            Fixed::from_num(-1000),
+            Fixed::from_num(25),
+            Fixed::ZERO,
+            Fixed::ZERO,
+        );
+        assert_eq!(
+            discharging.read_word(SbsCommand::Current),
+            (-1000i16) as u16
+        );
+        assert_eq!(discharging.read_word(SbsCommand::RunTimeToEmpty), 0);
+        assert!(discharging
+            .has_status(SbsRegisters::STATUS_DISCHARGING | SbsRegisters::STATUS_FULLY_DISCHARGED));
+    }
+
+    #[test]
+    fn test_registers_from_tracker() {
+        let config = TrackerConfig::default().with_capacity_mah(Fixed::from_num(2000));
+        let mut tracker = SocTracker::new(SocEstimator::new(BatteryChemistry::LiPo), config);
+        let voltage = Fixed::from_num(3.4);
+
+        assert_eq!(
+            SbsRegisters::from_tracker(&tracker, voltage, Fixed::ZERO),
+            None
+        );
+
+        tracker.update(voltage, Fixed::ZERO, Fixed::ONE).unwrap();
+        let registers = SbsRegisters::from_tracker(&tracker, voltage, Fixed::ZERO).unwrap();
+        let soc = tracker.soc().unwrap();
+        assert_eq!(registers.relative_soc, soc.round().to_num::<u16>());
+        assert_eq!(
+            registers.remaining_mah,
+            (soc * Fixed::from_num(20)).round().to_num::<u16>()
+        );
+        assert!(registers.has_status(SbsRegisters::STATUS_REMAINING_CAPACITY_ALARM));
+        assert!(!registers.has_status(SbsRegisters::STATUS_TERMINATE_DISCHARGE_ALARM));
+    }
+
+    #[test]
+    fn test_command_codes() {
+        assert_eq!(
+            SbsCommand::from_code(0x0D),
+            Some(SbsCommand::RelativeStateOfCharge)
+        );
+        assert_eq!(SbsCommand::from_code(0x16), Some(SbsCommand::BatteryStatus));
+        assert_eq!(SbsCommand::from_code(0x00), None);
+        assert_eq!(SbsCommand::RunTimeToEmpty as u8, 0x11);
+    }
+}
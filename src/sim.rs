@@ -0,0 +1,492 @@
+//! Battery discharge simulator
+//!
+//! A [`DischargeSimulator`] produces the voltage trace a cell of a given
+//! chemistry and capacity would show under a [`LoadProfile`], so tracker
+//! and filter configurations can be tuned and regression-tested without
+//! hardware. The model is deliberately simple:
+//!
+//! - Charge drawn from the cell is integrated exactly (128-bit accumulator),
+//!   so long runs at short intervals do not drift
+//! - The open-circuit voltage follows the chemistry curve
+//! - Cold reduces the usable capacity as modelled by
+//!   [`default_temperature_compensation_fixed()`], so an estimator with
+//!   default temperature compensation recovers the true SOC
+//! - Load current causes an `I * R` drop over the internal resistance
+//! - Uniform noise from a seeded generator makes traces reproducible
+
+use crate::{default_temperature_compensation_fixed, BatteryChemistry, Curve, Error, Fixed, Volts};
+
+/// 100% as a fixed-point constant
+const SOC_FULL: Fixed = Fixed::from_bits(100 << 16);
+
+/// Milliseconds per hour
+const MS_PER_HOUR: i128 = 3_600_000;
+
+/// Current drawn by the simulated device over time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LoadProfile {
+    /// Constant load in mA
+    Constant(Fixed),
+    /// Load switching between an idle and an active current
+    ///
+    /// Each period starts with `active_ms` at `active_ma`, followed by
+    /// `idle_ma` for the rest of `period_ms`.
+    Pulsed {
+        /// Idle load in mA
+        idle_ma: Fixed,
+        /// Active load in mA
+        active_ma: Fixed,
+        /// Period length in milliseconds
+        period_ms: u32,
+        /// Active time per period in milliseconds
+        active_ms: u32,
+    },
+}
+
+impl LoadProfile {
+    /// Load in mA at `time_ms` since the start of the simulation
+    ///
+    /// Positive values discharge the cell, negative values charge it.
+    pub fn load_ma(&self, time_ms: u64) -> Fixed {
+        match *self {
+            LoadProfile::Constant(load_ma) => load_ma,
+            LoadProfile::Pulsed {
+                idle_ma,
+                active_ma,
+                period_ms,
+                active_ms,
+            } => {
+                if time_ms % (period_ms.max(1) as u64) < active_ms as u64 {
+                    active_ma
+                } else {
+                    idle_ma
+                }
+            }
+        }
+    }
+}
+
+/// Simulation parameters
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{Fixed, SimConfig};
+///
+/// let config = SimConfig::default()
+///     .with_capacity_mah(Fixed::from_num(2000))
+///     .with_temperature(Fixed::from_num(0))
+///     .with_noise(Fixed::from_num(0.005));
+///
+/// assert_eq!(config.interval_ms, 1000);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SimConfig {
+    /// Nominal capacity in mAh at 25°C
+    pub capacity_mah: Fixed,
+    /// SOC in percent at the start of the simulation
+    pub initial_soc: Fixed,
+    /// Cell temperature in °C
+    pub temperature: Fixed,
+    /// Internal resistance in ohms
+    pub internal_resistance: Fixed,
+    /// Peak amplitude of the voltage noise in volts (0 disables noise)
+    pub noise: Fixed,
+    /// Time between samples in milliseconds
+    pub interval_ms: u32,
+    /// Seed of the noise generator
+    pub seed: u32,
+}
+
+impl SimConfig {
+    /// Default configuration
+    ///
+    /// - Capacity: 1000 mAh
+    /// - Initial SOC: 100%
+    /// - Temperature: 25°C
+    /// - No internal resistance, no noise
+    /// - Interval: 1 s
+    #[inline]
+    pub const fn default() -> Self {
+        Self {
+            capacity_mah: Fixed::from_bits(1000 << 16),
+            initial_soc: SOC_FULL,
+            temperature: Fixed::from_bits(25 << 16),
+            internal_resistance: Fixed::ZERO,
+            noise: Fixed::ZERO,
+            interval_ms: 1000,
+            seed: 0x2545_F491,
+        }
+    }
+
+    /// Set the nominal capacity in mAh
+    #[inline]
+    pub fn with_capacity_mah(mut self, capacity_mah: Fixed) -> Self {
+        self.capacity_mah = capacity_mah;
+        self
+    }
+
+    /// Set the initial SOC in percent
+    #[inline]
+    pub fn with_initial_soc(mut self, soc: Fixed) -> Self {
+        self.initial_soc = soc;
+        self
+    }
+
+    /// Set the cell temperature in °C
+    #[inline]
+    pub fn with_temperature(mut self, temperature: Fixed) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    /// Set the internal resistance in ohms
+    #[inline]
+    pub fn with_internal_resistance(mut self, resistance: Fixed) -> Self {
+        self.internal_resistance = resistance;
+        self
+    }
+
+    /// Set the peak noise amplitude in volts
+    #[inline]
+    pub fn with_noise(mut self, noise: Fixed) -> Self {
+        self.noise = noise;
+        self
+    }
+
+    /// Set the time between samples in milliseconds
+    #[inline]
+    pub fn with_interval_ms(mut self, interval_ms: u32) -> Self {
+        self.interval_ms = interval_ms;
+        self
+    }
+
+    /// Set the seed of the noise generator
+    #[inline]
+    pub fn with_seed(mut self, seed: u32) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+impl Default for SimConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::default()
+    }
+}
+
+/// One simulated measurement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SimSample {
+    /// Time since the start of the simulation in milliseconds
+    pub time_ms: u64,
+    /// Terminal voltage in volts, including load drop and noise
+    pub voltage: Fixed,
+    /// Battery current in mA (positive = charging, negative = discharging),
+    /// as expected by [`SocTracker::update()`](crate::SocTracker::update)
+    pub current_ma: Fixed,
+    /// True SOC in percent
+    pub soc: Fixed,
+}
+
+/// Synthetic voltage trace generator
+///
+/// Iterates over samples taken every [`SimConfig::interval_ms`], starting
+/// at time 0. A discharge ends after the sample at 0% SOC; a charging
+/// profile runs forever, so bound it with [`Iterator::take()`].
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{
+///     BatteryChemistry, DischargeSimulator, Fixed, LoadProfile, SimConfig, SocEstimator,
+///     SocTracker, TrackerConfig,
+/// };
+///
+/// let config = SimConfig::default()
+///     .with_capacity_mah(Fixed::from_num(500))
+///     .with_interval_ms(10_000)
+///     .with_noise(Fixed::from_num(0.01));
+/// let load = LoadProfile::Constant(Fixed::from_num(250));
+/// let sim = DischargeSimulator::new(BatteryChemistry::LiPo, config, load);
+///
+/// let mut tracker = SocTracker::new(
+///     SocEstimator::new(BatteryChemistry::LiPo),
+///     TrackerConfig::default().with_capacity_mah(Fixed::from_num(500)),
+/// );
+/// let dt = Fixed::from_num(10);
+/// let mut worst_error = Fixed::ZERO;
+/// for sample in sim {
+///     let soc = tracker.update(sample.voltage, sample.current_ma, dt).unwrap();
+///     worst_error = worst_error.max((soc - sample.soc).abs());
+/// }
+/// assert!(worst_error < Fixed::from_num(5));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DischargeSimulator {
+    curve: Curve,
+    config: SimConfig,
+    load: LoadProfile,
+    /// Usable capacity at the configured temperature in fixed-point bits
+    usable_capacity_bits: i128,
+    /// Charge drawn since the start in mA·ms, fixed-point bits
+    drawn_bits: i128,
+    time_ms: u64,
+    rng: u32,
+    done: bool,
+}
+
+impl DischargeSimulator {
+    /// Creates a simulator for a built-in chemistry
+    pub fn new(chemistry: BatteryChemistry, config: SimConfig, load: LoadProfile) -> Self {
+        Self::build(Curve::for_chemistry(chemistry), config, load)
+    }
+
+    /// Creates a simulator for a custom curve
+    ///
+    /// The simulator keeps its own copy of the curve, so it need not be
+    /// `'static`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(simulator)` - The simulator
+    /// * `Err(Error::InvalidCurve)` - The curve has fewer than 2 points
+    pub fn with_custom_curve(
+        curve: &Curve,
+        config: SimConfig,
+        load: LoadProfile,
+    ) -> Result<Self, Error> {
+        if curve.len() < 2 {
+            return Err(Error::InvalidCurve);
+        }
+        Ok(Self::build(curve, config, load))
+    }
+
+    fn build(curve: &Curve, config: SimConfig, load: LoadProfile) -> Self {
+        let usable_capacity =
+            default_temperature_compensation_fixed(config.capacity_mah, config.temperature);
+        let initial_soc = config.initial_soc.clamp(Fixed::ZERO, SOC_FULL);
+        let usable_capacity_bits = usable_capacity.to_bits().max(1) as i128;
+        // Start with the charge already drawn at the initial SOC
+        let drawn_bits =
+            usable_capacity_bits * MS_PER_HOUR * (SOC_FULL - initial_soc).to_bits() as i128
+                / SOC_FULL.to_bits() as i128;
+
+        Self {
+            curve: *curve,
+            config,
+            load,
+            usable_capacity_bits,
+            drawn_bits,
+            time_ms: 0,
+            rng: config.seed.max(1),
+            done: false,
+        }
+    }
+
+    /// True SOC in percent
+    pub fn soc(&self) -> Fixed {
+        let used = self.drawn_bits * SOC_FULL.to_bits() as i128
+            / (self.usable_capacity_bits * MS_PER_HOUR);
+        let soc = (SOC_FULL.to_bits() as i128 - used).clamp(0, SOC_FULL.to_bits() as i128);
+        Fixed::from_bits(soc as i32)
+    }
+
+    /// Next value of the xorshift noise generator in `[-noise, noise]`
+    fn next_noise(&mut self) -> Fixed {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 17;
+        self.rng ^= self.rng << 5;
+        // Map to [-1, 1) in 16 fractional bits
+        let unit = Fixed::from_bits((self.rng >> 15) as i32 - (1 << 16));
+        self.config.noise.saturating_mul(unit)
+    }
+
+    /// Terminal voltage at the current state for `load_ma`
+    fn voltage(&mut self, load_ma: Fixed) -> Fixed {
+        // Open-circuit voltage at the SOC the curve sees, which is higher
+        // than the true SOC when the cold shrinks the usable capacity
+        let curve_soc = self.soc().to_bits() as i64 * self.config.capacity_mah.to_bits() as i64
+            / self.usable_capacity_bits as i64;
+        let permille = ((curve_soc * 10 + (1 << 15)) >> 16).clamp(0, 1000) as u16;
+        let ocv_mv = self.curve.soc_permille_to_voltage_mv(permille).unwrap_or(0);
+
+        let drop = (load_ma / 1000).saturating_mul(self.config.internal_resistance);
+        let noise = self.next_noise();
+        Volts::from_millivolts(ocv_mv)
+            .raw()
+            .saturating_sub(drop)
+            .saturating_add(noise)
+    }
+}
+
+impl Iterator for DischargeSimulator {
+    type Item = SimSample;
+
+    fn next(&mut self) -> Option<SimSample> {
+        if self.done {
+            return None;
+        }
+
+        let load_ma = self.load.load_ma(self.time_ms);
+        let soc = self.soc();
+        let sample = SimSample {
+            time_ms: self.time_ms,
+            voltage: self.voltage(load_ma),
+            current_ma: -load_ma,
+            soc,
+        };
+        self.done = soc == Fixed::ZERO && load_ma >= Fixed::ZERO;
+
+        let interval = self.config.interval_ms;
+        let full_bits = self.usable_capacity_bits * MS_PER_HOUR;
+        self.drawn_bits =
+            (self.drawn_bits + load_ma.to_bits() as i128 * interval as i128).clamp(0, full_bits);
+        self.time_ms += interval as u64;
+        Some(sample)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CurvePoint;
+
+    #[test]
+    fn test_constant_discharge_duration() {
+        let config = SimConfig::default()
+            .with_capacity_mah(Fixed::from_num(1000))
+            .with_interval_ms(60_000);
+        let sim = DischargeSimulator::new(
+            BatteryChemistry::LiIon,
+            config,
+            LoadProfile::Constant(Fixed::from_num(1000)),
+        );
+
+        let mut count = 0;
+        let mut previous: Option<SimSample> = None;
+        for sample in sim {
+            match count {
+                0 => {
+                    assert_eq!(sample.soc, SOC_FULL);
+                    assert_eq!(sample.voltage, Fixed::from_num(4.2));
+                }
+                30 => assert_eq!(sample.soc, Fixed::from_num(50)),
+                _ => {}
+            }
+            if let Some(previous) = previous {
+                assert!(sample.voltage <= previous.voltage);
+            }
+            previous = Some(sample);
+            count += 1;
+        }
+
+        // One hour at 1C, one sample per minute, plus the start
+        assert_eq!(count, 61);
+        let last = previous.unwrap();
+        assert_eq!(last.soc, Fixed::ZERO);
+        assert_eq!(last.current_ma, Fixed::from_num(-1000));
+    }
+
+    #[test]
+    fn test_short_intervals_do_not_drift() {
+        // 10 ms steps at 100 mA drain less than the I16F16 resolution each
+        let config = SimConfig::default()
+            .with_capacity_mah(Fixed::from_num(2000))
+            .with_interval_ms(10);
+        let mut sim = DischargeSimulator::new(
+            BatteryChemistry::LiPo,
+            config,
+            LoadProfile::Constant(Fixed::from_num(100)),
+        );
+
+        // 36 s at 100 mA is 1 mAh, 0.05% of the capacity
+        let sample = sim.nth(3600).unwrap();
+        assert_eq!(sample.time_ms, 36_000);
+        assert!((sample.soc - Fixed::from_num(99.95)).abs() < Fixed::from_num(0.001));
+    }
+
+    #[test]
+    fn test_pulsed_load_and_resistance() {
+        let load = LoadProfile::Pulsed {
+            idle_ma: Fixed::from_num(10),
+            active_ma: Fixed::from_num(500),
+            period_ms: 10_000,
+            active_ms: 2_000,
+        };
+        assert_eq!(load.load_ma(1_999), Fixed::from_num(500));
+        assert_eq!(load.load_ma(2_000), Fixed::from_num(10));
+        assert_eq!(load.load_ma(10_500), Fixed::from_num(500));
+
+        let config = SimConfig::default()
+            .with_initial_soc(Fixed::from_num(50))
+            .with_internal_resistance(Fixed::from_num(0.2));
+        let mut sim = DischargeSimulator::new(BatteryChemistry::LiPo, config, load);
+        let active = sim.next().unwrap();
+        let idle = sim.nth(2).unwrap();
+        // 490 mA more through 0.2 ohm
+        let sag = idle.voltage - active.voltage;
+        assert!((sag - Fixed::from_num(0.098)).abs() < Fixed::from_num(0.002));
+    }
+
+    #[test]
+    fn test_cold_shortens_discharge() {
+        let run = |temperature: i32| {
+            let config = SimConfig::default()
+                .with_temperature(Fixed::from_num(temperature))
+                .with_interval_ms(60_000);
+            let load = LoadProfile::Constant(Fixed::from_num(1000));
+            DischargeSimulator::new(BatteryChemistry::LiPo, config, load).count()
+        };
+        assert!(run(-10) < run(25));
+    }
+
+    #[test]
+    fn test_noise_is_bounded_and_reproducible() {
+        let config = SimConfig::default().with_initial_soc(Fixed::from_num(50));
+        let load = LoadProfile::Constant(Fixed::ZERO);
+        let center = DischargeSimulator::new(BatteryChemistry::LiPo, config, load)
+            .next()
+            .unwrap()
+            .voltage;
+
+        let noise = Fixed::from_num(0.01);
+        let noisy = DischargeSimulator::new(BatteryChemistry::LiPo, config.with_noise(noise), load);
+        assert!(noisy.clone().take(100).eq(noisy.clone().take(100)));
+        assert!(noisy
+            .clone()
+            .take(100)
+            .all(|sample| (sample.voltage - center).abs() <= noise));
+        assert!(noisy.take(100).any(|sample| sample.voltage != center));
+    }
+
+    #[test]
+    fn test_custom_curve_validation() {
+        static EMPTY: Curve = Curve::empty();
+        let load = LoadProfile::Constant(Fixed::ONE);
+        assert_eq!(
+            DischargeSimulator::with_custom_curve(&EMPTY, SimConfig::default(), load),
+            Err(Error::InvalidCurve)
+        );
+    }
+
+    #[test]
+    fn test_custom_curve_above_i16_millivolts() {
+        // 13S pack curve, up to 54.6 V
+        static PACK: Curve = Curve::new(&[
+            CurvePoint {
+                voltage_mv: 39_000,
+                soc_tenth: 0,
+            },
+            CurvePoint {
+                voltage_mv: 54_600,
+                soc_tenth: 1000,
+            },
+        ]);
+        let load = LoadProfile::Constant(Fixed::ZERO);
+        let mut sim =
+            DischargeSimulator::with_custom_curve(&PACK, SimConfig::default(), load).unwrap();
+        assert_eq!(sim.next().unwrap().voltage, Fixed::from_num(54.6));
+    }
+}
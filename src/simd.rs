@@ -0,0 +1,238 @@
+//! Portable SIMD lookup for the slice estimation path
+//!
+//! Enabled with the `simd` feature on a nightly toolchain, which `core::simd`
+//! requires; the build script sets `cfg(nightly)`, and other toolchains keep
+//! the scalar path. [`SimdCurve`] holds the curve as lane-friendly tables
+//! and converts [`LANES`] measured voltages at once: measurement scaling,
+//! the segment search and the interpolation all run on vectors. Results are
+//! bit-identical to the scalar lookup, so the feature only changes speed.
+
+use crate::{Curve, EstimatorConfig, Fixed, MAX_CURVE_POINTS};
+use core::simd::cmp::{SimdOrd, SimdPartialOrd};
+use core::simd::num::SimdInt;
+use core::simd::{i32x8, i64x8, Select};
+
+/// Voltages converted per vector
+pub(crate) const LANES: usize = 8;
+
+/// Curve tables prepared once per slice
+pub(crate) struct SimdCurve {
+    /// Point voltages in mV
+    voltage_mv: [i32; MAX_CURVE_POINTS],
+    /// Point SOCs as fixed-point bits
+    soc_bits: [i32; MAX_CURVE_POINTS],
+    /// Slopes of the segments ending at each point
+    slopes: [i64; MAX_CURVE_POINTS],
+    len: usize,
+    gain: i64x8,
+    offset: i64x8,
+    divider_ratio: i64x8,
+}
+
+impl SimdCurve {
+    /// Prepares the tables
+    ///
+    /// Returns `None` for curves the vector path does not cover (fewer than
+    /// 2 points or voltages not strictly increasing); use the scalar lookup
+    /// for those.
+    pub(crate) fn new(curve: &Curve, config: &EstimatorConfig) -> Option<Self> {
+        let points = curve.points();
+        if points.len() < 2
+            || points
+                .windows(2)
+                .any(|w| w[0].voltage_mv >= w[1].voltage_mv)
+        {
+            return None;
+        }
+        let last = points[points.len() - 1];
+        let mut voltage_mv = [last.voltage_mv as i32; MAX_CURVE_POINTS];
+        let mut soc_bits = [last.soc_fixed().to_bits(); MAX_CURVE_POINTS];
+        for (i, point) in points.iter().enumerate() {
+            voltage_mv[i] = point.voltage_mv as i32;
+            soc_bits[i] = point.soc_fixed().to_bits();
+        }
+        Some(Self {
+            voltage_mv,
+            soc_bits,
+            slopes: core::array::from_fn(|i| curve.slope(i)),
+            len: points.len(),
+            gain: i64x8::splat(config.input_gain.to_bits() as i64),
+            offset: i64x8::splat(config.input_offset.to_bits() as i64),
+            divider_ratio: i64x8::splat(config.divider_ratio.to_bits() as i64),
+        })
+    }
+
+    /// Applies the measurement scaling and returns the battery voltages
+    ///
+    /// Matches [`EstimatorConfig::scale_voltage()`]. `measured` holds at
+    /// least [`LANES`] voltages; the rest are ignored.
+    pub(crate) fn scale(&self, measured: &[Fixed]) -> [Fixed; LANES] {
+        let bits = i64x8::from_array(core::array::from_fn(|lane| measured[lane].to_bits() as i64));
+        let scaled = saturating_mul(bits, self.gain);
+        let scaled = (scaled + self.offset).simd_clamp(I32_MIN, I32_MAX);
+        let scaled = saturating_mul(scaled, self.divider_ratio);
+        scaled.cast::<i32>().to_array().map(Fixed::from_bits)
+    }
+
+    /// Looks up the raw SOC of battery voltages
+    ///
+    /// Matches [`Curve::voltage_to_soc_fixed()`].
+    pub(crate) fn lookup(&self, voltages: &[Fixed; LANES]) -> [Fixed; LANES] {
+        let bits = i64x8::from_array(voltages.map(|voltage| voltage.to_bits() as i64));
+        // Saturating multiply by 1000, truncated to whole millivolts
+        let voltage_mv = ((bits * i64x8::splat(1000)).simd_clamp(I32_MIN, I32_MAX)
+            >> i64x8::splat(16))
+        .cast::<i32>();
+
+        // Segment search without gathers: a point at or below the voltage
+        // moves the segment to start there, so for sorted points the last
+        // match wins. Selecting per point costs a few vector operations,
+        // far less than the gathers of a binary search on curves of at
+        // most `MAX_CURVE_POINTS` points.
+        let (mut prev_mv, mut prev_soc) = self.point(0);
+        let mut curr_mv = i32x8::splat(self.voltage_mv[1]);
+        let mut slope = i64x8::splat(self.slopes[1]);
+        for i in 1..self.len - 1 {
+            let (point_mv, point_soc) = self.point(i);
+            let at_or_above = point_mv.simd_le(voltage_mv);
+            prev_mv = at_or_above.select(point_mv, prev_mv);
+            prev_soc = at_or_above.select(point_soc, prev_soc);
+            curr_mv = at_or_above.select(i32x8::splat(self.voltage_mv[i + 1]), curr_mv);
+            slope = at_or_above
+                .cast::<i64>()
+                .select(i64x8::splat(self.slopes[i + 1]), slope);
+        }
+
+        // One multiply-add with the precomputed slope, rounded as in
+        // `curve::interpolate()`
+        let offset = (voltage_mv - prev_mv).simd_clamp(i32x8::splat(0), curr_mv - prev_mv);
+        let delta = (slope * offset.cast::<i64>() + i64x8::splat(1 << 15)) >> i64x8::splat(16);
+        let interpolated = prev_soc + delta.cast::<i32>();
+
+        let low_mv = i32x8::splat(self.voltage_mv[0]);
+        let high_mv = i32x8::splat(self.voltage_mv[self.len - 1]);
+        let soc = voltage_mv.simd_le(low_mv).select(
+            i32x8::splat(self.soc_bits[0]),
+            voltage_mv
+                .simd_ge(high_mv)
+                .select(i32x8::splat(self.soc_bits[self.len - 1]), interpolated),
+        );
+        soc.to_array().map(Fixed::from_bits)
+    }
+
+    /// Voltage and SOC of point `i` in every lane
+    #[inline]
+    fn point(&self, i: usize) -> (i32x8, i32x8) {
+        (
+            i32x8::splat(self.voltage_mv[i]),
+            i32x8::splat(self.soc_bits[i]),
+        )
+    }
+}
+
+const I32_MIN: i64x8 = i64x8::from_array([i32::MIN as i64; LANES]);
+const I32_MAX: i64x8 = i64x8::from_array([i32::MAX as i64; LANES]);
+
+/// Fixed-point multiplication of 16.16 bits, saturated to `i32`
+#[inline]
+fn saturating_mul(a: i64x8, b: i64x8) -> i64x8 {
+    // |a|, |b| < 2^31, so the product fits in 63 bits
+    ((a * b) >> i64x8::splat(16)).simd_clamp(I32_MIN, I32_MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BatteryChemistry;
+
+    #[test]
+    fn test_simd_matches_scalar() {
+        for chemistry in [
+            BatteryChemistry::LiPo,
+            BatteryChemistry::LiFePO4,
+            BatteryChemistry::LiIon,
+            BatteryChemistry::Lipo410Full340Cutoff,
+        ] {
+            let curve = Curve::for_chemistry(chemistry);
+            let config = EstimatorConfig::default()
+                .with_input_gain(Fixed::from_num(1.013))
+                .with_input_offset(Fixed::from_num(-0.021))
+                .with_divider_ratio(Fixed::from_num(2));
+            let simd = SimdCurve::new(curve, &config).unwrap();
+
+            let mut measured = [Fixed::ZERO; LANES];
+            for step in 0..(3_000 / LANES as i32) {
+                for (lane, voltage) in measured.iter_mut().enumerate() {
+                    let mv = 1_000 + step * LANES as i32 + lane as i32;
+                    *voltage = Fixed::from_num(mv) / 1000 + Fixed::from_bits(lane as i32 * 7);
+                }
+                let scaled = simd.scale(&measured);
+                let socs = simd.lookup(&scaled);
+                for lane in 0..LANES {
+                    assert_eq!(scaled[lane], config.scale_voltage(measured[lane]));
+                    assert_eq!(Ok(socs[lane]), curve.voltage_to_soc_fixed(scaled[lane]));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_simd_saturation_matches_scalar() {
+        let curve = Curve::for_chemistry(BatteryChemistry::LiPo);
+        let config = EstimatorConfig::default().with_divider_ratio(Fixed::from_num(1000));
+        let simd = SimdCurve::new(curve, &config).unwrap();
+        let measured = [
+            Fixed::MIN,
+            Fixed::MAX,
+            Fixed::ZERO,
+            -Fixed::ONE,
+            Fixed::from_bits(1),
+            Fixed::from_num(0.0037),
+            Fixed::from_num(32),
+            Fixed::from_num(-32),
+        ];
+        let scaled = simd.scale(&measured);
+        let socs = simd.lookup(&scaled);
+        for lane in 0..LANES {
+            assert_eq!(scaled[lane], config.scale_voltage(measured[lane]));
+            assert_eq!(Ok(socs[lane]), curve.voltage_to_soc_fixed(scaled[lane]));
+        }
+
+        // Segments spanning most of the u16 range
+        let wide = Curve::new(&[
+            crate::CurvePoint {
+                voltage_mv: 0,
+                soc_tenth: 1000,
+            },
+            crate::CurvePoint {
+                voltage_mv: 7,
+                soc_tenth: 0,
+            },
+            crate::CurvePoint {
+                voltage_mv: 65_000,
+                soc_tenth: 65_535,
+            },
+            crate::CurvePoint {
+                voltage_mv: 65_535,
+                soc_tenth: 3,
+            },
+        ]);
+        let simd = SimdCurve::new(&wide, &EstimatorConfig::default()).unwrap();
+        let mut voltages = [Fixed::ZERO; LANES];
+        for step in 0..4_000 {
+            for (lane, voltage) in voltages.iter_mut().enumerate() {
+                *voltage = Fixed::from_bits((step * LANES as i32 + lane as i32) * 137);
+            }
+            let socs = simd.lookup(&voltages);
+            for lane in 0..LANES {
+                assert_eq!(Ok(socs[lane]), wide.voltage_to_soc_fixed(voltages[lane]));
+            }
+        }
+
+        let unsorted = Curve::new(&[
+            crate::CurvePoint::new(4.0, 100.0),
+            crate::CurvePoint::new(3.0, 0.0),
+        ]);
+        assert!(SimdCurve::new(&unsorted, &config).is_none());
+    }
+}
@@ -0,0 +1,361 @@
+//! Voltage sources
+//!
+//! A [`VoltageSource`] delivers battery voltage readings to
+//! [`SocTracker::sample()`](crate::SocTracker::sample), so the tracker can
+//! pull its own samples instead of every application converting ADC
+//! readings by hand. [`AdcConverter`] turns raw ADC counts into volts;
+//! with the `adc` feature, [`AdcVoltageSource`] adapts any `embedded-hal`
+//! 0.2 one-shot ADC channel. With the `async` feature,
+//! [`AsyncVoltageSource`] lets async firmware await conversions instead.
+
+use crate::{Error, Fixed};
+
+/// Provider of battery voltage readings
+///
+/// Implemented for every `FnMut() -> Result<Fixed, Error>` closure, so a
+/// custom driver can be plugged in without an extra type.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{Fixed, VoltageSource};
+///
+/// let mut source = || Ok(Fixed::from_num(3.75));
+/// assert_eq!(source.read_voltage(), Ok(Fixed::from_num(3.75)));
+/// ```
+pub trait VoltageSource {
+    /// Take one reading of the battery voltage in volts
+    ///
+    /// Failed reads are reported as [`Error::SensorFault`].
+    fn read_voltage(&mut self) -> Result<Fixed, Error>;
+}
+
+impl<F: FnMut() -> Result<Fixed, Error>> VoltageSource for F {
+    #[inline]
+    fn read_voltage(&mut self) -> Result<Fixed, Error> {
+        self()
+    }
+}
+
+/// Provider of battery voltage readings for async executors
+///
+/// The async counterpart of [`VoltageSource`], used by
+/// [`SocTracker::sample_async()`](crate::SocTracker::sample_async) so an
+/// Embassy task can await the ADC conversion without blocking the
+/// executor.
+///
+/// # Examples
+///
+/// ```ignore
+/// use battery_estimator::{AsyncVoltageSource, Error, Fixed};
+///
+/// struct BatteryAdc<'d> {
+///     adc: embassy_stm32::adc::Adc<'d, ADC1>,
+///     pin: PA0,
+/// }
+///
+/// impl AsyncVoltageSource for BatteryAdc<'_> {
+///     async fn read_voltage(&mut self) -> Result<Fixed, Error> {
+///         let counts = self.adc.read(&mut self.pin).await;
+///         Ok(Fixed::from_num(counts) * Fixed::from_num(3.3) / Fixed::from_num(4095))
+///     }
+/// }
+///
+/// tracker.sample_async(&mut battery_adc, Fixed::ZERO, Fixed::ONE).await?;
+/// ```
+#[cfg(feature = "async")]
+pub trait AsyncVoltageSource {
+    /// Take one reading of the battery voltage in volts
+    ///
+    /// Failed reads are reported as [`Error::SensorFault`].
+    fn read_voltage(&mut self) -> impl core::future::Future<Output = Result<Fixed, Error>>;
+}
+
+/// Conversion of raw ADC counts into battery volts
+///
+/// Scales counts to the pin voltage using the ADC reference voltage and
+/// resolution, corrects it with `gain` and `offset` (e.g., from a
+/// calibration against a reference meter) and multiplies by the ratio of
+/// the voltage divider in front of the pin:
+///
+/// `battery = (counts / full_scale * reference * gain + offset) * divider_ratio`
+///
+/// All arithmetic is done in 64-bit integers, so no precision is lost in
+/// the intermediate products. Apply the divider and corrections here or
+/// in the estimator's [`EstimatorConfig`](crate::EstimatorConfig), not in
+/// both.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{AdcConverter, Fixed};
+///
+/// // 12-bit ADC with a 3.3 V reference behind a 100k / 100k divider
+/// let converter = AdcConverter::new(Fixed::from_num(3.3), 12)
+///     .with_divider_ratio(Fixed::from_num(2));
+///
+/// assert_eq!(converter.full_scale(), 4095);
+/// assert_eq!(converter.counts_to_voltage(4095), Fixed::from_num(6.6));
+/// assert_eq!(converter.counts_to_voltage(0), Fixed::ZERO);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AdcConverter {
+    /// ADC reference voltage in volts
+    pub reference: Fixed,
+    /// ADC resolution in bits (at most 16)
+    pub resolution_bits: u8,
+    /// Battery voltage / ADC pin voltage
+    pub divider_ratio: Fixed,
+    /// Multiplicative correction of the pin voltage
+    pub gain: Fixed,
+    /// Additive correction of the pin voltage in volts
+    pub offset: Fixed,
+}
+
+impl AdcConverter {
+    /// Creates a converter with a 1:1 divider and no correction
+    ///
+    /// # Arguments
+    ///
+    /// * `reference` - ADC reference voltage in volts
+    /// * `resolution_bits` - ADC resolution (e.g., 12 for 0 to 4095 counts),
+    ///   at most 16
+    #[inline]
+    pub const fn new(reference: Fixed, resolution_bits: u8) -> Self {
+        Self {
+            reference,
+            resolution_bits,
+            divider_ratio: Fixed::ONE,
+            gain: Fixed::ONE,
+            offset: Fixed::ZERO,
+        }
+    }
+
+    /// Set the voltage divider ratio
+    ///
+    /// For a divider with `R1` from the battery to the ADC pin and `R2`
+    /// from the pin to ground, the ratio is `(R1 + R2) / R2`.
+    #[inline]
+    pub fn with_divider_ratio(mut self, ratio: Fixed) -> Self {
+        self.divider_ratio = ratio;
+        self
+    }
+
+    /// Set the multiplicative correction of the pin voltage
+    #[inline]
+    pub fn with_gain(mut self, gain: Fixed) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    /// Set the additive correction of the pin voltage in volts
+    #[inline]
+    pub fn with_offset(mut self, offset: Fixed) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Largest raw reading
+    #[inline]
+    pub const fn full_scale(&self) -> u32 {
+        let bits = if self.resolution_bits > 16 {
+            16
+        } else {
+            self.resolution_bits
+        };
+        (1u32 << bits) - 1
+    }
+
+    /// Battery voltage corresponding to a raw ADC reading
+    ///
+    /// Readings above full scale and results outside the fixed-point range
+    /// saturate.
+    pub fn counts_to_voltage(&self, counts: u32) -> Fixed {
+        let full_scale = self.full_scale();
+        let counts = counts.min(full_scale) as i64;
+        let pin = self.reference.to_bits() as i64 * counts / full_scale.max(1) as i64;
+        let corrected = ((pin * self.gain.to_bits() as i64) >> 16) + self.offset.to_bits() as i64;
+        let battery = (corrected * self.divider_ratio.to_bits() as i64) >> 16;
+        Fixed::from_bits(battery.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+    }
+}
+
+/// [`VoltageSource`] over an `embedded-hal` one-shot ADC channel
+///
+/// Converts raw counts into the battery voltage using the ADC reference
+/// voltage, its resolution and the ratio of the voltage divider in front
+/// of the pin. Conversions block until the ADC has a result. `Word` is
+/// the sample type of the ADC driver.
+///
+/// Apply the divider here or in the estimator's
+/// [`EstimatorConfig::divider_ratio`](crate::EstimatorConfig::divider_ratio),
+/// not in both.
+///
+/// # Examples
+///
+/// ```ignore
+/// use battery_estimator::{AdcVoltageSource, Fixed};
+///
+/// // 12-bit ADC with a 3.3 V reference behind a 1:2 divider
+/// let mut source = AdcVoltageSource::new(adc, pin, Fixed::from_num(3.3), 12)
+///     .with_divider_ratio(Fixed::from_num(2));
+///
+/// tracker.sample(&mut source, Fixed::ZERO, Fixed::ONE)?;
+/// ```
+#[cfg(feature = "adc")]
+#[derive(Debug)]
+pub struct AdcVoltageSource<Adc, Pin, Periph, Word = u16> {
+    adc: Adc,
+    pin: Pin,
+    converter: AdcConverter,
+    _periph: core::marker::PhantomData<fn() -> (Periph, Word)>,
+}
+
+#[cfg(feature = "adc")]
+impl<Adc, Pin, Periph, Word> AdcVoltageSource<Adc, Pin, Periph, Word> {
+    /// Creates a source with a 1:1 divider
+    ///
+    /// # Arguments
+    ///
+    /// * `adc` - The ADC peripheral (or a `&mut` to it)
+    /// * `pin` - The channel the battery is connected to
+    /// * `reference` - ADC reference voltage in volts
+    /// * `resolution_bits` - ADC resolution (e.g., 12 for 0 to 4095 counts),
+    ///   at most 16
+    #[inline]
+    pub const fn new(adc: Adc, pin: Pin, reference: Fixed, resolution_bits: u8) -> Self {
+        Self::with_converter(adc, pin, AdcConverter::new(reference, resolution_bits))
+    }
+
+    /// Creates a source converting readings with `converter`
+    #[inline]
+    pub const fn with_converter(adc: Adc, pin: Pin, converter: AdcConverter) -> Self {
+        Self {
+            adc,
+            pin,
+            converter,
+            _periph: core::marker::PhantomData,
+        }
+    }
+
+    /// Set the voltage divider ratio
+    ///
+    /// For a divider with `R1` from the battery to the ADC pin and `R2`
+    /// from the pin to ground, the ratio is `(R1 + R2) / R2`.
+    #[inline]
+    pub fn with_divider_ratio(mut self, ratio: Fixed) -> Self {
+        self.converter = self.converter.with_divider_ratio(ratio);
+        self
+    }
+
+    /// Get the conversion of raw readings
+    #[inline]
+    pub const fn converter(&self) -> &AdcConverter {
+        &self.converter
+    }
+
+    /// Battery voltage corresponding to a raw ADC reading
+    ///
+    /// See [`AdcConverter::counts_to_voltage()`].
+    #[inline]
+    pub fn counts_to_voltage(&self, counts: u32) -> Fixed {
+        self.converter.counts_to_voltage(counts)
+    }
+
+    /// Releases the ADC and the pin
+    #[inline]
+    pub fn release(self) -> (Adc, Pin) {
+        (self.adc, self.pin)
+    }
+}
+
+#[cfg(feature = "adc")]
+impl<Adc, Pin, Periph, Word> VoltageSource for AdcVoltageSource<Adc, Pin, Periph, Word>
+where
+    Adc: embedded_hal::adc::OneShot<Periph, Word, Pin>,
+    Pin: embedded_hal::adc::Channel<Periph>,
+    Word: Into<u32>,
+{
+    fn read_voltage(&mut self) -> Result<Fixed, Error> {
+        let counts = nb::block!(self.adc.read(&mut self.pin)).map_err(|_| Error::SensorFault)?;
+        Ok(self.counts_to_voltage(counts.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adc_converter() {
+        let converter = AdcConverter::new(Fixed::from_num(3), 10);
+        assert_eq!(converter.full_scale(), 1023);
+        assert_eq!(converter.counts_to_voltage(1023), Fixed::from_num(3));
+        assert_eq!(converter.counts_to_voltage(u32::MAX), Fixed::from_num(3));
+        assert!((converter.counts_to_voltage(341) - Fixed::ONE).abs() < Fixed::from_num(0.0001));
+
+        // Pin reads 1% high and 10 mV low, behind a 1:3 divider
+        let converter = converter
+            .with_gain(Fixed::from_num(0.99))
+            .with_offset(Fixed::from_num(0.01))
+            .with_divider_ratio(Fixed::from_num(3));
+        let voltage = converter.counts_to_voltage(682);
+        assert!((voltage - Fixed::from_num(5.97)).abs() < Fixed::from_num(0.001));
+
+        let saturating =
+            AdcConverter::new(Fixed::from_num(3.3), 24).with_divider_ratio(Fixed::from_num(30000));
+        assert_eq!(saturating.full_scale(), u16::MAX as u32);
+        assert_eq!(saturating.counts_to_voltage(u32::MAX), Fixed::MAX);
+    }
+
+    #[cfg(feature = "adc")]
+    struct MockAdc {
+        counts: u16,
+        fail: bool,
+    }
+
+    #[cfg(feature = "adc")]
+    struct MockPin;
+
+    #[cfg(feature = "adc")]
+    impl embedded_hal::adc::Channel<MockAdc> for MockPin {
+        type ID = u8;
+
+        fn channel() -> u8 {
+            0
+        }
+    }
+
+    #[cfg(feature = "adc")]
+    impl embedded_hal::adc::OneShot<MockAdc, u16, MockPin> for MockAdc {
+        type Error = ();
+
+        fn read(&mut self, _pin: &mut MockPin) -> nb::Result<u16, ()> {
+            if self.fail {
+                Err(nb::Error::Other(()))
+            } else {
+                Ok(self.counts)
+            }
+        }
+    }
+
+    #[cfg(feature = "adc")]
+    #[test]
+    fn test_adc_voltage_source() {
+        let adc = MockAdc {
+            counts: 2048,
+            fail: false,
+        };
+        let mut source = AdcVoltageSource::new(adc, MockPin, Fixed::from_num(4.095), 12)
+            .with_divider_ratio(Fixed::from_num(2));
+
+        let voltage = source.read_voltage().unwrap();
+        assert!((voltage - Fixed::from_num(4.096)).abs() < Fixed::from_num(0.001));
+        assert_eq!(source.counts_to_voltage(5000), Fixed::from_num(8.19));
+
+        let (mut adc, _pin) = source.release();
+        adc.fail = true;
+        let mut source = AdcVoltageSource::new(adc, MockPin, Fixed::from_num(3.3), 12);
+        assert_eq!(source.read_voltage(), Err(Error::SensorFault));
+    }
+}
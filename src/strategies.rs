@@ -0,0 +1,265 @@
+//! Ready-made `proptest` strategies
+//!
+//! Enabled with the `proptest` feature. The `valid_*` strategies produce
+//! inputs the crate documents as supported (sorted curves with SOCs in
+//! 0-100%, configurations within realistic ranges, cell voltages and
+//! temperatures of a working device), so properties about correct output
+//! can be stated directly. The `adversarial_*` strategies produce any bit
+//! pattern the types allow, for properties that must hold regardless,
+//! such as "never panics".
+//!
+//! # Examples
+//!
+//! ```
+//! use battery_estimator::strategies::{valid_curve, valid_voltage};
+//! use battery_estimator::Fixed;
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     fn soc_is_monotonic(curve in valid_curve(), a in valid_voltage(), b in valid_voltage()) {
+//!         let (low, high) = (a.min(b), a.max(b));
+//!         prop_assert!(curve.voltage_to_soc_fixed(low)? <= curve.voltage_to_soc_fixed(high)?);
+//!     }
+//! }
+//! # soc_is_monotonic();
+//! ```
+
+use crate::MAX_CURVE_POINTS;
+use crate::{BatteryChemistry, Curve, CurvePoint, EstimatorConfig, Fixed, OutputPolicy};
+use proptest::collection::{btree_set, vec};
+use proptest::prelude::*;
+use std::vec::Vec;
+
+/// Lowest voltage of [`valid_curve()`] points and [`valid_voltage()`] in mV
+const VALID_MIN_MV: u16 = 2_500;
+/// Highest voltage of [`valid_curve()`] points and [`valid_voltage()`] in mV
+const VALID_MAX_MV: u16 = 4_500;
+
+/// Any fixed-point value
+pub fn any_fixed() -> impl Strategy<Value = Fixed> {
+    any::<i32>().prop_map(Fixed::from_bits)
+}
+
+/// Fixed-point values from `low` to `high` inclusive
+pub fn fixed_in(low: Fixed, high: Fixed) -> impl Strategy<Value = Fixed> {
+    (low.to_bits()..=high.to_bits()).prop_map(Fixed::from_bits)
+}
+
+/// Any built-in chemistry
+pub fn chemistry() -> impl Strategy<Value = BatteryChemistry> {
+    prop_oneof![
+        Just(BatteryChemistry::LiPo),
+        Just(BatteryChemistry::LiFePO4),
+        Just(BatteryChemistry::LiIon),
+        Just(BatteryChemistry::Lipo410Full340Cutoff),
+    ]
+}
+
+/// Sorted curves of 2 to [`MAX_CURVE_POINTS`] points
+///
+/// Voltages strictly increase between 2.5 V and 4.5 V and SOCs
+/// never decrease within 0-100%.
+pub fn valid_curve() -> impl Strategy<Value = Curve> {
+    (2..=MAX_CURVE_POINTS)
+        .prop_flat_map(|len| {
+            (
+                btree_set(VALID_MIN_MV..=VALID_MAX_MV, len),
+                vec(0u16..=1000, len),
+            )
+        })
+        .prop_map(|(voltages, mut socs)| {
+            socs.sort_unstable();
+            let points: Vec<CurvePoint> = voltages
+                .into_iter()
+                .zip(socs)
+                .map(|(voltage_mv, soc_tenth)| CurvePoint {
+                    voltage_mv,
+                    soc_tenth,
+                })
+                .collect();
+            Curve::new(&points)
+        })
+}
+
+/// Curves of 0 to [`MAX_CURVE_POINTS`] arbitrary points
+///
+/// Includes empty, single-point, unsorted and duplicate-voltage curves and
+/// SOCs above 100%.
+pub fn adversarial_curve() -> impl Strategy<Value = Curve> {
+    vec(any::<(u16, u16)>(), 0..=MAX_CURVE_POINTS).prop_map(|raw| {
+        let points: Vec<CurvePoint> = raw
+            .into_iter()
+            .map(|(voltage_mv, soc_tenth)| CurvePoint {
+                voltage_mv,
+                soc_tenth,
+            })
+            .collect();
+        Curve::new(&points)
+    })
+}
+
+/// Configurations with realistic compensation and input parameters
+///
+/// Either compensation may be enabled. The divider ratio is 1 so that
+/// [`valid_voltage()`] values are cell voltages; gain and offset stay
+/// within a few percent of identity. The output policy is
+/// [`OutputPolicy::Clamp`].
+pub fn valid_config() -> impl Strategy<Value = EstimatorConfig> {
+    (
+        any::<(bool, bool)>(),
+        fixed_in(Fixed::from_num(-20), Fixed::from_num(60)),
+        fixed_in(Fixed::ZERO, Fixed::from_num(0.02)),
+        fixed_in(Fixed::ZERO, Fixed::from_num(10)),
+        fixed_in(Fixed::ZERO, Fixed::from_num(0.05)),
+        fixed_in(Fixed::from_num(0.95), Fixed::from_num(1.05)),
+        fixed_in(Fixed::from_num(-0.05), Fixed::from_num(0.05)),
+        fixed_in(Fixed::ZERO, Fixed::from_num(10_000)),
+    )
+        .prop_map(
+            |(
+                (temperature, aging),
+                nominal_temperature,
+                coefficient,
+                age_years,
+                aging_factor,
+                gain,
+                offset,
+                capacity_mah,
+            )| {
+                let mut config = EstimatorConfig::default()
+                    .with_nominal_temperature(nominal_temperature)
+                    .with_temperature_coefficient(coefficient)
+                    .with_age_years(age_years)
+                    .with_aging_factor(aging_factor)
+                    .with_input_gain(gain)
+                    .with_input_offset(offset)
+                    .with_capacity_mah(capacity_mah);
+                if temperature {
+                    config = config.with_temperature_compensation();
+                }
+                if aging {
+                    config = config.with_aging_compensation();
+                }
+                config
+            },
+        )
+}
+
+/// Configurations with any value in every field
+pub fn adversarial_config() -> impl Strategy<Value = EstimatorConfig> {
+    (
+        any::<(bool, bool)>(),
+        [
+            any_fixed(),
+            any_fixed(),
+            any_fixed(),
+            any_fixed(),
+            any_fixed(),
+        ],
+        [any_fixed(), any_fixed(), any_fixed(), any_fixed()],
+        prop_oneof![
+            Just(OutputPolicy::Clamp),
+            Just(OutputPolicy::Reject),
+            Just(OutputPolicy::Raw),
+        ],
+    )
+        .prop_map(
+            |((temperature, aging), compensation, input, output_policy)| {
+                let mut config = EstimatorConfig::default();
+                if temperature {
+                    config = config.with_temperature_compensation();
+                }
+                if aging {
+                    config = config.with_aging_compensation();
+                }
+                config.nominal_temperature = compensation[0];
+                config.temperature_coefficient = compensation[1];
+                config.age_years = compensation[2];
+                config.aging_factor = compensation[3];
+                config.internal_resistance = compensation[4];
+                config.divider_ratio = input[0];
+                config.input_gain = input[1];
+                config.input_offset = input[2];
+                config.capacity_mah = input[3];
+                config.output_policy = output_policy;
+                config
+            },
+        )
+}
+
+/// Cell voltages from 2.5 V to 4.5 V
+pub fn valid_voltage() -> impl Strategy<Value = Fixed> {
+    fixed_in(
+        Fixed::from_num(VALID_MIN_MV) / 1000,
+        Fixed::from_num(VALID_MAX_MV) / 1000,
+    )
+}
+
+/// Temperatures from -40 °C to 85 °C
+pub fn valid_temperature() -> impl Strategy<Value = Fixed> {
+    fixed_in(Fixed::from_num(-40), Fixed::from_num(85))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SocEstimator;
+
+    const SOC_FULL: Fixed = Fixed::from_bits(100 << 16);
+
+    proptest! {
+        #[test]
+        fn test_valid_curve_is_sorted(curve in valid_curve()) {
+            prop_assert!(curve.validate_sorted().is_ok());
+            prop_assert!(curve.len() >= 2);
+        }
+
+        #[test]
+        fn test_soc_within_range(
+            chemistry in chemistry(),
+            config in valid_config(),
+            voltage in valid_voltage(),
+            temperature in valid_temperature(),
+        ) {
+            let estimator = SocEstimator::with_config(chemistry, config)
+                .with_operating_temperature_range(Fixed::from_num(-40), Fixed::from_num(85));
+            let soc = estimator.estimate_soc_compensated_fixed(voltage, temperature)?;
+            prop_assert!((Fixed::ZERO..=SOC_FULL).contains(&soc));
+        }
+
+        #[test]
+        fn test_clamped_estimates_within_range(
+            chemistry in chemistry(),
+            config in valid_config(),
+            voltage in any_fixed(),
+            temperature in valid_temperature(),
+        ) {
+            let config = config.with_output_policy(OutputPolicy::Clamp);
+            let estimator = SocEstimator::with_config(chemistry, config)
+                .with_operating_temperature_range(Fixed::from_num(-40), Fixed::from_num(85));
+            let socs = [
+                estimator.estimate_soc_fixed(voltage)?,
+                estimator.estimate_soc_with_temp_fixed(voltage, temperature)?,
+                estimator.estimate_soc_compensated_fixed(voltage, temperature)?,
+                estimator.estimate_soc_under_load_fixed(voltage, Fixed::from_num(-1000))?,
+                estimator.estimate_soc_batch(&[voltage])?.soc,
+                Fixed::from_num(estimator.estimate_soc_permille(voltage.saturating_mul_int(1000).saturating_to_num())?) / 10,
+            ];
+            for soc in socs {
+                prop_assert!((Fixed::ZERO..=SOC_FULL).contains(&soc));
+            }
+        }
+
+        #[test]
+        fn test_adversarial_inputs_do_not_panic(
+            curve in adversarial_curve(),
+            config in adversarial_config(),
+            voltage in any_fixed(),
+            temperature in any_fixed(),
+        ) {
+            let _ = curve.voltage_to_soc_fixed(voltage);
+            let estimator = SocEstimator::with_config(BatteryChemistry::LiPo, config);
+            let _ = estimator.estimate_soc_compensated_fixed(voltage, temperature);
+        }
+    }
+}
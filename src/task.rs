@@ -0,0 +1,286 @@
+//! Periodic gauging task
+//!
+//! Every gauge firmware ends up with the same loop: wait for the next
+//! period, read the ADC, update the tracker and forward events. A
+//! [`GaugeTask`] is that loop, generic over an [`AsyncVoltageSource`], a
+//! [`GaugeTimer`] and an [`EventSink`], so an Embassy or RTIC task body is
+//! a single `run().await`. Voltage filtering is the tracker's own
+//! ([`SocTracker::with_voltage_filter()`]).
+
+use core::future::Future;
+
+use crate::{AsyncVoltageSource, Error, EventSink, Fixed, GaugeEvent, SocTracker};
+
+/// Source of the gauging period
+///
+/// Implemented over the executor's timer, e.g. Embassy's `Ticker` or an
+/// RTIC monotonic. Prefer a ticker over a plain delay so that the time
+/// spent sampling does not stretch the period.
+///
+/// # Examples
+///
+/// ```ignore
+/// use battery_estimator::GaugeTimer;
+///
+/// struct Period(embassy_time::Ticker);
+///
+/// impl GaugeTimer for Period {
+///     async fn wait(&mut self) {
+///         self.0.next().await
+///     }
+/// }
+/// ```
+pub trait GaugeTimer {
+    /// Wait until the next period starts
+    fn wait(&mut self) -> impl Future<Output = ()>;
+}
+
+/// Ready-made periodic gauging loop
+///
+/// # Examples
+///
+/// ```ignore
+/// use battery_estimator::{
+///     BatteryChemistry, Fixed, GaugeEvent, GaugeTask, KalmanFilter, SocEstimator, SocTracker,
+///     TrackerConfig,
+/// };
+///
+/// #[embassy_executor::task]
+/// async fn gauge(adc: BatteryAdc<'static>) {
+///     let tracker = SocTracker::new(
+///         SocEstimator::new(BatteryChemistry::LiPo),
+///         TrackerConfig::default().with_capacity_mah(Fixed::from_num(2000)),
+///     )
+///     .with_voltage_filter(KalmanFilter::default());
+///     let ticker = Period(Ticker::every(Duration::from_secs(1)));
+///     let sink = |event: GaugeEvent| EVENTS.try_send(event).ok();
+///
+///     GaugeTask::new(tracker, adc, ticker, sink, Fixed::ONE).run().await
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct GaugeTask<V, T, S> {
+    tracker: SocTracker,
+    source: V,
+    timer: T,
+    sink: S,
+    period: Fixed,
+    current_ma: Fixed,
+}
+
+impl<V: AsyncVoltageSource, T: GaugeTimer, S: EventSink> GaugeTask<V, T, S> {
+    /// Creates a task
+    ///
+    /// # Arguments
+    ///
+    /// * `tracker` - Tracker updated every period
+    /// * `source` - Battery voltage source
+    /// * `timer` - Timer marking the periods
+    /// * `sink` - Receiver of the tracker's events
+    /// * `period` - Period of `timer` in seconds, used as the tracker's time step
+    pub fn new(tracker: SocTracker, source: V, timer: T, sink: S, period: Fixed) -> Self {
+        Self {
+            tracker,
+            source,
+            timer,
+            sink,
+            period,
+            current_ma: Fixed::ZERO,
+        }
+    }
+
+    /// Sets the battery current in mA used for the following periods
+    /// (positive = charging, negative = discharging)
+    #[inline]
+    pub fn set_current_ma(&mut self, current_ma: Fixed) {
+        self.current_ma = current_ma;
+    }
+
+    /// Get the tracker
+    #[inline]
+    pub fn tracker(&self) -> &SocTracker {
+        &self.tracker
+    }
+
+    /// Get the tracker mutably, e.g. to set the temperature
+    #[inline]
+    pub fn tracker_mut(&mut self) -> &mut SocTracker {
+        &mut self.tracker
+    }
+
+    /// Waits for the next period, then samples and updates the tracker
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(soc)` - Tracked SOC percentage
+    /// * `Err(Error)` - The voltage read failed or the sample was rejected
+    ///   (see [`SocTracker::update_with_events()`]); the tracker is unchanged
+    pub async fn step(&mut self) -> Result<Fixed, Error> {
+        self.timer.wait().await;
+        let voltage = self.source.read_voltage().await?;
+        self.tracker
+            .update_with_events(voltage, self.current_ma, self.period, &mut self.sink)
+    }
+
+    /// Runs the loop forever
+    ///
+    /// Failed periods are skipped and reported to the sink: implausible
+    /// samples as [`GaugeEvent::SensorFault`], every other error as
+    /// [`GaugeEvent::SampleFailed`], so a persistently failing source is
+    /// visible. Use [`step()`](Self::step) to handle errors or read the SOC
+    /// after each period.
+    pub async fn run(&mut self) -> ! {
+        loop {
+            self.timer.wait().await;
+            let error = match self.source.read_voltage().await {
+                Ok(voltage) => match self.tracker.update_with_events(
+                    voltage,
+                    self.current_ma,
+                    self.period,
+                    &mut self.sink,
+                ) {
+                    // The tracker already reported rejected samples
+                    Ok(_) | Err(Error::SensorFault) => continue,
+                    Err(error) => error,
+                },
+                Err(error) => error,
+            };
+            self.sink.on_event(GaugeEvent::SampleFailed { error });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BatteryChemistry, SocEstimator, TrackerConfig};
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+
+    struct Ticks(u32);
+
+    impl GaugeTimer for Ticks {
+        async fn wait(&mut self) {
+            self.0 += 1;
+        }
+    }
+
+    struct Readings<'a>(&'a [Fixed]);
+
+    impl AsyncVoltageSource for Readings<'_> {
+        async fn read_voltage(&mut self) -> Result<Fixed, Error> {
+            let (&first, rest) = self.0.split_first().ok_or(Error::SensorFault)?;
+            self.0 = rest;
+            Ok(first)
+        }
+    }
+
+    /// Timer whose every wait is pending once, so a test can stop `run()`
+    struct Yielding(bool);
+
+    impl GaugeTimer for Yielding {
+        fn wait(&mut self) -> impl Future<Output = ()> {
+            core::future::poll_fn(|cx| {
+                self.0 = !self.0;
+                if self.0 {
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                } else {
+                    Poll::Ready(())
+                }
+            })
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn test_task_steps() {
+        let tracker = SocTracker::new(
+            SocEstimator::new(BatteryChemistry::LiPo),
+            TrackerConfig::default()
+                .with_voltage_gain(Fixed::ONE)
+                .with_max_slew_rate(Fixed::from_num(100)),
+        );
+        let readings = [Fixed::from_num(3.75), Fixed::from_num(3.0)];
+        let mut events = 0;
+        let mut last = None;
+        let mut sink = |event| {
+            events += 1;
+            last = Some(event);
+        };
+        let mut task = GaugeTask::new(
+            tracker,
+            Readings(&readings),
+            Ticks(0),
+            &mut sink,
+            Fixed::ONE,
+        );
+        task.set_current_ma(Fixed::from_num(-100));
+
+        assert_eq!(block_on(task.step()), Ok(Fixed::from_num(60)));
+        assert_eq!(block_on(task.step()), Ok(Fixed::ZERO));
+        assert_eq!(block_on(task.step()), Err(Error::SensorFault));
+        assert_eq!(task.timer.0, 3);
+        assert_eq!(task.tracker().soc(), Some(Fixed::ZERO));
+        // Low and critical threshold crossings, then empty
+        assert_eq!(events, 3);
+        assert_eq!(last, Some(GaugeEvent::Empty));
+    }
+
+    #[test]
+    fn test_task_run_reports_errors() {
+        let tracker = SocTracker::new(
+            SocEstimator::new(BatteryChemistry::LiPo),
+            TrackerConfig::default().with_max_voltage_step(Fixed::from_num(0.3)),
+        );
+        let readings = [Fixed::from_num(3.75), Fixed::from_num(0.5)];
+        let mut events = [None; 4];
+        let mut count = 0;
+        let mut sink = |event| {
+            events[count] = Some(event);
+            count += 1;
+        };
+        {
+            let mut task = GaugeTask::new(
+                tracker,
+                Readings(&readings),
+                Yielding(false),
+                &mut sink,
+                Fixed::ONE,
+            );
+
+            // Four periods: a good sample, a rejected one, then two reads
+            // from the exhausted source
+            let mut run = pin!(task.run());
+            let mut cx = Context::from_waker(Waker::noop());
+            for _ in 0..5 {
+                assert!(run.as_mut().poll(&mut cx).is_pending());
+            }
+        }
+
+        assert_eq!(
+            events,
+            [
+                Some(GaugeEvent::SensorFault {
+                    voltage: Fixed::from_num(0.5)
+                }),
+                Some(GaugeEvent::SampleFailed {
+                    error: Error::SensorFault
+                }),
+                Some(GaugeEvent::SampleFailed {
+                    error: Error::SensorFault
+                }),
+                None,
+            ]
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! Compact telemetry payloads
+//!
+//! LoRaWAN at low data rates carries as little as 11 bytes per uplink, and
+//! NB-IoT plans are billed by the byte. A [`TelemetryReport`] holds the
+//! values a backend needs to track a battery — SOC, voltage, temperature
+//! and status flags — in integer units, and encodes them with
+//! [postcard](https://docs.rs/postcard) into at most
+//! [`TelemetryReport::MAX_SIZE`] bytes. The same type decodes the payload
+//! on the host, so both ends share one definition.
+
+use crate::sbs::{saturate_i16, saturate_u16};
+use crate::{Error, Fixed, SocTracker};
+
+/// 100% as a fixed-point constant
+const SOC_FULL: Fixed = Fixed::from_bits(100 << 16);
+
+/// Battery status report for constrained uplinks
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{Fixed, TelemetryReport};
+///
+/// let report = TelemetryReport::new(
+///     Fixed::from_num(3.87),  // voltage
+///     Fixed::from_num(21.5),  // temperature in °C
+///     Fixed::from_num(72.4),  // SOC in percent
+///     TelemetryReport::FLAG_CHARGING,
+/// );
+///
+/// let mut buffer = [0u8; TelemetryReport::MAX_SIZE];
+/// let payload = report.encode(&mut buffer).unwrap();
+/// assert!(payload.len() <= 8);
+///
+/// // On the backend
+/// let decoded = TelemetryReport::decode(payload).unwrap();
+/// assert_eq!(decoded, report);
+/// assert_eq!(decoded.soc_tenth, 724);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct TelemetryReport {
+    /// SOC in tenths of a percent (0 to 1000)
+    pub soc_tenth: u16,
+    /// Voltage in mV
+    pub voltage_mv: u16,
+    /// Temperature in tenths of a °C
+    pub temperature_tenth: i16,
+    /// Status flags (`FLAG_*` constants)
+    pub flags: u8,
+}
+
+impl TelemetryReport {
+    /// Largest encoded size in bytes
+    ///
+    /// Integers are varint-encoded, so typical reports are shorter.
+    pub const MAX_SIZE: usize = 10;
+
+    /// The SOC is below the low threshold
+    pub const FLAG_LOW: u8 = 0x01;
+    /// The SOC is below the critical threshold
+    pub const FLAG_CRITICAL: u8 = 0x02;
+    /// The battery is charging
+    pub const FLAG_CHARGING: u8 = 0x04;
+    /// The last voltage reading was rejected as implausible
+    pub const FLAG_SENSOR_FAULT: u8 = 0x08;
+
+    /// Creates a report
+    ///
+    /// # Arguments
+    ///
+    /// * `voltage` - Battery voltage in volts
+    /// * `temperature` - Battery temperature in °C
+    /// * `soc` - SOC in percent (clamped to 0-100)
+    /// * `flags` - Status flags (`FLAG_*` constants)
+    pub fn new(voltage: Fixed, temperature: Fixed, soc: Fixed, flags: u8) -> Self {
+        let soc = soc.clamp(Fixed::ZERO, SOC_FULL);
+        Self {
+            soc_tenth: saturate_u16(soc.to_bits() as i64 * 10),
+            voltage_mv: saturate_u16(voltage.to_bits() as i64 * 1000),
+            temperature_tenth: saturate_i16(temperature.saturating_mul_int(10)),
+            flags,
+        }
+    }
+
+    /// Creates a report from a tracker's state
+    ///
+    /// The low and critical flags follow the tracker's SOC thresholds; the
+    /// charging flag is set for a positive current.
+    ///
+    /// # Returns
+    ///
+    /// `None` until the tracker has processed its first sample.
+    pub fn from_tracker(tracker: &SocTracker, voltage: Fixed, current_ma: Fixed) -> Option<Self> {
+        let soc = tracker.soc()?;
+        let config = tracker.config();
+
+        let mut flags = 0;
+        if soc < config.low_soc_threshold {
+            flags |= Self::FLAG_LOW;
+        }
+        if soc < config.critical_soc_threshold {
+            flags |= Self::FLAG_CRITICAL;
+        }
+        if current_ma > Fixed::ZERO {
+            flags |= Self::FLAG_CHARGING;
+        }
+        if tracker.is_sensor_fault() {
+            flags |= Self::FLAG_SENSOR_FAULT;
+        }
+        Some(Self::new(voltage, tracker.temperature(), soc, flags))
+    }
+
+    /// SOC in percent
+    #[inline]
+    pub fn soc(&self) -> Fixed {
+        Fixed::from_num(self.soc_tenth) / 10
+    }
+
+    /// Voltage in volts
+    #[inline]
+    pub fn voltage(&self) -> Fixed {
+        Fixed::from_num(self.voltage_mv) / 1000
+    }
+
+    /// Temperature in °C
+    #[inline]
+    pub fn temperature(&self) -> Fixed {
+        Fixed::from_num(self.temperature_tenth) / 10
+    }
+
+    /// Returns `true` if all bits of `flag` are set
+    #[inline]
+    pub const fn has_flag(&self, flag: u8) -> bool {
+        self.flags & flag == flag
+    }
+
+    /// Encodes the report into `buffer`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(payload)` - The used part of `buffer`
+    /// * `Err(Error::InvalidConfig)` - `buffer` is too small; a buffer of
+    ///   [`MAX_SIZE`](Self::MAX_SIZE) bytes always suffices
+    pub fn encode<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a mut [u8], Error> {
+        postcard::to_slice(self, buffer).map_err(|_| Error::InvalidConfig)
+    }
+
+    /// Decodes a payload produced by [`encode()`](Self::encode)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(report)` - The decoded report
+    /// * `Err(Error::InvalidState)` - The payload is truncated or corrupt
+    pub fn decode(payload: &[u8]) -> Result<Self, Error> {
+        postcard::from_bytes(payload).map_err(|_| Error::InvalidState)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BatteryChemistry, SocEstimator, TrackerConfig};
+
+    #[test]
+    fn test_report_round_trip() {
+        let report = TelemetryReport::new(
+            Fixed::from_num(65),
+            Fixed::from_num(-40.5),
+            Fixed::from_num(150),
+            0xFF,
+        );
+        assert_eq!(report.soc_tenth, 1000);
+        assert_eq!(report.voltage_mv, 65000);
+        assert_eq!(report.temperature_tenth, -405);
+
+        let mut buffer = [0u8; TelemetryReport::MAX_SIZE];
+        let payload = report.encode(&mut buffer).unwrap();
+        assert!(payload.len() <= TelemetryReport::MAX_SIZE);
+        assert_eq!(TelemetryReport::decode(payload), Ok(report));
+
+        let len = payload.len();
+        assert_eq!(
+            TelemetryReport::decode(&buffer[..len - 2]),
+            Err(Error::InvalidState)
+        );
+        assert_eq!(report.encode(&mut [0u8; 3]), Err(Error::InvalidConfig));
+    }
+
+    #[test]
+    fn test_report_from_tracker() {
+        let mut tracker = SocTracker::new(
+            SocEstimator::new(BatteryChemistry::LiPo),
+            TrackerConfig::default(),
+        );
+        let voltage = Fixed::from_num(3.3);
+        assert_eq!(
+            TelemetryReport::from_tracker(&tracker, voltage, Fixed::ZERO),
+            None
+        );
+
+        tracker.update(voltage, Fixed::ZERO, Fixed::ONE).unwrap();
+        let report =
+            TelemetryReport::from_tracker(&tracker, voltage, Fixed::from_num(100)).unwrap();
+        assert_eq!(report.voltage_mv, 3300);
+        assert_eq!(report.temperature(), Fixed::from_num(25));
+        assert!(report.has_flag(TelemetryReport::FLAG_LOW | TelemetryReport::FLAG_CHARGING));
+        assert!(!report.has_flag(TelemetryReport::FLAG_SENSOR_FAULT));
+    }
+}
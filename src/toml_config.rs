@@ -0,0 +1,298 @@
+//! TOML deployment files
+//!
+//! Gateways and edge devices are often configured by operators who edit a
+//! file rather than firmware. A [`DeploymentFile`] describes everything
+//! needed to estimate a battery — chemistry or custom curve, compensation
+//! and, for packs, the topology — and [`Deployment::load()`] turns it into
+//! ready-to-use estimators in one call.
+//!
+//! The chemistry and configuration tables use the same fields as the
+//! [JSON provisioning format](crate::EstimatorDocument):
+//!
+//! ```toml
+//! schema_version = 1
+//!
+//! [chemistry]
+//! name = "LiIon"
+//!
+//! [config]
+//! capacity_mah = 3000.0
+//! divider_ratio = 2.0
+//!
+//! [config.temperature_compensation]
+//! nominal_temperature = 25.0
+//! coefficient = 0.005
+//!
+//! [pack]
+//! series = 4
+//! parallel = 2
+//! cell_capacity_mah = 3000.0
+//! imbalance_threshold = 0.05
+//! ```
+//!
+//! The `pack` table is optional; `parallel` defaults to 1, the other pack
+//! fields to their [`PackConfig::default()`] values.
+
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ChemistryDescriptor, ConfigDescriptor, Error, EstimatorDocument, Fixed, OwnedEstimator,
+    PackConfig, PackEstimator, PackTopology, SCHEMA_VERSION,
+};
+
+/// Converts a TOML number into fixed-point, rejecting values out of range
+fn to_fixed(value: f64) -> Result<Fixed, Error> {
+    Fixed::checked_from_num(value).ok_or(Error::InvalidConfig)
+}
+
+/// Default parallel count of a [`PackDescriptor`]
+fn one() -> u8 {
+    1
+}
+
+/// Pack topology in natural units
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PackDescriptor {
+    /// Number of cell groups in series
+    pub series: u8,
+    /// Number of cells in each parallel group
+    #[serde(default = "one")]
+    pub parallel: u8,
+    /// Nominal capacity of a single cell in mAh (0 disables capacity outputs)
+    #[serde(default)]
+    pub cell_capacity_mah: f64,
+    /// Cell voltage spread in volts reported as imbalance, `None` for the default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub imbalance_threshold: Option<f64>,
+}
+
+impl PackDescriptor {
+    /// The described pack configuration
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(config)` - The pack configuration
+    /// * `Err(Error::InvalidConfig)` - The series count is zero, or a value
+    ///   is negative or does not fit the fixed-point range
+    pub fn to_config(&self) -> Result<PackConfig, Error> {
+        let cell_capacity_mah = to_fixed(self.cell_capacity_mah)?;
+        if self.series == 0 || cell_capacity_mah < Fixed::ZERO {
+            return Err(Error::InvalidConfig);
+        }
+
+        let mut config = PackConfig::default().with_topology(PackTopology::new(
+            self.series,
+            self.parallel,
+            cell_capacity_mah,
+        ));
+        if let Some(threshold) = self.imbalance_threshold {
+            let threshold = to_fixed(threshold)?;
+            if threshold < Fixed::ZERO {
+                return Err(Error::InvalidConfig);
+            }
+            config = config.with_imbalance_threshold(threshold);
+        }
+        Ok(config)
+    }
+}
+
+/// Contents of a TOML deployment file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeploymentFile {
+    /// Format version, [`SCHEMA_VERSION`]
+    pub schema_version: u32,
+    /// Chemistry or custom curve
+    pub chemistry: ChemistryDescriptor,
+    /// Estimator configuration
+    #[serde(default)]
+    pub config: ConfigDescriptor,
+    /// Pack topology, `None` for a single cell
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pack: Option<PackDescriptor>,
+}
+
+impl DeploymentFile {
+    /// Parses a deployment file
+    ///
+    /// Malformed TOML, unknown fields and unsupported schema versions are
+    /// reported as [`io::ErrorKind::InvalidData`].
+    pub fn from_toml(text: &str) -> io::Result<Self> {
+        let file: Self = ::toml::from_str(text)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        if file.schema_version != SCHEMA_VERSION {
+            return Err(Error::InvalidConfig.into());
+        }
+        Ok(file)
+    }
+
+    /// Creates the described estimators
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(deployment)` - The estimator and pack configuration
+    /// * `Err(Error)` - See [`EstimatorDocument::build()`] and
+    ///   [`PackDescriptor::to_config()`]
+    pub fn build(&self) -> Result<Deployment, Error> {
+        let estimator = EstimatorDocument {
+            schema_version: self.schema_version,
+            chemistry: self.chemistry.clone(),
+            config: self.config,
+        }
+        .build()?;
+        let pack = self
+            .pack
+            .as_ref()
+            .map(PackDescriptor::to_config)
+            .transpose()?;
+        Ok(Deployment { estimator, pack })
+    }
+}
+
+/// Estimators built from a [`DeploymentFile`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Deployment {
+    /// Per-cell estimator, owning its curve
+    pub estimator: OwnedEstimator,
+    /// Pack configuration, `None` for a single cell
+    pub pack: Option<PackConfig>,
+}
+
+impl Deployment {
+    /// Reads and builds a deployment file
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::OnceLock;
+    ///
+    /// use battery_estimator::Deployment;
+    ///
+    /// static DEPLOYMENT: OnceLock<Deployment> = OnceLock::new();
+    ///
+    /// let deployment = Deployment::load("/etc/battery/deployment.toml")?;
+    /// let pack = DEPLOYMENT.get_or_init(|| deployment).pack_estimator::<4>()?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(DeploymentFile::from_toml(&text)?.build()?)
+    }
+
+    /// Pack estimator for a pack of `CELLS` cells in series
+    ///
+    /// Borrows the curve of [`estimator`](Self::estimator), so the
+    /// deployment has to live for the rest of the program; see
+    /// [`OwnedEstimator::estimator()`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(pack)` - The configured pack estimator; a file without a
+    ///   `pack` table yields the default pack configuration
+    /// * `Err(Error::PackMismatch)` - The file describes a different series
+    ///   count
+    pub fn pack_estimator<const CELLS: usize>(
+        &'static self,
+    ) -> Result<PackEstimator<CELLS>, Error> {
+        let config = self.pack.unwrap_or_default();
+        if self.pack.is_some() && config.topology.series as usize != CELLS {
+            return Err(Error::PackMismatch);
+        }
+        Ok(PackEstimator::with_config(
+            self.estimator.estimator(),
+            config,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::OnceLock;
+
+    use super::*;
+    use crate::BatteryChemistry;
+
+    const PACK_FILE: &str = r#"
+        schema_version = 1
+
+        [chemistry]
+        name = "LiIon"
+
+        [config]
+        capacity_mah = 3000.0
+
+        [config.temperature_compensation]
+        nominal_temperature = 25.0
+        coefficient = 0.005
+
+        [pack]
+        series = 4
+        parallel = 2
+        cell_capacity_mah = 3000.0
+    "#;
+
+    #[test]
+    fn test_deployment_pack() {
+        static DEPLOYMENT: OnceLock<Deployment> = OnceLock::new();
+        let deployment = DEPLOYMENT.get_or_init(|| {
+            DeploymentFile::from_toml(PACK_FILE)
+                .unwrap()
+                .build()
+                .unwrap()
+        });
+        assert_eq!(
+            deployment.estimator.curve(),
+            crate::Curve::for_chemistry(BatteryChemistry::LiIon)
+        );
+        assert!(deployment
+            .estimator
+            .config()
+            .is_temperature_compensation_enabled());
+
+        let pack = deployment.pack_estimator::<4>().unwrap();
+        assert_eq!(pack.topology().cell_count(), 8);
+        assert_eq!(pack.topology().pack_capacity_mah(), Fixed::from_num(6000));
+        assert!(matches!(
+            deployment.pack_estimator::<3>(),
+            Err(Error::PackMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_deployment_custom_curve() {
+        let text = r#"
+            schema_version = 1
+
+            [chemistry]
+            name = "sensor-node"
+            curve = [[3.0, 0.0], [3.3, 50.0], [3.6, 100.0]]
+        "#;
+        static DEPLOYMENT: OnceLock<Deployment> = OnceLock::new();
+        let deployment =
+            DEPLOYMENT.get_or_init(|| DeploymentFile::from_toml(text).unwrap().build().unwrap());
+        assert_eq!(deployment.estimator.curve().len(), 3);
+        assert_eq!(deployment.pack, None);
+        assert_eq!(
+            deployment
+                .estimator
+                .estimator()
+                .estimate_soc_fixed(Fixed::from_num(3.3)),
+            Ok(Fixed::from_num(50))
+        );
+    }
+
+    #[test]
+    fn test_deployment_errors() {
+        assert!(DeploymentFile::from_toml("schema_version = 1").is_err());
+        assert!(DeploymentFile::from_toml(&PACK_FILE.replace("parallel", "paralel")).is_err());
+        assert!(DeploymentFile::from_toml(&PACK_FILE.replace("= 1\n", "= 2\n")).is_err());
+
+        let file =
+            DeploymentFile::from_toml(&PACK_FILE.replace("series = 4", "series = 0")).unwrap();
+        assert_eq!(file.build(), Err(Error::InvalidConfig));
+    }
+}
@@ -0,0 +1,1979 @@
+//! Stateful SOC tracking over time
+//!
+//! [`SocEstimator`] maps a single voltage reading to an SOC value. Real
+//! devices sample continuously, often at irregular intervals, and want a
+//! stable reading that does not jump with every load transient. The
+//! [`SocTracker`] keeps state between samples and combines:
+//!
+//! - **Coulomb counting** - integrates the measured current over the
+//!   elapsed time (requires a configured capacity)
+//! - **Voltage correction** - pulls the estimate towards the voltage-curve
+//!   SOC with a configurable gain per second
+//! - **Slew limiting** - bounds how fast the reported SOC may change
+//! - **Voltage pre-filtering** - optional [`KalmanFilter`] applied to the
+//!   raw voltage before the curve lookup
+//! - **Voltage statistics** - min/max/mean of the raw voltage since reset
+//! - **Plausibility checks** - rejects implausible voltage jumps between
+//!   consecutive samples as sensor faults
+//! - **Events** - threshold crossings, faults, and full/empty transitions
+//!   reported through an [`EventSink`]
+//! - **History** - optional [`SocHistory`] ring buffer of past SOC values
+//!   for trend display and rate-of-change
+//!
+//! All time-dependent terms are scaled by the `dt` passed to
+//! [`SocTracker::update()`], so the behavior is the same whether samples
+//! arrive every 10 ms or every 10 s.
+
+use crate::persist::{crc8, get_fixed, get_i64, put_fixed, put_i64};
+#[cfg(feature = "async")]
+use crate::AsyncVoltageSource;
+use crate::{instrument, logging};
+use crate::{
+    Crossing, Error, ErrorDetail, EventSink, Fixed, GaugeEvent, KalmanFilter, Percent, SocEstimate,
+    SocEstimator, SocHistory, VoltageSource, Volts,
+};
+
+/// 100% as a fixed-point constant
+const SOC_FULL: Fixed = Fixed::from_bits(100 << 16);
+
+/// Seconds per hour, used to convert mA·s into mAh
+const SECONDS_PER_HOUR: i64 = 3600;
+
+/// Format version written into saved state blobs
+const STATE_VERSION: u8 = 3;
+
+/// State flag: the SOC field holds a valid estimate
+const STATE_FLAG_SOC_VALID: u8 = 0x01;
+
+/// State flag: the voltage filter fields hold a valid estimate
+const STATE_FLAG_FILTER_VALID: u8 = 0x02;
+
+/// Running statistics of the raw voltage seen by a tracker
+///
+/// Useful for diagnostics: a minimum far below the cutoff voltage points
+/// to brown-outs under load, a maximum above the full-charge voltage to
+/// overcharge events. The tracker records every sample, including those it
+/// rejects as implausible or out of range, so such events are not hidden
+/// by the rejection.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{Fixed, VoltageStats};
+///
+/// let mut stats = VoltageStats::new();
+/// stats.record(Fixed::from_num(3.5));
+/// stats.record(Fixed::from_num(4.0));
+///
+/// assert_eq!(stats.min(), Some(Fixed::from_num(3.5)));
+/// assert_eq!(stats.max(), Some(Fixed::from_num(4.0)));
+/// assert_eq!(stats.mean(), Some(Fixed::from_num(3.75)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VoltageStats {
+    /// Lowest voltage recorded
+    min: Fixed,
+    /// Highest voltage recorded
+    max: Fixed,
+    /// Sum of recorded voltages (I16F16 bits, widened to avoid overflow)
+    sum_bits: i64,
+    /// Number of recorded voltages
+    count: u32,
+}
+
+impl VoltageStats {
+    /// Creates empty statistics
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            min: Fixed::MAX,
+            max: Fixed::MIN,
+            sum_bits: 0,
+            count: 0,
+        }
+    }
+
+    /// Records a voltage sample
+    ///
+    /// Once `u32::MAX` samples have been recorded, further samples still
+    /// update min/max but no longer change the mean.
+    pub fn record(&mut self, voltage: Fixed) {
+        self.min = self.min.min(voltage);
+        self.max = self.max.max(voltage);
+        if self.count < u32::MAX {
+            // At most u32::MAX samples keep the sum within 2^63
+            self.sum_bits = self.sum_bits.saturating_add(voltage.to_bits() as i64);
+            self.count += 1;
+        }
+    }
+
+    /// Returns the lowest recorded voltage, if any
+    #[inline]
+    pub fn min(&self) -> Option<Fixed> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    /// Returns the highest recorded voltage, if any
+    #[inline]
+    pub fn max(&self) -> Option<Fixed> {
+        (self.count > 0).then_some(self.max)
+    }
+
+    /// Returns the mean recorded voltage, if any
+    #[inline]
+    pub fn mean(&self) -> Option<Fixed> {
+        (self.count > 0).then(|| Fixed::from_bits((self.sum_bits / self.count as i64) as i32))
+    }
+
+    /// Returns the number of recorded samples
+    #[inline]
+    pub const fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Clears all statistics
+    #[inline]
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl Default for VoltageStats {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SOC tracker configuration
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{Fixed, TrackerConfig};
+///
+/// let config = TrackerConfig::default()
+///     .with_capacity_mah(Fixed::from_num(2000))
+///     .with_max_slew_rate(Fixed::from_num(0.5));
+///
+/// assert!(config.is_coulomb_counting_enabled());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrackerConfig {
+    /// Nominal battery capacity in mAh as fixed-point (0 disables coulomb counting)
+    pub capacity_mah: Fixed,
+    /// Fraction of the gap to the voltage-based SOC closed per second (0.0-1.0)
+    pub voltage_gain: Fixed,
+    /// Maximum change of the reported SOC in percent per second
+    pub max_slew_rate: Fixed,
+    /// Self-discharge while powered off in percent per day (used by warm start)
+    pub self_discharge_rate: Fixed,
+    /// Largest plausible voltage change between consecutive samples in volts
+    /// (0 disables the check)
+    pub max_voltage_step: Fixed,
+    /// Low-battery SOC threshold in percent for threshold events (0 disables)
+    pub low_soc_threshold: Fixed,
+    /// Critical-battery SOC threshold in percent for threshold events (0 disables)
+    pub critical_soc_threshold: Fixed,
+}
+
+impl TrackerConfig {
+    /// Default configuration
+    ///
+    /// - Coulomb counting disabled (capacity 0 mAh)
+    /// - Voltage gain: 0.05 per second (~20 s time constant)
+    /// - Maximum slew rate: 1.0 %/s
+    /// - Self-discharge: 0.1 %/day (~3% per month, typical for Li-ion/LiPo)
+    /// - Plausibility check disabled
+    /// - Low/critical SOC thresholds: 20% / 5%
+    #[inline]
+    pub const fn default() -> Self {
+        Self {
+            capacity_mah: Fixed::ZERO,
+            voltage_gain: Fixed::from_bits(3277),        // 0.05
+            max_slew_rate: Fixed::from_bits(1 << 16),    // 1.0
+            self_discharge_rate: Fixed::from_bits(6554), // 0.1
+            max_voltage_step: Fixed::ZERO,
+            low_soc_threshold: Fixed::from_bits(20 << 16),
+            critical_soc_threshold: Fixed::from_bits(5 << 16),
+        }
+    }
+
+    /// Set nominal capacity (enables coulomb counting when > 0)
+    #[inline]
+    pub fn with_capacity_mah(mut self, capacity_mah: Fixed) -> Self {
+        self.capacity_mah = capacity_mah;
+        self
+    }
+
+    /// Set voltage correction gain per second
+    #[inline]
+    pub fn with_voltage_gain(mut self, gain: Fixed) -> Self {
+        self.voltage_gain = gain;
+        self
+    }
+
+    /// Set maximum slew rate in percent per second
+    #[inline]
+    pub fn with_max_slew_rate(mut self, rate: Fixed) -> Self {
+        self.max_slew_rate = rate;
+        self
+    }
+
+    /// Set self-discharge rate in percent per day
+    #[inline]
+    pub fn with_self_discharge_rate(mut self, rate: Fixed) -> Self {
+        self.self_discharge_rate = rate;
+        self
+    }
+
+    /// Set the largest plausible voltage step between consecutive samples
+    ///
+    /// A value around 0.3 V catches loose connections and ADC glitches on
+    /// single cells without tripping on normal load transients.
+    #[inline]
+    pub fn with_max_voltage_step(mut self, step: Fixed) -> Self {
+        self.max_voltage_step = step;
+        self
+    }
+
+    /// Set the low-battery SOC threshold in percent
+    #[inline]
+    pub fn with_low_soc_threshold(mut self, threshold: Fixed) -> Self {
+        self.low_soc_threshold = threshold;
+        self
+    }
+
+    /// Set the critical-battery SOC threshold in percent
+    #[inline]
+    pub fn with_critical_soc_threshold(mut self, threshold: Fixed) -> Self {
+        self.critical_soc_threshold = threshold;
+        self
+    }
+
+    /// Returns `true` if a capacity is configured and current is integrated
+    #[inline]
+    pub fn is_coulomb_counting_enabled(&self) -> bool {
+        self.capacity_mah > Fixed::ZERO
+    }
+}
+
+// Non-const Default implementation
+impl Default for TrackerConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::default()
+    }
+}
+
+/// Stateful SOC tracker
+///
+/// Wraps an [`SocEstimator`] and keeps the SOC estimate, the integrated
+/// charge, and the current temperature between samples.
+///
+/// # Current Sign Convention
+///
+/// Current is given in mA and is **positive when charging** and
+/// **negative when discharging**.
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{BatteryChemistry, Fixed, SocEstimator, SocTracker, TrackerConfig};
+///
+/// let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+/// let config = TrackerConfig::default().with_capacity_mah(Fixed::from_num(2000));
+/// let mut tracker = SocTracker::new(estimator, config);
+///
+/// // First sample initializes from the voltage curve
+/// let soc = tracker
+///     .update(Fixed::from_num(3.7), Fixed::ZERO, Fixed::ZERO)
+///     .unwrap();
+/// assert!(soc > Fixed::from_num(45.0) && soc < Fixed::from_num(55.0));
+///
+/// // Discharging at 500 mA for 1.5 s
+/// let soc = tracker
+///     .update(Fixed::from_num(3.69), Fixed::from_num(-500), Fixed::from_num(1.5))
+///     .unwrap();
+/// assert!(soc < Fixed::from_num(55.0));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SocTracker {
+    estimator: SocEstimator,
+    config: TrackerConfig,
+    /// Battery temperature used for compensated estimation
+    temperature: Fixed,
+    /// Current SOC estimate, `None` until the first sample
+    soc: Option<Fixed>,
+    /// Net charge integrated since reset in mAh (positive = charged)
+    charge_mah: Fixed,
+    /// Net charge integrated since reset as 48.16 fixed-point mA·s
+    ///
+    /// Accumulated at full precision so that small per-sample increments
+    /// are not lost; `charge_mah` and the coulomb-counted SOC are derived
+    /// from this running total.
+    charge_mas_bits: i64,
+    /// Optional pre-filter applied to the raw voltage
+    voltage_filter: Option<KalmanFilter>,
+    /// Raw voltage statistics since reset
+    voltage_stats: VoltageStats,
+    /// Previous raw voltage, used for the plausibility check
+    last_voltage: Option<Fixed>,
+    /// `true` if the most recent sample was rejected as implausible
+    sensor_fault: bool,
+    /// Optional history of past SOC values
+    history: Option<SocHistory>,
+    /// Time elapsed since reset as 16.16 fixed-point seconds
+    elapsed_bits: u64,
+    /// Curve segment of the previous sample, if segment caching is enabled
+    segment: Option<usize>,
+}
+
+impl SocTracker {
+    /// Create a new tracker
+    ///
+    /// The temperature starts at the estimator's nominal temperature.
+    pub const fn new(estimator: SocEstimator, config: TrackerConfig) -> Self {
+        Self {
+            temperature: estimator.config().nominal_temperature,
+            estimator,
+            config,
+            soc: None,
+            charge_mah: Fixed::ZERO,
+            charge_mas_bits: 0,
+            voltage_filter: None,
+            voltage_stats: VoltageStats::new(),
+            last_voltage: None,
+            sensor_fault: false,
+            history: None,
+            elapsed_bits: 0,
+            segment: None,
+        }
+    }
+
+    /// Apply a Kalman pre-filter to the raw voltage before the curve lookup
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{
+    ///     BatteryChemistry, Fixed, KalmanFilter, SocEstimator, SocTracker, TrackerConfig,
+    /// };
+    ///
+    /// let tracker = SocTracker::new(
+    ///     SocEstimator::new(BatteryChemistry::LiPo),
+    ///     TrackerConfig::default(),
+    /// )
+    /// .with_voltage_filter(KalmanFilter::new(Fixed::from_num(0.25), Fixed::from_num(100)));
+    ///
+    /// assert!(tracker.voltage_filter().is_some());
+    /// ```
+    #[inline]
+    pub fn with_voltage_filter(mut self, filter: KalmanFilter) -> Self {
+        self.voltage_filter = Some(filter);
+        self
+    }
+
+    /// Remember the curve segment of each sample
+    ///
+    /// Consecutive samples of a slowly changing voltage almost always fall
+    /// into the same curve segment. With caching enabled, a sample that is
+    /// still bracketed by the previous segment is interpolated without any
+    /// search; otherwise the segment is searched as usual. Results are
+    /// identical either way.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, Fixed, SocEstimator, SocTracker, TrackerConfig};
+    ///
+    /// let mut tracker = SocTracker::new(
+    ///     SocEstimator::new(BatteryChemistry::LiPo),
+    ///     TrackerConfig::default(),
+    /// )
+    /// .with_segment_cache();
+    ///
+    /// assert!(tracker.is_segment_cache_enabled());
+    /// tracker.update(Fixed::from_num(3.75), Fixed::ZERO, Fixed::ONE).unwrap();
+    /// ```
+    #[inline]
+    pub fn with_segment_cache(mut self) -> Self {
+        self.segment = Some(0);
+        self
+    }
+
+    /// Returns `true` if the curve segment of each sample is cached
+    #[inline]
+    pub const fn is_segment_cache_enabled(&self) -> bool {
+        self.segment.is_some()
+    }
+
+    /// Record the tracked SOC into a history ring buffer
+    ///
+    /// Entries are timestamped with the time accumulated from the `dt`
+    /// values passed to [`update()`](Self::update) since the last reset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{
+    ///     BatteryChemistry, Fixed, SocEstimator, SocHistory, SocTracker, TrackerConfig,
+    /// };
+    ///
+    /// let mut tracker = SocTracker::new(
+    ///     SocEstimator::new(BatteryChemistry::LiPo),
+    ///     TrackerConfig::default(),
+    /// )
+    /// .with_history(SocHistory::new(1000));
+    ///
+    /// for _ in 0..10 {
+    ///     tracker
+    ///         .update(Fixed::from_num(3.75), Fixed::ZERO, Fixed::from_num(0.5))
+    ///         .unwrap();
+    /// }
+    ///
+    /// // One entry per second of tracked time
+    /// assert_eq!(tracker.history().unwrap().len(), 5);
+    /// assert_eq!(tracker.soc_rate_per_hour(), Some(Fixed::ZERO));
+    /// ```
+    #[inline]
+    pub fn with_history(mut self, history: SocHistory) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Create a tracker resuming from a previously stored SOC
+    ///
+    /// Instead of snapping to the voltage curve on the first sample, the
+    /// tracker starts from `stored_soc` reduced by the configured
+    /// self-discharge over `off_hours`, and then converges towards the
+    /// voltage-based SOC at the usual gain and slew rate. This avoids a
+    /// visible jump when the first reading after power-up is distorted by
+    /// load or relaxation effects.
+    ///
+    /// # Arguments
+    ///
+    /// * `estimator` - Underlying voltage-based estimator
+    /// * `config` - Tracker configuration
+    /// * `stored_soc` - SOC saved before power-off (clamped to 0-100)
+    /// * `off_hours` - Time spent powered off in hours (negative values are treated as 0)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, Fixed, SocEstimator, SocTracker, TrackerConfig};
+    ///
+    /// let config = TrackerConfig::default().with_self_discharge_rate(Fixed::from_num(0.5));
+    /// let tracker = SocTracker::warm_start(
+    ///     SocEstimator::new(BatteryChemistry::LiPo),
+    ///     config,
+    ///     Fixed::from_num(80.0),
+    ///     Fixed::from_num(48), // 2 days
+    /// );
+    ///
+    /// assert_eq!(tracker.soc(), Some(Fixed::from_num(79.0)));
+    /// ```
+    pub fn warm_start(
+        estimator: SocEstimator,
+        config: TrackerConfig,
+        stored_soc: Fixed,
+        off_hours: Fixed,
+    ) -> Self {
+        let loss = config
+            .self_discharge_rate
+            .max(Fixed::ZERO)
+            .saturating_mul(off_hours.max(Fixed::ZERO))
+            / Fixed::from_num(24);
+        let soc = stored_soc
+            .clamp(Fixed::ZERO, SOC_FULL)
+            .saturating_sub(loss)
+            .max(Fixed::ZERO);
+
+        let mut tracker = Self::new(estimator, config);
+        tracker.soc = Some(soc);
+        tracker
+    }
+
+    /// Process a new sample
+    ///
+    /// # Arguments
+    ///
+    /// * `voltage` - Battery voltage as fixed-point value
+    /// * `current_ma` - Battery current in mA (positive = charging)
+    /// * `dt` - Time elapsed since the previous sample in seconds
+    ///   (negative values are treated as 0)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(soc)` - Tracked SOC percentage (0.0 to 100.0) as fixed-point
+    /// * `Err(Error::SensorFault)` - The voltage jumped by more than
+    ///   `max_voltage_step` since the previous sample; the sample is ignored
+    ///   apart from becoming the reference for the next plausibility check
+    /// * `Err(Error::NumericalError)` - The charge counter or SOC update
+    ///   overflowed the fixed-point range; the SOC is unchanged
+    /// * `Err(Error)` - The voltage-curve lookup failed; the state is unchanged
+    ///
+    /// # Behavior
+    ///
+    /// - The first sample after construction or [`reset()`](Self::reset)
+    ///   initializes the SOC directly from the voltage curve
+    /// - Afterwards the coulomb-counted SOC is corrected towards the voltage
+    ///   SOC by `voltage_gain * dt` (at most fully)
+    /// - The resulting change is limited to `max_slew_rate * dt`
+    ///
+    /// The plausibility check compares each sample with the previous raw
+    /// sample, accepted or not. A genuine level change (e.g., a charger
+    /// being connected) is therefore rejected once and accepted from the
+    /// next consistent sample on.
+    pub fn update(&mut self, voltage: Fixed, current_ma: Fixed, dt: Fixed) -> Result<Fixed, Error> {
+        self.update_with_events(voltage, current_ma, dt, &mut |_: GaugeEvent| {})
+    }
+
+    /// Process a new sample with a typed voltage
+    ///
+    /// Typed variant of [`update()`](Self::update); the current and `dt`
+    /// keep their units of mA and seconds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{
+    ///     BatteryChemistry, Fixed, Percent, SocEstimator, SocTracker, TrackerConfig, Volts,
+    /// };
+    ///
+    /// let mut tracker = SocTracker::new(
+    ///     SocEstimator::new(BatteryChemistry::LiPo),
+    ///     TrackerConfig::default(),
+    /// );
+    ///
+    /// let soc = tracker
+    ///     .track(Volts::from_millivolts(3750), Fixed::ZERO, Fixed::ZERO)
+    ///     .unwrap();
+    /// assert_eq!(soc, Percent::new(Fixed::from_num(60)));
+    /// ```
+    pub fn track(
+        &mut self,
+        voltage: Volts,
+        current_ma: Fixed,
+        dt: Fixed,
+    ) -> Result<Percent, Error> {
+        self.update(voltage.raw(), current_ma, dt).map(Percent::new)
+    }
+
+    /// Like [`update()`](Self::update), but reports the measurement that
+    /// caused an error
+    ///
+    /// The error carries the voltage and the tracker's
+    /// [temperature](Self::temperature) as an
+    /// [`ErrorContext::Measurement`](crate::ErrorContext::Measurement).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{
+    ///     BatteryChemistry, Error, ErrorContext, Fixed, SocEstimator, SocTracker, TrackerConfig,
+    /// };
+    ///
+    /// let config = TrackerConfig::default().with_max_voltage_step(Fixed::from_num(0.2));
+    /// let mut tracker = SocTracker::new(SocEstimator::new(BatteryChemistry::LiPo), config);
+    /// tracker.update_detail(Fixed::from_num(3.8), Fixed::ZERO, Fixed::ONE).unwrap();
+    ///
+    /// // An open sense line reads as 0 V
+    /// let detail = tracker.update_detail(Fixed::ZERO, Fixed::ZERO, Fixed::ONE).unwrap_err();
+    /// assert_eq!(detail.error, Error::SensorFault);
+    /// assert_eq!(
+    ///     detail.context,
+    ///     ErrorContext::Measurement { voltage_mv: 0, temperature_cdeg: 2500 }
+    /// );
+    /// ```
+    pub fn update_detail(
+        &mut self,
+        voltage: Fixed,
+        current_ma: Fixed,
+        dt: Fixed,
+    ) -> Result<Fixed, ErrorDetail> {
+        let temperature = self.temperature;
+        self.update(voltage, current_ma, dt)
+            .map_err(|error| error.at_measurement(voltage, temperature))
+    }
+
+    /// Take a reading from `source` and process it
+    ///
+    /// Same as [`update()`](Self::update) with the voltage read from the
+    /// source. A failed read returns the source's error and leaves the
+    /// tracker unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, Fixed, SocEstimator, SocTracker, TrackerConfig};
+    ///
+    /// let mut tracker = SocTracker::new(
+    ///     SocEstimator::new(BatteryChemistry::LiPo),
+    ///     TrackerConfig::default(),
+    /// );
+    /// let mut adc = || Ok(Fixed::from_num(3.75));
+    ///
+    /// let soc = tracker.sample(&mut adc, Fixed::ZERO, Fixed::ONE).unwrap();
+    /// assert_eq!(soc, Fixed::from_num(60));
+    /// ```
+    pub fn sample<V: VoltageSource + ?Sized>(
+        &mut self,
+        source: &mut V,
+        current_ma: Fixed,
+        dt: Fixed,
+    ) -> Result<Fixed, Error> {
+        let voltage = source.read_voltage()?;
+        self.update(voltage, current_ma, dt)
+    }
+
+    /// Await a reading from `source` and process it
+    ///
+    /// Async variant of [`sample()`](Self::sample). Only the read is
+    /// awaited; the update itself completes without suspending.
+    #[cfg(feature = "async")]
+    pub async fn sample_async<V: AsyncVoltageSource + ?Sized>(
+        &mut self,
+        source: &mut V,
+        current_ma: Fixed,
+        dt: Fixed,
+    ) -> Result<Fixed, Error> {
+        let voltage = source.read_voltage().await?;
+        self.update(voltage, current_ma, dt)
+    }
+
+    /// Process a new sample and report events to `sink`
+    ///
+    /// Behaves exactly like [`update()`](Self::update) and additionally
+    /// emits:
+    ///
+    /// - [`GaugeEvent::SensorFault`] when a sample is rejected as implausible
+    /// - [`GaugeEvent::ThresholdCrossed`] when the tracked SOC crosses the
+    ///   configured low or critical threshold in either direction
+    /// - [`GaugeEvent::Full`] / [`GaugeEvent::Empty`] when the tracked SOC
+    ///   reaches 100% / 0%
+    ///
+    /// The sample that initializes the tracker emits no SOC events, since
+    /// there is no previous value to compare against.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{
+    ///     BatteryChemistry, Fixed, GaugeEvent, SocEstimator, SocTracker, TrackerConfig,
+    /// };
+    ///
+    /// let config = TrackerConfig::default()
+    ///     .with_voltage_gain(Fixed::ONE)
+    ///     .with_max_slew_rate(Fixed::from_num(100));
+    /// let mut tracker = SocTracker::new(SocEstimator::new(BatteryChemistry::LiPo), config);
+    /// let mut full = false;
+    /// let mut sink = |event| {
+    ///     if event == GaugeEvent::Full {
+    ///         full = true;
+    ///     }
+    /// };
+    ///
+    /// tracker
+    ///     .update_with_events(Fixed::from_num(4.0), Fixed::ZERO, Fixed::ONE, &mut sink)
+    ///     .unwrap();
+    /// tracker
+    ///     .update_with_events(Fixed::from_num(4.25), Fixed::ZERO, Fixed::ONE, &mut sink)
+    ///     .unwrap();
+    /// assert!(full);
+    /// ```
+    pub fn update_with_events<S: EventSink + ?Sized>(
+        &mut self,
+        voltage: Fixed,
+        current_ma: Fixed,
+        dt: Fixed,
+        sink: &mut S,
+    ) -> Result<Fixed, Error> {
+        let previous = self.soc;
+        let soc = match self.step(voltage, current_ma, dt) {
+            Ok(soc) => soc,
+            Err(Error::SensorFault) => {
+                sink.on_event(GaugeEvent::SensorFault { voltage });
+                return Err(Error::SensorFault);
+            }
+            Err(e) => return Err(e),
+        };
+
+        if let Some(previous) = previous {
+            // Report crossings in the order the SOC passed them
+            let low = self.config.low_soc_threshold;
+            let critical = self.config.critical_soc_threshold;
+            let (first, second) = if soc < previous {
+                (low.max(critical), low.min(critical))
+            } else {
+                (low.min(critical), low.max(critical))
+            };
+            for threshold in [first, second] {
+                if threshold <= Fixed::ZERO {
+                    continue;
+                }
+                let direction = if previous >= threshold && soc < threshold {
+                    Crossing::Falling
+                } else if previous < threshold && soc >= threshold {
+                    Crossing::Rising
+                } else {
+                    continue;
+                };
+                sink.on_event(GaugeEvent::ThresholdCrossed {
+                    threshold,
+                    soc,
+                    direction,
+                });
+            }
+
+            if previous < SOC_FULL && soc >= SOC_FULL {
+                sink.on_event(GaugeEvent::Full);
+            }
+            if previous > Fixed::ZERO && soc <= Fixed::ZERO {
+                sink.on_event(GaugeEvent::Empty);
+            }
+        }
+
+        Ok(soc)
+    }
+
+    /// Core update step shared by the public update methods
+    ///
+    /// The coulomb counting and the correction use checked arithmetic: a
+    /// result outside the I16F16 range (e.g., a charge counter beyond
+    /// ±32767 mAh) is reported as [`Error::NumericalError`] and leaves the
+    /// SOC and charge counter unchanged. Only a rejected sample updates
+    /// the voltage used for the plausibility check of the next one; every
+    /// sample updates the voltage statistics.
+    fn step(&mut self, voltage: Fixed, current_ma: Fixed, dt: Fixed) -> Result<Fixed, Error> {
+        self.voltage_stats.record(voltage);
+        if let Some(previous_voltage) = self.last_voltage {
+            let max_step = self.config.max_voltage_step;
+            let jump = voltage
+                .checked_sub(previous_voltage)
+                .ok_or(Error::NumericalError)?;
+            if max_step > Fixed::ZERO && (jump > max_step || jump < -max_step) {
+                logging::implausible_sample(voltage, previous_voltage, max_step);
+                // The next sample is checked against this one, so a real
+                // step change is accepted once it proves consistent
+                self.last_voltage = Some(voltage);
+                self.sensor_fault = true;
+                return Err(Error::SensorFault);
+            }
+        }
+
+        let mut filter = self.voltage_filter;
+        let filtered = match filter.as_mut() {
+            Some(filter) => filter.update(voltage),
+            None => voltage,
+        };
+        let voltage_soc = match self.segment.as_mut() {
+            Some(segment) => {
+                self.estimator
+                    .estimate_soc_compensated_near(filtered, self.temperature, segment)?
+            }
+            None => self
+                .estimator
+                .estimate_soc_compensated_fixed(filtered, self.temperature)?,
+        };
+        let dt = dt.max(Fixed::ZERO);
+
+        instrument::ops(1, 1);
+        let delta_mas_bits = (current_ma.to_bits() as i64 * dt.to_bits() as i64) >> 16;
+        let charge_mas_bits = self
+            .charge_mas_bits
+            .checked_add(delta_mas_bits)
+            .ok_or(Error::NumericalError)?;
+        let charge_mah = i32::try_from(charge_mas_bits / SECONDS_PER_HOUR)
+            .map(Fixed::from_bits)
+            .map_err(|_| Error::NumericalError)?;
+
+        let soc = match self.soc {
+            Some(previous) => self.blend(previous, voltage_soc, charge_mas_bits, dt)?,
+            None => voltage_soc,
+        };
+
+        self.last_voltage = Some(voltage);
+        self.sensor_fault = false;
+        self.voltage_filter = filter;
+        self.elapsed_bits = self.elapsed_bits.wrapping_add(dt.to_bits() as u64);
+        self.charge_mah = charge_mah;
+        self.charge_mas_bits = charge_mas_bits;
+        self.set_soc(soc);
+        Ok(soc)
+    }
+
+    /// Combines the coulomb-counted prediction with the voltage estimate
+    ///
+    /// The coulomb-counted SOC change is the difference between the SOC
+    /// equivalents of the running charge total before and after the sample,
+    /// so rounding does not accumulate over many small samples.
+    fn blend(
+        &self,
+        previous: Fixed,
+        voltage_soc: Fixed,
+        charge_mas_bits: i64,
+        dt: Fixed,
+    ) -> Result<Fixed, Error> {
+        instrument::ops(3, 0);
+        let mut predicted = previous;
+        if self.config.is_coulomb_counting_enabled() {
+            instrument::ops(1, 1);
+            // mA·s bits * 100% / (3600 s/h * capacity mAh), in 16.16 percent
+            let divisor = SECONDS_PER_HOUR as i128 * self.config.capacity_mah.to_bits() as i128;
+            let soc_bits = |mas_bits: i64| (mas_bits as i128 * (100 << 16)).div_euclid(divisor);
+            let delta_soc =
+                i32::try_from(soc_bits(charge_mas_bits) - soc_bits(self.charge_mas_bits))
+                    .map(Fixed::from_bits)
+                    .map_err(|_| Error::NumericalError)?;
+            predicted = predicted
+                .checked_add(delta_soc)
+                .ok_or(Error::NumericalError)?;
+        }
+
+        let gain = self
+            .config
+            .voltage_gain
+            .saturating_mul(dt)
+            .clamp(Fixed::ZERO, Fixed::ONE);
+        let corrected = voltage_soc
+            .checked_sub(predicted)
+            .and_then(|error| gain.checked_mul(error))
+            .and_then(|correction| predicted.checked_add(correction))
+            .ok_or(Error::NumericalError)?;
+
+        let max_step = self
+            .config
+            .max_slew_rate
+            .max(Fixed::ZERO)
+            .saturating_mul(dt);
+        let step = corrected
+            .checked_sub(previous)
+            .ok_or(Error::NumericalError)?
+            .max(max_step.saturating_neg())
+            .min(max_step);
+
+        Ok(previous.saturating_add(step).clamp(Fixed::ZERO, SOC_FULL))
+    }
+
+    /// Stores a new SOC estimate and records it into the history
+    fn set_soc(&mut self, soc: Fixed) {
+        self.soc = Some(soc);
+        if let Some(history) = self.history.as_mut() {
+            let timestamp_ms = (self.elapsed_bits.wrapping_mul(1000) >> 16) as u32;
+            history.record(timestamp_ms, soc);
+        }
+    }
+
+    /// Set the battery temperature (°C) used for compensated estimation
+    #[inline]
+    pub fn set_temperature(&mut self, temperature: Fixed) {
+        self.temperature = temperature;
+    }
+
+    /// Returns the battery temperature used for compensated estimation
+    #[inline]
+    pub const fn temperature(&self) -> Fixed {
+        self.temperature
+    }
+
+    /// Returns the tracked SOC, or `None` before the first sample
+    #[inline]
+    pub const fn soc(&self) -> Option<Fixed> {
+        self.soc
+    }
+
+    /// Returns the net charge integrated since reset in mAh (positive = charged)
+    #[inline]
+    pub const fn charge_mah(&self) -> Fixed {
+        self.charge_mah
+    }
+
+    /// Returns the voltage pre-filter, if configured
+    #[inline]
+    pub const fn voltage_filter(&self) -> Option<&KalmanFilter> {
+        self.voltage_filter.as_ref()
+    }
+
+    /// Returns the SOC history, if enabled
+    #[inline]
+    pub const fn history(&self) -> Option<&SocHistory> {
+        self.history.as_ref()
+    }
+
+    /// Returns the average SOC rate of change over the history in %/h
+    ///
+    /// Returns `None` if no history is enabled or it holds fewer than two
+    /// entries.
+    #[inline]
+    pub fn soc_rate_per_hour(&self) -> Option<Fixed> {
+        self.history.as_ref().and_then(SocHistory::rate_per_hour)
+    }
+
+    /// Returns `true` if the most recent sample was rejected as implausible
+    #[inline]
+    pub const fn is_sensor_fault(&self) -> bool {
+        self.sensor_fault
+    }
+
+    /// Returns the raw voltage statistics since the last reset
+    ///
+    /// Rejected samples are included.
+    #[inline]
+    pub const fn voltage_stats(&self) -> &VoltageStats {
+        &self.voltage_stats
+    }
+
+    /// Clears the voltage statistics without touching the SOC estimate
+    #[inline]
+    pub fn reset_voltage_stats(&mut self) {
+        self.voltage_stats.reset();
+    }
+
+    /// Returns the underlying estimator
+    #[inline]
+    pub const fn estimator(&self) -> &SocEstimator {
+        &self.estimator
+    }
+
+    /// Returns the tracker configuration
+    #[inline]
+    pub const fn config(&self) -> &TrackerConfig {
+        &self.config
+    }
+
+    /// Update the tracker configuration
+    #[inline]
+    pub fn update_config(&mut self, config: TrackerConfig) {
+        self.config = config;
+    }
+
+    /// Size in bytes of a saved state blob
+    pub const STATE_SIZE: usize = 27;
+
+    /// Serialize the learned state into a small blob
+    ///
+    /// The blob contains the SOC estimate, the integrated charge, the
+    /// temperature, and the voltage filter state, protected by a version
+    /// byte and a CRC-8. It is meant to be written to flash or RTC RAM
+    /// before deep sleep and passed to [`restore_state()`](Self::restore_state)
+    /// after wake-up or reset. The estimator and tracker configuration are
+    /// not included.
+    ///
+    /// # Layout
+    ///
+    /// All multi-byte values are little-endian; the 4-byte values are I16F16
+    /// bits. The charge is stored at full precision, so a save and restore
+    /// does not round it to whole mAh.
+    ///
+    /// | Offset | Size | Content |
+    /// |--------|------|---------|
+    /// | 0 | 1 | Format version |
+    /// | 1 | 1 | Flags (bit 0: SOC valid, bit 1: filter valid) |
+    /// | 2 | 4 | SOC in percent |
+    /// | 6 | 8 | Integrated charge in mA·s, 16 fractional bits |
+    /// | 14 | 4 | Temperature in °C |
+    /// | 18 | 4 | Filtered voltage in volts |
+    /// | 22 | 4 | Filter variance in mV² |
+    /// | 26 | 1 | CRC-8 (poly 0x07) over all preceding bytes |
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, Fixed, SocEstimator, SocTracker, TrackerConfig};
+    ///
+    /// let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+    /// let mut tracker = SocTracker::new(estimator, TrackerConfig::default());
+    /// tracker.update(Fixed::from_num(3.75), Fixed::ZERO, Fixed::ZERO).unwrap();
+    ///
+    /// let blob = tracker.save_state();
+    ///
+    /// // ... deep sleep ...
+    ///
+    /// let mut restored = SocTracker::new(estimator, TrackerConfig::default());
+    /// restored.restore_state(&blob).unwrap();
+    /// assert_eq!(restored.soc(), tracker.soc());
+    /// ```
+    pub fn save_state(&self) -> [u8; Self::STATE_SIZE] {
+        let mut blob = [0u8; Self::STATE_SIZE];
+        blob[0] = STATE_VERSION;
+
+        if let Some(soc) = self.soc {
+            blob[1] |= STATE_FLAG_SOC_VALID;
+            put_fixed(&mut blob, 2, soc);
+        }
+        put_i64(&mut blob, 6, self.charge_mas_bits);
+        put_fixed(&mut blob, 14, self.temperature);
+
+        if let Some(filter) = self.voltage_filter {
+            if let Some(estimate) = filter.estimate() {
+                blob[1] |= STATE_FLAG_FILTER_VALID;
+                put_fixed(&mut blob, 18, estimate);
+                put_fixed(&mut blob, 22, filter.covariance());
+            }
+        }
+
+        let last = Self::STATE_SIZE - 1;
+        blob[last] = crc8(&blob[..last]);
+        blob
+    }
+
+    /// Restore state previously produced by [`save_state()`](Self::save_state)
+    ///
+    /// Filter state in the blob is ignored if this tracker has no voltage
+    /// filter configured. The voltage plausibility check starts over, so
+    /// the first sample after a restore is not compared against a voltage
+    /// seen before it.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - State restored; tracking continues from the saved SOC
+    /// * `Err(Error::InvalidState)` - Checksum, version, or value check
+    ///   failed; the tracker is left unchanged
+    pub fn restore_state(&mut self, blob: &[u8; Self::STATE_SIZE]) -> Result<(), Error> {
+        let last = Self::STATE_SIZE - 1;
+        if blob[0] != STATE_VERSION || blob[last] != crc8(&blob[..last]) {
+            return Err(Error::InvalidState);
+        }
+        let flags = blob[1];
+        if flags & !(STATE_FLAG_SOC_VALID | STATE_FLAG_FILTER_VALID) != 0 {
+            return Err(Error::InvalidState);
+        }
+
+        let soc = if flags & STATE_FLAG_SOC_VALID != 0 {
+            let soc = get_fixed(blob, 2);
+            if soc < Fixed::ZERO || soc > SOC_FULL {
+                return Err(Error::InvalidState);
+            }
+            Some(soc)
+        } else {
+            None
+        };
+
+        let filter_state = if flags & STATE_FLAG_FILTER_VALID != 0 {
+            let covariance = get_fixed(blob, 22);
+            if covariance < Fixed::ZERO {
+                return Err(Error::InvalidState);
+            }
+            Some((get_fixed(blob, 18), covariance))
+        } else {
+            None
+        };
+
+        let charge_mas_bits = get_i64(blob, 6);
+        let charge_mah = i32::try_from(charge_mas_bits / SECONDS_PER_HOUR)
+            .map(Fixed::from_bits)
+            .map_err(|_| Error::InvalidState)?;
+
+        self.soc = soc;
+        self.charge_mah = charge_mah;
+        self.charge_mas_bits = charge_mas_bits;
+        self.temperature = get_fixed(blob, 14);
+        self.last_voltage = None;
+        self.sensor_fault = false;
+        if let Some(filter) = self.voltage_filter.as_mut() {
+            match filter_state {
+                Some((estimate, covariance)) => filter.set_state(estimate, covariance),
+                None => filter.reset(),
+            }
+        }
+        Ok(())
+    }
+
+    /// Forget the SOC estimate and integrated charge
+    ///
+    /// The next sample re-initializes the SOC from the voltage curve. The
+    /// voltage filter, the history, and the voltage statistics are reset as
+    /// well.
+    pub fn reset(&mut self) {
+        self.soc = None;
+        self.elapsed_bits = 0;
+        if let Some(history) = self.history.as_mut() {
+            history.clear();
+        }
+        self.charge_mah = Fixed::ZERO;
+        self.charge_mas_bits = 0;
+        self.voltage_stats.reset();
+        self.last_voltage = None;
+        self.sensor_fault = false;
+        if let Some(filter) = self.voltage_filter.as_mut() {
+            filter.reset();
+        }
+    }
+}
+
+/// Stateless lookup through the tracker's estimator
+///
+/// The trait methods take `&self`, so they neither advance the tracker nor
+/// use its filtered SOC; read [`SocTracker::soc()`] for the tracked value.
+impl SocEstimate for SocTracker {
+    #[inline]
+    fn estimate_soc_fixed(&self, voltage: Fixed) -> Result<Fixed, Error> {
+        self.estimator.estimate_soc_fixed(voltage)
+    }
+
+    #[inline]
+    fn voltage_range_fixed(&self) -> (Fixed, Fixed) {
+        self.estimator.voltage_range_fixed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BatteryChemistry, EstimatorConfig};
+
+    fn lipo_tracker(config: TrackerConfig) -> SocTracker {
+        SocTracker::new(SocEstimator::new(BatteryChemistry::LiPo), config)
+    }
+
+    #[test]
+    fn test_tracker_first_sample_uses_curve() {
+        let mut tracker = lipo_tracker(TrackerConfig::default());
+        assert_eq!(tracker.soc(), None);
+
+        let soc = tracker
+            .update(Fixed::from_num(3.75), Fixed::ZERO, Fixed::ZERO)
+            .unwrap();
+        assert_eq!(soc, Fixed::from_num(60.0));
+        assert_eq!(tracker.soc(), Some(soc));
+    }
+
+    #[test]
+    fn test_tracker_zero_dt_holds_soc() {
+        let mut tracker = lipo_tracker(TrackerConfig::default());
+        tracker
+            .update(Fixed::from_num(3.75), Fixed::ZERO, Fixed::ZERO)
+            .unwrap();
+
+        // A sample with no elapsed time must not move the estimate
+        let soc = tracker
+            .update(Fixed::from_num(4.2), Fixed::ZERO, Fixed::ZERO)
+            .unwrap();
+        assert_eq!(soc, Fixed::from_num(60.0));
+
+        // Negative dt is treated as zero
+        let soc = tracker
+            .update(Fixed::from_num(4.2), Fixed::ZERO, Fixed::from_num(-5))
+            .unwrap();
+        assert_eq!(soc, Fixed::from_num(60.0));
+    }
+
+    #[test]
+    fn test_tracker_slew_limit_scales_with_dt() {
+        let config = TrackerConfig::default()
+            .with_voltage_gain(Fixed::ONE)
+            .with_max_slew_rate(Fixed::from_num(0.5));
+        let mut tracker = lipo_tracker(config);
+        tracker
+            .update(Fixed::from_num(3.75), Fixed::ZERO, Fixed::ZERO)
+            .unwrap();
+
+        // Voltage jumps to 100%, but only 0.5 %/s may be applied
+        let soc = tracker
+            .update(Fixed::from_num(4.25), Fixed::ZERO, Fixed::from_num(2))
+            .unwrap();
+        assert_eq!(soc, Fixed::from_num(61.0));
+
+        let soc = tracker
+            .update(Fixed::from_num(4.25), Fixed::ZERO, Fixed::from_num(0.2))
+            .unwrap();
+        assert!((soc - Fixed::from_num(61.1)).abs() < Fixed::from_num(0.001));
+    }
+
+    #[test]
+    fn test_tracker_coulomb_counting() {
+        let config = TrackerConfig::default()
+            .with_capacity_mah(Fixed::from_num(1000))
+            .with_voltage_gain(Fixed::ZERO)
+            .with_max_slew_rate(Fixed::from_num(100));
+        let mut tracker = lipo_tracker(config);
+        tracker
+            .update(Fixed::from_num(3.75), Fixed::ZERO, Fixed::ZERO)
+            .unwrap();
+
+        // 360 mA for 100 s = 10 mAh = 1% of 1000 mAh
+        let soc = tracker
+            .update(
+                Fixed::from_num(3.75),
+                Fixed::from_num(-360),
+                Fixed::from_num(100),
+            )
+            .unwrap();
+        assert!((soc - Fixed::from_num(59.0)).abs() < Fixed::from_num(0.01));
+        assert!((tracker.charge_mah() + Fixed::from_num(10)).abs() < Fixed::from_num(0.01));
+    }
+
+    #[test]
+    fn test_tracker_coulomb_counting_small_steps() {
+        let config = TrackerConfig::default()
+            .with_capacity_mah(Fixed::from_num(2000))
+            .with_voltage_gain(Fixed::ZERO)
+            .with_max_slew_rate(Fixed::from_num(100));
+        let voltage = Fixed::from_num(3.75);
+
+        // 100 mA at 10 Hz and 500 mA at 1 Hz for one hour each: 5% and 25%
+        for (current, dt, samples, expected) in
+            [(-100, 0.1, 36_000, 55.0), (-500, 1.0, 3_600, 35.0)]
+        {
+            let mut tracker = lipo_tracker(config);
+            tracker.update(voltage, Fixed::ZERO, Fixed::ZERO).unwrap();
+            for _ in 0..samples {
+                tracker
+                    .update(voltage, Fixed::from_num(current), Fixed::from_num(dt))
+                    .unwrap();
+            }
+
+            let soc = tracker.soc().unwrap();
+            assert!(
+                (soc - Fixed::from_num(expected)).abs() < Fixed::from_num(0.01),
+                "soc = {}",
+                soc
+            );
+        }
+    }
+
+    #[test]
+    fn test_tracker_irregular_sampling_equivalent() {
+        let config = TrackerConfig::default()
+            .with_capacity_mah(Fixed::from_num(1000))
+            .with_voltage_gain(Fixed::ZERO)
+            .with_max_slew_rate(Fixed::from_num(100));
+
+        let mut coarse = lipo_tracker(config);
+        let mut fine = lipo_tracker(config);
+        let voltage = Fixed::from_num(3.75);
+        let current = Fixed::from_num(-720);
+
+        coarse.update(voltage, current, Fixed::ZERO).unwrap();
+        fine.update(voltage, current, Fixed::ZERO).unwrap();
+
+        coarse
+            .update(voltage, current, Fixed::from_num(10))
+            .unwrap();
+        for dt in [1.0, 2.5, 0.5, 4.0, 2.0] {
+            fine.update(voltage, current, Fixed::from_num(dt)).unwrap();
+        }
+
+        let diff = (coarse.soc().unwrap() - fine.soc().unwrap()).abs();
+        assert!(diff < Fixed::from_num(0.01), "diff = {}", diff);
+    }
+
+    #[test]
+    fn test_tracker_voltage_correction() {
+        let config = TrackerConfig::default()
+            .with_voltage_gain(Fixed::from_num(0.1))
+            .with_max_slew_rate(Fixed::from_num(100));
+        let mut tracker = lipo_tracker(config);
+        tracker
+            .update(Fixed::from_num(3.75), Fixed::ZERO, Fixed::ZERO)
+            .unwrap();
+
+        // 10% of the 21.25% gap towards 81.25% after 1 s
+        let soc = tracker
+            .update(Fixed::from_num(3.875), Fixed::ZERO, Fixed::ONE)
+            .unwrap();
+        assert!((soc - Fixed::from_num(62.125)).abs() < Fixed::from_num(0.01));
+
+        // A very long gap is capped at a full correction
+        let soc = tracker
+            .update(Fixed::from_num(3.875), Fixed::ZERO, Fixed::from_num(60))
+            .unwrap();
+        assert!((soc - Fixed::from_num(81.25)).abs() < Fixed::from_num(0.01));
+    }
+
+    #[test]
+    fn test_tracker_output_clamped() {
+        let config = TrackerConfig::default()
+            .with_capacity_mah(Fixed::from_num(100))
+            .with_voltage_gain(Fixed::ZERO)
+            .with_max_slew_rate(Fixed::from_num(1000));
+        let mut tracker = lipo_tracker(config);
+        tracker
+            .update(Fixed::from_num(4.2), Fixed::ZERO, Fixed::ZERO)
+            .unwrap();
+
+        let soc = tracker
+            .update(
+                Fixed::from_num(4.2),
+                Fixed::from_num(1000),
+                Fixed::from_num(60),
+            )
+            .unwrap();
+        assert_eq!(soc, Fixed::from_num(100));
+    }
+
+    #[test]
+    fn test_tracker_error_leaves_state() {
+        use crate::{Curve, CurvePoint};
+        static BAD: Curve = Curve::new(&[CurvePoint::new(3.0, 0.0)]);
+
+        let mut tracker = SocTracker::new(
+            SocEstimator::with_custom_curve(&BAD),
+            TrackerConfig::default(),
+        );
+        assert_eq!(
+            tracker.update(Fixed::from_num(3.0), Fixed::from_num(-100), Fixed::ONE),
+            Err(Error::InvalidCurve)
+        );
+        assert_eq!(tracker.soc(), None);
+        assert_eq!(tracker.charge_mah(), Fixed::ZERO);
+    }
+
+    #[test]
+    fn test_tracker_overflow_reported() {
+        let config = TrackerConfig::default().with_capacity_mah(Fixed::from_num(0.001));
+        let mut tracker = lipo_tracker(config);
+        let voltage = Fixed::from_num(3.75);
+        tracker.update(voltage, Fixed::ZERO, Fixed::ONE).unwrap();
+        let soc = tracker.soc();
+
+        // 1 mAh into a 0.001 mAh capacity is a 100000% SOC change
+        assert_eq!(
+            tracker.update(voltage, Fixed::from_num(3600), Fixed::ONE),
+            Err(Error::NumericalError)
+        );
+        assert_eq!(tracker.soc(), soc);
+        assert_eq!(tracker.charge_mah(), Fixed::ZERO);
+
+        // The charge counter leaves the I16F16 range
+        let mut tracker = lipo_tracker(TrackerConfig::default());
+        let hour = Fixed::from_num(3600);
+        tracker.update(voltage, Fixed::ZERO, Fixed::ONE).unwrap();
+        tracker
+            .update(voltage, Fixed::from_num(28800), hour)
+            .unwrap();
+        assert_eq!(
+            tracker.update(voltage, Fixed::from_num(28800), hour),
+            Err(Error::NumericalError)
+        );
+        assert_eq!(tracker.charge_mah(), Fixed::from_num(28800));
+    }
+
+    #[test]
+    fn test_tracker_temperature_and_reset() {
+        let estimator = SocEstimator::with_temperature_compensation(
+            BatteryChemistry::LiPo,
+            Fixed::from_num(25.0),
+            Fixed::from_num(0.005),
+        );
+        let mut tracker = SocTracker::new(estimator, TrackerConfig::default());
+        assert_eq!(tracker.temperature(), Fixed::from_num(25.0));
+
+        let warm = tracker
+            .update(Fixed::from_num(3.75), Fixed::ZERO, Fixed::ZERO)
+            .unwrap();
+
+        tracker.reset();
+        tracker.set_temperature(Fixed::ZERO);
+        let cold = tracker
+            .update(Fixed::from_num(3.75), Fixed::ZERO, Fixed::ZERO)
+            .unwrap();
+
+        assert!(cold < warm);
+    }
+
+    #[test]
+    fn test_tracker_state_round_trip() {
+        let config = TrackerConfig::default().with_capacity_mah(Fixed::from_num(1000));
+        let mut tracker = lipo_tracker(config);
+        tracker.set_temperature(Fixed::from_num(-5.5));
+        tracker
+            .update(Fixed::from_num(3.75), Fixed::ZERO, Fixed::ZERO)
+            .unwrap();
+        tracker
+            .update(
+                Fixed::from_num(3.75),
+                Fixed::from_num(-361),
+                Fixed::from_num(10),
+            )
+            .unwrap();
+
+        let blob = tracker.save_state();
+        let mut restored = lipo_tracker(config);
+        restored.restore_state(&blob).unwrap();
+
+        assert_eq!(restored.soc(), tracker.soc());
+        assert_eq!(restored.charge_mah(), tracker.charge_mah());
+        assert_eq!(restored.temperature(), Fixed::from_num(-5.5));
+        assert_eq!(restored.save_state(), blob);
+
+        // The 10 mA·s beyond the last whole mAh survive the restore
+        for tracker in [&mut tracker, &mut restored] {
+            tracker
+                .update(
+                    Fixed::from_num(3.75),
+                    Fixed::from_num(-359),
+                    Fixed::from_num(10),
+                )
+                .unwrap();
+            assert_eq!(tracker.charge_mah(), Fixed::from_num(-2));
+        }
+
+        // Tracking continues identically after the restore
+        let a = tracker
+            .update(Fixed::from_num(3.7), Fixed::from_num(-100), Fixed::ONE)
+            .unwrap();
+        let b = restored
+            .update(Fixed::from_num(3.7), Fixed::from_num(-100), Fixed::ONE)
+            .unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_tracker_restore_then_update() {
+        let config = TrackerConfig::default().with_max_voltage_step(Fixed::from_num(0.3));
+        let mut tracker = lipo_tracker(config);
+        tracker
+            .update(Fixed::from_num(3.75), Fixed::ZERO, Fixed::ONE)
+            .unwrap();
+        let blob = tracker.save_state();
+
+        tracker
+            .update(Fixed::from_num(4.0), Fixed::ZERO, Fixed::ONE)
+            .unwrap();
+        tracker.restore_state(&blob).unwrap();
+
+        // 0.35 V below the last sample before the restore, but that sample
+        // is not part of the restored state
+        assert!(tracker
+            .update(Fixed::from_num(3.65), Fixed::ZERO, Fixed::ONE)
+            .is_ok());
+        assert!(!tracker.is_sensor_fault());
+    }
+
+    #[test]
+    fn test_tracker_state_without_soc() {
+        let tracker = lipo_tracker(TrackerConfig::default());
+        let blob = tracker.save_state();
+
+        let mut restored = lipo_tracker(TrackerConfig::default());
+        restored
+            .update(Fixed::from_num(3.75), Fixed::ZERO, Fixed::ZERO)
+            .unwrap();
+        restored.restore_state(&blob).unwrap();
+        assert_eq!(restored.soc(), None);
+    }
+
+    #[test]
+    fn test_tracker_state_rejects_corruption() {
+        let mut tracker = lipo_tracker(TrackerConfig::default());
+        tracker
+            .update(Fixed::from_num(3.75), Fixed::ZERO, Fixed::ZERO)
+            .unwrap();
+        let blob = tracker.save_state();
+        let last = SocTracker::STATE_SIZE - 1;
+
+        let mut target = lipo_tracker(TrackerConfig::default());
+
+        // Erased flash
+        assert_eq!(
+            target.restore_state(&[0xFF; SocTracker::STATE_SIZE]),
+            Err(Error::InvalidState)
+        );
+        // Zeroed RAM
+        assert_eq!(
+            target.restore_state(&[0x00; SocTracker::STATE_SIZE]),
+            Err(Error::InvalidState)
+        );
+
+        // Single bit flip in the payload
+        let mut flipped = blob;
+        flipped[3] ^= 0x10;
+        assert_eq!(target.restore_state(&flipped), Err(Error::InvalidState));
+
+        // Unknown version with a matching checksum
+        let mut versioned = blob;
+        versioned[0] = STATE_VERSION + 1;
+        versioned[last] = crc8(&versioned[..last]);
+        assert_eq!(target.restore_state(&versioned), Err(Error::InvalidState));
+
+        // Out-of-range SOC with a matching checksum
+        let mut out_of_range = blob;
+        out_of_range[2..6].copy_from_slice(&Fixed::from_num(150).to_bits().to_le_bytes());
+        out_of_range[last] = crc8(&out_of_range[..last]);
+        assert_eq!(
+            target.restore_state(&out_of_range),
+            Err(Error::InvalidState)
+        );
+
+        assert_eq!(target.soc(), None);
+    }
+
+    #[test]
+    fn test_tracker_warm_start_self_discharge() {
+        let config = TrackerConfig::default().with_self_discharge_rate(Fixed::from_num(0.5));
+        let estimator = SocEstimator::new(BatteryChemistry::LiPo);
+
+        // 0.5 %/day for 48 h
+        let tracker =
+            SocTracker::warm_start(estimator, config, Fixed::from_num(60), Fixed::from_num(48));
+        assert_eq!(tracker.soc(), Some(Fixed::from_num(59)));
+
+        // Self-discharge never goes below empty
+        let tracker = SocTracker::warm_start(
+            estimator,
+            config,
+            Fixed::from_num(1),
+            Fixed::from_num(10000),
+        );
+        assert_eq!(tracker.soc(), Some(Fixed::ZERO));
+
+        // Invalid inputs are clamped
+        let tracker =
+            SocTracker::warm_start(estimator, config, Fixed::from_num(150), Fixed::from_num(-5));
+        assert_eq!(tracker.soc(), Some(Fixed::from_num(100)));
+    }
+
+    #[test]
+    fn test_tracker_warm_start_converges_smoothly() {
+        let config = TrackerConfig::default()
+            .with_voltage_gain(Fixed::from_num(0.1))
+            .with_max_slew_rate(Fixed::from_num(1.0));
+        let mut tracker = SocTracker::warm_start(
+            SocEstimator::new(BatteryChemistry::LiPo),
+            config,
+            Fixed::from_num(70),
+            Fixed::ZERO,
+        );
+
+        // Voltage says 60%, but the first sample moves at most 1 %/s
+        let soc = tracker
+            .update(Fixed::from_num(3.75), Fixed::ZERO, Fixed::ONE)
+            .unwrap();
+        assert_eq!(soc, Fixed::from_num(69));
+
+        for _ in 0..120 {
+            tracker
+                .update(Fixed::from_num(3.75), Fixed::ZERO, Fixed::ONE)
+                .unwrap();
+        }
+        let diff = (tracker.soc().unwrap() - Fixed::from_num(60)).abs();
+        assert!(diff < Fixed::from_num(0.1), "diff = {}", diff);
+    }
+
+    #[test]
+    fn test_tracker_voltage_filter_smooths_input() {
+        let config = TrackerConfig::default()
+            .with_voltage_gain(Fixed::ONE)
+            .with_max_slew_rate(Fixed::from_num(100));
+        let filter = KalmanFilter::new(Fixed::from_num(0.25), Fixed::from_num(100));
+        let mut filtered = lipo_tracker(config).with_voltage_filter(filter);
+        let mut raw = lipo_tracker(config);
+
+        for tracker in [&mut filtered, &mut raw] {
+            tracker
+                .update(Fixed::from_num(3.75), Fixed::ZERO, Fixed::ZERO)
+                .unwrap();
+        }
+
+        // A 50 mV spike moves the unfiltered tracker much further
+        let spike = Fixed::from_num(3.80);
+        let a = filtered.update(spike, Fixed::ZERO, Fixed::ONE).unwrap();
+        let b = raw.update(spike, Fixed::ZERO, Fixed::ONE).unwrap();
+        assert!(a < b, "filtered {} vs raw {}", a, b);
+        assert!(filtered.voltage_filter().unwrap().estimate().unwrap() < spike);
+    }
+
+    #[test]
+    fn test_tracker_state_includes_filter() {
+        let filter = KalmanFilter::new(Fixed::from_num(0.25), Fixed::from_num(100));
+        let mut tracker = lipo_tracker(TrackerConfig::default()).with_voltage_filter(filter);
+        for voltage in [3.75, 3.76, 3.74, 3.75] {
+            tracker
+                .update(Fixed::from_num(voltage), Fixed::ZERO, Fixed::ONE)
+                .unwrap();
+        }
+
+        let blob = tracker.save_state();
+        let mut restored = lipo_tracker(TrackerConfig::default()).with_voltage_filter(filter);
+        restored.restore_state(&blob).unwrap();
+        assert_eq!(restored.voltage_filter(), tracker.voltage_filter());
+
+        // A tracker without a filter accepts the blob and ignores the filter
+        let mut plain = lipo_tracker(TrackerConfig::default());
+        plain.restore_state(&blob).unwrap();
+        assert_eq!(plain.soc(), tracker.soc());
+        assert!(plain.voltage_filter().is_none());
+
+        // Reset clears the filter estimate
+        restored.reset();
+        assert_eq!(restored.voltage_filter().unwrap().estimate(), None);
+    }
+
+    #[test]
+    fn test_voltage_stats_empty() {
+        let stats = VoltageStats::default();
+        assert_eq!(stats.count(), 0);
+        assert_eq!(stats.min(), None);
+        assert_eq!(stats.max(), None);
+        assert_eq!(stats.mean(), None);
+    }
+
+    #[test]
+    fn test_voltage_stats_no_overflow() {
+        let mut stats = VoltageStats::new();
+        for _ in 0..100_000 {
+            stats.record(Fixed::from_num(4.25));
+        }
+        assert_eq!(stats.mean(), Some(Fixed::from_num(4.25)));
+        assert_eq!(stats.count(), 100_000);
+    }
+
+    #[test]
+    fn test_tracker_voltage_stats() {
+        let filter = KalmanFilter::new(Fixed::from_num(0.25), Fixed::from_num(100));
+        let mut tracker = lipo_tracker(TrackerConfig::default()).with_voltage_filter(filter);
+
+        for voltage in [3.75, 3.25, 4.25, 3.75] {
+            tracker
+                .update(Fixed::from_num(voltage), Fixed::ZERO, Fixed::ONE)
+                .unwrap();
+        }
+
+        // Statistics see the raw readings, not the filtered ones
+        let stats = tracker.voltage_stats();
+        assert_eq!(stats.count(), 4);
+        assert_eq!(stats.min(), Some(Fixed::from_num(3.25)));
+        assert_eq!(stats.max(), Some(Fixed::from_num(4.25)));
+        assert_eq!(stats.mean(), Some(Fixed::from_num(3.75)));
+
+        tracker.reset_voltage_stats();
+        assert_eq!(tracker.voltage_stats().count(), 0);
+        assert!(tracker.soc().is_some());
+
+        tracker
+            .update(Fixed::from_num(3.5), Fixed::ZERO, Fixed::ONE)
+            .unwrap();
+        tracker.reset();
+        assert_eq!(tracker.voltage_stats().count(), 0);
+    }
+
+    #[test]
+    fn test_tracker_voltage_stats_include_rejected_samples() {
+        let config = TrackerConfig::default().with_max_voltage_step(Fixed::from_num(0.3));
+        let estimator = SocEstimator::with_config(
+            BatteryChemistry::LiPo,
+            EstimatorConfig::default().with_strict_voltage_range(),
+        );
+        let mut tracker = SocTracker::new(estimator, config);
+
+        tracker
+            .update(Fixed::from_num(3.75), Fixed::ZERO, Fixed::ONE)
+            .unwrap();
+        // Brown-out dip, rejected as implausible
+        assert_eq!(
+            tracker.update(Fixed::from_num(2.9), Fixed::ZERO, Fixed::ONE),
+            Err(Error::SensorFault)
+        );
+        // Consistent with the dip but below the curve
+        assert_eq!(
+            tracker.update(Fixed::from_num(2.8), Fixed::ZERO, Fixed::ONE),
+            Err(Error::VoltageOutOfRange)
+        );
+
+        let stats = tracker.voltage_stats();
+        assert_eq!(stats.count(), 3);
+        assert_eq!(stats.min(), Some(Fixed::from_num(2.8)));
+        assert_eq!(stats.max(), Some(Fixed::from_num(3.75)));
+    }
+
+    #[test]
+    fn test_tracker_plausibility_check() {
+        let config = TrackerConfig::default().with_max_voltage_step(Fixed::from_num(0.3));
+        let mut tracker = lipo_tracker(config);
+
+        tracker
+            .update(Fixed::from_num(3.75), Fixed::ZERO, Fixed::ONE)
+            .unwrap();
+        let soc = tracker.soc();
+
+        // Sense wire drops out
+        assert_eq!(
+            tracker.update(Fixed::from_num(0.5), Fixed::ZERO, Fixed::ONE),
+            Err(Error::SensorFault)
+        );
+        assert!(tracker.is_sensor_fault());
+        assert_eq!(tracker.soc(), soc);
+        assert_eq!(tracker.voltage_stats().count(), 2);
+
+        // Jumping back is also implausible relative to the glitch
+        assert_eq!(
+            tracker.update(Fixed::from_num(3.75), Fixed::ZERO, Fixed::ONE),
+            Err(Error::SensorFault)
+        );
+
+        // Consecutive consistent samples are accepted again
+        assert!(tracker
+            .update(Fixed::from_num(3.76), Fixed::ZERO, Fixed::ONE)
+            .is_ok());
+        assert!(!tracker.is_sensor_fault());
+    }
+
+    #[test]
+    fn test_tracker_failed_lookup_keeps_last_voltage() {
+        let config = TrackerConfig::default().with_max_voltage_step(Fixed::from_num(0.5));
+        let estimator = SocEstimator::with_config(
+            BatteryChemistry::LiPo,
+            EstimatorConfig::default().with_strict_voltage_range(),
+        );
+        let mut tracker = SocTracker::new(estimator, config);
+
+        assert!(tracker
+            .update(Fixed::from_num(3.5), Fixed::ZERO, Fixed::ONE)
+            .is_ok());
+
+        // Within the step limit, but below the curve
+        assert_eq!(
+            tracker.update(Fixed::from_num(3.1), Fixed::ZERO, Fixed::ONE),
+            Err(Error::VoltageOutOfRange)
+        );
+
+        // Checked against 3.5 V, not the rejected 3.1 V
+        assert!(tracker
+            .update(Fixed::from_num(3.75), Fixed::ZERO, Fixed::ONE)
+            .is_ok());
+        assert!(!tracker.is_sensor_fault());
+    }
+
+    #[test]
+    fn test_tracker_plausibility_small_steps_and_disabled() {
+        let config = TrackerConfig::default().with_max_voltage_step(Fixed::from_num(0.3));
+        let mut tracker = lipo_tracker(config);
+        for voltage in [3.75, 3.5, 3.3, 3.55] {
+            assert!(tracker
+                .update(Fixed::from_num(voltage), Fixed::ZERO, Fixed::ONE)
+                .is_ok());
+        }
+
+        let mut tracker = lipo_tracker(TrackerConfig::default());
+        for voltage in [3.75, 0.5, 4.25] {
+            assert!(tracker
+                .update(Fixed::from_num(voltage), Fixed::ZERO, Fixed::ONE)
+                .is_ok());
+        }
+        assert!(!tracker.is_sensor_fault());
+    }
+
+    #[test]
+    fn test_tracker_threshold_events() {
+        use crate::{Curve, CurvePoint};
+        static LINEAR: Curve =
+            Curve::new(&[CurvePoint::new(3.0, 0.0), CurvePoint::new(4.0, 100.0)]);
+
+        let config = TrackerConfig::default()
+            .with_voltage_gain(Fixed::ONE)
+            .with_max_slew_rate(Fixed::from_num(100));
+        let mut tracker = SocTracker::new(SocEstimator::with_custom_curve(&LINEAR), config);
+
+        let mut events = [None; 8];
+        let mut count = 0;
+        let mut sink = |event| {
+            events[count] = Some(event);
+            count += 1;
+        };
+
+        // 50% -> 10% -> 3% -> 0% -> 25% -> 100%
+        for voltage in [3.5, 3.1, 3.03, 3.0, 3.25, 4.0] {
+            tracker
+                .update_with_events(Fixed::from_num(voltage), Fixed::ZERO, Fixed::ONE, &mut sink)
+                .unwrap();
+        }
+
+        let crossing = |event: Option<GaugeEvent>| match event {
+            Some(GaugeEvent::ThresholdCrossed {
+                threshold,
+                direction,
+                ..
+            }) => Some((threshold.to_num::<i32>(), direction)),
+            _ => None,
+        };
+        assert_eq!(crossing(events[0]), Some((20, Crossing::Falling)));
+        assert_eq!(crossing(events[1]), Some((5, Crossing::Falling)));
+        assert_eq!(events[2], Some(GaugeEvent::Empty));
+        assert_eq!(crossing(events[3]), Some((5, Crossing::Rising)));
+        assert_eq!(crossing(events[4]), Some((20, Crossing::Rising)));
+        assert_eq!(events[5], Some(GaugeEvent::Full));
+        assert_eq!(count, 6);
+    }
+
+    #[test]
+    fn test_tracker_fault_event_and_first_sample() {
+        let config = TrackerConfig::default().with_max_voltage_step(Fixed::from_num(0.3));
+        let mut tracker = lipo_tracker(config);
+
+        let mut last = None;
+        let mut count = 0;
+        let mut sink = |event| {
+            last = Some(event);
+            count += 1;
+        };
+
+        // Initializing below the critical threshold emits nothing
+        tracker
+            .update_with_events(Fixed::from_num(3.2), Fixed::ZERO, Fixed::ONE, &mut sink)
+            .unwrap();
+
+        let result =
+            tracker.update_with_events(Fixed::from_num(4.0), Fixed::ZERO, Fixed::ONE, &mut sink);
+        assert_eq!(result, Err(Error::SensorFault));
+        assert_eq!(count, 1);
+        assert_eq!(
+            last,
+            Some(GaugeEvent::SensorFault {
+                voltage: Fixed::from_num(4.0)
+            })
+        );
+    }
+
+    #[test]
+    fn test_tracker_history() {
+        use crate::{Curve, CurvePoint};
+        static LINEAR: Curve =
+            Curve::new(&[CurvePoint::new(3.0, 0.0), CurvePoint::new(4.0, 100.0)]);
+
+        let config = TrackerConfig::default()
+            .with_voltage_gain(Fixed::ONE)
+            .with_max_slew_rate(Fixed::from_num(100));
+        let mut tracker = SocTracker::new(SocEstimator::with_custom_curve(&LINEAR), config)
+            .with_history(SocHistory::new(60_000));
+        assert_eq!(tracker.soc_rate_per_hour(), None);
+
+        // 50% -> 37.5% over 30 minutes in 10 s steps
+        tracker
+            .update(Fixed::from_num(3.5), Fixed::ZERO, Fixed::ZERO)
+            .unwrap();
+        for _ in 0..180 {
+            tracker
+                .update(Fixed::from_num(3.375), Fixed::ZERO, Fixed::from_num(10))
+                .unwrap();
+        }
+
+        let history = tracker.history().unwrap();
+        assert_eq!(history.len(), 31);
+        assert_eq!(history.oldest().unwrap().timestamp_ms, 0);
+        assert_eq!(history.latest().unwrap().timestamp_ms, 1_800_000);
+        let rate = tracker.soc_rate_per_hour().unwrap();
+        assert!(
+            (rate + Fixed::from_num(25)).abs() < Fixed::from_num(0.1),
+            "rate = {}",
+            rate
+        );
+
+        tracker.reset();
+        assert!(tracker.history().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_tracker_sample() {
+        let mut tracker = SocTracker::new(
+            SocEstimator::new(BatteryChemistry::LiPo),
+            TrackerConfig::default(),
+        );
+
+        let mut failing = || Err(Error::SensorFault);
+        assert_eq!(
+            tracker.sample(&mut failing, Fixed::ZERO, Fixed::ONE),
+            Err(Error::SensorFault)
+        );
+        assert_eq!(tracker.soc(), None);
+
+        let mut adc = || Ok(Fixed::from_num(3.75));
+        let source: &mut dyn VoltageSource = &mut adc;
+        assert_eq!(
+            tracker.sample(source, Fixed::ZERO, Fixed::ONE),
+            Ok(Fixed::from_num(60))
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_tracker_sample_async() {
+        use core::future::Future;
+        use core::pin::pin;
+        use core::task::{Context, Poll, Waker};
+
+        /// Source that is pending once before every reading
+        struct SlowAdc {
+            ready: bool,
+        }
+
+        impl AsyncVoltageSource for SlowAdc {
+            async fn read_voltage(&mut self) -> Result<Fixed, Error> {
+                core::future::poll_fn(|cx| {
+                    self.ready = !self.ready;
+                    if self.ready {
+                        cx.waker().wake_by_ref();
+                        Poll::Pending
+                    } else {
+                        Poll::Ready(Ok(Fixed::from_num(3.75)))
+                    }
+                })
+                .await
+            }
+        }
+
+        let mut tracker = SocTracker::new(
+            SocEstimator::new(BatteryChemistry::LiPo),
+            TrackerConfig::default(),
+        );
+        let mut adc = SlowAdc { ready: false };
+        let mut future = pin!(tracker.sample_async(&mut adc, Fixed::ZERO, Fixed::ONE));
+        let mut cx = Context::from_waker(Waker::noop());
+
+        assert!(future.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(
+            future.as_mut().poll(&mut cx),
+            Poll::Ready(Ok(Fixed::from_num(60)))
+        );
+    }
+
+    #[test]
+    fn test_tracker_as_dyn_soc_estimate() {
+        let mut tracker = lipo_tracker(TrackerConfig::default());
+        tracker
+            .update(Fixed::from_num(4.25), Fixed::ZERO, Fixed::ONE)
+            .unwrap();
+
+        // The trait lookup is stateless and does not disturb the tracker
+        let gauge: &dyn SocEstimate = &tracker;
+        assert_eq!(
+            gauge.estimate_soc_fixed(Fixed::from_num(3.75)).unwrap(),
+            Fixed::from_num(60)
+        );
+        assert_eq!(
+            gauge.voltage_range_fixed(),
+            tracker.estimator().voltage_range_fixed()
+        );
+        assert_eq!(tracker.soc(), Some(Fixed::from_num(100)));
+    }
+
+    #[test]
+    fn test_tracker_segment_cache_matches_search() {
+        let estimator = SocEstimator::with_temperature_compensation(
+            BatteryChemistry::LiIon,
+            Fixed::from_num(25),
+            Fixed::from_num(0.005),
+        );
+        let config = TrackerConfig::default().with_max_voltage_step(Fixed::ZERO);
+        let mut searched = SocTracker::new(estimator, config);
+        let mut cached = SocTracker::new(estimator, config).with_segment_cache();
+        assert!(!searched.is_segment_cache_enabled());
+        searched.set_temperature(Fixed::from_num(10));
+        cached.set_temperature(Fixed::from_num(10));
+
+        // Slow discharge with a jump across several segments
+        let voltages = (0..400)
+            .map(|i| 4_250 - i * 4)
+            .chain([3_000, 4_000, 2_400, 2_450]);
+        for mv in voltages {
+            let voltage = Fixed::from_num(mv) / Fixed::from_num(1000);
+            let dt = Fixed::ONE;
+            assert_eq!(
+                cached.update(voltage, Fixed::ZERO, dt),
+                searched.update(voltage, Fixed::ZERO, dt)
+            );
+        }
+    }
+}
@@ -7,6 +7,17 @@
 
 use fixed::types::I16F16;
 
+use crate::Error;
+
+/// SOC in tenths of a percent as fixed-point percent
+///
+/// Exact for the whole `u16` range, where `Fixed::from_num()` would
+/// overflow above 32767.
+#[inline]
+pub(crate) const fn soc_from_tenths(tenths: u16) -> Fixed {
+    Fixed::from_bits((((tenths as i64) << 16) / 10) as i32)
+}
+
 /// Fixed-point type for internal calculations
 ///
 /// Uses 16.16 fixed-point format:
@@ -51,6 +62,8 @@ pub type Fixed = I16F16;
 /// - **Higher cutoff** (3.4V vs 3.2V) - Prevents deep discharge
 /// - **Benefit**: Extended cycle life at cost of reduced capacity
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum BatteryChemistry {
     /// Standard Lithium Polymer battery
     ///
@@ -84,6 +97,58 @@ pub enum BatteryChemistry {
     Lipo410Full340Cutoff,
 }
 
+/// Parses the variant name, e.g. for host-side configuration files and
+/// language bindings
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{BatteryChemistry, Error};
+///
+/// assert_eq!("LiFePO4".parse(), Ok(BatteryChemistry::LiFePO4));
+/// assert_eq!("NiMH".parse::<BatteryChemistry>(), Err(Error::InvalidConfig));
+/// ```
+impl core::str::FromStr for BatteryChemistry {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self, Error> {
+        match name {
+            "LiPo" => Ok(BatteryChemistry::LiPo),
+            "LiFePO4" => Ok(BatteryChemistry::LiFePO4),
+            "LiIon" => Ok(BatteryChemistry::LiIon),
+            "Lipo410Full340Cutoff" => Ok(BatteryChemistry::Lipo410Full340Cutoff),
+            _ => Err(Error::InvalidConfig),
+        }
+    }
+}
+
+impl BatteryChemistry {
+    /// Rated discharge temperature range in °C as `(min, max)`
+    ///
+    /// Temperature compensation is a linear model fitted around room
+    /// temperature; outside these limits it no longer describes the cell,
+    /// so compensated estimates report
+    /// [`Error::TemperatureOutOfRange`](crate::Error::TemperatureOutOfRange).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{BatteryChemistry, Fixed};
+    ///
+    /// let (min, max) = BatteryChemistry::LiFePO4.operating_temperature_range();
+    /// assert_eq!((min, max), (Fixed::from_num(-30), Fixed::from_num(60)));
+    /// ```
+    pub const fn operating_temperature_range(self) -> (Fixed, Fixed) {
+        let (min, max): (i32, i32) = match self {
+            BatteryChemistry::LiPo => (-20, 60),
+            BatteryChemistry::LiFePO4 => (-30, 60),
+            BatteryChemistry::LiIon => (-20, 60),
+            BatteryChemistry::Lipo410Full340Cutoff => (-20, 60),
+        };
+        (Fixed::from_bits(min << 16), Fixed::from_bits(max << 16))
+    }
+}
+
 /// A single point on a voltage-SOC curve
 ///
 /// This struct represents one data point in a battery discharge curve,
@@ -101,6 +166,7 @@ pub enum BatteryChemistry {
 /// # Examples
 ///
 /// ```
+/// # #[cfg(not(feature = "no-float"))] {
 /// use battery_estimator::CurvePoint;
 ///
 /// // Create a curve point at 3.7V with 50% SOC
@@ -114,8 +180,10 @@ pub enum BatteryChemistry {
 ///
 /// // Create from tuple
 /// let point2: CurvePoint = (3.8, 75.0).into();
+/// # }
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CurvePoint {
     /// Voltage in millivolts (mV)
     ///
@@ -147,6 +215,7 @@ impl CurvePoint {
     /// # Examples
     ///
     /// ```
+    /// # #[cfg(not(feature = "no-float"))] {
     /// use battery_estimator::CurvePoint;
     ///
     /// let point = CurvePoint::new(3.7, 50.0);
@@ -156,6 +225,7 @@ impl CurvePoint {
     /// // Negative voltage is clamped to 0
     /// let clamped = CurvePoint::new(-1.0, 50.0);
     /// assert_eq!(clamped.voltage(), 0.0);
+    /// # }
     /// ```
     #[inline]
     pub const fn new(voltage: f32, soc: f32) -> Self {
@@ -193,6 +263,7 @@ impl CurvePoint {
     /// # Examples
     ///
     /// ```
+    /// # #[cfg(not(feature = "no-float"))] {
     /// use battery_estimator::CurvePoint;
     /// use fixed::types::I16F16;
     ///
@@ -201,6 +272,7 @@ impl CurvePoint {
     /// let point = CurvePoint::from_fixed(voltage, soc);
     /// assert_eq!(point.voltage(), 3.7);
     /// assert_eq!(point.soc(), 50.0);
+    /// # }
     /// ```
     #[inline]
     pub fn from_fixed(voltage: Fixed, soc: Fixed) -> Self {
@@ -274,11 +346,13 @@ impl CurvePoint {
     /// # Examples
     ///
     /// ```
+    /// # #[cfg(not(feature = "no-float"))] {
     /// use battery_estimator::CurvePoint;
     ///
     /// let point = CurvePoint::from_raw(3700, 500);
     /// assert_eq!(point.voltage(), 3.7);
     /// assert_eq!(point.soc(), 50.0);
+    /// # }
     /// ```
     #[inline]
     pub const fn from_raw(voltage_mv: u16, soc_tenth: u16) -> Self {
@@ -302,7 +376,7 @@ impl CurvePoint {
     /// ```
     #[inline]
     pub fn voltage_fixed(&self) -> Fixed {
-        Fixed::from_num(self.voltage_mv) / Fixed::from_num(1000)
+        Fixed::from_bits((((self.voltage_mv as i64) << 16) / 1000) as i32)
     }
 
     /// Returns the state of charge in percent as a fixed-point value
@@ -319,7 +393,7 @@ impl CurvePoint {
     /// ```
     #[inline]
     pub fn soc_fixed(&self) -> Fixed {
-        Fixed::from_num(self.soc_tenth) / Fixed::from_num(10)
+        soc_from_tenths(self.soc_tenth)
     }
 
     /// Returns the voltage in volts
@@ -332,6 +406,7 @@ impl CurvePoint {
     /// let point = CurvePoint::new(3.7, 50.0);
     /// assert_eq!(point.voltage(), 3.7);
     /// ```
+    #[cfg(not(feature = "no-float"))]
     #[inline]
     pub const fn voltage(&self) -> f32 {
         self.voltage_mv as f32 / 1000.0
@@ -347,12 +422,14 @@ impl CurvePoint {
     /// let point = CurvePoint::new(3.7, 50.0);
     /// assert_eq!(point.soc(), 50.0);
     /// ```
+    #[cfg(not(feature = "no-float"))]
     #[inline]
     pub const fn soc(&self) -> f32 {
         self.soc_tenth as f32 / 10.0
     }
 }
 
+#[cfg(not(feature = "no-float"))]
 impl From<(f32, f32)> for CurvePoint {
     /// Creates a curve point from a tuple (voltage, soc)
     ///
@@ -387,7 +464,27 @@ const fn is_finite_const(value: f32) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    extern crate alloc;
+
+    #[test]
+    fn test_chemistry_from_str() {
+        let chemistries = [
+            BatteryChemistry::LiPo,
+            BatteryChemistry::LiFePO4,
+            BatteryChemistry::LiIon,
+            BatteryChemistry::Lipo410Full340Cutoff,
+        ];
+        for chemistry in chemistries {
+            let name = alloc::format!("{:?}", chemistry);
+            assert_eq!(name.parse(), Ok(chemistry));
+        }
+        assert_eq!(
+            "lipo".parse::<BatteryChemistry>(),
+            Err(Error::InvalidConfig)
+        );
+    }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_point_creation() {
         let point = CurvePoint::new(3.7, 50.0);
@@ -395,6 +492,7 @@ mod tests {
         assert_eq!(point.soc(), 50.0);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_point_zero_values() {
         let point = CurvePoint::new(0.0, 0.0);
@@ -402,6 +500,7 @@ mod tests {
         assert_eq!(point.soc(), 0.0);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_point_boundary_values() {
         // Test maximum voltage (u16 max / 1000 = 65.535V)
@@ -410,6 +509,7 @@ mod tests {
         assert_eq!(max_point.soc(), 100.0);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_point_decimal_voltage() {
         // Test two decimal places
@@ -418,6 +518,7 @@ mod tests {
         assert_eq!(point.soc(), 75.5);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_point_negative_voltage() {
         // Test that negative voltage is handled
@@ -428,6 +529,7 @@ mod tests {
         assert!(point.voltage() >= 0.0); // Will be positive due to wrapping
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_point_soc_bounds() {
         // Test minimum SOC
@@ -439,6 +541,7 @@ mod tests {
         assert_eq!(max_soc.soc(), 100.0);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_point_soc_precision() {
         // Test that SOC is stored with 0.1% precision
@@ -448,6 +551,7 @@ mod tests {
         assert!((50.0..=50.2).contains(&soc));
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_point_from_tuple() {
         let point: CurvePoint = (3.8, 75.0).into();
@@ -473,6 +577,7 @@ mod tests {
         assert_eq!(point1, point2);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_point_voltage_exceeds_max() {
         // Test voltage > 65.535 is clamped to 65.535
@@ -480,6 +585,7 @@ mod tests {
         assert_eq!(point.voltage(), 65.535);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_point_soc_exceeds_max() {
         // Test SOC > 100.0 is clamped to 100.0
@@ -487,6 +593,7 @@ mod tests {
         assert_eq!(point.soc(), 100.0);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_point_nan_values() {
         // Test NaN voltage is treated as 0.0
@@ -498,6 +605,7 @@ mod tests {
         assert_eq!(point2.soc(), 0.0);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_point_infinity_values() {
         // Test infinity voltage is treated as 0.0
@@ -509,6 +617,7 @@ mod tests {
         assert_eq!(point2.soc(), 0.0);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_point_from_trait() {
         // Explicitly test the From trait implementation
@@ -519,6 +628,7 @@ mod tests {
         assert_eq!(point.soc(), 80.0);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_point_new_unchecked() {
         // Test the new_unchecked function
@@ -568,6 +678,7 @@ mod tests {
         assert_eq!(chem1, chem2);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_point_extreme_soc() {
         // Test SOC values beyond normal range
@@ -584,6 +695,7 @@ mod tests {
         );
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_point_voltage_precision() {
         // Test that voltage precision is maintained
@@ -593,6 +705,7 @@ mod tests {
         assert!((point.voltage() - 3.715).abs() < 0.001);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_point_from_fixed() {
         let voltage = Fixed::from_num(3.7);
@@ -621,6 +734,7 @@ mod tests {
         assert_eq!(soc, Fixed::from_num(50.0));
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_point_from_raw() {
         let point = CurvePoint::from_raw(3700, 500);
@@ -628,6 +742,7 @@ mod tests {
         assert_eq!(point.soc(), 50.0);
     }
 
+    #[cfg(not(feature = "no-float"))]
     #[test]
     fn test_curve_point_from_fixed_clamping() {
         // Test negative voltage clamping
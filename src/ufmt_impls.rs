@@ -0,0 +1,180 @@
+//! `ufmt` formatting for tiny targets
+//!
+//! Enabled with the `ufmt` feature. `core::fmt` costs several KB of flash on
+//! AVR/MSP430-class parts; these `uDisplay` implementations format without
+//! pulling it in. Error messages, curve point indices and measurement
+//! contexts read exactly as with `Display`. Fixed-point values are printed
+//! rounded to a fixed number of decimals (3 for volts, 1 for degrees and
+//! percent), whereas `Display` prints them in full, so an
+//! [`ErrorDetail`] with a voltage context reads "16.800 V" here and
+//! "16.8 V" with `Display`.
+
+use crate::{Celsius, Error, ErrorContext, ErrorDetail, Fixed, Percent, Volts};
+use ufmt::{uDisplay, uWrite, uwrite, Formatter};
+
+/// Writes `value` rounded to `decimals` decimal places
+fn write_fixed<W: uWrite + ?Sized>(
+    f: &mut Formatter<'_, W>,
+    value: Fixed,
+    decimals: u32,
+) -> Result<(), W::Error> {
+    let bits = value.to_bits() as i64;
+    if bits < 0 {
+        f.write_str("-")?;
+    }
+
+    let scale = 10u64.pow(decimals);
+    let scaled = (bits.unsigned_abs() * scale + (1 << 15)) >> 16;
+    uwrite!(f, "{}", scaled / scale)?;
+
+    if decimals > 0 {
+        f.write_str(".")?;
+        let mut frac = scaled % scale;
+        let mut digit_scale = scale / 10;
+        while digit_scale > 0 {
+            uwrite!(f, "{}", frac / digit_scale)?;
+            frac %= digit_scale;
+            digit_scale /= 10;
+        }
+    }
+    Ok(())
+}
+
+impl uDisplay for Error {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        f.write_str(self.message())
+    }
+}
+
+impl uDisplay for ErrorDetail {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        f.write_str(self.error.message())?;
+        match self.context {
+            ErrorContext::None => Ok(()),
+            ErrorContext::Voltage(voltage) => {
+                f.write_str(" (")?;
+                write_fixed(f, voltage, 3)?;
+                f.write_str(" V)")
+            }
+            ErrorContext::Temperature(temperature) => {
+                f.write_str(" (")?;
+                write_fixed(f, temperature, 1)?;
+                f.write_str(" °C)")
+            }
+            ErrorContext::CurvePoint(index) => uwrite!(f, " (curve point {})", index),
+            ErrorContext::Measurement {
+                voltage_mv,
+                temperature_cdeg,
+            } => {
+                uwrite!(f, " ({} mV, ", voltage_mv)?;
+                if temperature_cdeg < 0 {
+                    f.write_str("-")?;
+                }
+                let cdeg = temperature_cdeg.unsigned_abs();
+                let padding = if cdeg % 100 < 10 { "0" } else { "" };
+                uwrite!(f, "{}.{}{} °C)", cdeg / 100, padding, cdeg % 100)
+            }
+        }
+    }
+}
+
+impl uDisplay for Volts {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        write_fixed(f, self.raw(), 3)?;
+        f.write_str(" V")
+    }
+}
+
+impl uDisplay for Celsius {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        write_fixed(f, self.raw(), 1)?;
+        f.write_str(" °C")
+    }
+}
+
+impl uDisplay for Percent {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        write_fixed(f, self.raw(), 1)?;
+        f.write_str("%")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    extern crate alloc;
+    use alloc::format;
+    use alloc::string::String;
+    use core::convert::Infallible;
+
+    struct Buffer(String);
+
+    impl uWrite for Buffer {
+        type Error = Infallible;
+
+        fn write_str(&mut self, s: &str) -> Result<(), Infallible> {
+            self.0.push_str(s);
+            Ok(())
+        }
+    }
+
+    fn render<T: uDisplay>(value: &T) -> String {
+        let mut buffer = Buffer(String::new());
+        uwrite!(&mut buffer, "{}", value).unwrap();
+        buffer.0
+    }
+
+    #[test]
+    fn test_udisplay_output() {
+        assert_eq!(render(&Error::SensorFault), "Implausible sensor reading");
+        assert_eq!(
+            render(&Error::PackMismatch.at_voltage(Fixed::from_num(16.8))),
+            "Pack voltage does not match configuration (16.800 V)"
+        );
+        let detail =
+            Error::NumericalError.at_measurement(Fixed::from_num(3.7126), Fixed::from_num(-5.05));
+        assert_eq!(
+            render(&detail),
+            "Numerical error in calculation (3713 mV, -5.05 °C)"
+        );
+        assert_eq!(render(&Volts::from_millivolts(3705)), "3.705 V");
+        assert_eq!(render(&Celsius::new(Fixed::from_num(-2.25))), "-2.3 °C");
+        assert_eq!(render(&Percent::new(Fixed::from_num(99.96))), "100.0%");
+    }
+
+    #[test]
+    fn test_udisplay_matches_display() {
+        let same = [
+            ErrorDetail::from(Error::VoltageOutOfRange),
+            Error::InvalidCurve.at_curve_point(3),
+            Error::NumericalError.at_measurement(Fixed::from_num(3.7126), Fixed::from_num(-5.05)),
+        ];
+        for detail in same {
+            assert_eq!(render(&detail), format!("{}", detail));
+            assert_eq!(render(&detail.error), format!("{}", detail.error));
+        }
+
+        // Fixed-point contexts use fixed decimals instead of the full value
+        let voltage = Error::PackMismatch.at_voltage(Fixed::from_num(16.5));
+        assert_eq!(
+            format!("{}", voltage),
+            "Pack voltage does not match configuration (16.5 V)"
+        );
+        assert_eq!(
+            render(&voltage),
+            "Pack voltage does not match configuration (16.500 V)"
+        );
+        let temperature = ErrorDetail::new(
+            Error::TemperatureOutOfRange,
+            ErrorContext::Temperature(Fixed::from_num(-20.25)),
+        );
+        assert_eq!(
+            format!("{}", temperature),
+            "Temperature outside operating range (-20.25 °C)"
+        );
+        assert_eq!(
+            render(&temperature),
+            "Temperature outside operating range (-20.3 °C)"
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Strongly typed physical quantities
+//!
+//! Most of the API takes bare [`Fixed`] values, which makes it easy to pass a
+//! temperature where a voltage is expected. The newtypes in this module wrap
+//! a [`Fixed`] value with its unit so such mix-ups fail to compile. Each type
+//! exposes the raw value through [`raw()`](Volts::raw) and converts to and
+//! from [`Fixed`] with `From`.
+//!
+//! The typed entry points are [`SocEstimate::estimate()`](crate::SocEstimate::estimate),
+//! available on every estimator, [`SocEstimator::estimate_with_temp()`](crate::SocEstimator::estimate_with_temp),
+//! [`SocEstimator::estimate_compensated()`](crate::SocEstimator::estimate_compensated)
+//! and [`SocTracker::track()`](crate::SocTracker::track).
+
+use crate::Fixed;
+
+macro_rules! unit_newtype {
+    ($(#[$meta:meta])* $name:ident, $unit:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub struct $name(Fixed);
+
+        impl $name {
+            #[doc = concat!("Wraps a raw value in ", $unit)]
+            #[inline]
+            pub const fn new(value: Fixed) -> Self {
+                Self(value)
+            }
+
+            #[doc = concat!("Returns the raw value in ", $unit)]
+            #[inline]
+            pub const fn raw(self) -> Fixed {
+                self.0
+            }
+        }
+
+        impl From<Fixed> for $name {
+            #[inline]
+            fn from(value: Fixed) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$name> for Fixed {
+            #[inline]
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+    };
+}
+
+unit_newtype!(
+    /// A voltage in volts
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use battery_estimator::{Fixed, Volts};
+    ///
+    /// let voltage = Volts::from_millivolts(3750);
+    /// assert_eq!(voltage.raw(), Fixed::from_num(3.75));
+    /// ```
+    Volts,
+    "volts"
+);
+
+unit_newtype!(
+    /// A temperature in degrees Celsius
+    Celsius,
+    "degrees Celsius"
+);
+
+unit_newtype!(
+    /// A state of charge in percent (0 to 100)
+    Percent,
+    "percent"
+);
+
+impl Volts {
+    /// Creates a voltage from a millivolt reading
+    ///
+    /// Covers the whole `u16` range (up to 65.535 V), rounded to the
+    /// nearest fixed-point step.
+    #[inline]
+    pub const fn from_millivolts(millivolts: u16) -> Self {
+        // At most 65535 * 65536 / 1000, well within i32
+        let bits = (millivolts as i64 * (1 << 16) + 500) / 1000;
+        Self(Fixed::from_bits(bits as i32))
+    }
+}
+
+impl Percent {
+    /// Returns the percentage as a fraction (0 to 1)
+    #[inline]
+    pub fn as_fraction(self) -> Fixed {
+        self.0 / Fixed::from_bits(100 << 16)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_round_trip() {
+        let voltage = Volts::new(Fixed::from_num(3.75));
+        assert_eq!(Fixed::from(voltage), Fixed::from_num(3.75));
+        assert_eq!(
+            Celsius::from(Fixed::from_num(-10)).raw(),
+            Fixed::from_num(-10)
+        );
+        assert_eq!(
+            Percent::new(Fixed::from_num(25)).as_fraction(),
+            Fixed::from_num(0.25)
+        );
+        assert_eq!(
+            Volts::from_millivolts(4200),
+            Volts::new(Fixed::from_num(4.2))
+        );
+    }
+
+    #[test]
+    fn test_volts_from_millivolts_full_range() {
+        assert_eq!(Volts::from_millivolts(0).raw(), Fixed::ZERO);
+        assert_eq!(Volts::from_millivolts(48000).raw(), Fixed::from_num(48));
+        assert_eq!(
+            Volts::from_millivolts(u16::MAX).raw(),
+            Fixed::from_num(65.535)
+        );
+        for mv in (0..=u16::MAX).step_by(97) {
+            let volts = Volts::from_millivolts(mv).raw();
+            assert_eq!(volts, Fixed::from_num(mv as f64 / 1000.0), "{mv} mV");
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! Unbounded curve backed by `alloc::vec::Vec`
+//!
+//! Enabled with the `alloc` feature. Lab discharge profiles often have
+//! hundreds of points, far beyond [`MAX_CURVE_POINTS`](crate::MAX_CURVE_POINTS).
+//! [`VecCurve`] holds any number of points on the heap for host-side
+//! analysis tools, while the no_std core stays allocation-free.
+
+use alloc::vec::Vec;
+
+#[cfg(not(feature = "no-float"))]
+use crate::curve::volts_from_f32;
+use crate::curve::{interpolate_points, validate_points};
+use crate::{CurvePoint, Error, ErrorDetail, Fixed, SocEstimate};
+
+/// A voltage-to-SOC curve with any number of points
+///
+/// Interpolation behaves like
+/// [`Curve::voltage_to_soc_fixed()`](crate::Curve::voltage_to_soc_fixed).
+///
+/// # Examples
+///
+/// ```
+/// use battery_estimator::{CurvePoint, Fixed, VecCurve};
+///
+/// // 401 points, one per 2.5 mV
+/// let curve: VecCurve = (0..=400u16)
+///     .map(|i| CurvePoint::from_raw(3000 + i * 5 / 2, i * 5 / 2))
+///     .collect();
+///
+/// assert_eq!(curve.len(), 401);
+/// assert_eq!(curve.voltage_to_soc_fixed(Fixed::from_num(3.5)).unwrap(), Fixed::from_num(50));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VecCurve {
+    points: Vec<CurvePoint>,
+}
+
+impl VecCurve {
+    /// Creates an empty curve
+    #[inline]
+    pub const fn new() -> Self {
+        Self { points: Vec::new() }
+    }
+
+    /// Appends a point
+    #[inline]
+    pub fn push(&mut self, point: CurvePoint) {
+        self.points.push(point);
+    }
+
+    /// Removes and returns the last point
+    #[inline]
+    pub fn pop(&mut self) -> Option<CurvePoint> {
+        self.points.pop()
+    }
+
+    /// Removes all points
+    #[inline]
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
+
+    /// Sorts the points by increasing voltage
+    ///
+    /// Lab logs are usually recorded while discharging, i.e. in order of
+    /// decreasing voltage.
+    #[inline]
+    pub fn sort_by_voltage(&mut self) {
+        self.points.sort_unstable_by_key(|p| p.voltage_mv);
+    }
+
+    /// Returns the points in order
+    #[inline]
+    pub fn points(&self) -> &[CurvePoint] {
+        &self.points
+    }
+
+    /// Returns the number of points
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Returns `true` if the curve has no points
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Checks that the curve has at least 2 points with strictly
+    /// increasing voltages
+    #[inline]
+    pub fn validate_sorted(&self) -> Result<(), Error> {
+        self.validate_sorted_detail().map_err(Error::from)
+    }
+
+    /// Like [`validate_sorted()`](Self::validate_sorted), but reports the
+    /// index of the first point that breaks the voltage order (saturating
+    /// at 255)
+    #[inline]
+    pub fn validate_sorted_detail(&self) -> Result<(), ErrorDetail> {
+        validate_points(&self.points)
+    }
+
+    /// Converts a voltage to SOC percentage using fixed-point arithmetic
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(soc)` - SOC percentage as fixed-point
+    /// * `Err(Error::InvalidCurve)` - Curve has fewer than 2 points
+    /// * `Err(Error::NumericalError)` - Points out of order
+    #[inline]
+    pub fn voltage_to_soc_fixed(&self, voltage: Fixed) -> Result<Fixed, Error> {
+        interpolate_points(&self.points, voltage)
+    }
+
+    /// Converts a voltage in volts to SOC percentage
+    ///
+    /// Returns [`Error::NonFiniteInput`] for a NaN or infinite voltage.
+    #[cfg(not(feature = "no-float"))]
+    pub fn voltage_to_soc(&self, voltage: f32) -> Result<f32, Error> {
+        self.voltage_to_soc_fixed(volts_from_f32(voltage)?)
+            .map(|soc| soc.to_num::<f32>())
+    }
+
+    /// Returns the voltage of the first and last point as fixed-point values
+    ///
+    /// Both are zero for an empty curve.
+    pub fn voltage_range_fixed(&self) -> (Fixed, Fixed) {
+        match (self.points.first(), self.points.last()) {
+            (Some(first), Some(last)) => (first.voltage_fixed(), last.voltage_fixed()),
+            _ => (Fixed::ZERO, Fixed::ZERO),
+        }
+    }
+}
+
+impl From<Vec<CurvePoint>> for VecCurve {
+    #[inline]
+    fn from(points: Vec<CurvePoint>) -> Self {
+        Self { points }
+    }
+}
+
+impl FromIterator<CurvePoint> for VecCurve {
+    fn from_iter<I: IntoIterator<Item = CurvePoint>>(iter: I) -> Self {
+        Self {
+            points: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl SocEstimate for VecCurve {
+    #[inline]
+    fn estimate_soc_fixed(&self, voltage: Fixed) -> Result<Fixed, Error> {
+        self.voltage_to_soc_fixed(voltage)
+    }
+
+    #[inline]
+    fn voltage_range_fixed(&self) -> (Fixed, Fixed) {
+        VecCurve::voltage_range_fixed(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_vec_curve_sort_and_lookup() {
+        // Recorded while discharging
+        let mut curve = VecCurve::from(vec![
+            CurvePoint::new(4.0, 100.0),
+            CurvePoint::new(3.5, 50.0),
+            CurvePoint::new(3.0, 0.0),
+        ]);
+        assert_eq!(
+            curve.validate_sorted_detail(),
+            Err(Error::InvalidCurve.at_curve_point(1))
+        );
+
+        curve.sort_by_voltage();
+        assert_eq!(curve.validate_sorted(), Ok(()));
+        assert_eq!(curve.voltage_to_soc(3.25).unwrap(), 25.0);
+        assert_eq!(
+            curve.voltage_range_fixed(),
+            (Fixed::from_num(3), Fixed::from_num(4))
+        );
+
+        curve.clear();
+        assert_eq!(curve.voltage_to_soc(3.25), Err(Error::InvalidCurve));
+    }
+
+    #[test]
+    fn test_vec_curve_matches_curve() {
+        let fixed = crate::Curve::for_chemistry(crate::BatteryChemistry::LiPo);
+        let curve: VecCurve = fixed.points().iter().copied().collect();
+        for mv in (3_000..=4_400).step_by(7) {
+            let voltage = Fixed::from_num(mv) / 1000;
+            assert_eq!(
+                curve.voltage_to_soc_fixed(voltage),
+                fixed.voltage_to_soc_fixed(voltage)
+            );
+        }
+
+        // Spans beyond the fixed-point range of whole millivolts
+        let wide = VecCurve::from(vec![
+            CurvePoint {
+                voltage_mv: 0,
+                soc_tenth: 0,
+            },
+            CurvePoint {
+                voltage_mv: u16::MAX,
+                soc_tenth: u16::MAX,
+            },
+        ]);
+        let soc = wide.voltage_to_soc_fixed(Fixed::from_num(30)).unwrap();
+        assert!((soc - Fixed::from_num(3000)).abs() < Fixed::from_num(0.1));
+    }
+}
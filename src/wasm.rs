@@ -0,0 +1,246 @@
+//! WebAssembly bindings
+//!
+//! Exposes curves and the estimator to JavaScript through `wasm-bindgen`,
+//! so dashboards and provisioning UIs can preview SOC curves and check
+//! parameters with the same code that runs on the device. Build with
+//! [wasm-pack](https://rustwasm.github.io/wasm-pack/) and the `wasm` feature:
+//!
+//! ```text
+//! wasm-pack build --target web -- --features wasm
+//! ```
+//!
+//! ```javascript
+//! import init, { WasmCurve, WasmEstimator } from "./pkg/battery_estimator.js";
+//!
+//! await init();
+//! const curve = WasmCurve.forChemistry("LiPo");
+//! const preview = curve.sample(50); // [voltage, soc, voltage, soc, ...]
+//! const estimator = new WasmEstimator("LiPo");
+//! estimator.setTemperatureCompensation(25, 0.005);
+//! estimator.estimateSocCompensated(3.7, 10);
+//! ```
+//!
+//! Points are passed as flat `[voltage, soc, ...]` arrays. Errors are
+//! thrown as JavaScript `Error`s carrying the crate's error message.
+
+use alloc::vec::Vec;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{BatteryChemistry, Curve, CurvePoint, Error, EstimatorConfig, Fixed, SocEstimator};
+
+/// Converts a crate error into a JavaScript `Error`
+fn to_js_err(error: Error) -> JsError {
+    JsError::new(error.message())
+}
+
+/// Converts a JavaScript number into fixed-point, rejecting values out of range
+fn to_fixed(value: f64) -> Result<Fixed, Error> {
+    Fixed::checked_from_num(value).ok_or(Error::InvalidConfig)
+}
+
+/// Builds a validated curve from flat `[voltage, soc, ...]` pairs
+fn curve_from_flat(points: &[f32]) -> Result<Curve, Error> {
+    if points.len() % 2 != 0 || points.len() / 2 > crate::MAX_CURVE_POINTS {
+        return Err(Error::InvalidCurve);
+    }
+    let points: Vec<CurvePoint> = points
+        .chunks_exact(2)
+        .map(|pair| CurvePoint::new(pair[0], pair[1]))
+        .collect();
+    let curve = Curve::new(&points);
+    curve.validate_sorted()?;
+    Ok(curve)
+}
+
+/// Evenly spaced `[voltage, soc, ...]` samples across the curve's range
+fn sample_curve(curve: &Curve, steps: u16) -> Result<Vec<f64>, Error> {
+    let (min, max) = curve.voltage_range();
+    let (min, max) = (min as f64, max as f64);
+    let steps = steps.max(2);
+    let mut samples = Vec::with_capacity(steps as usize * 2);
+    for step in 0..steps {
+        let voltage = min + (max - min) * step as f64 / (steps - 1) as f64;
+        samples.push(voltage);
+        samples.push(curve.voltage_to_soc_f64(voltage)?);
+    }
+    Ok(samples)
+}
+
+/// Voltage-to-SOC curve
+#[wasm_bindgen]
+pub struct WasmCurve {
+    curve: Curve,
+}
+
+#[wasm_bindgen]
+impl WasmCurve {
+    /// Creates a curve from flat `[voltage, soc, ...]` pairs, ordered by
+    /// increasing voltage
+    #[wasm_bindgen(constructor)]
+    pub fn new(points: &[f32]) -> Result<WasmCurve, JsError> {
+        let curve = curve_from_flat(points).map_err(to_js_err)?;
+        Ok(Self { curve })
+    }
+
+    /// Built-in curve of a chemistry ("LiPo", "LiFePO4", "LiIon",
+    /// "Lipo410Full340Cutoff")
+    #[wasm_bindgen(js_name = forChemistry)]
+    pub fn for_chemistry(chemistry: &str) -> Result<WasmCurve, JsError> {
+        let chemistry = chemistry.parse::<BatteryChemistry>().map_err(to_js_err)?;
+        Ok(Self {
+            curve: *Curve::for_chemistry(chemistry),
+        })
+    }
+
+    /// Points as flat `[voltage, soc, ...]` pairs
+    pub fn points(&self) -> Vec<f32> {
+        self.curve
+            .points()
+            .iter()
+            .flat_map(|point| [point.voltage(), point.soc()])
+            .collect()
+    }
+
+    /// `steps` evenly spaced `[voltage, soc, ...]` samples for plotting
+    pub fn sample(&self, steps: u16) -> Result<Vec<f64>, JsError> {
+        sample_curve(&self.curve, steps).map_err(to_js_err)
+    }
+
+    /// SOC in percent at a voltage
+    #[wasm_bindgen(js_name = voltageToSoc)]
+    pub fn voltage_to_soc(&self, voltage: f64) -> Result<f64, JsError> {
+        self.curve.voltage_to_soc_f64(voltage).map_err(to_js_err)
+    }
+}
+
+/// Voltage-based SOC estimator
+#[wasm_bindgen]
+pub struct WasmEstimator {
+    /// The estimation curve, owned so custom curves need no `'static` copy
+    curve: Curve,
+    /// Configuration only; lookups use `curve`
+    estimator: SocEstimator,
+}
+
+#[wasm_bindgen]
+impl WasmEstimator {
+    /// Creates an estimator for a built-in chemistry
+    #[wasm_bindgen(constructor)]
+    pub fn new(chemistry: &str) -> Result<WasmEstimator, JsError> {
+        let chemistry = chemistry.parse::<BatteryChemistry>().map_err(to_js_err)?;
+        let estimator = SocEstimator::new(chemistry);
+        Ok(Self {
+            curve: *estimator.curve(),
+            estimator,
+        })
+    }
+
+    /// Creates an estimator for a custom curve
+    #[wasm_bindgen(js_name = withCurve)]
+    pub fn with_curve(curve: &WasmCurve) -> WasmEstimator {
+        // Custom-curve defaults; the built-in curve is never looked up
+        let placeholder = Curve::for_chemistry(BatteryChemistry::LiPo);
+        Self {
+            curve: curve.curve,
+            estimator: SocEstimator::with_custom_curve(placeholder),
+        }
+    }
+
+    /// Enables temperature compensation, rejecting invalid parameters
+    #[wasm_bindgen(js_name = setTemperatureCompensation)]
+    pub fn set_temperature_compensation(
+        &mut self,
+        nominal_temp: f64,
+        coefficient: f64,
+    ) -> Result<(), JsError> {
+        let config = self
+            .estimator
+            .config()
+            .with_temperature_compensation()
+            .with_nominal_temperature(to_fixed(nominal_temp).map_err(to_js_err)?)
+            .with_temperature_coefficient(to_fixed(coefficient).map_err(to_js_err)?);
+        self.apply_config(config)
+    }
+
+    /// Enables aging compensation, rejecting invalid parameters
+    #[wasm_bindgen(js_name = setAgingCompensation)]
+    pub fn set_aging_compensation(
+        &mut self,
+        age_years: f64,
+        aging_factor: f64,
+    ) -> Result<(), JsError> {
+        let config = self
+            .estimator
+            .config()
+            .with_aging_compensation()
+            .with_age_years(to_fixed(age_years).map_err(to_js_err)?)
+            .with_aging_factor(to_fixed(aging_factor).map_err(to_js_err)?);
+        self.apply_config(config)
+    }
+
+    /// SOC in percent at a voltage, without compensation
+    #[wasm_bindgen(js_name = estimateSoc)]
+    pub fn estimate_soc(&self, voltage: f64) -> Result<f64, JsError> {
+        self.estimator
+            .estimate_soc_f64_on(&self.curve, voltage)
+            .map_err(to_js_err)
+    }
+
+    /// SOC in percent using the configured compensation
+    #[wasm_bindgen(js_name = estimateSocCompensated)]
+    pub fn estimate_soc_compensated(&self, voltage: f64, temperature: f64) -> Result<f64, JsError> {
+        self.estimator
+            .estimate_soc_compensated_f64_on(&self.curve, voltage, temperature)
+            .map_err(to_js_err)
+    }
+}
+
+impl WasmEstimator {
+    /// Validates and stores a configuration
+    fn apply_config(&mut self, config: EstimatorConfig) -> Result<(), JsError> {
+        config.validate().map_err(to_js_err)?;
+        self.estimator.update_config(config);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curve_from_flat() {
+        let curve = curve_from_flat(&[3.0, 0.0, 4.0, 100.0]).unwrap();
+        assert_eq!(curve.voltage_to_soc_f64(3.5), Ok(50.0));
+
+        assert_eq!(curve_from_flat(&[3.0, 0.0, 4.0]), Err(Error::InvalidCurve));
+        assert_eq!(
+            curve_from_flat(&[4.0, 100.0, 3.0, 0.0]),
+            Err(Error::InvalidCurve)
+        );
+    }
+
+    #[test]
+    fn test_sample_curve() {
+        let curve = curve_from_flat(&[3.0, 0.0, 4.0, 100.0]).unwrap();
+        let samples = sample_curve(&curve, 3).unwrap();
+
+        assert_eq!(samples.len(), 6);
+        assert_eq!(samples[2..4], [3.5, 50.0]);
+    }
+
+    #[test]
+    fn test_estimator_with_curve() {
+        let curve = WasmCurve {
+            curve: curve_from_flat(&[3.0, 0.0, 4.0, 100.0]).unwrap(),
+        };
+        let estimator = WasmEstimator::with_curve(&curve);
+
+        assert_eq!(estimator.estimate_soc(3.5).ok(), Some(50.0));
+        assert_eq!(
+            estimator.estimate_soc_compensated(3.5, 25.0).ok(),
+            Some(50.0)
+        );
+    }
+}